@@ -0,0 +1,278 @@
+use serde::{Deserialize, Serialize};
+use std::f64::consts::{PI, SQRT_2};
+use utoipa::ToSchema;
+
+// -----------------------------------------------
+// PRICING DEFAULTS
+// -----------------------------------------------
+pub const DEFAULT_RISK_FREE_RATE: f64 = 0.065;  // Approx. 91-day T-bill yield
+pub const DAYS_PER_YEAR: f64 = 365.0;
+
+// Expiry-day options still carry a few trading hours
+const MIN_DAYS_TO_EXPIRY: f64 = 0.5;
+
+// Implied volatility solver bounds
+const IV_MIN: f64 = 0.0001;
+const IV_MAX: f64 = 5.0;
+const IV_TOLERANCE: f64 = 1e-6;
+const IV_MAX_ITERATIONS: usize = 100;
+
+/// Call or put
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+pub enum OptionKind {
+    #[serde(rename = "CE")]
+    Call,
+    #[serde(rename = "PE")]
+    Put,
+}
+
+impl OptionKind {
+    /// Parse "CE"/"PE" (also accepts "CALL"/"PUT")
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.trim().to_uppercase().as_str() {
+            "CE" | "CALL" | "C" => Some(OptionKind::Call),
+            "PE" | "PUT" | "P" => Some(OptionKind::Put),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            OptionKind::Call => "CE",
+            OptionKind::Put => "PE",
+        }
+    }
+
+    /// Intrinsic value at a given underlying price
+    pub fn intrinsic(&self, spot: f64, strike: f64) -> f64 {
+        match self {
+            OptionKind::Call => (spot - strike).max(0.0),
+            OptionKind::Put => (strike - spot).max(0.0),
+        }
+    }
+}
+
+/// Option sensitivities (theta per calendar day, vega per 1 vol point)
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct Greeks {
+    pub delta: f64,
+    pub gamma: f64,
+    pub theta: f64,
+    pub vega: f64,
+}
+
+impl std::ops::Add for Greeks {
+    type Output = Greeks;
+
+    fn add(self, other: Greeks) -> Greeks {
+        Greeks {
+            delta: self.delta + other.delta,
+            gamma: self.gamma + other.gamma,
+            theta: self.theta + other.theta,
+            vega: self.vega + other.vega,
+        }
+    }
+}
+
+impl std::ops::Mul<f64> for Greeks {
+    type Output = Greeks;
+
+    fn mul(self, qty: f64) -> Greeks {
+        Greeks {
+            delta: self.delta * qty,
+            gamma: self.gamma * qty,
+            theta: self.theta * qty,
+            vega: self.vega * qty,
+        }
+    }
+}
+
+/// Black-Scholes-Merton pricing model with a continuous carry yield.
+/// NSE stock/index options use spot with no yield; MCX options are on
+/// futures, so they use Black-76 (yield equal to the rate).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct PricingModel {
+    pub rate: f64,
+    pub dividend_yield: f64,
+}
+
+impl Default for PricingModel {
+    fn default() -> Self {
+        Self::nse()
+    }
+}
+
+impl PricingModel {
+    /// Spot-based model for NSE equity and index options
+    pub fn nse() -> Self {
+        Self { rate: DEFAULT_RISK_FREE_RATE, dividend_yield: 0.0 }
+    }
+
+    /// Black-76 model for MCX options on commodity futures
+    pub fn mcx() -> Self {
+        Self { rate: DEFAULT_RISK_FREE_RATE, dividend_yield: DEFAULT_RISK_FREE_RATE }
+    }
+
+    fn d1_d2(&self, spot: f64, strike: f64, t: f64, vol: f64) -> (f64, f64) {
+        let sqrt_t = t.sqrt();
+        let d1 = ((spot / strike).ln() + (self.rate - self.dividend_yield + 0.5 * vol * vol) * t)
+            / (vol * sqrt_t);
+        (d1, d1 - vol * sqrt_t)
+    }
+
+    /// Theoretical option price
+    pub fn price(&self, kind: OptionKind, spot: f64, strike: f64, t: f64, vol: f64) -> f64 {
+        if spot <= 0.0 || strike <= 0.0 {
+            return 0.0;
+        }
+        if t <= 0.0 || vol <= 0.0 {
+            return kind.intrinsic(spot, strike);
+        }
+
+        let (d1, d2) = self.d1_d2(spot, strike, t, vol);
+        let df_r = (-self.rate * t).exp();
+        let df_q = (-self.dividend_yield * t).exp();
+
+        match kind {
+            OptionKind::Call => spot * df_q * norm_cdf(d1) - strike * df_r * norm_cdf(d2),
+            OptionKind::Put => strike * df_r * norm_cdf(-d2) - spot * df_q * norm_cdf(-d1),
+        }
+    }
+
+    /// Option greeks
+    pub fn greeks(&self, kind: OptionKind, spot: f64, strike: f64, t: f64, vol: f64) -> Greeks {
+        if spot <= 0.0 || strike <= 0.0 || t <= 0.0 || vol <= 0.0 {
+            // At expiry only delta survives
+            let delta = match kind {
+                OptionKind::Call if spot > strike => 1.0,
+                OptionKind::Put if spot < strike => -1.0,
+                _ => 0.0,
+            };
+            return Greeks { delta, ..Greeks::default() };
+        }
+
+        let sqrt_t = t.sqrt();
+        let (d1, d2) = self.d1_d2(spot, strike, t, vol);
+        let df_r = (-self.rate * t).exp();
+        let df_q = (-self.dividend_yield * t).exp();
+        let pdf_d1 = norm_pdf(d1);
+
+        let gamma = df_q * pdf_d1 / (spot * vol * sqrt_t);
+        let vega = spot * df_q * pdf_d1 * sqrt_t / 100.0;
+        let decay = -spot * df_q * pdf_d1 * vol / (2.0 * sqrt_t);
+
+        let (delta, theta_annual) = match kind {
+            OptionKind::Call => (
+                df_q * norm_cdf(d1),
+                decay - self.rate * strike * df_r * norm_cdf(d2)
+                    + self.dividend_yield * spot * df_q * norm_cdf(d1),
+            ),
+            OptionKind::Put => (
+                df_q * (norm_cdf(d1) - 1.0),
+                decay + self.rate * strike * df_r * norm_cdf(-d2)
+                    - self.dividend_yield * spot * df_q * norm_cdf(-d1),
+            ),
+        };
+
+        Greeks {
+            delta,
+            gamma,
+            theta: theta_annual / DAYS_PER_YEAR,
+            vega,
+        }
+    }
+
+    /// Risk-neutral probability of finishing in the money at expiry
+    pub fn probability_itm(&self, kind: OptionKind, spot: f64, strike: f64, t: f64, vol: f64) -> f64 {
+        if spot <= 0.0 || strike <= 0.0 || t <= 0.0 || vol <= 0.0 {
+            return if kind.intrinsic(spot, strike) > 0.0 { 1.0 } else { 0.0 };
+        }
+
+        let (_, d2) = self.d1_d2(spot, strike, t, vol);
+        match kind {
+            OptionKind::Call => norm_cdf(d2),
+            OptionKind::Put => norm_cdf(-d2),
+        }
+    }
+
+    /// Solve for implied volatility (None if the price is outside no-arbitrage bounds)
+    pub fn implied_volatility(
+        &self,
+        kind: OptionKind,
+        market_price: f64,
+        spot: f64,
+        strike: f64,
+        t: f64,
+    ) -> Option<f64> {
+        if market_price <= 0.0 || spot <= 0.0 || strike <= 0.0 || t <= 0.0 {
+            return None;
+        }
+
+        let lower = self.price(kind, spot, strike, t, IV_MIN);
+        let upper = self.price(kind, spot, strike, t, IV_MAX);
+        if market_price < lower || market_price > upper {
+            return None;
+        }
+
+        // Newton-Raphson with bisection fallback
+        let (mut lo, mut hi) = (IV_MIN, IV_MAX);
+        let mut vol = 0.3;
+
+        for _ in 0..IV_MAX_ITERATIONS {
+            let diff = self.price(kind, spot, strike, t, vol) - market_price;
+            if diff.abs() < IV_TOLERANCE {
+                return Some(vol);
+            }
+
+            if diff > 0.0 {
+                hi = vol;
+            } else {
+                lo = vol;
+            }
+
+            let vega = self.greeks(kind, spot, strike, t, vol).vega * 100.0;
+            let newton = vol - diff / vega;
+            vol = if vega > 1e-8 && newton > lo && newton < hi {
+                newton
+            } else {
+                0.5 * (lo + hi)
+            };
+
+            if hi - lo < IV_TOLERANCE {
+                return Some(vol);
+            }
+        }
+
+        Some(vol)
+    }
+}
+
+/// Convert days to expiry into a year fraction (expiry day counts as half a day)
+pub fn years_to_expiry(days_to_expiry: i32) -> f64 {
+    (days_to_expiry as f64).max(MIN_DAYS_TO_EXPIRY) / DAYS_PER_YEAR
+}
+
+/// Standard normal probability density
+pub fn norm_pdf(x: f64) -> f64 {
+    (-0.5 * x * x).exp() / (2.0 * PI).sqrt()
+}
+
+/// Standard normal cumulative distribution
+pub fn norm_cdf(x: f64) -> f64 {
+    0.5 * (1.0 + erf(x / SQRT_2))
+}
+
+/// Error function (Abramowitz & Stegun 7.1.26, max error 1.5e-7)
+fn erf(x: f64) -> f64 {
+    let sign = if x < 0.0 { -1.0 } else { 1.0 };
+    let x = x.abs();
+
+    let t = 1.0 / (1.0 + 0.327_591_1 * x);
+    let poly = t * (0.254_829_592
+        + t * (-0.284_496_736
+        + t * (1.421_413_741
+        + t * (-1.453_152_027
+        + t * 1.061_405_429))));
+
+    sign * (1.0 - poly * (-x * x).exp())
+}
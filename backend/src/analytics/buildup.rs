@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Position buildup derived from the direction of price change vs OI change
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+pub enum Buildup {
+    #[serde(rename = "Long Buildup")]
+    LongBuildup,     // Price up, OI up
+    #[serde(rename = "Short Covering")]
+    ShortCovering,   // Price up, OI down
+    #[serde(rename = "Short Buildup")]
+    ShortBuildup,    // Price down, OI up
+    #[serde(rename = "Long Covering")]
+    LongCovering,    // Price down, OI down
+    #[default]
+    Neutral,
+}
+
+impl Buildup {
+    /// Display label (same strings used in API responses)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Buildup::LongBuildup => "Long Buildup",
+            Buildup::ShortCovering => "Short Covering",
+            Buildup::ShortBuildup => "Short Buildup",
+            Buildup::LongCovering => "Long Covering",
+            Buildup::Neutral => "Neutral",
+        }
+    }
+
+    /// Alert type name used by the rules engines
+    pub fn alert_type(&self) -> &'static str {
+        match self {
+            Buildup::LongBuildup => "LONG_BUILDUP",
+            Buildup::ShortCovering => "SHORT_COVERING",
+            Buildup::ShortBuildup => "SHORT_BUILDUP",
+            Buildup::LongCovering => "LONG_COVERING",
+            Buildup::Neutral => "NEUTRAL",
+        }
+    }
+}
+
+impl std::fmt::Display for Buildup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Classify buildup from price change and OI change (only the signs matter)
+pub fn classify_buildup(price_change: Option<f64>, oi_change: Option<f64>) -> Buildup {
+    let (Some(price), Some(oi)) = (price_change, oi_change) else {
+        return Buildup::Neutral;
+    };
+
+    if price > 0.0 && oi > 0.0 {
+        Buildup::LongBuildup
+    } else if price > 0.0 && oi < 0.0 {
+        Buildup::ShortCovering
+    } else if price < 0.0 && oi > 0.0 {
+        Buildup::ShortBuildup
+    } else if price < 0.0 && oi < 0.0 {
+        Buildup::LongCovering
+    } else {
+        Buildup::Neutral
+    }
+}
+
+/// Buildup counts across all CE/PE legs of a symbol
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct BuildupCounts {
+    pub long_buildup: usize,
+    pub short_covering: usize,
+    pub short_buildup: usize,
+    pub long_covering: usize,
+    pub neutral: usize,
+}
+
+impl BuildupCounts {
+    /// Record one classified leg
+    pub fn add(&mut self, buildup: Buildup) {
+        match buildup {
+            Buildup::LongBuildup => self.long_buildup += 1,
+            Buildup::ShortCovering => self.short_covering += 1,
+            Buildup::ShortBuildup => self.short_buildup += 1,
+            Buildup::LongCovering => self.long_covering += 1,
+            Buildup::Neutral => self.neutral += 1,
+        }
+    }
+
+    /// Total classified legs
+    pub fn total(&self) -> usize {
+        self.long_buildup + self.short_covering + self.short_buildup + self.long_covering + self.neutral
+    }
+}
+
+impl FromIterator<Buildup> for BuildupCounts {
+    fn from_iter<I: IntoIterator<Item = Buildup>>(iter: I) -> Self {
+        let mut counts = Self::default();
+        for buildup in iter {
+            counts.add(buildup);
+        }
+        counts
+    }
+}
+
+/// Per-symbol buildup counts for batch summaries
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SymbolBuildup {
+    pub symbol: String,
+    #[serde(flatten)]
+    pub counts: BuildupCounts,
+}
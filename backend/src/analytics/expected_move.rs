@@ -0,0 +1,99 @@
+use super::black_scholes::{years_to_expiry, OptionKind, PricingModel};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// IV and probabilities for a single option (IV as annualized percentage)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct StrikeProbabilities {
+    pub iv: f64,
+    pub prob_itm: f64,    // Finishing in the money at expiry
+    pub prob_touch: f64,  // Touching the strike before expiry (~2x ITM probability)
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct PriceBand {
+    pub lower: f64,
+    pub upper: f64,
+}
+
+/// Expected move to expiry implied by the ATM straddle and ATM IV
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ExpectedMove {
+    pub atm_strike: f64,
+    pub atm_straddle: f64,
+    pub straddle_move: f64,      // ± move priced by the straddle
+    pub straddle_move_pct: f64,
+    pub atm_iv: Option<f64>,
+    pub iv_move: Option<f64>,    // 1 standard deviation move from ATM IV
+    pub iv_move_pct: Option<f64>,
+    pub band_1sd: Option<PriceBand>,
+    pub band_2sd: Option<PriceBand>,
+}
+
+/// IV and ITM/touch probabilities from an option's market price
+pub fn strike_probabilities(
+    model: &PricingModel,
+    kind: OptionKind,
+    price: Option<f64>,
+    spot: f64,
+    strike: f64,
+    days_to_expiry: i32,
+) -> Option<StrikeProbabilities> {
+    let price = price.filter(|p| *p > 0.0)?;
+    let t = years_to_expiry(days_to_expiry);
+    let vol = model.implied_volatility(kind, price, spot, strike, t)?;
+
+    let prob_itm = model.probability_itm(kind, spot, strike, t, vol);
+    let already_touched = kind.intrinsic(spot, strike) > 0.0;
+
+    Some(StrikeProbabilities {
+        iv: vol * 100.0,
+        prob_itm,
+        prob_touch: if already_touched { 1.0 } else { (2.0 * prob_itm).min(1.0) },
+    })
+}
+
+/// Expected move from the ATM call/put prices.
+/// IV bands are lognormal: spot * exp(±n * sigma * sqrt(t)).
+pub fn compute_expected_move(
+    model: &PricingModel,
+    atm_strike: f64,
+    call_price: f64,
+    put_price: f64,
+    spot: f64,
+    days_to_expiry: i32,
+) -> Option<ExpectedMove> {
+    if spot <= 0.0 || call_price <= 0.0 || put_price <= 0.0 {
+        return None;
+    }
+
+    let t = years_to_expiry(days_to_expiry);
+    let atm_straddle = call_price + put_price;
+
+    let call_iv = model.implied_volatility(OptionKind::Call, call_price, spot, atm_strike, t);
+    let put_iv = model.implied_volatility(OptionKind::Put, put_price, spot, atm_strike, t);
+    let atm_vol = match (call_iv, put_iv) {
+        (Some(c), Some(p)) => Some((c + p) / 2.0),
+        (c, p) => c.or(p),
+    };
+
+    let sigma_move = atm_vol.map(|vol| vol * t.sqrt());
+    let band = |n: f64| {
+        sigma_move.map(|s| PriceBand {
+            lower: spot * (-n * s).exp(),
+            upper: spot * (n * s).exp(),
+        })
+    };
+
+    Some(ExpectedMove {
+        atm_strike,
+        atm_straddle,
+        straddle_move: atm_straddle,
+        straddle_move_pct: atm_straddle / spot * 100.0,
+        atm_iv: atm_vol.map(|v| v * 100.0),
+        iv_move: sigma_move.map(|s| spot * s),
+        iv_move_pct: sigma_move.map(|s| s * 100.0),
+        band_1sd: band(1.0),
+        band_2sd: band(2.0),
+    })
+}
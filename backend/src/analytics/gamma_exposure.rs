@@ -0,0 +1,203 @@
+use super::black_scholes::{years_to_expiry, OptionKind, PricingModel};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Spot range scanned for the zero-gamma flip level
+const FLIP_RANGE_PCT: f64 = 0.10;
+const FLIP_GRID_POINTS: usize = 81;
+
+/// OI and prices at a single strike (input for GEX computation)
+#[derive(Debug, Clone)]
+pub struct ExposureQuote {
+    pub strike: f64,
+    pub call_oi: f64,
+    pub put_oi: f64,
+    pub call_price: Option<f64>,
+    pub put_price: Option<f64>,
+}
+
+/// Dealer gamma regime at the current spot
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub enum GammaRegime {
+    Positive,  // Dealers long gamma: hedging dampens moves
+    Negative,  // Dealers short gamma: hedging amplifies moves
+}
+
+/// Gamma exposure at one strike (rupees per 1% move in the underlying)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StrikeExposure {
+    pub strike: f64,
+    pub call_oi: f64,
+    pub put_oi: f64,
+    pub call_gex: f64,
+    pub put_gex: f64,
+    pub net_gex: f64,
+}
+
+/// Aggregate gamma exposure, assuming dealers are long calls and short puts
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GammaExposure {
+    pub underlying_value: f64,
+    pub total_call_gex: f64,
+    pub total_put_gex: f64,
+    pub net_gex: f64,
+    pub regime: GammaRegime,
+    pub zero_gamma_level: Option<f64>,  // Spot at which net GEX changes sign
+    pub max_gex_strike: Option<f64>,    // Strike with the largest absolute net GEX
+    pub by_strike: Vec<StrikeExposure>,
+}
+
+/// Per-strike IVs used to reprice gamma at hypothetical spots
+struct StrikeVol {
+    strike: f64,
+    call_oi: f64,
+    put_oi: f64,
+    call_iv: f64,
+    put_iv: f64,
+}
+
+/// Compute GEX by strike and in aggregate.
+/// `point_value` converts one contract of OI into underlying units (lot size x multiplier).
+pub fn compute_gamma_exposure(
+    model: &PricingModel,
+    quotes: &[ExposureQuote],
+    underlying_value: f64,
+    days_to_expiry: i32,
+    point_value: f64,
+) -> Option<GammaExposure> {
+    if underlying_value <= 0.0 {
+        return None;
+    }
+
+    let t = years_to_expiry(days_to_expiry);
+    let solve = |kind: OptionKind, price: Option<f64>, strike: f64| {
+        price
+            .filter(|p| *p > 0.0)
+            .and_then(|p| model.implied_volatility(kind, p, underlying_value, strike, t))
+    };
+
+    let solved: Vec<(&ExposureQuote, Option<f64>, Option<f64>)> = quotes
+        .iter()
+        .filter(|q| q.strike > 0.0 && (q.call_oi > 0.0 || q.put_oi > 0.0))
+        .map(|q| {
+            (
+                q,
+                solve(OptionKind::Call, q.call_price, q.strike),
+                solve(OptionKind::Put, q.put_price, q.strike),
+            )
+        })
+        .collect();
+
+    // Strikes without a usable price borrow the average IV
+    let ivs: Vec<f64> = solved.iter().flat_map(|(_, c, p)| [*c, *p]).flatten().collect();
+    if ivs.is_empty() {
+        return None;
+    }
+    let fallback_iv = ivs.iter().sum::<f64>() / ivs.len() as f64;
+
+    let mut strikes: Vec<StrikeVol> = solved
+        .into_iter()
+        .map(|(q, call_iv, put_iv)| StrikeVol {
+            strike: q.strike,
+            call_oi: q.call_oi,
+            put_oi: q.put_oi,
+            call_iv: call_iv.or(put_iv).unwrap_or(fallback_iv),
+            put_iv: put_iv.or(call_iv).unwrap_or(fallback_iv),
+        })
+        .collect();
+    strikes.sort_by(|a, b| a.strike.partial_cmp(&b.strike).unwrap_or(std::cmp::Ordering::Equal));
+
+    let by_strike: Vec<StrikeExposure> = strikes
+        .iter()
+        .map(|s| {
+            let (call_gex, put_gex) = strike_gex(model, s, underlying_value, t, point_value);
+            StrikeExposure {
+                strike: s.strike,
+                call_oi: s.call_oi,
+                put_oi: s.put_oi,
+                call_gex,
+                put_gex,
+                net_gex: call_gex - put_gex,
+            }
+        })
+        .collect();
+
+    let total_call_gex: f64 = by_strike.iter().map(|s| s.call_gex).sum();
+    let total_put_gex: f64 = by_strike.iter().map(|s| s.put_gex).sum();
+    let net_gex = total_call_gex - total_put_gex;
+
+    let max_gex_strike = by_strike
+        .iter()
+        .max_by(|a, b| {
+            a.net_gex
+                .abs()
+                .partial_cmp(&b.net_gex.abs())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .map(|s| s.strike);
+
+    Some(GammaExposure {
+        underlying_value,
+        total_call_gex,
+        total_put_gex,
+        net_gex,
+        regime: if net_gex >= 0.0 { GammaRegime::Positive } else { GammaRegime::Negative },
+        zero_gamma_level: find_zero_gamma_level(model, &strikes, underlying_value, t, point_value),
+        max_gex_strike,
+        by_strike,
+    })
+}
+
+/// Call and put GEX at one strike for a given spot
+fn strike_gex(model: &PricingModel, s: &StrikeVol, spot: f64, t: f64, point_value: f64) -> (f64, f64) {
+    // Gamma x OI x contract size x S^2 x 1% = rupee delta change per 1% move
+    let scale = point_value * spot * spot * 0.01;
+    let call_gamma = model.greeks(OptionKind::Call, spot, s.strike, t, s.call_iv).gamma;
+    let put_gamma = model.greeks(OptionKind::Put, spot, s.strike, t, s.put_iv).gamma;
+    (call_gamma * s.call_oi * scale, put_gamma * s.put_oi * scale)
+}
+
+/// Scan hypothetical spots for the sign change in net GEX closest to the current spot
+fn find_zero_gamma_level(
+    model: &PricingModel,
+    strikes: &[StrikeVol],
+    underlying_value: f64,
+    t: f64,
+    point_value: f64,
+) -> Option<f64> {
+    let low = underlying_value * (1.0 - FLIP_RANGE_PCT);
+    let step = 2.0 * FLIP_RANGE_PCT * underlying_value / (FLIP_GRID_POINTS - 1) as f64;
+
+    let profile: Vec<(f64, f64)> = (0..FLIP_GRID_POINTS)
+        .map(|i| {
+            let spot = low + step * i as f64;
+            let net: f64 = strikes
+                .iter()
+                .map(|s| {
+                    let (call, put) = strike_gex(model, s, spot, t, point_value);
+                    call - put
+                })
+                .sum();
+            (spot, net)
+        })
+        .collect();
+
+    profile
+        .windows(2)
+        .filter_map(|w| {
+            let ((x0, y0), (x1, y1)) = (w[0], w[1]);
+            if y0 == 0.0 {
+                Some(x0)
+            } else if y0 * y1 < 0.0 {
+                Some(x0 + (x1 - x0) * (-y0) / (y1 - y0))
+            } else {
+                None
+            }
+        })
+        .min_by(|a, b| {
+            (a - underlying_value)
+                .abs()
+                .partial_cmp(&(b - underlying_value).abs())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+}
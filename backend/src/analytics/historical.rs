@@ -0,0 +1,198 @@
+use super::indicators::IndicatorLines;
+use super::realized_volatility::{realized_volatility, RealizedVolatility};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+pub const DEFAULT_ROLLING_WINDOW: usize = 5;
+pub(crate) const TRADING_DAYS_PER_YEAR: f64 = 252.0;
+
+/// One daily bar, normalized across exchanges
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct OhlcBar {
+    pub date: NaiveDate,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: Option<f64>,
+    pub open_interest: Option<f64>,
+    pub change_in_oi: Option<f64>,
+    pub underlying_value: Option<f64>,
+    pub return_pct: Option<f64>,            // Close-to-close
+    pub rolling_mean_return_pct: Option<f64>,
+    pub rolling_volatility_pct: Option<f64>,  // Stdev of the last `window` returns (not annualized)
+}
+
+impl OhlcBar {
+    /// Bar from whatever prices the source provides; missing O/H/L fall back to close
+    pub fn new(date: NaiveDate, open: Option<f64>, high: Option<f64>, low: Option<f64>, close: f64) -> Self {
+        let positive = |v: Option<f64>| v.filter(|p| *p > 0.0);
+        let open = positive(open).unwrap_or(close);
+        Self {
+            date,
+            open,
+            high: positive(high).unwrap_or(open.max(close)),
+            low: positive(low).unwrap_or(open.min(close)),
+            close,
+            ..Default::default()
+        }
+    }
+}
+
+/// Identifies the contract a series belongs to
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct SeriesMeta {
+    pub exchange: String,
+    pub symbol: String,
+    pub instrument: String,  // FUTIDX / OPTSTK / FUTCOM / OPTFUT ...
+    pub expiry: String,
+    pub strike: Option<f64>,
+    pub option_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct SeriesStats {
+    pub bars: usize,
+    pub first_date: Option<NaiveDate>,
+    pub last_date: Option<NaiveDate>,
+    pub period_return_pct: Option<f64>,
+    pub mean_return_pct: Option<f64>,
+    pub return_stdev_pct: Option<f64>,
+    pub annualized_volatility_pct: Option<f64>,
+    pub highest_high: Option<f64>,
+    pub lowest_low: Option<f64>,
+    pub max_drawdown_pct: Option<f64>,
+    pub total_volume: Option<f64>,
+    pub average_volume: Option<f64>,
+    pub last_open_interest: Option<f64>,
+    pub net_change_in_oi: Option<f64>,
+    pub realized_volatility: RealizedVolatility,
+}
+
+/// Normalized OHLC + OI + volume series, the same shape for NSE and MCX
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct HistoricalSeries {
+    #[serde(flatten)]
+    pub meta: SeriesMeta,
+    pub rolling_window: usize,
+    pub bars: Vec<OhlcBar>,
+    pub stats: SeriesStats,
+    #[serde(default, skip_serializing_if = "IndicatorLines::is_empty")]
+    #[schema(value_type = std::collections::BTreeMap<String, Vec<Option<f64>>>)]
+    pub indicators: IndicatorLines,  // Requested with `indicators=`, aligned with `bars`
+}
+
+/// Parse the date formats used by the NSE and MCX historical APIs
+pub fn parse_series_date(raw: &str) -> Option<NaiveDate> {
+    let raw = raw.trim();
+
+    // MCX "/Date(1765737000000)/"
+    if let (Some(start), Some(end)) = (raw.find('('), raw.find(')')) {
+        let millis: i64 = raw.get(start + 1..end)?.parse().ok()?;
+        return DateTime::from_timestamp(millis / 1000, 0)
+            .map(|dt| dt.with_timezone(&chrono::Local).date_naive());
+    }
+
+    for format in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S", "%d-%b-%Y %H:%M:%S"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(raw, format) {
+            return Some(dt.date());
+        }
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(raw) {
+        return Some(dt.date_naive());
+    }
+    ["%d-%b-%Y", "%Y-%m-%d", "%d %b %Y", "%d/%m/%Y", "%Y%m%d", "%d%b%Y"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(raw, format).ok())
+}
+
+/// Sort by date (last bar wins on duplicates), then fill change in OI,
+/// returns and rolling stats. A source-provided change in OI is kept only
+/// for the first bar, where there is no previous OI to diff against.
+pub fn normalize_series(meta: SeriesMeta, mut bars: Vec<OhlcBar>, window: usize) -> HistoricalSeries {
+    let window = window.max(2);
+
+    bars.sort_by_key(|b| b.date);
+    bars.reverse();
+    bars.dedup_by_key(|b| b.date);
+    bars.reverse();
+
+    for i in 1..bars.len() {
+        let (previous, current) = (&bars[i - 1], &bars[i]);
+        let change_in_oi = match (current.open_interest, previous.open_interest) {
+            (Some(current), Some(previous)) => Some(current - previous),
+            _ => current.change_in_oi,
+        };
+        let return_pct = (previous.close > 0.0).then(|| (current.close / previous.close - 1.0) * 100.0);
+
+        bars[i].change_in_oi = change_in_oi;
+        bars[i].return_pct = return_pct;
+    }
+
+    for i in 0..bars.len() {
+        let returns: Vec<f64> = bars[i.saturating_sub(window - 1)..=i]
+            .iter()
+            .filter_map(|b| b.return_pct)
+            .collect();
+        if returns.len() == window {
+            bars[i].rolling_mean_return_pct = Some(mean(&returns));
+            bars[i].rolling_volatility_pct = Some(stdev(&returns));
+        }
+    }
+
+    let stats = compute_stats(&bars);
+    HistoricalSeries { meta, rolling_window: window, bars, stats, indicators: IndicatorLines::new() }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// Sample standard deviation
+fn stdev(values: &[f64]) -> f64 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+    let m = mean(values);
+    (values.iter().map(|v| (v - m).powi(2)).sum::<f64>() / (values.len() - 1) as f64).sqrt()
+}
+
+fn compute_stats(bars: &[OhlcBar]) -> SeriesStats {
+    let (Some(first), Some(last)) = (bars.first(), bars.last()) else {
+        return SeriesStats::default();
+    };
+
+    let returns: Vec<f64> = bars.iter().filter_map(|b| b.return_pct).collect();
+    let volumes: Vec<f64> = bars.iter().filter_map(|b| b.volume).collect();
+    let oi_changes: Vec<f64> = bars.iter().skip(1).filter_map(|b| b.change_in_oi).collect();
+
+    let mut peak = f64::MIN;
+    let mut max_drawdown: f64 = 0.0;
+    for bar in bars {
+        peak = peak.max(bar.close);
+        if peak > 0.0 {
+            max_drawdown = max_drawdown.max((peak - bar.close) / peak * 100.0);
+        }
+    }
+
+    let return_stdev = (returns.len() >= 2).then(|| stdev(&returns));
+
+    SeriesStats {
+        bars: bars.len(),
+        first_date: Some(first.date),
+        last_date: Some(last.date),
+        period_return_pct: (first.close > 0.0).then(|| (last.close / first.close - 1.0) * 100.0),
+        mean_return_pct: (!returns.is_empty()).then(|| mean(&returns)),
+        return_stdev_pct: return_stdev,
+        annualized_volatility_pct: return_stdev.map(|s| s * TRADING_DAYS_PER_YEAR.sqrt()),
+        highest_high: bars.iter().map(|b| b.high).reduce(f64::max),
+        lowest_low: bars.iter().map(|b| b.low).reduce(f64::min),
+        max_drawdown_pct: Some(max_drawdown),
+        total_volume: (!volumes.is_empty()).then(|| volumes.iter().sum()),
+        average_volume: (!volumes.is_empty()).then(|| mean(&volumes)),
+        last_open_interest: bars.iter().rev().find_map(|b| b.open_interest),
+        net_change_in_oi: (!oi_changes.is_empty()).then(|| oi_changes.iter().sum()),
+        realized_volatility: realized_volatility(bars),
+    }
+}
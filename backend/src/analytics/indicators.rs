@@ -0,0 +1,259 @@
+use super::historical::{HistoricalSeries, OhlcBar};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// Periods used when the request gives none, e.g. `indicators=rsi,bb`
+const DEFAULT_MA_PERIOD: usize = 20;
+const DEFAULT_RSI_PERIOD: usize = 14;
+const DEFAULT_ATR_PERIOD: usize = 14;
+const DEFAULT_BOLLINGER_WIDTH: f64 = 2.0;
+const DEFAULT_OI_PERIOD: usize = 5;
+
+/// One requested indicator, parsed from `indicators=sma:20,ema:9,rsi,bb:20:2,vwap,oi_sma:5`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IndicatorSpec {
+    Sma { period: usize },
+    Ema { period: usize },
+    Rsi { period: usize },
+    Atr { period: usize },
+    Bollinger { period: usize, width: f64 },
+    Vwap,
+    OiSma { period: usize },
+    OiEma { period: usize },
+}
+
+impl IndicatorSpec {
+    /// Parse one `name[:param[:param]]` entry
+    pub fn parse(raw: &str) -> Result<Self> {
+        let mut parts = raw.trim().split(':');
+        let name = parts.next().unwrap_or_default().trim().to_lowercase();
+        let params: Vec<&str> = parts.map(str::trim).collect();
+
+        let period = |index: usize, default: usize| -> Result<usize> {
+            match params.get(index) {
+                None => Ok(default),
+                Some(p) => match p.parse::<usize>() {
+                    Ok(n) if n > 0 => Ok(n),
+                    _ => Err(anyhow!("Invalid period '{}' for indicator '{}'", p, name)),
+                },
+            }
+        };
+
+        let spec = match name.as_str() {
+            "sma" => IndicatorSpec::Sma { period: period(0, DEFAULT_MA_PERIOD)? },
+            "ema" => IndicatorSpec::Ema { period: period(0, DEFAULT_MA_PERIOD)? },
+            "rsi" => IndicatorSpec::Rsi { period: period(0, DEFAULT_RSI_PERIOD)? },
+            "atr" => IndicatorSpec::Atr { period: period(0, DEFAULT_ATR_PERIOD)? },
+            "bb" | "bollinger" => {
+                let width = match params.get(1) {
+                    None => DEFAULT_BOLLINGER_WIDTH,
+                    Some(w) => w
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|w| *w > 0.0)
+                        .ok_or_else(|| anyhow!("Invalid band width '{}' for indicator '{}'", w, name))?,
+                };
+                IndicatorSpec::Bollinger { period: period(0, DEFAULT_MA_PERIOD)?, width }
+            }
+            "vwap" => IndicatorSpec::Vwap,
+            "oi_sma" | "oi_ma" => IndicatorSpec::OiSma { period: period(0, DEFAULT_OI_PERIOD)? },
+            "oi_ema" => IndicatorSpec::OiEma { period: period(0, DEFAULT_OI_PERIOD)? },
+            "" => return Err(anyhow!("Empty indicator name")),
+            other => return Err(anyhow!(
+                "Unknown indicator '{}' (expected sma, ema, rsi, atr, bb, vwap, oi_sma, oi_ema)", other
+            )),
+        };
+        Ok(spec)
+    }
+
+    /// Parse the comma-separated `indicators=` query value
+    pub fn parse_list(raw: &str) -> Result<Vec<Self>> {
+        raw.split(',')
+            .filter(|part| !part.trim().is_empty())
+            .map(Self::parse)
+            .collect()
+    }
+}
+
+/// One value per bar; None where the indicator is not yet defined
+pub type IndicatorLine = Vec<Option<f64>>;
+
+/// Indicator lines keyed by name ("sma_20", "bb_20_2_upper", ...),
+/// each aligned index-for-index with the series bars
+pub type IndicatorLines = BTreeMap<String, IndicatorLine>;
+
+/// Compute the requested indicators over a normalized series
+pub fn compute_indicators(bars: &[OhlcBar], specs: &[IndicatorSpec]) -> IndicatorLines {
+    let closes: Vec<Option<f64>> = bars.iter().map(|b| Some(b.close)).collect();
+    let open_interest: Vec<Option<f64>> = bars.iter().map(|b| b.open_interest).collect();
+    let mut lines = IndicatorLines::new();
+
+    for spec in specs {
+        match *spec {
+            IndicatorSpec::Sma { period } => {
+                lines.insert(format!("sma_{}", period), sma(&closes, period));
+            }
+            IndicatorSpec::Ema { period } => {
+                lines.insert(format!("ema_{}", period), ema(&closes, period));
+            }
+            IndicatorSpec::Rsi { period } => {
+                lines.insert(format!("rsi_{}", period), rsi(bars, period));
+            }
+            IndicatorSpec::Atr { period } => {
+                lines.insert(format!("atr_{}", period), atr(bars, period));
+            }
+            IndicatorSpec::Bollinger { period, width } => {
+                let (upper, middle, lower) = bollinger(&closes, period, width);
+                let key = format!("bb_{}_{}", period, width);
+                lines.insert(format!("{}_upper", key), upper);
+                lines.insert(format!("{}_middle", key), middle);
+                lines.insert(format!("{}_lower", key), lower);
+            }
+            IndicatorSpec::Vwap => {
+                lines.insert("vwap".to_string(), vwap(bars));
+            }
+            IndicatorSpec::OiSma { period } => {
+                lines.insert(format!("oi_sma_{}", period), sma(&open_interest, period));
+            }
+            IndicatorSpec::OiEma { period } => {
+                lines.insert(format!("oi_ema_{}", period), ema(&open_interest, period));
+            }
+        }
+    }
+
+    lines
+}
+
+/// Attach the requested indicators to a series (replaces any already computed)
+pub fn apply_indicators(series: &mut HistoricalSeries, specs: &[IndicatorSpec]) {
+    series.indicators = compute_indicators(&series.bars, specs);
+}
+
+/// Simple moving average; None until `period` consecutive values exist
+pub fn sma(values: &[Option<f64>], period: usize) -> IndicatorLine {
+    (0..values.len())
+        .map(|i| {
+            if period == 0 || i + 1 < period {
+                return None;
+            }
+            let window: Option<Vec<f64>> = values[i + 1 - period..=i].iter().copied().collect();
+            window.map(|w| w.iter().sum::<f64>() / period as f64)
+        })
+        .collect()
+}
+
+/// Exponential moving average seeded with the SMA of the first `period` values.
+/// A gap in the input restarts the seed.
+pub fn ema(values: &[Option<f64>], period: usize) -> IndicatorLine {
+    let alpha = 2.0 / (period as f64 + 1.0);
+    let seeds = sma(values, period);
+    let mut previous: Option<f64> = None;
+
+    values
+        .iter()
+        .zip(seeds)
+        .map(|(value, seed)| {
+            previous = match (*value, previous) {
+                (Some(v), Some(p)) => Some(p + alpha * (v - p)),
+                (Some(_), None) => seed,
+                (None, _) => None,
+            };
+            previous
+        })
+        .collect()
+}
+
+/// Wilder's RSI over closes
+pub fn rsi(bars: &[OhlcBar], period: usize) -> IndicatorLine {
+    let mut out = vec![None; bars.len()];
+    if period == 0 || bars.len() <= period {
+        return out;
+    }
+
+    let changes: Vec<f64> = bars.windows(2).map(|w| w[1].close - w[0].close).collect();
+    let mut avg_gain = changes[..period].iter().map(|c| c.max(0.0)).sum::<f64>() / period as f64;
+    let mut avg_loss = changes[..period].iter().map(|c| (-c).max(0.0)).sum::<f64>() / period as f64;
+    out[period] = Some(rsi_value(avg_gain, avg_loss));
+
+    for (i, change) in changes.iter().enumerate().skip(period) {
+        avg_gain = (avg_gain * (period - 1) as f64 + change.max(0.0)) / period as f64;
+        avg_loss = (avg_loss * (period - 1) as f64 + (-change).max(0.0)) / period as f64;
+        out[i + 1] = Some(rsi_value(avg_gain, avg_loss));
+    }
+    out
+}
+
+fn rsi_value(avg_gain: f64, avg_loss: f64) -> f64 {
+    if avg_loss == 0.0 {
+        return if avg_gain == 0.0 { 50.0 } else { 100.0 };
+    }
+    100.0 - 100.0 / (1.0 + avg_gain / avg_loss)
+}
+
+/// Wilder's average true range
+pub fn atr(bars: &[OhlcBar], period: usize) -> IndicatorLine {
+    let mut out = vec![None; bars.len()];
+    if period == 0 || bars.len() < period {
+        return out;
+    }
+
+    let true_ranges: Vec<f64> = bars
+        .iter()
+        .enumerate()
+        .map(|(i, bar)| match i.checked_sub(1).map(|p| bars[p].close) {
+            Some(prev_close) => (bar.high - bar.low)
+                .max((bar.high - prev_close).abs())
+                .max((bar.low - prev_close).abs()),
+            None => bar.high - bar.low,
+        })
+        .collect();
+
+    let mut current = true_ranges[..period].iter().sum::<f64>() / period as f64;
+    out[period - 1] = Some(current);
+    for i in period..bars.len() {
+        current = (current * (period - 1) as f64 + true_ranges[i]) / period as f64;
+        out[i] = Some(current);
+    }
+    out
+}
+
+/// Bollinger bands: SMA ± width × population standard deviation
+pub fn bollinger(
+    values: &[Option<f64>],
+    period: usize,
+    width: f64,
+) -> (IndicatorLine, IndicatorLine, IndicatorLine) {
+    let middle = sma(values, period);
+    let mut upper = vec![None; values.len()];
+    let mut lower = vec![None; values.len()];
+
+    for (i, mid) in middle.iter().enumerate() {
+        let Some(mid) = *mid else { continue };
+        let variance = values[i + 1 - period..=i]
+            .iter()
+            .flatten()
+            .map(|v| (v - mid).powi(2))
+            .sum::<f64>()
+            / period as f64;
+        upper[i] = Some(mid + width * variance.sqrt());
+        lower[i] = Some(mid - width * variance.sqrt());
+    }
+    (upper, middle, lower)
+}
+
+/// Cumulative VWAP of the typical price over the series; None until volume trades
+pub fn vwap(bars: &[OhlcBar]) -> IndicatorLine {
+    let mut price_volume = 0.0;
+    let mut total_volume = 0.0;
+
+    bars.iter()
+        .map(|bar| {
+            let volume = bar.volume.unwrap_or(0.0).max(0.0);
+            price_volume += (bar.high + bar.low + bar.close) / 3.0 * volume;
+            total_volume += volume;
+            (total_volume > 0.0).then(|| price_volume / total_volume)
+        })
+        .collect()
+}
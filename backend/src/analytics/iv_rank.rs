@@ -0,0 +1,241 @@
+use super::historical::parse_series_date;
+use crate::contracts::Exchange;
+use crate::settings;
+use crate::utility::{self, FileLock};
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+use utoipa::ToSchema;
+
+pub const DEFAULT_IV_HISTORY_FILE: &str = "iv_history.json";
+const MAX_IV_HISTORY: usize = 400;  // Observations kept per symbol (> 252 trading days)
+
+/// Lookbacks (in observations, i.e. trading days) for IV rank and percentile
+pub const IV_RANK_WINDOWS: [usize; 3] = [30, 90, 252];
+
+// Extreme IV percentile rule: only once enough history has accumulated
+pub const MIN_IV_OBSERVATIONS: usize = 20;
+pub const IV_PERCENTILE_HIGH: f64 = 90.0;
+pub const IV_PERCENTILE_LOW: f64 = 10.0;
+
+/// One day's ATM implied volatility (annualized %)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct IvObservation {
+    pub date: NaiveDate,
+    pub atm_iv: f64,
+}
+
+/// Current ATM IV against its own history, flat so batch tables can sort on any column
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct IvRankStats {
+    pub atm_iv: f64,
+    pub observations: usize,
+    pub iv_rank_30: Option<f64>,
+    pub iv_percentile_30: Option<f64>,
+    pub iv_rank_90: Option<f64>,
+    pub iv_percentile_90: Option<f64>,
+    pub iv_rank_252: Option<f64>,
+    pub iv_percentile_252: Option<f64>,
+}
+
+/// Per-symbol IV rank row for batch responses
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SymbolIvRank {
+    pub symbol: String,
+    #[serde(flatten)]
+    pub stats: IvRankStats,
+}
+
+/// Direction and description for the extreme IV percentile rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct IvExtreme {
+    pub alert_type: &'static str,  // "IV_PERCENTILE_HIGH" or "IV_PERCENTILE_LOW"
+    pub description: String,
+}
+
+impl IvRankStats {
+    /// Longest-lookback percentile outside the [low, high] band, once history is long enough
+    pub fn extreme(&self) -> Option<IvExtreme> {
+        if self.observations < MIN_IV_OBSERVATIONS {
+            return None;
+        }
+        let percentile = self.iv_percentile_252?;
+        let lookback = self.observations.min(252);
+
+        let alert_type = if percentile >= IV_PERCENTILE_HIGH {
+            "IV_PERCENTILE_HIGH"
+        } else if percentile <= IV_PERCENTILE_LOW {
+            "IV_PERCENTILE_LOW"
+        } else {
+            return None;
+        };
+
+        Some(IvExtreme {
+            alert_type,
+            description: format!(
+                "ATM IV {:.2}% is at the {:.0}th percentile of the last {} days (rank {:.0})",
+                self.atm_iv, percentile, lookback, self.iv_rank_252.unwrap_or(percentile)
+            ),
+        })
+    }
+}
+
+/// IV rank and percentile of the latest observation over the last `window` observations.
+/// Rank = position between the window low and high; percentile = share of earlier days below.
+pub fn iv_rank(history: &[IvObservation], window: usize) -> (Option<f64>, Option<f64>) {
+    let Some(current) = history.last() else {
+        return (None, None);
+    };
+    let window = &history[history.len().saturating_sub(window)..];
+    let previous = &window[..window.len() - 1];
+    if previous.is_empty() {
+        return (None, None);
+    }
+
+    let low = window.iter().map(|o| o.atm_iv).fold(f64::INFINITY, f64::min);
+    let high = window.iter().map(|o| o.atm_iv).fold(f64::NEG_INFINITY, f64::max);
+    let rank = (high > low).then(|| (current.atm_iv - low) / (high - low) * 100.0);
+
+    let below = previous.iter().filter(|o| o.atm_iv < current.atm_iv).count();
+    let percentile = below as f64 / previous.len() as f64 * 100.0;
+
+    (rank, Some(percentile))
+}
+
+/// Rank/percentile over the standard 30/90/252-day lookbacks
+pub fn compute_iv_rank(history: &[IvObservation]) -> Option<IvRankStats> {
+    let current = history.last()?;
+    let [(iv_rank_30, iv_percentile_30), (iv_rank_90, iv_percentile_90), (iv_rank_252, iv_percentile_252)] =
+        IV_RANK_WINDOWS.map(|window| iv_rank(history, window));
+
+    Some(IvRankStats {
+        atm_iv: current.atm_iv,
+        observations: history.len(),
+        iv_rank_30,
+        iv_percentile_30,
+        iv_rank_90,
+        iv_percentile_90,
+        iv_rank_252,
+        iv_percentile_252,
+    })
+}
+
+/// Trading date of a chain timestamp ("18-Dec-2025 15:30:00"), today if unparseable
+pub fn observation_date(timestamp: &str) -> NaiveDate {
+    parse_series_date(timestamp).unwrap_or_else(|| Local::now().date_naive())
+}
+
+/// Daily ATM IV per symbol, persisted as JSON (one file for both exchanges)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IvHistoryStore {
+    #[serde(default)]
+    pub symbols: BTreeMap<String, Vec<IvObservation>>,
+}
+
+impl IvHistoryStore {
+    /// Store location from the iv_history_file setting (IV_HISTORY_FILE)
+    pub fn path() -> PathBuf {
+        PathBuf::from(&settings::current().iv_history_file)
+    }
+
+    /// Store key, e.g. "NSE:NIFTY"
+    pub fn key(exchange: Exchange, symbol: &str) -> String {
+        let exchange = match exchange {
+            Exchange::Nse => "NSE",
+            Exchange::Mcx => "MCX",
+        };
+        format!("{}:{}", exchange, symbol.trim().to_uppercase())
+    }
+
+    /// Load the store, starting empty if the file does not exist yet
+    pub fn load_file(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&text).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Write via a temp file so a crash never leaves a truncated store
+    pub fn save_file(&self, path: &Path) -> Result<()> {
+        utility::write_atomic(path, serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn history(&self, exchange: Exchange, symbol: &str) -> &[IvObservation] {
+        self.symbols
+            .get(&Self::key(exchange, symbol))
+            .map(|h| h.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Record a day's ATM IV (the latest value for a date wins) and return the updated ranks
+    pub fn record(&mut self, exchange: Exchange, symbol: &str, date: NaiveDate, atm_iv: f64) -> Option<IvRankStats> {
+        if !atm_iv.is_finite() || atm_iv <= 0.0 {
+            return None;
+        }
+
+        let history = self.symbols.entry(Self::key(exchange, symbol)).or_default();
+        match history.binary_search_by_key(&date, |o| o.date) {
+            Ok(index) => history[index].atm_iv = atm_iv,
+            Err(index) => history.insert(index, IvObservation { date, atm_iv }),
+        }
+        if history.len() > MAX_IV_HISTORY {
+            history.drain(..history.len() - MAX_IV_HISTORY);
+        }
+
+        // Ranks are for the recorded date, even when back-filling an older day
+        let upto = history.partition_point(|o| o.date <= date);
+        compute_iv_rank(&history[..upto])
+    }
+
+    /// Ranks an ATM IV would get if recorded, leaving the store untouched
+    pub fn rank(&self, exchange: Exchange, symbol: &str, date: NaiveDate, atm_iv: f64) -> Option<IvRankStats> {
+        let mut scratch = Self::default();
+        scratch.symbols.insert(Self::key(exchange, symbol), self.history(exchange, symbol).to_vec());
+        scratch.record(exchange, symbol, date, atm_iv)
+    }
+}
+
+/// Record a batch of (symbol, date, ATM IV) with a single locked load/save and return ranks per symbol
+pub fn record_atm_ivs(
+    path: &Path,
+    exchange: Exchange,
+    entries: &[(String, NaiveDate, f64)],
+) -> Result<HashMap<String, IvRankStats>> {
+    if entries.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let _lock = FileLock::exclusive(path)?;
+    let mut store = IvHistoryStore::load_file(path)?;
+    let ranks = entries
+        .iter()
+        .filter_map(|(symbol, date, atm_iv)| {
+            store.record(exchange, symbol, *date, *atm_iv).map(|stats| (symbol.clone(), stats))
+        })
+        .collect();
+
+    store.save_file(path)?;
+    Ok(ranks)
+}
+
+/// `record_atm_ivs` against the configured store on the blocking pool. A history that
+/// cannot be read or saved is left untouched and the run gets no ranks.
+pub async fn record_daily_atm_ivs(
+    exchange: Exchange,
+    entries: Vec<(String, NaiveDate, f64)>,
+) -> HashMap<String, IvRankStats> {
+    let path = IvHistoryStore::path();
+    let recorded = tokio::task::spawn_blocking(move || record_atm_ivs(&path, exchange, &entries)).await;
+    match recorded.map_err(anyhow::Error::from).and_then(|result| result) {
+        Ok(ranks) => ranks,
+        Err(e) => {
+            warn!("Failed to update IV history: {}", e);
+            HashMap::new()
+        }
+    }
+}
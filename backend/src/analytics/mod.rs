@@ -0,0 +1,27 @@
+pub mod black_scholes;
+pub mod buildup;
+pub mod expected_move;
+pub mod gamma_exposure;
+pub mod historical;
+pub mod indicators;
+pub mod iv_rank;
+pub mod realized_volatility;
+pub mod strategy;
+pub mod volatility;
+
+pub use black_scholes::{years_to_expiry, Greeks, OptionKind, PricingModel};
+pub use buildup::{classify_buildup, Buildup, BuildupCounts, SymbolBuildup};
+pub use expected_move::{compute_expected_move, strike_probabilities, ExpectedMove, PriceBand, StrikeProbabilities};
+pub use gamma_exposure::{compute_gamma_exposure, ExposureQuote, GammaExposure, GammaRegime};
+pub use historical::{
+    normalize_series, parse_series_date, HistoricalSeries, OhlcBar, SeriesMeta, SeriesStats,
+    DEFAULT_ROLLING_WINDOW,
+};
+pub use indicators::{apply_indicators, compute_indicators, IndicatorLine, IndicatorLines, IndicatorSpec};
+pub use iv_rank::{
+    compute_iv_rank, iv_rank, observation_date, record_atm_ivs, record_daily_atm_ivs, IvExtreme, IvHistoryStore,
+    IvObservation, IvRankStats, SymbolIvRank,
+};
+pub use realized_volatility::{realized_volatility, RealizedVolatility};
+pub use strategy::{analyze_strategy, Side, StrategyAnalysis, StrategyLegRequest};
+pub use volatility::{build_expiry_smile, build_volatility_surface, SmileQuote, VolatilitySurface};
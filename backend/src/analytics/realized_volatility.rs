@@ -0,0 +1,58 @@
+use super::historical::{OhlcBar, TRADING_DAYS_PER_YEAR};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Annualized realized volatility (percent) from daily bars
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct RealizedVolatility {
+    pub bars: usize,
+    pub close_to_close_pct: Option<f64>,  // Stdev of log close-to-close returns
+    pub parkinson_pct: Option<f64>,       // High-low range estimator
+    pub garman_klass_pct: Option<f64>,    // Range plus open-to-close estimator
+}
+
+/// Close-to-close, Parkinson and Garman-Klass volatility over the whole series.
+/// Bars without a usable range (e.g. O/H/L defaulted to close) still count
+/// towards close-to-close but contribute zero range.
+pub fn realized_volatility(bars: &[OhlcBar]) -> RealizedVolatility {
+    let annualize = |daily_variance: f64| (daily_variance * TRADING_DAYS_PER_YEAR).sqrt() * 100.0;
+
+    let log_returns: Vec<f64> = bars
+        .windows(2)
+        .filter(|w| w[0].close > 0.0 && w[1].close > 0.0)
+        .map(|w| (w[1].close / w[0].close).ln())
+        .collect();
+
+    let close_to_close_pct = (log_returns.len() >= 2).then(|| {
+        let mean = log_returns.iter().sum::<f64>() / log_returns.len() as f64;
+        let variance = log_returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>()
+            / (log_returns.len() - 1) as f64;
+        annualize(variance)
+    });
+
+    let ranged: Vec<(f64, f64)> = bars
+        .iter()
+        .filter(|b| b.open > 0.0 && b.high > 0.0 && b.low > 0.0 && b.close > 0.0 && b.high >= b.low)
+        .map(|b| ((b.high / b.low).ln(), (b.close / b.open).ln()))
+        .collect();
+
+    let (parkinson_pct, garman_klass_pct) = if ranged.is_empty() {
+        (None, None)
+    } else {
+        let n = ranged.len() as f64;
+        let parkinson = ranged.iter().map(|(hl, _)| hl.powi(2)).sum::<f64>() / (4.0 * 2f64.ln() * n);
+        let garman_klass = ranged
+            .iter()
+            .map(|(hl, co)| 0.5 * hl.powi(2) - (2.0 * 2f64.ln() - 1.0) * co.powi(2))
+            .sum::<f64>()
+            / n;
+        (Some(annualize(parkinson)), (garman_klass >= 0.0).then(|| annualize(garman_klass)))
+    };
+
+    RealizedVolatility {
+        bars: bars.len(),
+        close_to_close_pct,
+        parkinson_pct,
+        garman_klass_pct,
+    }
+}
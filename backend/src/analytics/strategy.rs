@@ -0,0 +1,272 @@
+use super::black_scholes::{years_to_expiry, Greeks, OptionKind, PricingModel};
+use super::volatility::SmileQuote;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Payoff curve spans the strikes/underlying plus this margin on each side
+const PAYOFF_RANGE_PCT: f64 = 0.10;
+const PAYOFF_CURVE_POINTS: usize = 101;
+// Strike match tolerance when looking up market prices
+const STRIKE_EPSILON: f64 = 1e-6;
+
+/// Buy or sell
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Side {
+    #[serde(alias = "buy", alias = "Buy")]
+    Buy,
+    #[serde(alias = "sell", alias = "Sell")]
+    Sell,
+}
+
+impl Side {
+    pub fn sign(&self) -> f64 {
+        match self {
+            Side::Buy => 1.0,
+            Side::Sell => -1.0,
+        }
+    }
+}
+
+fn default_lots() -> u32 {
+    1
+}
+
+/// One leg as submitted by the client
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StrategyLegRequest {
+    pub strike: f64,
+    pub option_type: OptionKind,
+    pub side: Side,
+    #[serde(default = "default_lots")]
+    pub lots: u32,
+    pub entry_price: Option<f64>,  // Defaults to current last price
+}
+
+/// Resolved leg with position-level greeks
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StrategyLeg {
+    pub strike: f64,
+    pub option_type: OptionKind,
+    pub side: Side,
+    pub lots: u32,
+    pub quantity: f64,
+    pub entry_price: f64,
+    pub current_price: Option<f64>,
+    pub iv: Option<f64>,  // Annualized percentage
+    pub greeks: Greeks,   // Signed and scaled by quantity
+}
+
+impl StrategyLeg {
+    fn signed_quantity(&self) -> f64 {
+        self.side.sign() * self.quantity
+    }
+
+    /// P&L at expiry for a given underlying price
+    fn expiry_pnl(&self, underlying: f64) -> f64 {
+        self.signed_quantity() * (self.option_type.intrinsic(underlying, self.strike) - self.entry_price)
+    }
+
+    /// Theoretical P&L today for a given underlying price
+    fn theoretical_pnl(&self, model: &PricingModel, underlying: f64, t: f64) -> f64 {
+        let value = match self.iv {
+            Some(iv) => model.price(self.option_type, underlying, self.strike, t, iv / 100.0),
+            None => self.option_type.intrinsic(underlying, self.strike),
+        };
+        self.signed_quantity() * (value - self.entry_price)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PayoffPoint {
+    pub underlying: f64,
+    pub expiry_pnl: f64,
+    pub current_pnl: f64,
+}
+
+/// Strategy payoff, risk and greeks
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StrategyAnalysis {
+    pub underlying_value: f64,
+    pub days_to_expiry: i32,
+    pub lot_size: f64,
+    pub net_premium: f64,          // Positive = net credit received
+    pub mark_to_market: f64,       // P&L at current prices
+    pub max_profit: Option<f64>,   // None = unlimited
+    pub max_loss: Option<f64>,     // None = unlimited (reported as a negative P&L)
+    pub breakevens: Vec<f64>,
+    pub net_greeks: Greeks,
+    pub legs: Vec<StrategyLeg>,
+    pub payoff: Vec<PayoffPoint>,
+}
+
+/// Price a multi-leg strategy against the current chain.
+/// `quotes` supplies last prices per strike (used for default entry prices and IV).
+pub fn analyze_strategy(
+    model: &PricingModel,
+    requests: &[StrategyLegRequest],
+    quotes: &[SmileQuote],
+    underlying_value: f64,
+    days_to_expiry: i32,
+    lot_size: f64,
+) -> Result<StrategyAnalysis> {
+    if requests.is_empty() {
+        return Err(anyhow!("Strategy must have at least one leg"));
+    }
+    if underlying_value <= 0.0 {
+        return Err(anyhow!("Underlying value not available"));
+    }
+    if lot_size <= 0.0 {
+        return Err(anyhow!("Lot size must be positive"));
+    }
+
+    let t = years_to_expiry(days_to_expiry);
+
+    let legs = requests
+        .iter()
+        .map(|req| resolve_leg(model, req, quotes, underlying_value, t, lot_size))
+        .collect::<Result<Vec<_>>>()?;
+
+    let net_premium = legs
+        .iter()
+        .map(|leg| -leg.signed_quantity() * leg.entry_price)
+        .sum();
+
+    let mark_to_market = legs
+        .iter()
+        .filter_map(|leg| Some(leg.signed_quantity() * (leg.current_price? - leg.entry_price)))
+        .sum();
+
+    let net_greeks = legs
+        .iter()
+        .fold(Greeks::default(), |acc, leg| acc + leg.greeks);
+
+    let (max_profit, max_loss, breakevens) = expiry_profile(&legs);
+
+    // Payoff grid around strikes and underlying
+    let low_anchor = legs.iter().map(|l| l.strike).fold(underlying_value, f64::min);
+    let high_anchor = legs.iter().map(|l| l.strike).fold(underlying_value, f64::max);
+    let low = low_anchor * (1.0 - PAYOFF_RANGE_PCT);
+    let high = high_anchor * (1.0 + PAYOFF_RANGE_PCT);
+    let step = (high - low) / (PAYOFF_CURVE_POINTS - 1) as f64;
+
+    let payoff = (0..PAYOFF_CURVE_POINTS)
+        .map(|i| {
+            let underlying = low + step * i as f64;
+            PayoffPoint {
+                underlying,
+                expiry_pnl: legs.iter().map(|l| l.expiry_pnl(underlying)).sum(),
+                current_pnl: legs.iter().map(|l| l.theoretical_pnl(model, underlying, t)).sum(),
+            }
+        })
+        .collect();
+
+    Ok(StrategyAnalysis {
+        underlying_value,
+        days_to_expiry,
+        lot_size,
+        net_premium,
+        mark_to_market,
+        max_profit,
+        max_loss,
+        breakevens,
+        net_greeks,
+        legs,
+        payoff,
+    })
+}
+
+fn resolve_leg(
+    model: &PricingModel,
+    req: &StrategyLegRequest,
+    quotes: &[SmileQuote],
+    underlying_value: f64,
+    t: f64,
+    lot_size: f64,
+) -> Result<StrategyLeg> {
+    if req.strike <= 0.0 || req.lots == 0 || req.entry_price.is_some_and(|p| p <= 0.0) {
+        return Err(anyhow!("Invalid leg: strike, lots and entry_price must be positive"));
+    }
+
+    let current_price = quotes
+        .iter()
+        .find(|q| (q.strike - req.strike).abs() < STRIKE_EPSILON)
+        .and_then(|q| match req.option_type {
+            OptionKind::Call => q.call_price,
+            OptionKind::Put => q.put_price,
+        })
+        .filter(|p| *p > 0.0);
+
+    let entry_price = req.entry_price.or(current_price).ok_or_else(|| {
+        anyhow!(
+            "No last price for {} {}; provide entry_price",
+            req.strike,
+            req.option_type.code()
+        )
+    })?;
+
+    // Prefer the live price for IV, fall back to the entry price
+    let iv = current_price
+        .or(Some(entry_price))
+        .and_then(|p| model.implied_volatility(req.option_type, p, underlying_value, req.strike, t));
+
+    let quantity = req.lots as f64 * lot_size;
+    let greeks = iv
+        .map(|v| model.greeks(req.option_type, underlying_value, req.strike, t, v))
+        .unwrap_or_default()
+        * (req.side.sign() * quantity);
+
+    Ok(StrategyLeg {
+        strike: req.strike,
+        option_type: req.option_type,
+        side: req.side,
+        lots: req.lots,
+        quantity,
+        entry_price,
+        current_price,
+        iv: iv.map(|v| v * 100.0),
+        greeks,
+    })
+}
+
+/// Max profit, max loss and breakevens of the piecewise-linear expiry payoff
+fn expiry_profile(legs: &[StrategyLeg]) -> (Option<f64>, Option<f64>, Vec<f64>) {
+    let pnl_at = |s: f64| legs.iter().map(|l| l.expiry_pnl(s)).sum::<f64>();
+
+    // Kinks are at the strikes; the payoff is linear in between
+    let mut kinks: Vec<f64> = std::iter::once(0.0).chain(legs.iter().map(|l| l.strike)).collect();
+    kinks.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    kinks.dedup_by(|a, b| (*a - *b).abs() < STRIKE_EPSILON);
+
+    let values: Vec<f64> = kinks.iter().map(|&s| pnl_at(s)).collect();
+
+    // Slope beyond the highest strike comes from calls only
+    let upper_slope: f64 = legs
+        .iter()
+        .filter(|l| l.option_type == OptionKind::Call)
+        .map(|l| l.signed_quantity())
+        .sum();
+
+    let bounded_max = values.iter().cloned().fold(f64::MIN, f64::max);
+    let bounded_min = values.iter().cloned().fold(f64::MAX, f64::min);
+    let max_profit = if upper_slope > STRIKE_EPSILON { None } else { Some(bounded_max) };
+    let max_loss = if upper_slope < -STRIKE_EPSILON { None } else { Some(bounded_min) };
+
+    let mut breakevens = Vec::new();
+    for i in 0..kinks.len() - 1 {
+        let (x0, x1, y0, y1) = (kinks[i], kinks[i + 1], values[i], values[i + 1]);
+        if y0 * y1 < 0.0 {
+            breakevens.push(x0 + (x1 - x0) * (-y0) / (y1 - y0));
+        } else if y1 == 0.0 && y0 != 0.0 {
+            breakevens.push(x1);
+        }
+    }
+
+    let (last_x, last_y) = (kinks[kinks.len() - 1], values[values.len() - 1]);
+    if upper_slope.abs() > STRIKE_EPSILON && last_y * upper_slope < 0.0 {
+        breakevens.push(last_x - last_y / upper_slope);
+    }
+
+    (max_profit, max_loss, breakevens)
+}
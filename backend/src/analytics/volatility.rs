@@ -0,0 +1,309 @@
+use super::black_scholes::{years_to_expiry, OptionKind, PricingModel};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Number of points on the fitted smile curve
+const FITTED_CURVE_POINTS: usize = 41;
+// Minimum strikes with a valid IV before fitting a curve
+const MIN_POINTS_FOR_FIT: usize = 3;
+// Reference delta for risk reversal / butterfly
+const WING_DELTA: f64 = 0.25;
+
+/// Market prices at a single strike (input for IV computation)
+#[derive(Debug, Clone)]
+pub struct SmileQuote {
+    pub strike: f64,
+    pub call_price: Option<f64>,
+    pub put_price: Option<f64>,
+}
+
+/// IV at a single strike (all IVs are annualized percentages)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SmilePoint {
+    pub strike: f64,
+    pub log_moneyness: f64,  // ln(strike / underlying)
+    pub call_iv: Option<f64>,
+    pub put_iv: Option<f64>,
+    pub iv: Option<f64>,     // OTM side preferred
+    pub call_delta: Option<f64>,
+    pub put_delta: Option<f64>,
+}
+
+/// Quadratic smile fit: iv = a + b*m + c*m^2, with m = ln(K/S)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
+pub struct SmileFit {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub r_squared: f64,
+}
+
+impl SmileFit {
+    pub fn iv_at(&self, log_moneyness: f64) -> f64 {
+        self.a + self.b * log_moneyness + self.c * log_moneyness * log_moneyness
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FittedPoint {
+    pub strike: f64,
+    pub iv: f64,
+}
+
+/// Smile and skew metrics for one expiry
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ExpirySmile {
+    pub expiry: String,
+    pub days_to_expiry: i32,
+    pub underlying_value: f64,
+    pub atm_strike: f64,
+    pub atm_iv: Option<f64>,
+    pub call_25d_iv: Option<f64>,
+    pub put_25d_iv: Option<f64>,
+    pub risk_reversal_25d: Option<f64>,  // 25D call IV - 25D put IV
+    pub butterfly_25d: Option<f64>,      // Wing average - ATM IV
+    pub skew_slope: Option<f64>,         // d(IV)/d(log-moneyness) at the money
+    pub fit: Option<SmileFit>,
+    pub points: Vec<SmilePoint>,
+    pub fitted_curve: Vec<FittedPoint>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TermStructurePoint {
+    pub expiry: String,
+    pub days_to_expiry: i32,
+    pub atm_iv: Option<f64>,
+}
+
+/// Full volatility surface for a symbol
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct VolatilitySurface {
+    pub symbol: String,
+    pub underlying_value: f64,
+    pub term_structure: Vec<TermStructurePoint>,
+    pub smiles: Vec<ExpirySmile>,
+}
+
+/// Compute the IV at a strike from its market price (as a percentage)
+pub fn implied_vol_pct(
+    model: &PricingModel,
+    kind: OptionKind,
+    price: Option<f64>,
+    spot: f64,
+    strike: f64,
+    days_to_expiry: i32,
+) -> Option<f64> {
+    let price = price.filter(|p| *p > 0.0)?;
+    model
+        .implied_volatility(kind, price, spot, strike, years_to_expiry(days_to_expiry))
+        .map(|iv| iv * 100.0)
+}
+
+/// Build the smile for one expiry from per-strike prices
+pub fn build_expiry_smile(
+    model: &PricingModel,
+    expiry: &str,
+    days_to_expiry: i32,
+    underlying_value: f64,
+    quotes: &[SmileQuote],
+) -> ExpirySmile {
+    let t = years_to_expiry(days_to_expiry);
+
+    let mut points: Vec<SmilePoint> = quotes
+        .iter()
+        .filter(|q| q.strike > 0.0)
+        .map(|q| {
+            let call_iv = implied_vol_pct(model, OptionKind::Call, q.call_price, underlying_value, q.strike, days_to_expiry);
+            let put_iv = implied_vol_pct(model, OptionKind::Put, q.put_price, underlying_value, q.strike, days_to_expiry);
+
+            // OTM options carry the cleanest volatility information
+            let iv = if q.strike >= underlying_value {
+                call_iv.or(put_iv)
+            } else {
+                put_iv.or(call_iv)
+            };
+
+            let call_delta = call_iv.map(|v| {
+                model.greeks(OptionKind::Call, underlying_value, q.strike, t, v / 100.0).delta
+            });
+            let put_delta = put_iv.map(|v| {
+                model.greeks(OptionKind::Put, underlying_value, q.strike, t, v / 100.0).delta
+            });
+
+            SmilePoint {
+                strike: q.strike,
+                log_moneyness: (q.strike / underlying_value).ln(),
+                call_iv,
+                put_iv,
+                iv,
+                call_delta,
+                put_delta,
+            }
+        })
+        .collect();
+
+    points.sort_by(|a, b| a.strike.partial_cmp(&b.strike).unwrap_or(std::cmp::Ordering::Equal));
+
+    // ATM: strike closest to underlying, average of available call/put IV
+    let atm_point = points.iter().min_by(|a, b| {
+        (a.strike - underlying_value)
+            .abs()
+            .partial_cmp(&(b.strike - underlying_value).abs())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let atm_strike = atm_point.map(|p| p.strike).unwrap_or(0.0);
+    let atm_iv = atm_point.and_then(|p| match (p.call_iv, p.put_iv) {
+        (Some(c), Some(p)) => Some((c + p) / 2.0),
+        (c, p) => c.or(p),
+    });
+
+    // 25-delta wings
+    let call_wing: Vec<(f64, f64)> = points
+        .iter()
+        .filter_map(|p| Some((p.call_delta?, p.call_iv?)))
+        .collect();
+    let put_wing: Vec<(f64, f64)> = points
+        .iter()
+        .filter_map(|p| Some((p.put_delta?, p.put_iv?)))
+        .collect();
+    let call_25d_iv = interpolate_by_delta(&call_wing, WING_DELTA);
+    let put_25d_iv = interpolate_by_delta(&put_wing, -WING_DELTA);
+
+    let risk_reversal_25d = match (call_25d_iv, put_25d_iv) {
+        (Some(c), Some(p)) => Some(c - p),
+        _ => None,
+    };
+    let butterfly_25d = match (call_25d_iv, put_25d_iv, atm_iv) {
+        (Some(c), Some(p), Some(atm)) => Some((c + p) / 2.0 - atm),
+        _ => None,
+    };
+
+    // Smooth curve through the OTM IVs
+    let samples: Vec<(f64, f64)> = points
+        .iter()
+        .filter_map(|p| Some((p.log_moneyness, p.iv?)))
+        .collect();
+    let fit = fit_quadratic(&samples);
+
+    let fitted_curve = match (fit, points.first(), points.last()) {
+        (Some(fit), Some(first), Some(last)) if last.strike > first.strike => {
+            let step = (last.strike - first.strike) / (FITTED_CURVE_POINTS - 1) as f64;
+            (0..FITTED_CURVE_POINTS)
+                .map(|i| {
+                    let strike = first.strike + step * i as f64;
+                    FittedPoint {
+                        strike,
+                        iv: fit.iv_at((strike / underlying_value).ln()),
+                    }
+                })
+                .collect()
+        }
+        _ => Vec::new(),
+    };
+
+    ExpirySmile {
+        expiry: expiry.to_string(),
+        days_to_expiry,
+        underlying_value,
+        atm_strike,
+        atm_iv,
+        call_25d_iv,
+        put_25d_iv,
+        risk_reversal_25d,
+        butterfly_25d,
+        skew_slope: fit.map(|f| f.b),
+        fit,
+        points,
+        fitted_curve,
+    }
+}
+
+/// Assemble the surface and ATM term structure from per-expiry smiles
+pub fn build_volatility_surface(
+    symbol: &str,
+    underlying_value: f64,
+    mut smiles: Vec<ExpirySmile>,
+) -> VolatilitySurface {
+    smiles.sort_by_key(|s| s.days_to_expiry);
+
+    let term_structure = smiles
+        .iter()
+        .map(|s| TermStructurePoint {
+            expiry: s.expiry.clone(),
+            days_to_expiry: s.days_to_expiry,
+            atm_iv: s.atm_iv,
+        })
+        .collect();
+
+    VolatilitySurface {
+        symbol: symbol.to_string(),
+        underlying_value,
+        term_structure,
+        smiles,
+    }
+}
+
+/// Linearly interpolate IV at a target delta from (delta, iv) pairs
+fn interpolate_by_delta(pairs: &[(f64, f64)], target: f64) -> Option<f64> {
+    let mut sorted: Vec<(f64, f64)> = pairs.to_vec();
+    sorted.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    sorted.windows(2).find_map(|w| {
+        let ((d0, v0), (d1, v1)) = (w[0], w[1]);
+        if target >= d0 && target <= d1 && d1 > d0 {
+            Some(v0 + (v1 - v0) * (target - d0) / (d1 - d0))
+        } else {
+            None
+        }
+    })
+}
+
+/// Least-squares quadratic fit of (x, y) samples
+fn fit_quadratic(samples: &[(f64, f64)]) -> Option<SmileFit> {
+    if samples.len() < MIN_POINTS_FOR_FIT {
+        return None;
+    }
+
+    // Normal equations for y = a + b*x + c*x^2
+    let (mut s0, mut s1, mut s2, mut s3, mut s4) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    let (mut t0, mut t1, mut t2) = (0.0, 0.0, 0.0);
+    for &(x, y) in samples {
+        let x2 = x * x;
+        s0 += 1.0;
+        s1 += x;
+        s2 += x2;
+        s3 += x2 * x;
+        s4 += x2 * x2;
+        t0 += y;
+        t1 += x * y;
+        t2 += x2 * y;
+    }
+
+    let det = |m: [[f64; 3]; 3]| {
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    };
+
+    let m = [[s0, s1, s2], [s1, s2, s3], [s2, s3, s4]];
+    let d = det(m);
+    if d.abs() < 1e-18 {
+        return None;
+    }
+
+    // Cramer's rule
+    let a = det([[t0, s1, s2], [t1, s2, s3], [t2, s3, s4]]) / d;
+    let b = det([[s0, t0, s2], [s1, t1, s3], [s2, t2, s4]]) / d;
+    let c = det([[s0, s1, t0], [s1, s2, t1], [s2, s3, t2]]) / d;
+
+    let mean = t0 / s0;
+    let (mut ss_tot, mut ss_res) = (0.0, 0.0);
+    for &(x, y) in samples {
+        let fitted = a + b * x + c * x * x;
+        ss_tot += (y - mean).powi(2);
+        ss_res += (y - fitted).powi(2);
+    }
+    let r_squared = if ss_tot > 0.0 { 1.0 - ss_res / ss_tot } else { 1.0 };
+
+    Some(SmileFit { a, b, c, r_squared })
+}
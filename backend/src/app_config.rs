@@ -23,14 +23,11 @@ impl AppConfig {
     /// Log configuration details for CI environments
     pub fn log_ci_config(&self) {
         if nse_config::is_ci_environment() {
-            println!("{}", "Running in CI environment (GitHub Actions)".blue().bold());
-            println!("{} Mode: {}", "→".cyan(), self.mode.yellow());
-            println!("{} Exchange: {}", "→".cyan(), self.exchange.yellow());
+            tracing::info!(mode = %self.mode, exchange = %self.exchange, "Running in CI environment (GitHub Actions)");
             
             if self.mode == "server" {
-                println!("{} Server mode not supported in CI - switching to batch", "⚠".yellow());
+                tracing::warn!("Server mode not supported in CI - switching to batch");
             }
-            println!();
         }
     }
 
@@ -0,0 +1,119 @@
+use super::engine::{run_backtest, BacktestConfig, BacktestReport, EntryTiming};
+use super::snapshots;
+use crate::analytics::Side;
+use crate::contracts::Exchange;
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use std::path::PathBuf;
+use tracing::{info, info_span};
+
+pub const BACKTEST_REPORT_FILE: &str = "backtest_report.json";
+
+/// Backtest Command Handler - replays stored snapshots through the rules
+pub struct BacktestCommands;
+
+impl BacktestCommands {
+    /// Run a backtest for one exchange and write the JSON report
+    pub fn run(exchange: &str) -> Result<()> {
+        info_span!("backtest", exchange).in_scope(|| Self::backtest(exchange))
+    }
+
+    fn backtest(exchange: &str) -> Result<()> {
+        let exchange = match exchange {
+            "nse" => Exchange::Nse,
+            "mcx" => Exchange::Mcx,
+            other => return Err(anyhow!("Backtest needs EXCHANGE=nse or EXCHANGE=mcx (got '{}')", other)),
+        };
+
+        info!("Alert Rules Backtest");
+
+        let dir = std::env::var("BACKTEST_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| snapshots::default_snapshot_dir(exchange));
+        let config = Self::config_from_env()?;
+
+        info!("Snapshots: {}", dir.display());
+        info!("Side: {:?}, entry: {:?}, holding: {} snapshot(s)",
+            config.side, config.entry, config.holding_snapshots);
+        if let Some(tp) = config.take_profit_pct {
+            info!("Take profit: {}%", tp);
+        }
+        if let Some(sl) = config.stop_loss_pct {
+            info!("Stop loss: {}%", sl);
+        }
+
+        let loaded = info_span!("load_snapshots").in_scope(|| snapshots::load_snapshots(exchange, &dir))?;
+        if loaded.is_empty() {
+            return Err(anyhow!(
+                "No snapshots found in {} (batch runs archive them when SNAPSHOT_DIR is set; exchange history is not loaded)",
+                dir.display()
+            ));
+        }
+
+        let report = run_backtest(loaded, &config);
+        Self::display_report(&report);
+
+        let output = std::env::var("BACKTEST_OUTPUT").unwrap_or_else(|_| BACKTEST_REPORT_FILE.to_string());
+        std::fs::write(&output, serde_json::to_string_pretty(&report)?)?;
+        info!("✓ Saved report to {}", output);
+
+        Ok(())
+    }
+
+    /// Build the simulation config from BACKTEST_* environment variables
+    fn config_from_env() -> Result<BacktestConfig> {
+        let mut config = BacktestConfig::default();
+
+        if let Ok(side) = std::env::var("BACKTEST_SIDE") {
+            config.side = match side.to_lowercase().as_str() {
+                "buy" => Side::Buy,
+                "sell" => Side::Sell,
+                _ => return Err(anyhow!("BACKTEST_SIDE must be 'buy' or 'sell'")),
+            };
+        }
+        if let Ok(entry) = std::env::var("BACKTEST_ENTRY") {
+            config.entry = match entry.to_lowercase().as_str() {
+                "same" => EntryTiming::SameSnapshot,
+                "next" => EntryTiming::NextSnapshot,
+                _ => return Err(anyhow!("BACKTEST_ENTRY must be 'same' or 'next'")),
+            };
+        }
+        if let Ok(holding) = std::env::var("BACKTEST_HOLDING") {
+            config.holding_snapshots = holding.parse()
+                .map_err(|_| anyhow!("BACKTEST_HOLDING must be a whole number"))?;
+        }
+        if let Ok(tp) = std::env::var("BACKTEST_TAKE_PROFIT_PCT") {
+            config.take_profit_pct = Some(tp.parse().map_err(|_| anyhow!("Invalid BACKTEST_TAKE_PROFIT_PCT"))?);
+        }
+        if let Ok(sl) = std::env::var("BACKTEST_STOP_LOSS_PCT") {
+            config.stop_loss_pct = Some(sl.parse().map_err(|_| anyhow!("Invalid BACKTEST_STOP_LOSS_PCT"))?);
+        }
+        if let Ok(types) = std::env::var("BACKTEST_ALERT_TYPES") {
+            config.alert_types = Some(types.split(',').map(|t| t.trim().to_uppercase()).collect());
+        }
+
+        Ok(config)
+    }
+
+    fn display_report(report: &BacktestReport) {
+        println!("{}", "=".repeat(60).blue());
+        println!("{}", "Backtest Results".cyan().bold());
+        println!("{}", "=".repeat(60).blue());
+        println!("{} Symbols: {}, snapshots: {}", "ℹ".blue(), report.symbols, report.snapshots);
+        println!("{} Alerts: {}, trades: {}, skipped: {}",
+            "ℹ".blue(), report.alerts_seen, report.trades_taken, report.skipped);
+        println!();
+
+        for stats in std::iter::once(&report.overall).chain(report.by_alert_type.iter()) {
+            println!(
+                "  {:<20} trades {:>5} | hit {:>5.1}% | avg {:>7.2}% | max DD {:>7.2}%",
+                stats.alert_type.yellow(),
+                stats.trades,
+                stats.hit_rate,
+                stats.avg_return_pct,
+                stats.max_drawdown_pct,
+            );
+        }
+        println!();
+    }
+}
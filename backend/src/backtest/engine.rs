@@ -0,0 +1,270 @@
+use crate::analytics::Side;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Identifies one option contract across snapshots
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ContractKey {
+    pub expiry: String,
+    pub strike: String,       // Formatted to 2 decimals so it can be hashed
+    pub option_type: String,  // "CE" or "PE"
+}
+
+impl ContractKey {
+    pub fn new(expiry: &str, strike: f64, option_type: &str) -> Self {
+        Self {
+            expiry: expiry.to_string(),
+            strike: format!("{:.2}", strike),
+            option_type: option_type.to_string(),
+        }
+    }
+}
+
+/// An alert fired at a snapshot
+#[derive(Debug, Clone)]
+pub struct SnapshotAlert {
+    pub alert_type: String,
+    pub contract: ContractKey,
+}
+
+/// One stored chain observation for a symbol
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub symbol: String,
+    pub timestamp: NaiveDateTime,
+    pub underlying_value: f64,
+    pub alerts: Vec<SnapshotAlert>,
+    pub prices: HashMap<ContractKey, f64>,
+}
+
+/// When to enter after an alert fires
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryTiming {
+    SameSnapshot,  // At the alert's own last price
+    NextSnapshot,  // At the next observed price (more realistic)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestConfig {
+    pub side: Side,
+    pub entry: EntryTiming,
+    pub holding_snapshots: usize,      // Exit after this many snapshots
+    pub take_profit_pct: Option<f64>,
+    pub stop_loss_pct: Option<f64>,
+    pub alert_types: Option<Vec<String>>,  // Only these alert types (None = all)
+}
+
+impl Default for BacktestConfig {
+    fn default() -> Self {
+        Self {
+            side: Side::Buy,
+            entry: EntryTiming::NextSnapshot,
+            holding_snapshots: 1,
+            take_profit_pct: None,
+            stop_loss_pct: None,
+            alert_types: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ExitReason {
+    TakeProfit,
+    StopLoss,
+    HoldingPeriod,
+    EndOfData,  // Contract disappeared or snapshots ran out before the holding period
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeResult {
+    pub symbol: String,
+    pub alert_type: String,
+    pub contract: ContractKey,
+    pub entry_time: NaiveDateTime,
+    pub exit_time: NaiveDateTime,
+    pub entry_price: f64,
+    pub exit_price: f64,
+    pub return_pct: f64,
+    pub max_adverse_pct: f64,  // Worst open return while held
+    pub exit_reason: ExitReason,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AlertTypeStats {
+    pub alert_type: String,
+    pub trades: usize,
+    pub wins: usize,
+    pub hit_rate: f64,
+    pub avg_return_pct: f64,
+    pub total_return_pct: f64,
+    pub best_return_pct: f64,
+    pub worst_return_pct: f64,
+    pub max_drawdown_pct: f64,  // Peak-to-trough of cumulative return, trades in exit order
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestReport {
+    pub config: BacktestConfig,
+    pub symbols: usize,
+    pub snapshots: usize,
+    pub alerts_seen: usize,
+    pub trades_taken: usize,
+    pub skipped: usize,  // Alerts with no entry or exit price available
+    pub overall: AlertTypeStats,
+    pub by_alert_type: Vec<AlertTypeStats>,
+    pub trades: Vec<TradeResult>,
+}
+
+/// Replay snapshots, simulate a trade per alert and aggregate the results
+pub fn run_backtest(snapshots: Vec<Snapshot>, config: &BacktestConfig) -> BacktestReport {
+    let total_snapshots = snapshots.len();
+
+    // Group by symbol in time order
+    let mut by_symbol: BTreeMap<String, Vec<Snapshot>> = BTreeMap::new();
+    for snapshot in snapshots {
+        by_symbol.entry(snapshot.symbol.clone()).or_default().push(snapshot);
+    }
+
+    let mut trades = Vec::new();
+    let mut alerts_seen = 0;
+    let mut skipped = 0;
+
+    for series in by_symbol.values_mut() {
+        series.sort_by_key(|s| s.timestamp);
+
+        for (idx, snapshot) in series.iter().enumerate() {
+            for alert in &snapshot.alerts {
+                let wanted = config
+                    .alert_types
+                    .as_ref()
+                    .map(|types| types.iter().any(|t| t == &alert.alert_type))
+                    .unwrap_or(true);
+                if !wanted {
+                    continue;
+                }
+
+                alerts_seen += 1;
+                match simulate_trade(series, idx, alert, config) {
+                    Some(trade) => trades.push(trade),
+                    None => skipped += 1,
+                }
+            }
+        }
+    }
+
+    trades.sort_by_key(|t| t.exit_time);
+
+    let mut grouped: BTreeMap<String, Vec<&TradeResult>> = BTreeMap::new();
+    for trade in &trades {
+        grouped.entry(trade.alert_type.clone()).or_default().push(trade);
+    }
+
+    let by_alert_type = grouped
+        .into_iter()
+        .map(|(alert_type, group)| compute_stats(&alert_type, &group))
+        .collect();
+
+    BacktestReport {
+        config: config.clone(),
+        symbols: by_symbol.len(),
+        snapshots: total_snapshots,
+        alerts_seen,
+        trades_taken: trades.len(),
+        skipped,
+        overall: compute_stats("ALL", &trades.iter().collect::<Vec<_>>()),
+        by_alert_type,
+        trades,
+    }
+}
+
+/// Walk forward from the alert snapshot until an exit condition is met
+fn simulate_trade(
+    series: &[Snapshot],
+    alert_idx: usize,
+    alert: &SnapshotAlert,
+    config: &BacktestConfig,
+) -> Option<TradeResult> {
+    let key = &alert.contract;
+    let sign = config.side.sign();
+
+    let entry_idx = match config.entry {
+        EntryTiming::SameSnapshot => alert_idx,
+        EntryTiming::NextSnapshot => alert_idx + 1,
+    };
+    let entry = series.get(entry_idx)?;
+    let entry_price = entry.prices.get(key).copied().filter(|p| *p > 0.0)?;
+
+    let holding = config.holding_snapshots.max(1);
+    let mut exit: Option<(&Snapshot, f64, ExitReason)> = None;
+    let mut max_adverse_pct: f64 = 0.0;
+
+    for (step, snapshot) in series.iter().skip(entry_idx + 1).take(holding).enumerate() {
+        let Some(price) = snapshot.prices.get(key).copied() else {
+            continue;
+        };
+
+        let return_pct = sign * (price - entry_price) / entry_price * 100.0;
+        max_adverse_pct = max_adverse_pct.min(return_pct);
+
+        // Exiting before the final step only happens on TP/SL or missing data
+        let reason = if step + 1 == holding { ExitReason::HoldingPeriod } else { ExitReason::EndOfData };
+        exit = Some((snapshot, price, reason));
+
+        if config.take_profit_pct.is_some_and(|tp| return_pct >= tp) {
+            exit = Some((snapshot, price, ExitReason::TakeProfit));
+            break;
+        }
+        if config.stop_loss_pct.is_some_and(|sl| return_pct <= -sl) {
+            exit = Some((snapshot, price, ExitReason::StopLoss));
+            break;
+        }
+    }
+
+    let (exit_snapshot, exit_price, exit_reason) = exit?;
+
+    Some(TradeResult {
+        symbol: entry.symbol.clone(),
+        alert_type: alert.alert_type.clone(),
+        contract: key.clone(),
+        entry_time: entry.timestamp,
+        exit_time: exit_snapshot.timestamp,
+        entry_price,
+        exit_price,
+        return_pct: sign * (exit_price - entry_price) / entry_price * 100.0,
+        max_adverse_pct,
+        exit_reason,
+    })
+}
+
+fn compute_stats(alert_type: &str, trades: &[&TradeResult]) -> AlertTypeStats {
+    if trades.is_empty() {
+        return AlertTypeStats { alert_type: alert_type.to_string(), ..Default::default() };
+    }
+
+    let returns: Vec<f64> = trades.iter().map(|t| t.return_pct).collect();
+    let wins = returns.iter().filter(|r| **r > 0.0).count();
+    let total: f64 = returns.iter().sum();
+
+    // Drawdown of the cumulative return curve
+    let (mut equity, mut peak, mut max_drawdown) = (0.0_f64, 0.0_f64, 0.0_f64);
+    for r in &returns {
+        equity += r;
+        peak = peak.max(equity);
+        max_drawdown = max_drawdown.max(peak - equity);
+    }
+
+    AlertTypeStats {
+        alert_type: alert_type.to_string(),
+        trades: trades.len(),
+        wins,
+        hit_rate: wins as f64 / trades.len() as f64 * 100.0,
+        avg_return_pct: total / trades.len() as f64,
+        total_return_pct: total,
+        best_return_pct: returns.iter().cloned().fold(f64::MIN, f64::max),
+        worst_return_pct: returns.iter().cloned().fold(f64::MAX, f64::min),
+        max_drawdown_pct: max_drawdown,
+    }
+}
@@ -0,0 +1,10 @@
+pub mod backtest_commands;
+pub mod engine;
+pub mod snapshots;
+
+pub use backtest_commands::BacktestCommands;
+pub use engine::{
+    run_backtest, AlertTypeStats, BacktestConfig, BacktestReport, ContractKey, EntryTiming,
+    ExitReason, Snapshot, SnapshotAlert, TradeResult,
+};
+pub use snapshots::load_snapshots;
@@ -0,0 +1,191 @@
+use super::engine::{ContractKey, Snapshot, SnapshotAlert};
+use crate::contracts::Exchange;
+use crate::mcx::processor::ProcessedMcxOptionData;
+use crate::mcx::rules as mcx_rules;
+use crate::nse::processor::ProcessedOptionData;
+use crate::nse::rules as nse_rules;
+use crate::settings;
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDateTime};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+// Batch runs archive a copy of every record under <snapshot_dir>/<exchange>/<run>/ (SNAPSHOT_DIR)
+const DEFAULT_SNAPSHOT_ROOT: &str = "snapshots";
+const SNAPSHOT_TIMESTAMP_FORMAT: &str = "%d-%b-%Y %H:%M:%S";
+
+/// Stored batch record: `{ "record": {...}, "data": [...] }`
+#[derive(Debug, Deserialize)]
+struct StoredRecord<T> {
+    record: RecordHeader,
+    data: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RecordHeader {
+    symbol: String,
+    timestamp: String,
+    underlying_value: f64,
+    #[serde(default)]
+    spread: f64,
+}
+
+fn exchange_dir(exchange: Exchange) -> &'static str {
+    match exchange {
+        Exchange::Nse => "nse",
+        Exchange::Mcx => "mcx",
+    }
+}
+
+/// Create this run's snapshot directory (None when snapshot_dir is not set)
+pub fn snapshot_run_dir(exchange: Exchange) -> Option<PathBuf> {
+    let root = settings::current().snapshot_dir.trim();
+    if root.is_empty() {
+        return None;
+    }
+    let dir = Path::new(root)
+        .join(exchange_dir(exchange))
+        .join(Local::now().format("%Y%m%d_%H%M%S").to_string());
+
+    match std::fs::create_dir_all(&dir) {
+        Ok(()) => Some(dir),
+        Err(e) => {
+            tracing::warn!("Failed to create snapshot directory {}: {}", dir.display(), e);
+            None
+        }
+    }
+}
+
+/// Default snapshot directory for an exchange
+pub fn default_snapshot_dir(exchange: Exchange) -> PathBuf {
+    let root = settings::current().snapshot_dir.trim();
+    let root = if root.is_empty() { DEFAULT_SNAPSHOT_ROOT } else { root };
+    Path::new(root).join(exchange_dir(exchange))
+}
+
+/// Load every stored record under `dir` (recursively) and replay it through the rules
+pub fn load_snapshots(exchange: Exchange, dir: &Path) -> Result<Vec<Snapshot>> {
+    let mut files = Vec::new();
+    collect_json_files(dir, &mut files)
+        .with_context(|| format!("Failed to read snapshot directory {}", dir.display()))?;
+
+    let mut snapshots = Vec::new();
+    for path in files {
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+
+        // Other JSON files (rules output, reports) are skipped
+        let snapshot = match exchange {
+            Exchange::Nse => serde_json::from_str(&text).ok().and_then(nse_snapshot),
+            Exchange::Mcx => serde_json::from_str(&text).ok().and_then(mcx_snapshot),
+        };
+
+        if let Some(snapshot) = snapshot {
+            snapshots.push(snapshot);
+        }
+    }
+
+    Ok(snapshots)
+}
+
+fn collect_json_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_json_files(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "json") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn parse_timestamp(timestamp: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(timestamp.trim(), SNAPSHOT_TIMESTAMP_FORMAT).ok()
+}
+
+fn nse_snapshot(stored: StoredRecord<ProcessedOptionData>) -> Option<Snapshot> {
+    let header = stored.record;
+    let timestamp = parse_timestamp(&header.timestamp)?;
+
+    let mut prices = HashMap::new();
+    for opt in &stored.data {
+        let expiry = opt.expiry_date.as_deref().unwrap_or("UNKNOWN");
+        let strike = opt.strike_price.unwrap_or(0.0);
+        if let Some(price) = opt.call.as_ref().and_then(|c| c.base.last_price) {
+            prices.insert(ContractKey::new(expiry, strike, "CE"), price);
+        }
+        if let Some(price) = opt.put.as_ref().and_then(|p| p.base.last_price) {
+            prices.insert(ContractKey::new(expiry, strike, "PE"), price);
+        }
+    }
+
+    let alerts = nse_rules::run_rules(
+        &stored.data,
+        header.symbol.clone(),
+        header.timestamp.clone(),
+        header.underlying_value,
+        header.spread,
+    )
+    .map(|output| {
+        output.alerts
+            .into_iter()
+            .map(|a| SnapshotAlert {
+                alert_type: a.alert_type,
+                contract: ContractKey::new(&a.expiry_date, a.strike_price, &a.option_type),
+            })
+            .collect()
+    })
+    .unwrap_or_default();
+
+    Some(Snapshot {
+        symbol: header.symbol,
+        timestamp,
+        underlying_value: header.underlying_value,
+        alerts,
+        prices,
+    })
+}
+
+fn mcx_snapshot(stored: StoredRecord<ProcessedMcxOptionData>) -> Option<Snapshot> {
+    let header = stored.record;
+    let timestamp = parse_timestamp(&header.timestamp)?;
+
+    let mut prices = HashMap::new();
+    for opt in &stored.data {
+        let expiry = opt.expiry_date.as_deref().unwrap_or("UNKNOWN");
+        if let Some(price) = opt.call.as_ref().and_then(|c| c.last_price) {
+            prices.insert(ContractKey::new(expiry, opt.strike_price, "CE"), price);
+        }
+        if let Some(price) = opt.put.as_ref().and_then(|p| p.last_price) {
+            prices.insert(ContractKey::new(expiry, opt.strike_price, "PE"), price);
+        }
+    }
+
+    let alerts = mcx_rules::run_mcx_rules(
+        &stored.data,
+        header.symbol.clone(),
+        header.timestamp.clone(),
+        header.underlying_value,
+        header.spread,
+    )
+    .map(|output| {
+        output.alerts
+            .into_iter()
+            .map(|a| SnapshotAlert {
+                alert_type: a.alert_type,
+                contract: ContractKey::new(&a.expiry_date, a.strike_price, &a.option_type),
+            })
+            .collect()
+    })
+    .unwrap_or_default();
+
+    Some(Snapshot {
+        symbol: header.symbol,
+        timestamp,
+        underlying_value: header.underlying_value,
+        alerts,
+        prices,
+    })
+}
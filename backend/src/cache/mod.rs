@@ -0,0 +1,5 @@
+pub mod prewarm;
+pub mod ttl_cache;
+
+pub use prewarm::{PrewarmRound, PrewarmSchedule};
+pub use ttl_cache::{CachePolicy, CacheStats, MarketHours, TtlCache};
@@ -0,0 +1,106 @@
+use super::MarketHours;
+use crate::settings::ExchangeSettings;
+use crate::utility::watchlist;
+use std::future::Future;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// Env var with extra hot symbols, same format as `batch --watchlist`
+pub const WATCHLIST_ENV: &str = "WATCHLIST";
+
+/// Outcome of one pre-warm pass over the hot symbols
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PrewarmRound {
+    pub refreshed: usize,
+    pub failed: usize,
+}
+
+impl PrewarmRound {
+    pub fn record<T>(&mut self, result: anyhow::Result<T>) -> Option<T> {
+        match result {
+            Ok(value) => {
+                self.refreshed += 1;
+                Some(value)
+            }
+            Err(e) => {
+                debug!("Pre-warm fetch failed: {}", e);
+                self.failed += 1;
+                None
+            }
+        }
+    }
+}
+
+/// When the pre-warm loop runs next
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrewarmSchedule {
+    pub interval: Duration,       // During market hours
+    pub idle_interval: Duration,  // Outside market hours, and the cap for failure backoff
+    pub market_hours: Option<MarketHours>,
+}
+
+impl PrewarmSchedule {
+    /// Delay before the next round: the market interval doubled per consecutive failed round
+    /// (capped at the idle interval), or the idle interval while the market is closed
+    pub fn next_delay(&self, market_open: bool, consecutive_failures: u32) -> Duration {
+        if !market_open {
+            return self.idle_interval;
+        }
+        self.interval
+            .saturating_mul(2u32.saturating_pow(consecutive_failures.min(16)))
+            .min(self.idle_interval)
+    }
+
+    fn market_open(&self) -> bool {
+        self.market_hours.is_none_or(|hours| hours.is_open())
+    }
+}
+
+/// Indices, configured `prewarm.symbols` and the WATCHLIST names, uppercased and de-duplicated
+pub fn hot_symbols(settings: &ExchangeSettings) -> Vec<String> {
+    let watchlist = match std::env::var(WATCHLIST_ENV) {
+        Ok(value) if !value.trim().is_empty() => watchlist::parse(&value).unwrap_or_else(|e| {
+            warn!("Ignoring {} for pre-warm: {}", WATCHLIST_ENV, e);
+            Vec::new()
+        }),
+        _ => Vec::new(),
+    };
+
+    let mut symbols: Vec<String> = Vec::new();
+    for symbol in settings.indices.iter().chain(&settings.prewarm.symbols).chain(&watchlist) {
+        let symbol = symbol.trim().to_uppercase();
+        if !symbol.is_empty() && !symbols.contains(&symbol) {
+            symbols.push(symbol);
+        }
+    }
+    symbols
+}
+
+/// Run `round` once at startup, then on `schedule` until the task is aborted
+pub fn spawn<F, Fut>(name: &'static str, schedule: PrewarmSchedule, round: F) -> JoinHandle<()>
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = PrewarmRound> + Send,
+{
+    tokio::spawn(async move {
+        let mut failures = 0u32;
+        let mut first = true;
+        loop {
+            let result = round().await;
+            if result.failed > 0 && result.refreshed == 0 {
+                failures = failures.saturating_add(1);
+                warn!(cache = name, failed = result.failed, failures, "Pre-warm round failed");
+            } else {
+                failures = 0;
+                if first {
+                    info!(cache = name, refreshed = result.refreshed, failed = result.failed, "Caches pre-warmed");
+                } else {
+                    debug!(cache = name, refreshed = result.refreshed, failed = result.failed, "Pre-warm round");
+                }
+            }
+            first = false;
+            tokio::time::sleep(schedule.next_delay(schedule.market_open(), failures)).await;
+        }
+    })
+}
@@ -0,0 +1,363 @@
+use crate::utility::metrics;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Datelike, FixedOffset, NaiveTime, Utc, Weekday};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
+use tracing::warn;
+use utoipa::ToSchema;
+
+const IST_OFFSET_SECS: i32 = 5 * 3600 + 30 * 60;
+
+/// Trading session in IST, Monday to Friday (exchange holidays are not modelled)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarketHours {
+    pub open: NaiveTime,
+    pub close: NaiveTime,
+}
+
+impl MarketHours {
+    pub fn is_open_at(&self, now: DateTime<Utc>) -> bool {
+        let ist = now.with_timezone(&FixedOffset::east_opt(IST_OFFSET_SECS).expect("valid IST offset"));
+        !matches!(ist.weekday(), Weekday::Sat | Weekday::Sun) && (self.open..self.close).contains(&ist.time())
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open_at(Utc::now())
+    }
+}
+
+/// How long one resource stays fresh, how long it may be served stale, and how many entries to keep
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CachePolicy {
+    pub ttl: Duration,          // Outside market hours
+    pub market_ttl: Duration,   // During market hours
+    pub stale: Duration,        // After expiry: serve the old value while refreshing in the background
+    pub max_entries: usize,     // Least recently used entries are evicted beyond this
+    pub market_hours: Option<MarketHours>,
+}
+
+impl CachePolicy {
+    /// Same TTL all day, no stale window
+    pub fn fixed(ttl: Duration, max_entries: usize) -> Self {
+        Self { ttl, market_ttl: ttl, stale: Duration::ZERO, max_entries, market_hours: None }
+    }
+
+    /// TTL in effect right now
+    pub fn current_ttl(&self) -> Duration {
+        match self.market_hours {
+            Some(hours) if hours.is_open() => self.market_ttl,
+            _ => self.ttl,
+        }
+    }
+}
+
+/// Counters and sizes for GET /cache-stats
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CacheStats {
+    pub name: String,
+    pub entries: usize,
+    pub max_entries: usize,
+    pub ttl_secs: u64,          // Currently in effect
+    pub stale_secs: u64,
+    pub in_flight: usize,
+    pub hits: u64,
+    pub stale_hits: u64,
+    pub misses: u64,
+    pub coalesced: u64,         // Misses that waited on another caller's fetch
+    pub refreshes: u64,         // Stale-while-revalidate and pre-warm fetches
+    pub evictions: u64,
+    pub errors: u64,
+    pub hit_rate: Option<f64>,  // (hits + stale hits) / lookups, %
+    pub newest_age_secs: Option<u64>,  // Age of the most recently stored entry
+}
+
+struct Entry<V> {
+    value: V,
+    stored_at: Instant,
+    last_used: u64,
+}
+
+type Flight<V> = Arc<OnceCell<Result<V, String>>>;
+
+struct Inner<K, V> {
+    entries: HashMap<K, Entry<V>>,
+    in_flight: HashMap<K, Flight<V>>,
+    clock: u64,  // Logical time for LRU ordering
+}
+
+#[derive(Default)]
+struct Counters {
+    hits: AtomicU64,
+    stale_hits: AtomicU64,
+    misses: AtomicU64,
+    coalesced: AtomicU64,
+    refreshes: AtomicU64,
+    evictions: AtomicU64,
+    errors: AtomicU64,
+}
+
+enum Lookup<V> {
+    Fresh(V),
+    Stale(V),
+    Miss,
+}
+
+/// Keyed TTL cache shared by server handlers. Clones share the same entries.
+pub struct TtlCache<K, V> {
+    name: Arc<str>,
+    policy: CachePolicy,
+    inner: Arc<Mutex<Inner<K, V>>>,
+    counters: Arc<Counters>,
+}
+
+impl<K, V> Clone for TtlCache<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            name: Arc::clone(&self.name),
+            policy: self.policy,
+            inner: Arc::clone(&self.inner),
+            counters: Arc::clone(&self.counters),
+        }
+    }
+}
+
+impl<K, V> TtlCache<K, V>
+where
+    K: Eq + Hash + Clone + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+{
+    /// `name` labels the cache in stats and in the Prometheus cache counters, e.g. "nse_contract_info"
+    pub fn new(name: &str, policy: CachePolicy) -> Self {
+        Self {
+            name: Arc::from(name),
+            policy,
+            inner: Arc::new(Mutex::new(Inner { entries: HashMap::new(), in_flight: HashMap::new(), clock: 0 })),
+            counters: Arc::new(Counters::default()),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn policy(&self) -> &CachePolicy {
+        &self.policy
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner<K, V>> {
+        self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lookup(&self, key: &K) -> Lookup<V> {
+        let ttl = self.policy.current_ttl();
+        let stale = self.policy.stale;
+        let mut inner = self.lock();
+        inner.clock += 1;
+        let clock = inner.clock;
+
+        let Some(entry) = inner.entries.get_mut(key) else {
+            return Lookup::Miss;
+        };
+        let age = entry.stored_at.elapsed();
+        if age < ttl {
+            entry.last_used = clock;
+            Lookup::Fresh(entry.value.clone())
+        } else if age < ttl + stale {
+            entry.last_used = clock;
+            Lookup::Stale(entry.value.clone())
+        } else {
+            Lookup::Miss
+        }
+    }
+
+    fn count(&self, counter: &AtomicU64) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Fresh value only; never fetches
+    pub fn get(&self, key: &K) -> Option<V> {
+        let value = match self.lookup(key) {
+            Lookup::Fresh(value) => Some(value),
+            _ => None,
+        };
+        self.count(if value.is_some() { &self.counters.hits } else { &self.counters.misses });
+        metrics::registry().record_cache(&self.name, value.is_some());
+        value
+    }
+
+    pub fn insert(&self, key: K, value: V) {
+        let mut inner = self.lock();
+        self.insert_locked(&mut inner, key, value);
+    }
+
+    fn insert_locked(&self, inner: &mut Inner<K, V>, key: K, value: V) {
+        inner.clock += 1;
+        let last_used = inner.clock;
+        inner.entries.insert(key, Entry { value, stored_at: Instant::now(), last_used });
+
+        while inner.entries.len() > self.policy.max_entries.max(1) {
+            let Some(oldest) = inner.entries.iter().min_by_key(|(_, e)| e.last_used).map(|(k, _)| k.clone()) else {
+                break;
+            };
+            inner.entries.remove(&oldest);
+            self.count(&self.counters.evictions);
+        }
+    }
+
+    /// True when `key` is missing or stops being fresh within `margin`; always false when caching is disabled
+    pub fn expires_within(&self, key: &K, margin: Duration) -> bool {
+        let ttl = self.policy.current_ttl();
+        if ttl.is_zero() {
+            return false;
+        }
+        self.lock().entries.get(key).is_none_or(|entry| entry.stored_at.elapsed() + margin >= ttl)
+    }
+
+    /// Fetch and store `key` whatever its age (joins a fetch already in flight)
+    pub async fn refresh<F, Fut>(&self, key: K, fetch: F) -> Result<V>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V>>,
+    {
+        self.count(&self.counters.refreshes);
+        self.fetch_coalesced(key, fetch).await
+    }
+
+    pub fn invalidate(&self, key: &K) {
+        self.lock().entries.remove(key);
+    }
+
+    pub fn clear(&self) {
+        self.lock().entries.clear();
+    }
+
+    /// Fresh value, or stale value plus a background refresh, or a fetch shared with concurrent callers
+    pub async fn get_or_fetch<F, Fut>(&self, key: K, fetch: F) -> Result<V>
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<V>> + Send + 'static,
+    {
+        match self.lookup(&key) {
+            Lookup::Fresh(value) => {
+                self.count(&self.counters.hits);
+                metrics::registry().record_cache(&self.name, true);
+                Ok(value)
+            }
+            Lookup::Stale(value) => {
+                self.count(&self.counters.stale_hits);
+                metrics::registry().record_cache(&self.name, true);
+                self.spawn_refresh(key, fetch);
+                Ok(value)
+            }
+            Lookup::Miss => {
+                self.count(&self.counters.misses);
+                metrics::registry().record_cache(&self.name, false);
+                self.fetch_coalesced(key, fetch).await
+            }
+        }
+    }
+
+    /// Join the in-flight fetch for `key`, or start one; returns (flight, started_here)
+    fn join_flight(&self, key: &K) -> (Flight<V>, bool) {
+        let mut inner = self.lock();
+        match inner.in_flight.get(key) {
+            Some(flight) => (Arc::clone(flight), false),
+            None => {
+                let flight: Flight<V> = Arc::new(OnceCell::new());
+                inner.in_flight.insert(key.clone(), Arc::clone(&flight));
+                (flight, true)
+            }
+        }
+    }
+
+    /// First caller to finish a flight stores the value and retires the flight
+    fn finish_flight(&self, key: &K, flight: &Flight<V>, result: &Result<V, String>) {
+        let mut inner = self.lock();
+        if !inner.in_flight.get(key).is_some_and(|current| Arc::ptr_eq(current, flight)) {
+            return;
+        }
+        inner.in_flight.remove(key);
+        match result {
+            Ok(value) => self.insert_locked(&mut inner, key.clone(), value.clone()),
+            Err(_) => self.count(&self.counters.errors),
+        }
+    }
+
+    async fn fetch_coalesced<F, Fut>(&self, key: K, fetch: F) -> Result<V>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V>>,
+    {
+        let (flight, started) = self.join_flight(&key);
+        if !started {
+            self.count(&self.counters.coalesced);
+        }
+
+        // If the caller running the fetch is cancelled, the next waiter's fetch takes over
+        let result = flight
+            .get_or_init(|| async move { fetch().await.map_err(|e| format!("{:#}", e)) })
+            .await
+            .clone();
+        self.finish_flight(&key, &flight, &result);
+        result.map_err(|e| anyhow!(e))
+    }
+
+    fn spawn_refresh<F, Fut>(&self, key: K, fetch: F)
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<V>> + Send + 'static,
+    {
+        let (flight, started) = self.join_flight(&key);
+        if !started {
+            return;  // Already refreshing
+        }
+        self.count(&self.counters.refreshes);
+
+        let cache = self.clone();
+        tokio::spawn(async move {
+            let result = flight
+                .get_or_init(|| async move { fetch().await.map_err(|e| format!("{:#}", e)) })
+                .await
+                .clone();
+            cache.finish_flight(&key, &flight, &result);
+            if let Err(e) = result {
+                warn!(cache = %cache.name, "Background refresh failed: {}", e);
+            }
+        });
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let (entries, in_flight, newest_age) = {
+            let inner = self.lock();
+            let newest_age = inner.entries.values().map(|entry| entry.stored_at.elapsed()).min();
+            (inner.entries.len(), inner.in_flight.len(), newest_age)
+        };
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        let (hits, stale_hits, misses) = (load(&self.counters.hits), load(&self.counters.stale_hits), load(&self.counters.misses));
+        let lookups = hits + stale_hits + misses;
+
+        CacheStats {
+            name: self.name.to_string(),
+            entries,
+            max_entries: self.policy.max_entries,
+            ttl_secs: self.policy.current_ttl().as_secs(),
+            stale_secs: self.policy.stale.as_secs(),
+            in_flight,
+            hits,
+            stale_hits,
+            misses,
+            coalesced: load(&self.counters.coalesced),
+            refreshes: load(&self.counters.refreshes),
+            evictions: load(&self.counters.evictions),
+            errors: load(&self.counters.errors),
+            hit_rate: (lookups > 0).then(|| (hits + stale_hits) as f64 / lookups as f64 * 100.0),
+            newest_age_secs: newest_age.map(|age| age.as_secs()),
+        }
+    }
+}
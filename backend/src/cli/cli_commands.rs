@@ -0,0 +1,217 @@
+use super::{ExportArgs, ExportFormat, HistoryArgs, OpenApiArgs, RulesTestArgs};
+use crate::analytics::{compute_iv_rank, IvHistoryStore};
+use crate::contracts::Exchange;
+use crate::mcx::mcx_api_server::McxApiDoc;
+use crate::nse::nse_api_server::NseApiDoc;
+use crate::nse::rules::{self, RulesOutput};
+use crate::screener::screener_commands::{load_batch_metrics, StoredRecord};
+use crate::screener::metrics_to_csv;
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use std::path::PathBuf;
+use tracing::info;
+use utoipa::OpenApi;
+
+pub const EXPORT_CSV_FILE: &str = "batch_metrics.csv";
+pub const EXPORT_JSON_FILE: &str = "batch_metrics.json";
+
+/// CLI-only command handlers (history, export, rules test, openapi)
+pub struct CliCommands;
+
+impl CliCommands {
+    /// Print the stored ATM IV history for one symbol, or a summary of every stored symbol
+    pub fn history(args: &HistoryArgs) -> Result<()> {
+        let exchange = parse_exchange(&args.exchange)?;
+        let path = args.file.clone().unwrap_or_else(IvHistoryStore::path);
+        if !path.exists() {
+            return Err(anyhow!("No IV history at {} (run a batch first)", path.display()));
+        }
+        let store = IvHistoryStore::load_file(&path)?;
+
+        match &args.symbol {
+            Some(symbol) => Self::display_symbol_history(&store, exchange, symbol, args.limit),
+            None => Self::display_history_summary(&store, exchange),
+        }
+    }
+
+    fn display_symbol_history(store: &IvHistoryStore, exchange: Exchange, symbol: &str, limit: usize) -> Result<()> {
+        let history = store.history(exchange, symbol);
+        if history.is_empty() {
+            return Err(anyhow!("No IV history for {}", IvHistoryStore::key(exchange, symbol)));
+        }
+
+        let fmt = |value: Option<f64>| value.map(|v| format!("{:.1}", v)).unwrap_or_else(|| "-".to_string());
+
+        println!("{}", "=".repeat(60).blue());
+        println!("{}", format!("ATM IV History: {}", IvHistoryStore::key(exchange, symbol)).cyan().bold());
+        println!("{}", "=".repeat(60).blue());
+        if let Some(stats) = compute_iv_rank(history) {
+            println!("{} Observations: {}, latest ATM IV: {:.2}%", "ℹ".blue(), stats.observations, stats.atm_iv);
+            println!(
+                "{} IV rank 30/90/252: {} / {} / {}",
+                "ℹ".blue(), fmt(stats.iv_rank_30), fmt(stats.iv_rank_90), fmt(stats.iv_rank_252)
+            );
+            println!(
+                "{} IV percentile 30/90/252: {} / {} / {}",
+                "ℹ".blue(), fmt(stats.iv_percentile_30), fmt(stats.iv_percentile_90), fmt(stats.iv_percentile_252)
+            );
+        }
+        println!();
+
+        println!("  {:<12} {:>8}", "Date", "ATM IV");
+        for obs in history.iter().rev().take(limit) {
+            println!("  {:<12} {:>8.2}", obs.date.to_string(), obs.atm_iv);
+        }
+        println!();
+        Ok(())
+    }
+
+    fn display_history_summary(store: &IvHistoryStore, exchange: Exchange) -> Result<()> {
+        let prefix = IvHistoryStore::key(exchange, "");
+        let rows: Vec<_> = store
+            .symbols
+            .iter()
+            .filter_map(|(key, history)| Some((key.strip_prefix(&prefix)?, history)))
+            .filter(|(_, history)| !history.is_empty())
+            .collect();
+        if rows.is_empty() {
+            return Err(anyhow!("No IV history stored for {}", prefix.trim_end_matches(':')));
+        }
+
+        let fmt = |value: Option<f64>| value.map(|v| format!("{:.1}", v)).unwrap_or_else(|| "-".to_string());
+
+        println!("{}", "=".repeat(60).blue());
+        println!("{}", format!("ATM IV History ({} symbols)", rows.len()).cyan().bold());
+        println!("{}", "=".repeat(60).blue());
+        println!(
+            "  {:<12} {:>5} {:<12} {:>8} {:>7} {:>7}",
+            "Symbol", "Obs", "Last date", "ATM IV", "IVR", "IVP"
+        );
+        for (symbol, history) in rows {
+            let stats = compute_iv_rank(history);
+            let last = history.last().expect("non-empty history");
+            println!(
+                "  {:<12} {:>5} {:<12} {:>8.2} {:>7} {:>7}",
+                symbol.yellow(),
+                history.len(),
+                last.date.to_string(),
+                last.atm_iv,
+                fmt(stats.as_ref().and_then(|s| s.iv_rank_252)),
+                fmt(stats.as_ref().and_then(|s| s.iv_percentile_252)),
+            );
+        }
+        println!();
+        Ok(())
+    }
+
+    /// Write the last batch run's per-symbol metrics as CSV or JSON
+    pub fn export(args: &ExportArgs) -> Result<()> {
+        let metrics = load_batch_metrics(&args.dir, &args.rules)?;
+        if metrics.is_empty() {
+            return Err(anyhow!("No processed symbols found in {} (run a batch first)", args.dir.display()));
+        }
+
+        let (contents, default_output) = match args.format {
+            ExportFormat::Csv => (metrics_to_csv(&metrics), EXPORT_CSV_FILE),
+            ExportFormat::Json => (serde_json::to_string_pretty(&metrics)?, EXPORT_JSON_FILE),
+        };
+        let output = args.output.clone().unwrap_or_else(|| PathBuf::from(default_output));
+        std::fs::write(&output, contents)?;
+        info!(symbols = metrics.len(), "✓ Exported {} symbols to {}", metrics.len(), output.display());
+        Ok(())
+    }
+
+    /// Re-run the NSE alert rules on a stored processed_data file
+    pub fn rules_test(args: &RulesTestArgs) -> Result<()> {
+        let path = PathBuf::from(&args.input);
+        let path = if path.is_file() {
+            path
+        } else {
+            args.dir.join(format!("{}.json", args.input.trim().to_uppercase()))
+        };
+        let stored = StoredRecord::load(&path)?;
+        let output = run_stored_rules(stored);
+
+        if args.json {
+            println!("{}", serde_json::to_string_pretty(&output)?);
+            return Ok(());
+        }
+
+        println!("{}", "=".repeat(60).blue());
+        println!("{}", format!("Rules Test: {}", path.display()).cyan().bold());
+        println!("{}", "=".repeat(60).blue());
+        match output {
+            Some(output) => {
+                println!("{} {} alert(s) for {} @ {:.2}", "ℹ".blue(), output.alerts.len(), output.symbol, output.underlying_value);
+                println!();
+                for alert in &output.alerts {
+                    println!(
+                        "  {:<22} {:<3} {:>10.2} {:<12} {}",
+                        alert.alert_type.yellow(),
+                        alert.option_type,
+                        alert.strike_price,
+                        alert.expiry_date,
+                        alert.description,
+                    );
+                }
+            }
+            None => println!("{} No alerts", "ℹ".blue()),
+        }
+        println!();
+        Ok(())
+    }
+
+    /// Write the NSE or MCX server's OpenAPI spec to a file or stdout
+    pub fn openapi(args: &OpenApiArgs) -> Result<()> {
+        let spec = match parse_exchange(&args.exchange)? {
+            Exchange::Nse => NseApiDoc::openapi(),
+            Exchange::Mcx => McxApiDoc::openapi(),
+        };
+        let json = spec.to_pretty_json()?;
+
+        match &args.output {
+            Some(output) => {
+                std::fs::write(output, json)?;
+                info!("✓ Wrote {} OpenAPI spec to {}", args.exchange.to_uppercase(), output.display());
+            }
+            None => println!("{}", json),
+        }
+        Ok(())
+    }
+}
+
+/// Strike rules plus the IV percentile rule, as the batch runs them
+fn run_stored_rules(stored: StoredRecord) -> Option<RulesOutput> {
+    let header = stored.record;
+    let output = rules::run_rules(
+        &stored.data,
+        header.symbol.clone(),
+        header.timestamp.clone(),
+        header.underlying_value,
+        header.spread,
+    );
+
+    let first = stored.data.first();
+    let iv_alert = header.iv_rank.as_ref().and_then(|iv| {
+        rules::check_iv_percentile_rule(
+            &header.symbol,
+            first.and_then(|opt| opt.expiry_date.as_deref()).unwrap_or("UNKNOWN"),
+            iv,
+            header.spread,
+            first.map(|opt| opt.days_to_expiry).unwrap_or(0),
+        )
+    });
+
+    match iv_alert {
+        Some(alert) => Some(rules::append_alert(output, alert, &header.timestamp, header.underlying_value)),
+        None => output,
+    }
+}
+
+fn parse_exchange(name: &str) -> Result<Exchange> {
+    match name {
+        "nse" => Ok(Exchange::Nse),
+        "mcx" => Ok(Exchange::Mcx),
+        other => Err(anyhow!("Invalid exchange '{}'. Use 'nse' or 'mcx'", other)),
+    }
+}
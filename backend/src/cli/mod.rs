@@ -0,0 +1,169 @@
+pub mod cli_commands;
+
+pub use cli_commands::{CliCommands, EXPORT_CSV_FILE, EXPORT_JSON_FILE};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+/// Command-line interface. Every option falls back to the environment variable
+/// the env-only configuration already used, so `MODE=batch EXCHANGE=nse` keeps working.
+#[derive(Debug, Parser)]
+#[command(
+    name = "nse-analyzer",
+    version,
+    about = "NSE / MCX option chain analyzer",
+    after_help = "Without a subcommand MODE, EXCHANGE and PORT select what runs (MODE=batch EXCHANGE=nse ...)."
+)]
+pub struct Cli {
+    /// Settings file with [nse] / [mcx] tunables [default: nse-analyzer.toml]
+    #[arg(long, global = true, env = "ANALYZER_CONFIG")]
+    pub config: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Fetch every option chain (or a watchlist), run the rules and write the batch output
+    Batch(BatchArgs),
+    /// Fetch and analyse one symbol's option chain
+    Single(SingleArgs),
+    /// Start the API server for one exchange
+    Serve(ServeArgs),
+    /// Show stored daily ATM IV history and IV rank
+    History(HistoryArgs),
+    /// Export the last batch run's per-symbol metrics as CSV or JSON
+    Export(ExportArgs),
+    /// Alert rule tools
+    #[command(subcommand)]
+    Rules(RulesCommand),
+    /// Replay stored snapshots through the alert rules (BACKTEST_* env vars)
+    ///
+    /// Only SNAPSHOT_DIR archives written by earlier batch runs are replayed;
+    /// exchange historical data is not loaded, so run batches with SNAPSHOT_DIR set first.
+    Backtest(ExchangeArgs),
+    /// Rank the last batch run's symbols (SCREENER_* env vars)
+    Screener(ExchangeArgs),
+    /// Validate and print the effective settings (defaults < config file < env vars)
+    Config,
+    /// Write a server's OpenAPI 3 spec (input for generating the frontend TS types)
+    Openapi(OpenApiArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct BatchArgs {
+    /// Exchange to process
+    #[arg(long, short, env = "EXCHANGE", default_value = "both", value_parser = ["nse", "mcx", "both"])]
+    pub exchange: String,
+
+    /// Only process these symbols: a comma-separated list or a file with one symbol per line
+    #[arg(long, short, env = "WATCHLIST")]
+    pub watchlist: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct SingleArgs {
+    /// Symbol, e.g. NIFTY or COPPER [env: NSE_SYMBOL / MCX_SYMBOL]
+    pub symbol: Option<String>,
+
+    /// Expiry, e.g. 30-Dec-2025 (NSE) or 31DEC2025 (MCX) [env: NSE_EXPIRY / MCX_EXPIRY]
+    #[arg(long)]
+    pub expiry: Option<String>,
+
+    /// Exchange
+    #[arg(long, short, env = "EXCHANGE", default_value = "nse", value_parser = ["nse", "mcx"])]
+    pub exchange: String,
+}
+
+#[derive(Debug, Args)]
+pub struct ServeArgs {
+    /// Exchange
+    #[arg(long, short, env = "EXCHANGE", default_value = "nse", value_parser = ["nse", "mcx"])]
+    pub exchange: String,
+
+    /// Port to listen on [env: PORT / NSE_PORT / MCX_PORT, default 3001]
+    #[arg(long, short)]
+    pub port: Option<u16>,
+}
+
+#[derive(Debug, Args)]
+pub struct HistoryArgs {
+    /// Symbol to show; lists every stored symbol when omitted
+    pub symbol: Option<String>,
+
+    /// Exchange
+    #[arg(long, short, env = "EXCHANGE", default_value = "nse", value_parser = ["nse", "mcx"])]
+    pub exchange: String,
+
+    /// Most recent observations to show
+    #[arg(long, short = 'n', default_value_t = 20)]
+    pub limit: usize,
+
+    /// IV history store [env: IV_HISTORY_FILE, default iv_history.json]
+    #[arg(long)]
+    pub file: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// Output format
+    #[arg(long, short, default_value = "csv")]
+    pub format: ExportFormat,
+
+    /// Output file [default: batch_metrics.csv / batch_metrics.json]
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+
+    /// Directory of per-symbol batch output
+    #[arg(long, env = "SCREENER_DIR", default_value = "processed_data")]
+    pub dir: PathBuf,
+
+    /// Batch rules file used for alert counts
+    #[arg(long, env = "SCREENER_RULES", default_value = "batch_rules.json")]
+    pub rules: PathBuf,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum RulesCommand {
+    /// Run the NSE alert rules against a stored processed_data file
+    Test(RulesTestArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct RulesTestArgs {
+    /// Symbol (reads <dir>/<SYMBOL>.json) or a path to a processed_data file
+    pub input: String,
+
+    /// Directory of per-symbol batch output
+    #[arg(long, env = "SCREENER_DIR", default_value = "processed_data")]
+    pub dir: PathBuf,
+
+    /// Print the rules output as JSON instead of a table
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ExchangeArgs {
+    /// Exchange
+    #[arg(long, short, env = "EXCHANGE", default_value = "nse", value_parser = ["nse", "mcx"])]
+    pub exchange: String,
+}
+
+#[derive(Debug, Args)]
+pub struct OpenApiArgs {
+    /// Exchange whose server spec to write
+    #[arg(long, short, env = "EXCHANGE", default_value = "nse", value_parser = ["nse", "mcx"])]
+    pub exchange: String,
+
+    /// Output file; prints to stdout when omitted
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+}
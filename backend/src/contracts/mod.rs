@@ -0,0 +1,3 @@
+pub mod registry;
+
+pub use registry::{parse_nse_market_lots, ContractRegistry, ContractSpec, Exchange};
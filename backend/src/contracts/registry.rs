@@ -0,0 +1,184 @@
+use crate::mcx::config as mcx_config;
+use crate::nse::config as nse_config;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use utoipa::ToSchema;
+
+// Optional JSON file with extra/overriding specs (array of ContractSpec)
+pub const CONTRACT_SPECS_FILE_ENV: &str = "CONTRACT_SPECS_FILE";
+pub const DEFAULT_CONTRACT_SPECS_FILE: &str = "contract_specs.json";
+
+fn default_multiplier() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Exchange {
+    Nse,
+    Mcx,
+}
+
+/// Contract specification for one NSE symbol or MCX commodity.
+/// Rupee value of a 1-point move for one lot is `lot_size * multiplier`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct ContractSpec {
+    pub exchange: Exchange,
+    pub symbol: String,
+    pub lot_size: f64,         // Units per lot (NSE market lot; 1 for MCX)
+    #[serde(default = "default_multiplier")]
+    pub multiplier: f64,       // Quote units per unit (MCX price quotation vs trading unit)
+    pub tick_size: f64,
+    pub unit: Option<String>,  // Trading unit, e.g. "100 BBL"
+}
+
+impl ContractSpec {
+    pub fn nse(symbol: &str, lot_size: f64) -> Self {
+        Self {
+            exchange: Exchange::Nse,
+            symbol: symbol.to_uppercase(),
+            lot_size,
+            multiplier: 1.0,
+            tick_size: nse_config::NSE_OPTION_TICK_SIZE,
+            unit: None,
+        }
+    }
+
+    pub fn mcx(symbol: &str, multiplier: f64, tick_size: f64, unit: &str) -> Self {
+        Self {
+            exchange: Exchange::Mcx,
+            symbol: symbol.to_uppercase(),
+            lot_size: 1.0,
+            multiplier,
+            tick_size,
+            unit: Some(unit.to_string()),
+        }
+    }
+
+    /// Rupee value of a 1-point price move for one lot
+    pub fn point_value(&self) -> f64 {
+        self.lot_size * self.multiplier
+    }
+
+    /// Rupee value of one tick for one lot
+    pub fn tick_value(&self) -> f64 {
+        self.tick_size * self.point_value()
+    }
+
+    /// Notional value of `contracts` lots at `price`
+    pub fn notional(&self, price: f64, contracts: f64) -> f64 {
+        price * contracts * self.point_value()
+    }
+}
+
+/// Lot size / contract spec lookup for both exchanges
+#[derive(Debug, Clone, Default)]
+pub struct ContractRegistry {
+    nse: HashMap<String, ContractSpec>,
+    mcx: HashMap<String, ContractSpec>,
+}
+
+impl ContractRegistry {
+    /// Built-in specs: NSE index lots and the MCX commodity table
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::default();
+
+        for (symbol, lot_size) in nse_config::NSE_INDEX_LOT_SIZES {
+            registry.insert(ContractSpec::nse(symbol, *lot_size));
+        }
+        for (symbol, multiplier, tick_size, unit) in mcx_config::MCX_CONTRACT_SPECS {
+            registry.insert(ContractSpec::mcx(symbol, *multiplier, *tick_size, unit));
+        }
+
+        registry
+    }
+
+    /// Built-in specs overlaid with the specs file (if present)
+    pub fn load() -> Self {
+        let mut registry = Self::with_defaults();
+
+        let path = std::env::var(CONTRACT_SPECS_FILE_ENV)
+            .unwrap_or_else(|_| DEFAULT_CONTRACT_SPECS_FILE.to_string());
+
+        if Path::new(&path).exists() {
+            match registry.load_file(&path) {
+                Ok(count) => tracing::info!("Loaded {} contract specs from {}", count, path),
+                Err(e) => tracing::warn!("Failed to load contract specs from {}: {}", path, e),
+            }
+        }
+
+        registry
+    }
+
+    /// Merge specs from a JSON file, returns number of specs loaded
+    pub fn load_file(&mut self, path: &str) -> Result<usize> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path))?;
+        let specs: Vec<ContractSpec> = serde_json::from_str(&text)
+            .with_context(|| format!("Failed to parse {}", path))?;
+
+        let count = specs.len();
+        for spec in specs {
+            self.insert(spec);
+        }
+        Ok(count)
+    }
+
+    /// Merge NSE lot sizes (e.g. from the F&O contract master), returns number merged
+    pub fn merge_nse_lot_sizes(&mut self, lot_sizes: &HashMap<String, f64>) -> usize {
+        for (symbol, lot_size) in lot_sizes {
+            match self.nse.get_mut(symbol) {
+                Some(spec) => spec.lot_size = *lot_size,
+                None => self.insert(ContractSpec::nse(symbol, *lot_size)),
+            }
+        }
+        lot_sizes.len()
+    }
+
+    pub fn insert(&mut self, mut spec: ContractSpec) {
+        spec.symbol = spec.symbol.to_uppercase();
+        match spec.exchange {
+            Exchange::Nse => self.nse.insert(spec.symbol.clone(), spec),
+            Exchange::Mcx => self.mcx.insert(spec.symbol.clone(), spec),
+        };
+    }
+
+    pub fn nse(&self, symbol: &str) -> Option<&ContractSpec> {
+        self.nse.get(&symbol.to_uppercase())
+    }
+
+    pub fn mcx(&self, commodity: &str) -> Option<&ContractSpec> {
+        self.mcx.get(&commodity.to_uppercase())
+    }
+
+    pub fn len(&self) -> usize {
+        self.nse.len() + self.mcx.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Parse NSE fo_mktlots.csv into symbol -> current month lot size
+pub fn parse_nse_market_lots(csv: &str) -> HashMap<String, f64> {
+    csv.lines()
+        .filter_map(|line| {
+            let cols: Vec<&str> = line.split(',').map(|c| c.trim()).collect();
+            let symbol = cols.get(1)?;
+            if symbol.is_empty() || symbol.eq_ignore_ascii_case("SYMBOL") {
+                return None;
+            }
+
+            // First populated month column is the nearest expiry
+            let lot_size = cols.iter()
+                .skip(2)
+                .find_map(|c| c.parse::<f64>().ok())
+                .filter(|lot| *lot > 0.0)?;
+
+            Some((symbol.to_uppercase(), lot_size))
+        })
+        .collect()
+}
@@ -0,0 +1,50 @@
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::future::Future;
+use tokio::task::{Id, JoinSet};
+
+/// Spawned per-symbol fetches that are aborted when dropped, so cancelling the
+/// job awaiting them also stops requests already in flight
+pub struct FetchTasks<T> {
+    set: JoinSet<Result<T>>,
+    order: HashMap<Id, usize>,
+}
+
+impl<T> Default for FetchTasks<T> {
+    fn default() -> Self {
+        Self { set: JoinSet::new(), order: HashMap::new() }
+    }
+}
+
+impl<T: Send + 'static> FetchTasks<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn spawn<F>(&mut self, task: F)
+    where
+        F: Future<Output = Result<T>> + Send + 'static,
+    {
+        let index = self.order.len();
+        let handle = self.set.spawn(task);
+        self.order.insert(handle.id(), index);
+    }
+
+    /// Wait for every task; results come back in spawn order
+    pub async fn join_all(mut self) -> Vec<Result<T>> {
+        let mut results: Vec<Option<Result<T>>> = (0..self.order.len()).map(|_| None).collect();
+        while let Some(joined) = self.set.join_next_with_id().await {
+            let (id, result) = match joined {
+                Ok((id, result)) => (id, result),
+                Err(e) => (e.id(), Err(anyhow!("Task error: {}", e))),
+            };
+            if let Some(&index) = self.order.get(&id) {
+                results[index] = Some(result);
+            }
+        }
+        results
+            .into_iter()
+            .map(|result| result.unwrap_or_else(|| Err(anyhow!("Task error: no result"))))
+            .collect()
+    }
+}
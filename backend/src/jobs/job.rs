@@ -0,0 +1,372 @@
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tokio::task::AbortHandle;
+use utoipa::ToSchema;
+
+// Finished jobs (and their results) stay retrievable for this long
+const JOB_RETENTION: Duration = Duration::from_secs(6 * 60 * 60);
+const MAX_FINISHED_JOBS: usize = 50;
+
+static JOB_COUNTER: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn is_finished(self) -> bool {
+        self != JobStatus::Running
+    }
+}
+
+/// A symbol that failed to fetch or process
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct JobFailure {
+    pub symbol: String,
+    pub error: String,
+}
+
+/// Live counters shared between a job and the fetch tasks it spawns
+#[derive(Debug, Default)]
+pub struct JobProgress {
+    total: AtomicUsize,
+    fetched: AtomicUsize,
+    failed: AtomicUsize,
+    cancelled: AtomicBool,
+    stage: Mutex<String>,
+    completed_symbols: Mutex<Vec<String>>,
+    failures: Mutex<Vec<JobFailure>>,
+    keep_partial_results: AtomicBool,
+    partial_results: Mutex<Vec<Value>>,
+}
+
+/// Point-in-time copy of `JobProgress` for API responses
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct ProgressSnapshot {
+    pub stage: String,
+    pub total: usize,
+    pub fetched: usize,
+    pub failed: usize,
+    pub pending: usize,
+    pub percent: f64,
+    pub completed_symbols: Vec<String>,  // Partial results: symbols fetched so far
+    pub failures: Vec<JobFailure>,
+}
+
+impl JobProgress {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    pub fn set_stage(&self, stage: &str) {
+        *self.stage.lock().unwrap() = stage.to_string();
+    }
+
+    /// Add to the number of items the job will fetch
+    pub fn add_total(&self, count: usize) {
+        self.total.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn record_success(&self, symbol: &str) {
+        self.fetched.fetch_add(1, Ordering::Relaxed);
+        self.completed_symbols.lock().unwrap().push(symbol.to_string());
+    }
+
+    pub fn record_failure(&self, symbol: &str, error: impl ToString) {
+        self.failed.fetch_add(1, Ordering::Relaxed);
+        self.failures.lock().unwrap().push(JobFailure {
+            symbol: symbol.to_string(),
+            error: error.to_string(),
+        });
+    }
+
+    /// Keep a per-symbol result as each item arrives (jobs started by `JobRegistry` do)
+    pub fn keep_partial_results(&self) {
+        self.keep_partial_results.store(true, Ordering::Relaxed);
+    }
+
+    pub fn wants_partial_results(&self) -> bool {
+        self.keep_partial_results.load(Ordering::Relaxed)
+    }
+
+    /// Results are stored as JSON so one progress type serves every job kind
+    pub fn record_partial(&self, result: &impl Serialize) {
+        if !self.wants_partial_results() {
+            return;
+        }
+        match serde_json::to_value(result) {
+            Ok(value) => self.partial_results.lock().unwrap().push(value),
+            Err(e) => tracing::warn!("Dropped partial result: {}", e),
+        }
+    }
+
+    pub fn partial_results(&self) -> Vec<Value> {
+        self.partial_results.lock().unwrap().clone()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Error for fetch tasks to bail out with once the job is cancelled
+    pub fn check_cancelled(&self) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(anyhow!("Job cancelled"));
+        }
+        Ok(())
+    }
+
+    pub fn snapshot(&self) -> ProgressSnapshot {
+        let total = self.total.load(Ordering::Relaxed);
+        let fetched = self.fetched.load(Ordering::Relaxed);
+        let failed = self.failed.load(Ordering::Relaxed);
+        let done = fetched + failed;
+
+        ProgressSnapshot {
+            stage: self.stage.lock().unwrap().clone(),
+            total,
+            fetched,
+            failed,
+            pending: total.saturating_sub(done),
+            percent: if total > 0 { done as f64 / total as f64 * 100.0 } else { 0.0 },
+            completed_symbols: self.completed_symbols.lock().unwrap().clone(),
+            failures: self.failures.lock().unwrap().clone(),
+        }
+    }
+}
+
+struct Job<T> {
+    id: String,
+    kind: String,
+    status: JobStatus,
+    created_at: String,
+    finished_at: Option<String>,
+    started: Instant,
+    finished: Option<Instant>,
+    progress: Arc<JobProgress>,
+    result: Option<T>,
+    error: Option<String>,
+    abort: Option<AbortHandle>,
+}
+
+/// Job state as returned by `GET /jobs/{id}`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct JobView<T> {
+    pub id: String,
+    pub kind: String,
+    pub status: JobStatus,
+    pub created_at: String,
+    pub finished_at: Option<String>,
+    pub elapsed_ms: u64,
+    pub progress: ProgressSnapshot,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<T>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schema(value_type = Vec<Object>)]
+    pub partial_results: Vec<Value>,  // Per-symbol results so far, until the job completes
+    pub error: Option<String>,
+}
+
+impl<T: Clone> Job<T> {
+    fn view(&self, with_result: bool) -> JobView<T> {
+        let elapsed = self.finished.unwrap_or_else(Instant::now).duration_since(self.started);
+        JobView {
+            id: self.id.clone(),
+            kind: self.kind.clone(),
+            status: self.status,
+            created_at: self.created_at.clone(),
+            finished_at: self.finished_at.clone(),
+            elapsed_ms: elapsed.as_millis() as u64,
+            progress: self.progress.snapshot(),
+            result: if with_result { self.result.clone() } else { None },
+            partial_results: if with_result && self.status != JobStatus::Completed {
+                self.progress.partial_results()
+            } else {
+                Vec::new()
+            },
+            error: self.error.clone(),
+        }
+    }
+
+    fn finish(&mut self, status: JobStatus) {
+        self.status = status;
+        self.finished = Some(Instant::now());
+        self.finished_at = Some(timestamp());
+        self.abort = None;
+    }
+}
+
+fn timestamp() -> String {
+    Local::now().format("%d-%b-%Y %H:%M:%S").to_string()
+}
+
+/// In-memory background jobs with progress, cancellation and cached results
+pub struct JobRegistry<T> {
+    jobs: Arc<RwLock<HashMap<String, Job<T>>>>,
+}
+
+impl<T> Clone for JobRegistry<T> {
+    fn clone(&self) -> Self {
+        Self { jobs: Arc::clone(&self.jobs) }
+    }
+}
+
+impl<T> Default for JobRegistry<T> {
+    fn default() -> Self {
+        Self { jobs: Arc::new(RwLock::new(HashMap::new())) }
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> JobRegistry<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start `run` in the background and return its job id.
+    /// While a job of the same kind is still running its id is returned instead.
+    pub async fn spawn<F, Fut>(&self, kind: &str, run: F) -> String
+    where
+        F: FnOnce(Arc<JobProgress>) -> Fut,
+        Fut: Future<Output = Result<T>> + Send + 'static,
+    {
+        let mut jobs = self.jobs.write().await;
+        if let Some(job) = jobs.values().find(|job| job.kind == kind && job.status == JobStatus::Running) {
+            return job.id.clone();
+        }
+        prune(&mut jobs);
+
+        let id = format!(
+            "{}-{}-{}",
+            kind,
+            Local::now().format("%Y%m%d%H%M%S"),
+            JOB_COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        let progress = JobProgress::new();
+        progress.keep_partial_results();
+        let future = run(Arc::clone(&progress));
+
+        let registry = self.clone();
+        let job_id = id.clone();
+        let handle = tokio::spawn(async move {
+            let outcome = future.await;
+            registry.complete(&job_id, outcome).await;
+        });
+
+        jobs.insert(id.clone(), Job {
+            id: id.clone(),
+            kind: kind.to_string(),
+            status: JobStatus::Running,
+            created_at: timestamp(),
+            finished_at: None,
+            started: Instant::now(),
+            finished: None,
+            progress,
+            result: None,
+            error: None,
+            abort: Some(handle.abort_handle()),
+        });
+        id
+    }
+
+    async fn complete(&self, id: &str, outcome: Result<T>) {
+        let mut jobs = self.jobs.write().await;
+        let Some(job) = jobs.get_mut(id) else { return };
+        if job.status.is_finished() {
+            return;  // Cancelled while finishing
+        }
+        match outcome {
+            Ok(result) => {
+                job.result = Some(result);
+                job.finish(JobStatus::Completed);
+            }
+            Err(e) => {
+                job.error = Some(e.to_string());
+                job.finish(JobStatus::Failed);
+            }
+        }
+    }
+
+    /// Job status, progress and the result once completed (partial results until then)
+    pub async fn get(&self, id: &str) -> Option<JobView<T>> {
+        self.jobs.read().await.get(id).map(|job| job.view(true))
+    }
+
+    /// All retained jobs, newest first, without results
+    pub async fn list(&self) -> Vec<JobView<T>> {
+        let jobs = self.jobs.read().await;
+        let mut views: Vec<(Instant, JobView<T>)> = jobs.values().map(|job| (job.started, job.view(false))).collect();
+        views.sort_by_key(|(started, _)| std::cmp::Reverse(*started));
+        views.into_iter().map(|(_, view)| view).collect()
+    }
+
+    /// Result of the most recently completed job of a kind
+    pub async fn latest_result(&self, kind: &str) -> Option<T> {
+        let jobs = self.jobs.read().await;
+        jobs.values()
+            .filter(|job| job.kind == kind && job.status == JobStatus::Completed)
+            .max_by_key(|job| job.finished)
+            .and_then(|job| job.result.clone())
+    }
+
+    /// Cancel every running job (on shutdown); returns how many were cancelled
+    pub async fn cancel_all(&self) -> usize {
+        let mut jobs = self.jobs.write().await;
+        let mut cancelled = 0;
+        for job in jobs.values_mut().filter(|job| job.status == JobStatus::Running) {
+            job.progress.cancel();
+            if let Some(abort) = &job.abort {
+                abort.abort();
+            }
+            job.finish(JobStatus::Cancelled);
+            cancelled += 1;
+        }
+        cancelled
+    }
+
+    /// Cancel a running job (finished jobs are returned unchanged)
+    pub async fn cancel(&self, id: &str) -> Option<JobView<T>> {
+        let mut jobs = self.jobs.write().await;
+        let job = jobs.get_mut(id)?;
+        if job.status == JobStatus::Running {
+            job.progress.cancel();
+            if let Some(abort) = &job.abort {
+                abort.abort();
+            }
+            job.finish(JobStatus::Cancelled);
+        }
+        Some(job.view(false))
+    }
+}
+
+/// Drop finished jobs past the retention period, then the oldest beyond the cap
+fn prune<T>(jobs: &mut HashMap<String, Job<T>>) {
+    jobs.retain(|_, job| job.finished.is_none_or(|at| at.elapsed() < JOB_RETENTION));
+
+    let mut finished: Vec<(Instant, String)> = jobs
+        .values()
+        .filter_map(|job| job.finished.map(|at| (at, job.id.clone())))
+        .collect();
+    if finished.len() > MAX_FINISHED_JOBS {
+        finished.sort();
+        for (_, id) in finished.iter().take(finished.len() - MAX_FINISHED_JOBS) {
+            jobs.remove(id);
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use super::job::{JobRegistry, JobView};
+use crate::utility::ApiResponse;
+use anyhow::{anyhow, Result};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    routing::get,
+    Router,
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::time::Instant;
+
+// -----------------------------------------------
+// JOB HANDLERS
+// -----------------------------------------------
+
+fn respond<T>(result: Result<T>, start_time: Instant) -> Json<ApiResponse<T>> {
+    let processing_time_ms = Some(start_time.elapsed().as_millis() as u64);
+    match result {
+        Ok(data) => Json(ApiResponse { success: true, data: Some(data), error: None, processing_time_ms }),
+        Err(e) => Json(ApiResponse { success: false, data: None, error: Some(e.to_string()), processing_time_ms }),
+    }
+}
+
+// The job routes are generic over each server's result type, which the path macro cannot
+// name; the spec documents the result as untyped (see GET .../batch-analysis for its schema)
+
+/// GET /jobs - Retained jobs, newest first (results omitted)
+#[utoipa::path(get, path = "/jobs", tag = "jobs", responses((status = 200, body = ApiResponse<Vec<JobView<Value>>>)))]
+pub(crate) async fn list_jobs<T>(
+    State(registry): State<JobRegistry<T>>,
+) -> Result<Json<ApiResponse<Vec<JobView<T>>>>, StatusCode>
+where
+    T: Clone + Serialize + Send + Sync + 'static,
+{
+    let start_time = Instant::now();
+    Ok(respond(Ok(registry.list().await), start_time))
+}
+
+/// GET /jobs/{id} - Status, progress and partial results; the result once completed
+#[utoipa::path(get, path = "/jobs/{id}", tag = "jobs", params(("id" = String, Path, description = "Job id")), responses((status = 200, body = ApiResponse<JobView<Value>>)))]
+pub(crate) async fn get_job<T>(
+    State(registry): State<JobRegistry<T>>,
+    Path(id): Path<String>,
+) -> Result<Json<ApiResponse<JobView<T>>>, StatusCode>
+where
+    T: Clone + Serialize + Send + Sync + 'static,
+{
+    let start_time = Instant::now();
+    let job = registry.get(&id).await.ok_or_else(|| anyhow!("Job '{}' not found", id));
+    Ok(respond(job, start_time))
+}
+
+/// DELETE /jobs/{id} - Cancel a running job
+#[utoipa::path(delete, path = "/jobs/{id}", tag = "jobs", params(("id" = String, Path, description = "Job id")), responses((status = 200, body = ApiResponse<JobView<Value>>)))]
+pub(crate) async fn cancel_job<T>(
+    State(registry): State<JobRegistry<T>>,
+    Path(id): Path<String>,
+) -> Result<Json<ApiResponse<JobView<T>>>, StatusCode>
+where
+    T: Clone + Serialize + Send + Sync + 'static,
+{
+    let start_time = Instant::now();
+    let job = registry.cancel(&id).await.ok_or_else(|| anyhow!("Job '{}' not found", id));
+    Ok(respond(job, start_time))
+}
+
+// -----------------------------------------------
+// ROUTES
+// -----------------------------------------------
+
+/// Job routes for one server's registry
+pub fn get_job_routes<T>(registry: JobRegistry<T>) -> Router
+where
+    T: Clone + Serialize + Send + Sync + 'static,
+{
+    Router::new()
+        .route("/jobs", get(list_jobs::<T>))
+        .route("/jobs/{id}", get(get_job::<T>).delete(cancel_job::<T>))
+        .with_state(registry)
+}
+
+/// Endpoint list for the server startup banner
+pub fn print_job_endpoints() {
+    println!("   GET  /jobs");
+    println!("   GET  /jobs/{{id}}");
+    println!("   DEL  /jobs/{{id}}");
+}
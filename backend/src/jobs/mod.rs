@@ -0,0 +1,7 @@
+pub mod fetch_tasks;
+pub mod job;
+pub mod jobs_api;
+
+pub use fetch_tasks::FetchTasks;
+pub use job::{JobFailure, JobProgress, JobRegistry, JobStatus, JobView, ProgressSnapshot};
+pub use jobs_api::{get_job_routes, print_job_endpoints};
@@ -1,3 +1,13 @@
 pub mod nse;
 pub mod mcx;
-pub mod utility;
\ No newline at end of file
+pub mod analytics;
+pub mod backtest;
+pub mod cache;
+pub mod cli;
+pub mod contracts;
+pub mod jobs;
+pub mod portfolio;
+pub mod report;
+pub mod screener;
+pub mod settings;
+pub mod utility;
@@ -2,15 +2,35 @@ mod app_config;
 use nse_analyzer::nse::nse_commands;
 use nse_analyzer::mcx::mcx_commands;
 use nse_analyzer::nse::config as nse_config;
-// use nse_analyzer::mcx::config as mcx_config;
+use nse_analyzer::mcx::config as mcx_config;
+use nse_analyzer::backtest::BacktestCommands;
+use nse_analyzer::cli::{Cli, CliCommands, Command, RulesCommand};
+use nse_analyzer::screener::ScreenerCommands;
+use nse_analyzer::settings::{self, Settings};
+use nse_analyzer::utility::{logging, shutdown, watchlist};
 use app_config::{AppConfig, Colorize};
 use nse_commands::NSECommands;
 use mcx_commands::MCXCommands;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use tracing::{error, info};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize configuration
+    // Parse first so --help / usage errors print without log noise
+    let cli = Cli::parse();
+
+    // LOG_FORMAT / RUST_LOG / LOG_LEVEL select output format and verbosity
+    logging::init();
+
+    // Tunables: built-in defaults < nse-analyzer.toml (--config / ANALYZER_CONFIG) < NSE_* / MCX_* env vars
+    settings::install(Settings::load(cli.config.as_deref())?);
+
+    if let Some(command) = cli.command {
+        return execute_subcommand(command).await;
+    }
+
+    // No subcommand: MODE / EXCHANGE / PORT environment variables
     let app_config = AppConfig::from_env();
     app_config.validate()?;
     app_config.log_ci_config();
@@ -19,6 +39,55 @@ async fn main() -> Result<()> {
     execute_command(&app_config).await
 }
 
+/// Execute an explicit CLI subcommand (flags override, env vars fill the gaps)
+async fn execute_subcommand(command: Command) -> Result<()> {
+    match command {
+        Command::Batch(args) => {
+            let watchlist = args.watchlist.as_deref().map(watchlist::parse).transpose()?;
+            if let Some(symbols) = &watchlist {
+                info!(count = symbols.len(), "Watchlist: {}", symbols.join(", "));
+            }
+            run_batch(&args.exchange, watchlist.as_deref()).await
+        }
+        Command::Single(args) => match args.exchange.as_str() {
+            "mcx" => {
+                let symbol = args.symbol.unwrap_or_else(mcx_config::get_single_symbol);
+                let expiry = args.expiry.unwrap_or_else(mcx_config::get_single_expiry);
+                MCXCommands::run_single(&symbol.to_uppercase(), &expiry).await
+            }
+            _ => {
+                let symbol = args.symbol.unwrap_or_else(nse_config::get_single_symbol);
+                let expiry = args.expiry.unwrap_or_else(nse_config::get_single_expiry);
+                NSECommands::run_single(&symbol.to_uppercase(), &expiry).await
+            }
+        },
+        Command::Serve(args) => {
+            let config = AppConfig {
+                mode: "server".to_string(),
+                port: args.port.unwrap_or_else(|| AppConfig::from_env().port),
+                exchange: args.exchange,
+            };
+            run_server_mode(&config).await
+        }
+        Command::History(args) => CliCommands::history(&args),
+        Command::Export(args) => CliCommands::export(&args),
+        Command::Rules(RulesCommand::Test(args)) => CliCommands::rules_test(&args),
+        Command::Openapi(args) => CliCommands::openapi(&args),
+        Command::Backtest(args) => {
+            info!("Running {} backtest...", args.exchange.to_uppercase());
+            BacktestCommands::run(&args.exchange)
+        }
+        Command::Screener(args) => {
+            info!("Running {} screener...", args.exchange.to_uppercase());
+            ScreenerCommands::run(&args.exchange).await
+        }
+        Command::Config => {
+            print!("{}", settings::current().to_toml()?);
+            Ok(())
+        }
+    }
+}
+
 /// Execute the appropriate command based on the configuration mode and exchange
 async fn execute_command(config: &AppConfig) -> Result<()> {
     match config.mode.as_str() {
@@ -31,9 +100,17 @@ async fn execute_command(config: &AppConfig) -> Result<()> {
             }
         }
         "batch" => run_batch_mode(config).await,
+        "backtest" => {
+            info!("Running {} backtest...", config.exchange.to_uppercase());
+            BacktestCommands::run(&config.exchange)
+        }
+        "screener" => {
+            info!("Running {} screener...", config.exchange.to_uppercase());
+            ScreenerCommands::run(&config.exchange).await
+        }
         _ => {
             if nse_config::is_ci_environment() {
-                println!("{} GitHub Actions only supports batch mode, switching to batch", "ℹ".blue());
+                info!("GitHub Actions only supports batch mode, switching to batch");
                 run_batch_mode(config).await
             } else {
                 handle_invalid_mode(&config.mode)
@@ -46,15 +123,11 @@ async fn execute_command(config: &AppConfig) -> Result<()> {
 async fn run_server_mode(config: &AppConfig) -> Result<()> {
     match config.exchange.as_str() {
         "nse" => {
-            println!("{}", "Starting NSE API Server...".green());
-            println!("{} Port: {}", "→".cyan(), config.port);
-            println!("{} Exchange: NSE Only", "→".cyan());
+            info!(port = config.port, "Starting NSE API Server...");
             NSECommands::run_server(config.port).await
         }
         "mcx" => {
-            println!("{}", "Starting MCX API Server...".green());
-            println!("{} Port: {}", "→".cyan(), config.port);
-            println!("{} Exchange: MCX Only", "→".cyan());
+            info!(port = config.port, "Starting MCX API Server...");
             MCXCommands::run_server(config.port).await
         }
         "both" => {
@@ -76,35 +149,48 @@ async fn run_server_mode(config: &AppConfig) -> Result<()> {
 
 /// Run batch mode for the specified exchange(s)
 async fn run_batch_mode(config: &AppConfig) -> Result<()> {
-    match config.exchange.as_str() {
+    run_batch(&config.exchange, None).await
+}
+
+/// Run the batch for one exchange or both, optionally restricted to a watchlist.
+/// Ctrl+C / SIGTERM aborts the in-flight fetches; files already written are complete.
+async fn run_batch(exchange: &str, watchlist: Option<&[String]>) -> Result<()> {
+    tokio::select! {
+        result = run_batch_for_exchange(exchange, watchlist) => result,
+        _ = shutdown::signal() => Err(anyhow!("Batch cancelled by shutdown signal")),
+    }
+}
+
+async fn run_batch_for_exchange(exchange: &str, watchlist: Option<&[String]>) -> Result<()> {
+    match exchange {
         "nse" => {
-            println!("{}", "Running NSE batch analysis...".green());
-            NSECommands::run_batch().await
+            info!("Running NSE batch analysis...");
+            NSECommands::run_batch_for(watchlist).await
         }
         "mcx" => {
-            println!("{}", "Running MCX batch analysis...".green());
-            MCXCommands::run_batch().await
+            info!("Running MCX batch analysis...");
+            MCXCommands::run_batch_for(watchlist).await
         }
         "both" => {
-            println!("{}", "Running batch analysis for both NSE and MCX...".green());
+            info!("Running batch analysis for both NSE and MCX...");
             
             // Run NSE batch first
-            println!("\n{}", "Starting NSE batch analysis...".cyan());
-            if let Err(e) = NSECommands::run_batch().await {
-                eprintln!("{} NSE batch failed: {}", "✗".red(), e);
+            info!("Starting NSE batch analysis...");
+            if let Err(e) = NSECommands::run_batch_for(watchlist).await {
+                error!("NSE batch failed: {}", e);
             }
             
             // Then run MCX batch
-            println!("\n{}", "Starting MCX batch analysis...".cyan());
-            if let Err(e) = MCXCommands::run_batch().await {
-                eprintln!("{} MCX batch failed: {}", "✗".red(), e);
+            info!("Starting MCX batch analysis...");
+            if let Err(e) = MCXCommands::run_batch_for(watchlist).await {
+                error!("MCX batch failed: {}", e);
             }
             
-            println!("\n{}", "Both analyses completed!".green());
+            info!("Both analyses completed!");
             Ok(())
         }
         _ => {
-            eprintln!("Invalid exchange '{}'. Use 'nse', 'mcx', or 'both'", config.exchange);
+            eprintln!("Invalid exchange '{}'. Use 'nse', 'mcx', or 'both'", exchange);
             print_usage();
             std::process::exit(1);
         }
@@ -132,7 +218,7 @@ fn print_orchestration_help() {
 
 /// Handle invalid mode by showing usage and exiting
 fn handle_invalid_mode(mode: &str) -> Result<()> {
-    eprintln!("Invalid mode '{}'. Use 'batch' or 'server'", mode);
+    eprintln!("Invalid mode '{}'. Use 'batch', 'server', 'backtest' or 'screener'", mode);
     print_usage();
     std::process::exit(1);
 }
@@ -144,7 +230,7 @@ fn handle_ci_mode_override(mode: &str, exchange: &str) -> bool {
             "nse" => NSECommands::handle_ci_mode_override(mode),
             "mcx" => MCXCommands::handle_ci_mode_override(mode),
             "both" => {
-                println!("{} GitHub Actions only supports batch mode, running batch for both exchanges", "ℹ".blue());
+                info!("GitHub Actions only supports batch mode, running batch for both exchanges");
                 true
             }
             _ => true
@@ -156,12 +242,17 @@ fn handle_ci_mode_override(mode: &str, exchange: &str) -> bool {
 
 /// Print usage instructions
 fn print_usage() {
-    eprintln!("Set environment variables to control execution:");
+    eprintln!("Run 'nse-analyzer --help' for subcommands (batch, single, serve, history, export, rules test),");
+    eprintln!("or set environment variables to control execution:");
     eprintln!();
     eprintln!("Environment Variables:");
-    eprintln!("  MODE or NSE_MODE or MCX_MODE  - Execution mode ('batch' or 'server')");
+    eprintln!("  MODE or NSE_MODE or MCX_MODE  - Execution mode ('batch', 'server', 'backtest' or 'screener')");
     eprintln!("  EXCHANGE                      - Exchange to use ('nse' or 'mcx')");
     eprintln!("  PORT or NSE_PORT or MCX_PORT  - Server port");
+    eprintln!("  RUST_LOG or LOG_LEVEL         - Log filter, e.g. 'info' or 'info,nse_analyzer::nse=debug'");
+    eprintln!("  LOG_FORMAT                    - Log output ('text', 'compact' or 'json')");
+    eprintln!("  ANALYZER_CONFIG               - Settings file (default nse-analyzer.toml)");
+    eprintln!("  NSE_* / MCX_*                 - Setting overrides, e.g. NSE_MAX_CONCURRENT=3 MCX_RETRY_MAX_ATTEMPTS=5");
     eprintln!();
     eprintln!("Server Examples (Separate Services):");
     eprintln!("  MODE=server EXCHANGE=nse PORT=3001 cargo run      # NSE server on port 3001");
@@ -172,6 +263,14 @@ fn print_usage() {
     eprintln!("  MODE=batch EXCHANGE=mcx cargo run                 # MCX batch analysis");
     eprintln!("  MODE=batch EXCHANGE=both cargo run                # Both exchanges batch");
     eprintln!();
+    eprintln!("Backtest Examples (replays SNAPSHOT_DIR archives only; record them with SNAPSHOT_DIR set on batch runs):");
+    eprintln!("  MODE=backtest EXCHANGE=nse cargo run              # Backtest NSE alerts");
+    eprintln!("  MODE=backtest EXCHANGE=mcx BACKTEST_HOLDING=3 BACKTEST_STOP_LOSS_PCT=20 cargo run");
+    eprintln!();
+    eprintln!("Screener Examples (ranks the last batch run's processed_data/):");
+    eprintln!("  MODE=screener EXCHANGE=nse SCREENER_FILTER='pcr > 1.2 and alerts > 0' cargo run");
+    eprintln!("  MODE=screener EXCHANGE=nse SCREENER_SORT=-basis_pct SCREENER_LIMIT=10 cargo run");
+    eprintln!();
     eprintln!("GitHub Actions (CI):");
     eprintln!("  EXCHANGE=nse cargo run                            # Auto-switches to batch");
     eprintln!("  EXCHANGE=mcx cargo run                            # Auto-switches to batch");
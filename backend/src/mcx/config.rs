@@ -1,6 +1,10 @@
 use std::time::Duration;
 use reqwest::{ RequestBuilder};
 
+// Concurrency, retry, timeout, cache, market hours and index constants below are the
+// built-in defaults; nse-analyzer.toml and MCX_* env vars override them at
+// runtime (see crate::settings).
+
 // -----------------------------------------------
 // MCX API ENDPOINTS
 // -----------------------------------------------
@@ -13,6 +17,34 @@ pub const MCX_FUTURE_QUOTE_API: &str = "https://www.mcxindia.com/BackPage.aspx/G
 pub const MCX_OPTION_QUOTE_API: &str = "https://www.mcxindia.com/BackPage.aspx/GetQuoteOption";
 pub const MCX_FUTURE_SYMBOLS_API: &str = "https://www.mcxindia.com/api/ContractAvailableForTrading/StaggeredProductDetailsCurrent";
 
+// -----------------------------------------------
+// CONTRACT SPECS
+// -----------------------------------------------
+// (commodity, multiplier = quote units per lot, tick size, trading unit)
+pub const MCX_CONTRACT_SPECS: &[(&str, f64, f64, &str)] = &[
+    ("GOLD", 100.0, 1.0, "1 KG"),
+    ("GOLDM", 10.0, 1.0, "100 GRMS"),
+    ("GOLDGUINEA", 1.0, 1.0, "8 GRMS"),
+    ("GOLDPETAL", 1.0, 1.0, "1 GRMS"),
+    ("SILVER", 30.0, 1.0, "30 KGS"),
+    ("SILVERM", 5.0, 1.0, "5 KGS"),
+    ("SILVERMIC", 1.0, 1.0, "1 KG"),
+    ("CRUDEOIL", 100.0, 1.0, "100 BBL"),
+    ("CRUDEOILM", 10.0, 1.0, "10 BBL"),
+    ("NATURALGAS", 1250.0, 0.10, "1250 MMBTU"),
+    ("NATGASMINI", 250.0, 0.10, "250 MMBTU"),
+    ("COPPER", 2500.0, 0.05, "2500 KGS"),
+    ("ZINC", 5000.0, 0.05, "5 MT"),
+    ("ZINCMINI", 1000.0, 0.05, "1 MT"),
+    ("ALUMINIUM", 5000.0, 0.05, "5 MT"),
+    ("ALUMINI", 1000.0, 0.05, "1 MT"),
+    ("LEAD", 5000.0, 0.05, "5 MT"),
+    ("LEADMINI", 1000.0, 0.05, "1 MT"),
+    ("NICKEL", 1500.0, 0.10, "1500 KGS"),
+    ("MENTHAOIL", 360.0, 0.10, "360 KGS"),
+    ("COTTONCNDY", 48.0, 10.0, "48 CANDY"),
+];
+
 // -----------------------------------------------
 // HTTP CLIENT CONFIG
 // -----------------------------------------------
@@ -41,6 +73,12 @@ pub const RETRY_FACTOR: u64 = 2;
 pub const RETRY_MAX_DELAY_SECS: u64 = 10;
 pub const RETRY_MAX_ATTEMPTS: usize = 3;
 
+// -----------------------------------------------
+// CIRCUIT BREAKER (consecutive failed fetches, after retries)
+// -----------------------------------------------
+pub const CIRCUIT_FAILURE_THRESHOLD: u32 = 5;  // 0 = never open
+pub const CIRCUIT_COOLDOWN_SECS: u64 = 30;     // Fail fast this long before probing again
+
 // -----------------------------------------------
 // GITHUB ACTIONS TIMEOUT CONFIG
 // -----------------------------------------------
@@ -52,6 +90,48 @@ pub const GITHUB_ACTIONS_TIMEOUT_SECS: u64 = 300;  // 5 minute timeout for CI
 pub const DEFAULT_MAX_CONCURRENT: usize = 3;
 pub const CI_MAX_CONCURRENT: usize = 2;
 
+// -----------------------------------------------
+// MARKET HOURS (IST, Monday to Friday)
+// -----------------------------------------------
+pub const MARKET_OPEN_IST: &str = "09:00";
+pub const MARKET_CLOSE_IST: &str = "23:30";
+
+// Paper-portfolio margin as a fraction of underlying notional (rough SPAN + exposure proxy)
+pub const MARGIN_RATE: f64 = 0.10;
+
+// -----------------------------------------------
+// SERVER CACHE
+// -----------------------------------------------
+// (resource, ttl secs, market-hours ttl secs, stale secs, max entries)
+pub const CACHE_POLICIES: &[(&str, u64, u64, u64, usize)] = &[
+    ("ticker_list", 3_600, 900, 300, 1),
+    ("option_chains", 900, 30, 30, 200),
+    ("future_quotes", 600, 15, 15, 200),
+    ("option_quotes", 600, 15, 15, 500),
+    ("future_symbols", 21_600, 21_600, 3_600, 1),
+    ("historic_data", 21_600, 3_600, 3_600, 100),
+];
+
+// -----------------------------------------------
+// CACHE PRE-WARM (hot symbols = indices + these + WATCHLIST)
+// -----------------------------------------------
+pub const PREWARM_INTERVAL_SECS: u64 = 10;  // Market hours; below the shortest market TTL
+pub const PREWARM_IDLE_INTERVAL_SECS: u64 = 1_800;
+pub const PREWARM_SYMBOLS: &[&str] = &["CRUDEOIL", "GOLD", "SILVER", "NATURALGAS", "COPPER"];
+
+// -----------------------------------------------
+// VOLATILITY SURFACE
+// -----------------------------------------------
+pub const SURFACE_MAX_EXPIRIES: usize = 4;
+pub const SURFACE_EXPIRIES_LIMIT: usize = 8;
+
+// -----------------------------------------------
+// API SERVER ACCESS (API keys are configured only in settings)
+// -----------------------------------------------
+pub const SERVER_BIND: &str = "127.0.0.1";
+pub const SERVER_REQUESTS_PER_MINUTE: u32 = 300;     // Per API key; 0 = unlimited
+pub const SERVER_BATCH_REQUESTS_PER_HOUR: u32 = 20;  // POST batch-analysis per API key
+
 // -----------------------------------------------
 // HTTP HEADERS
 // -----------------------------------------------
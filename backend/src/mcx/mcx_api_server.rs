@@ -1,11 +1,23 @@
 use super::config;
 use super::models::{Ticker, OptionChainResponse};
 use super::mcx_client::MCXClient;
+use crate::nse::NSEClient;
 use super::processor;
-use anyhow::Result;
+use crate::cache::{prewarm, CacheStats, PrewarmRound, TtlCache};
+use crate::analytics::{
+    self, IndicatorSpec, IvHistoryStore, HistoricalSeries, IvRankStats, PricingModel, SeriesMeta, SymbolIvRank, StrategyAnalysis, StrategyLegRequest, SymbolBuildup,
+    VolatilitySurface, DEFAULT_ROLLING_WINDOW,
+};
+use crate::contracts::{ContractRegistry, ContractSpec, Exchange};
+use crate::jobs::{self, FetchTasks, JobProgress, JobRegistry, JobView};
+use crate::portfolio::{self, MarketData};
+use crate::settings::ExchangeSettings;
+use crate::utility::{self, metrics, upstream, ApiResponse, Readiness};
+use anyhow::{anyhow, Result};
 use axum::{
     extract::{Query, State},
     http::StatusCode,
+    middleware,
     response::Json,
     routing::{get, post},
     Router,
@@ -13,9 +25,11 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
+use std::time::Instant;
+use tokio::sync::{RwLock, Semaphore};
 use tower_http::cors::CorsLayer;
+use tracing::{info, instrument, warn};
+use utoipa::{IntoParams, OpenApi, ToSchema};
 use chrono::{NaiveDate};
 
 // -----------------------------------------------
@@ -27,19 +41,22 @@ pub struct TickerListQuery {
     // No parameters needed for ticker list
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct OptionChainQuery {
     pub commodity: String,
     pub expiry: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct OptionQuoteQuery {
     pub commodity: String,
     pub expiry: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SpecificOptionQuoteQuery {
     pub commodity: String,
     pub expiry: String,
@@ -47,7 +64,23 @@ pub struct SpecificOptionQuoteQuery {
     pub strike_price: String, // e.g., "1120.00"
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct VolatilitySurfaceQuery {
+    pub commodity: String,
+    pub max_expiries: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct StrategyRequest {
+    pub commodity: String,
+    pub expiry: String,
+    pub legs: Vec<StrategyLegRequest>,
+    pub lot_size: Option<f64>,  // Defaults to the registry point value
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct HistoricDataQuery {
     pub symbol: String,
     pub expiry: String,
@@ -56,14 +89,8 @@ pub struct HistoricDataQuery {
     pub instrument_name: String, // e.g., "OPTFUT", "FUTCOM"
     pub option_type: Option<String>, // e.g., "CE", "PE" - only for OPTFUT
     pub strike: Option<String>, // e.g., "1120.00" - only for OPTFUT
-}
-
-#[derive(Debug, Serialize)]
-pub struct ApiResponse<T> {
-    pub success: bool,
-    pub data: Option<T>,
-    pub error: Option<String>,
-    pub processing_time_ms: Option<u64>,
+    pub window: Option<usize>, // Rolling window for the normalized series
+    pub indicators: Option<String>, // e.g. "sma:20,ema:9,rsi:14,atr:14,bb:20:2,vwap,oi_sma:5"
 }
 
 #[derive(Debug, Serialize)]
@@ -73,13 +100,15 @@ pub struct TickerListResponse {
     pub total_tickers: usize,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct BatchAnalysisResponse {
     pub summary: BatchSummary,
     pub rules_output: Vec<super::rules::McxRulesOutput>,
+    pub buildup_summary: Vec<SymbolBuildup>,
+    pub iv_ranks: Vec<SymbolIvRank>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct BatchSummary {
     pub total_tickers: usize,
     pub total_unique_symbols: usize,
@@ -116,11 +145,64 @@ pub struct ProductData {
 }
 
 // Enhanced response structure to include latest expiry
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct EnhancedSingleAnalysisResponse {
     #[serde(flatten)]
     pub analysis: processor::McxSingleAnalysisResponse,
     pub latest_future_expiry: Option<String>,
+    pub contract_spec: Option<ContractSpec>,
+    pub ce_oi_notional: Option<f64>,
+    pub pe_oi_notional: Option<f64>,
+    pub iv_rank: Option<IvRankStats>,
+}
+
+impl EnhancedSingleAnalysisResponse {
+    /// Attach the commodity's contract spec to the analysis and its alerts
+    fn new(
+        mut analysis: processor::McxSingleAnalysisResponse,
+        latest_future_expiry: Option<String>,
+        contract_spec: Option<ContractSpec>,
+    ) -> Self {
+        if let Some(alerts) = analysis.alerts.as_mut() {
+            alerts.contract_spec = contract_spec.clone();
+        }
+
+        let oi_notional = |oi: f64| {
+            contract_spec.as_ref().map(|spec| spec.notional(analysis.underlying_value, oi))
+        };
+        let ce_oi_notional = oi_notional(analysis.ce_oi);
+        let pe_oi_notional = oi_notional(analysis.pe_oi);
+
+        Self {
+            analysis,
+            latest_future_expiry,
+            contract_spec,
+            ce_oi_notional,
+            pe_oi_notional,
+            iv_rank: None,
+        }
+    }
+
+    /// Attach IV rank/percentile and the extreme IV percentile alert
+    fn with_iv_rank(mut self, iv_rank: Option<IvRankStats>) -> Self {
+        let analysis = &mut self.analysis;
+        let expiry = analysis.processed_data.first()
+            .and_then(|opt| opt.expiry_date.clone())
+            .unwrap_or_default();
+
+        let alert = iv_rank.as_ref().and_then(|iv| {
+            super::rules::check_mcx_iv_percentile_rule(&analysis.symbol, &expiry, iv, analysis.spread, analysis.days_to_expiry)
+        });
+        if let Some(alert) = alert {
+            let output = super::rules::append_mcx_alert(
+                analysis.alerts.take(), alert, &analysis.timestamp, analysis.underlying_value,
+            );
+            analysis.alerts = Some(super::rules::McxRulesOutput { contract_spec: self.contract_spec.clone(), ..output });
+        }
+
+        self.iv_rank = iv_rank;
+        self
+    }
 }
 
 // Historic data processing structures
@@ -177,26 +259,369 @@ pub struct HistoricSummary {
 #[derive(Clone)]
 pub struct AppState {
     client: Arc<MCXClient>,
-    cache: Arc<RwLock<Cache>>,
+    cache: Cache,
+    contracts: Arc<RwLock<ContractRegistry>>,
+    jobs: JobRegistry<BatchAnalysisResponse>,
+    market: Arc<MarketData>,  // Paper portfolio marks (shares this server's client)
 }
 
-#[derive(Default)]
+const BATCH_JOB_KIND: &str = "mcx-batch";
+
+/// Upstream responses, one cache per resource (policies from `[mcx.cache]` settings)
+#[derive(Clone)]
 struct Cache {
-    ticker_list: Option<(Vec<Ticker>, Instant)>,
-    option_chains: HashMap<String, (OptionChainResponse, Instant)>,
-    future_quotes: HashMap<String, (serde_json::Value, Instant)>,
-    option_quotes: HashMap<String, (serde_json::Value, Instant)>,
-    future_symbols: Option<(serde_json::Value, Instant)>,
-    historic_data: HashMap<String, (serde_json::Value, Instant)>,
+    ticker_list: TtlCache<(), Vec<Ticker>>,
+    option_chains: TtlCache<(String, String), OptionChainResponse>,  // (commodity, expiry)
+    future_quotes: TtlCache<(String, String), serde_json::Value>,  // (commodity, expiry), enriched
+    option_quotes: TtlCache<String, serde_json::Value>,
+    future_symbols: TtlCache<(), serde_json::Value>,  // Raw; processed per request
+    historic_data: TtlCache<String, HistoricalSeries>,  // Per contract, strike and window; indicators added per request
 }
 
-const CACHE_DURATION: Duration = Duration::from_secs(300); // 5 minutes
+impl Cache {
+    fn new(settings: &ExchangeSettings) -> Self {
+        Self {
+            ticker_list: TtlCache::new("mcx_ticker_list", settings.cache_policy("ticker_list")),
+            option_chains: TtlCache::new("mcx_option_chains", settings.cache_policy("option_chains")),
+            future_quotes: TtlCache::new("mcx_future_quotes", settings.cache_policy("future_quotes")),
+            option_quotes: TtlCache::new("mcx_option_quotes", settings.cache_policy("option_quotes")),
+            future_symbols: TtlCache::new("mcx_future_symbols", settings.cache_policy("future_symbols")),
+            historic_data: TtlCache::new("mcx_historic_data", settings.cache_policy("historic_data")),
+        }
+    }
+
+    fn stats(&self) -> Vec<CacheStats> {
+        vec![
+            self.ticker_list.stats(),
+            self.option_chains.stats(),
+            self.future_quotes.stats(),
+            self.option_quotes.stats(),
+            self.future_symbols.stats(),
+            self.historic_data.stats(),
+        ]
+    }
+}
 
 impl AppState {
     pub fn new() -> Result<Self> {
+        let client = Arc::new(MCXClient::new()?);
         Ok(Self {
-            client: Arc::new(MCXClient::new()?),
-            cache: Arc::new(RwLock::new(Cache::default())),
+            cache: Cache::new(client.settings()),
+            client: Arc::clone(&client),
+            contracts: Arc::new(RwLock::new(ContractRegistry::load())),
+            jobs: JobRegistry::new(),
+            market: Arc::new(MarketData::with_clients(Arc::new(NSEClient::new()?), client)),
+        })
+    }
+
+    pub fn settings(&self) -> &ExchangeSettings {
+        self.client.settings()
+    }
+
+    async fn tickers(&self) -> Result<Vec<Ticker>> {
+        let client = Arc::clone(&self.client);
+        self.cache.ticker_list.get_or_fetch((), move || async move { client.fetch_ticker_list().await }).await
+    }
+
+    async fn option_chain(&self, commodity: &str, expiry: &str) -> Result<OptionChainResponse> {
+        let client = Arc::clone(&self.client);
+        let key = (commodity.to_string(), expiry.to_string());
+        let (commodity, expiry) = key.clone();
+        self.cache.option_chains
+            .get_or_fetch(key, move || async move { client.fetch_option_chain(&commodity, &expiry).await })
+            .await
+    }
+
+    async fn future_symbols(&self) -> Result<serde_json::Value> {
+        let client = Arc::clone(&self.client);
+        self.cache.future_symbols.get_or_fetch((), move || async move { client.fetch_future_symbols().await }).await
+    }
+
+    /// Refresh whatever is about to expire: the ticker list, future symbols and the
+    /// nearest-expiry chain of each hot commodity
+    async fn prewarm(&self, symbols: &[String]) -> PrewarmRound {
+        let margin = self.settings().prewarm_schedule().interval;
+        let mut round = PrewarmRound::default();
+
+        let tickers = if self.cache.ticker_list.expires_within(&(), margin) {
+            let client = Arc::clone(&self.client);
+            round.record(self.cache.ticker_list.refresh((), || async move { client.fetch_ticker_list().await }).await)
+        } else {
+            self.tickers().await.ok()
+        };
+
+        if self.cache.future_symbols.expires_within(&(), margin) {
+            let client = Arc::clone(&self.client);
+            round.record(self.cache.future_symbols.refresh((), || async move { client.fetch_future_symbols().await }).await);
+        }
+
+        let Some(tickers) = tickers else {
+            return round;
+        };
+        for symbol in symbols {
+            let nearest_expiry = MCXClient::get_expiries_for_symbol(&tickers, symbol)
+                .into_iter()
+                .filter_map(|expiry| processor::calculate_days_to_expiry(&expiry).ok().map(|days| (days, expiry)))
+                .filter(|(days, _)| *days >= 0)
+                .min_by_key(|(days, _)| *days)
+                .map(|(_, expiry)| expiry);
+            let Some(expiry) = nearest_expiry else {
+                continue;
+            };
+
+            let key = (symbol.clone(), expiry.clone());
+            if self.cache.option_chains.expires_within(&key, margin) {
+                let client = Arc::clone(&self.client);
+                round.record(self.cache.option_chains.refresh(key, || async move {
+                    client.fetch_option_chain(symbol, &expiry).await
+                }).await);
+            }
+        }
+        round
+    }
+
+    /// IV rank/percentile of an ATM IV against the recorded history, without storing it
+    async fn lookup_iv_rank(&self, symbol: &str, date: NaiveDate, atm_iv: f64) -> Option<IvRankStats> {
+        let path = IvHistoryStore::path();
+        let symbol = symbol.to_string();
+        let ranked = tokio::task::spawn_blocking(move || {
+            IvHistoryStore::load_file(&path).map(|store| store.rank(Exchange::Mcx, &symbol, date, atm_iv))
+        })
+        .await;
+        match ranked.map_err(anyhow::Error::from).and_then(|result| result) {
+            Ok(ranks) => ranks,
+            Err(e) => {
+                warn!("Failed to read IV history: {}", e);
+                None
+            }
+        }
+    }
+
+    /// IV rank/percentile of the analysis' ATM IV against the batch-recorded history
+    async fn analysis_iv_rank(&self, analysis: &processor::McxSingleAnalysisResponse) -> Option<IvRankStats> {
+        let atm_iv = processor::calculate_expected_move(&analysis.processed_data, analysis.underlying_value)?.atm_iv?;
+        let date = analytics::observation_date(&analysis.timestamp);
+        self.lookup_iv_rank(&analysis.symbol, date, atm_iv).await
+    }
+
+    /// Batch analysis for the latest expiry per symbol, reporting to `progress`
+    #[instrument(skip_all, fields(exchange = "mcx"))]
+    async fn batch_analysis(&self, progress: Arc<JobProgress>) -> Result<BatchAnalysisResponse> {
+        let start_time = Instant::now();
+
+        // Step 1: Fetch all MCX tickers
+        progress.set_stage("tickers");
+        let all_tickers = self.client.fetch_ticker_list().await
+            .map_err(|e| {
+                metrics::registry().record_batch_failure("mcx");
+                anyhow!("Failed to fetch ticker list: {}", e)
+            })?;
+
+        let total_tickers = all_tickers.len();
+        let unique_symbols = MCXClient::get_unique_symbols(&all_tickers);
+        let total_unique_symbols = unique_symbols.len();
+
+        // Step 2: Filter to only latest expiry per symbol
+        let filtered_tickers = MCXClient::filter_latest_expiry_per_symbol(all_tickers);
+        let filtered_count = filtered_tickers.len();
+
+        info!(
+            total_tickers,
+            unique_symbols = total_unique_symbols,
+            filtered = filtered_count,
+            "Batch analysis: {} tickers, {} unique symbols, {} latest expiry ({:.1}% reduction)",
+            total_tickers,
+            total_unique_symbols,
+            filtered_count,
+            (1.0 - (filtered_count as f64 / total_tickers as f64)) * 100.0
+        );
+
+        let max_concurrent = self.settings().concurrency(config::is_ci_environment());
+
+        // Step 3: Bulk process filtered tickers (only latest expiry per symbol)
+        progress.set_stage("fetching");
+        let results = self.client.clone()
+            .fetch_all_option_chains_tracked(filtered_tickers.clone(), max_concurrent, Arc::clone(&progress))
+            .await;
+        progress.check_cancelled()?;
+        progress.set_stage("processing");
+
+        // Step 4: Process results and apply processor + rules
+        let mut batch_results = Vec::new();
+        let mut successful_count = 0;
+        let mut failed_count = 0;
+        let mut batch_for_rules = Vec::new();
+        let mut buildup_summary = Vec::new();
+        let mut iv_entries = Vec::new();
+        let mut iv_context = HashMap::new();
+
+        for (ticker, result) in filtered_tickers.iter().zip(results.iter()) {
+            match result {
+                Ok((_, chain)) => {
+                    // Process the MCX option chain data
+                    // Get underlying value from first available data point  
+                    let underlying_value = chain.d.data.iter()
+                        .find_map(|d| d.underlying_value)
+                        .unwrap_or(0.0);
+
+                    match processor::process_mcx_option_data(
+                        chain.d.data.clone(),
+                        underlying_value,
+                        &ticker.expiry_date,
+                    ) {
+                        Ok((processed_data, spread, days_to_expiry, _ce_oi, _pe_oi)) => {
+                            buildup_summary.push(SymbolBuildup {
+                                symbol: ticker.symbol.clone(),
+                                counts: processor::count_buildups(&processed_data),
+                            });
+
+                            if let Some(atm_iv) = processor::calculate_expected_move(&processed_data, underlying_value)
+                                .and_then(|m| m.atm_iv)
+                            {
+                                // Alerts keep the raw AsOn like the strike rules; the date needs it converted
+                                let timestamp = chain.d.summary.as_on.clone().unwrap_or_default();
+                                let date = analytics::observation_date(&processor::convert_mcx_timestamp(&timestamp));
+                                iv_entries.push((ticker.symbol.clone(), date, atm_iv));
+                                iv_context.insert(ticker.symbol.clone(), (
+                                    processed_data.first().and_then(|opt| opt.expiry_date.clone()).unwrap_or_default(),
+                                    days_to_expiry,
+                                    spread,
+                                    timestamp,
+                                    underlying_value,
+                                ));
+                            }
+
+                            // Store for rules processing
+                            batch_for_rules.push((
+                                ticker.symbol.clone(),
+                                chain.d.summary.as_on.clone().unwrap_or_else(|| "".to_string()),
+                                underlying_value,
+                                processed_data,
+                                spread,
+                            ));
+
+                            successful_count += 1;
+                        }
+                        Err(e) => {
+                            failed_count += 1;
+                            batch_results.push(BatchResult {
+                                ticker: ticker.clone(),
+                                rules_output: None,
+                                error: Some(format!("Processing error: {}", e)),
+                            });
+                        }
+                    }
+                }
+                Err(e) => {
+                    failed_count += 1;
+                    batch_results.push(BatchResult {
+                        ticker: ticker.clone(),
+                        rules_output: None,
+                        error: Some(e.to_string()),
+                    });
+                }
+            }
+        }
+
+        // Step 5: IV rank/percentile from the daily ATM IV history (only batch runs record: one nearest-expiry IV per symbol and day)
+        let mut iv_ranks: Vec<SymbolIvRank> = analytics::record_daily_atm_ivs(Exchange::Mcx, iv_entries)
+            .await
+            .into_iter()
+            .map(|(symbol, stats)| SymbolIvRank { symbol, stats })
+            .collect();
+        iv_ranks.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        let mut iv_alerts = Vec::new();
+        for row in &iv_ranks {
+            if let Some((expiry, days_to_expiry, spread, timestamp, underlying_value)) = iv_context.remove(&row.symbol)
+                && let Some(alert) = super::rules::check_mcx_iv_percentile_rule(&row.symbol, &expiry, &row.stats, spread, days_to_expiry)
+            {
+                iv_alerts.push((timestamp, underlying_value, alert));
+            }
+        }
+
+        // Step 6: Run rules on all successfully processed securities
+        let mut rules_outputs = super::rules::run_mcx_batch_rules(batch_for_rules);
+        super::rules::merge_mcx_symbol_alerts(&mut rules_outputs, iv_alerts);
+        {
+            let contracts = self.contracts.read().await;
+            for output in rules_outputs.iter_mut() {
+                output.contract_spec = contracts.mcx(&output.symbol).cloned();
+            }
+        }
+
+        // Step 7: Add rules outputs to batch results (only securities with alerts)
+        for rules_output in rules_outputs {
+            // Find the corresponding ticker for this symbol
+            if let Some(ticker) = filtered_tickers.iter().find(|t| t.symbol == rules_output.symbol) {
+                batch_results.push(BatchResult {
+                    ticker: ticker.clone(),
+                    rules_output: Some(rules_output),
+                    error: None,
+                });
+            }
+        }
+
+        // Calculate alerts count
+        let securities_with_alerts = batch_results.iter()
+            .filter(|r| r.rules_output.is_some())
+            .count();
+
+        let total_alerts: usize = batch_results.iter()
+            .filter_map(|r| r.rules_output.as_ref())
+            .map(|r| r.alerts.len())
+            .sum();
+
+        let mut summary = BatchSummary {
+            total_tickers,
+            total_unique_symbols,
+            filtered_latest_expiry: filtered_count,
+            successful: successful_count,
+            failed: failed_count,
+            securities_with_alerts,
+            total_alerts,
+            processing_time_ms: start_time.elapsed().as_millis() as u64,
+        };
+
+        // Extract only the rules outputs (alerts) for the response
+        let mut rules_outputs: Vec<super::rules::McxRulesOutput> = batch_results
+            .into_iter()
+            .filter_map(|r| r.rules_output)
+            .collect();
+
+        // Risk alerts for open paper positions join the market alerts
+        match portfolio::book_risk_alerts(&self.market, Exchange::Mcx).await {
+            Ok(risk) if !risk.mcx.is_empty() => {
+                portfolio::merge_mcx_risk_alerts(&mut rules_outputs, risk.mcx);
+                summary.securities_with_alerts = rules_outputs.len();
+                summary.total_alerts = rules_outputs.iter().map(|r| r.alerts.len()).sum();
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Skipped portfolio risk alerts: {}", e),
+        }
+
+        metrics::registry().record_batch("mcx", successful_count, failed_count);
+        metrics::registry().record_alerts(
+            "mcx",
+            rules_outputs.iter().flat_map(|r| r.alerts.iter().map(|a| a.alert_type.as_str())),
+        );
+
+        info!(
+            successful = successful_count,
+            failed = failed_count,
+            securities_with_alerts,
+            total_alerts,
+            processing_time_ms = summary.processing_time_ms,
+            "Batch analysis completed: {}/{} successful, {} alerts",
+            successful_count,
+            filtered_count,
+            total_alerts
+        );
+
+        Ok(BatchAnalysisResponse {
+            summary,
+            rules_output: rules_outputs,
+            buildup_summary,
+            iv_ranks,
         })
     }
 }
@@ -228,39 +653,28 @@ fn find_earliest_expiry(expiry_dates: &[String]) -> Option<String> {
 // -----------------------------------------------
 
 /// GET /mcx_health - Health check endpoint
+#[utoipa::path(get, path = "/mcx_health", tag = "mcx", responses((status = 200, description = "Server is up", body = String)))]
 async fn health() -> &'static str {
     "MCX server -> OK"
 }
 
+/// GET /ready - Readiness: upstream session, circuit breaker and cache freshness
+#[utoipa::path(get, path = "/ready", tag = "mcx", responses(
+    (status = 200, description = "Ready, starting or degraded", body = Readiness),
+    (status = 503, description = "Server is up but MCX is blocking requests (circuit open)", body = Readiness),
+))]
+async fn ready(State(app_state): State<AppState>) -> Readiness {
+    let upstream = upstream::health(Exchange::Mcx).status(&app_state.settings().circuit);
+    Readiness::evaluate(Exchange::Mcx, upstream, &app_state.cache.stats())
+}
+
 /// GET /api/mcx/tickers - Get all available MCX tickers
+#[utoipa::path(get, path = "/api/mcx/tickers", tag = "mcx", responses((status = 200, description = "Tickers and unique symbols", body = ApiResponse<serde_json::Value>)))]
 async fn get_ticker_list(State(app_state): State<AppState>) -> Result<Json<ApiResponse<serde_json::Value>>, StatusCode> {
     let start_time = Instant::now();
 
-    // Check cache first
-    {
-        let cache = app_state.cache.read().await;
-        if let Some((tickers, cached_at)) = &cache.ticker_list {
-            if cached_at.elapsed() < CACHE_DURATION {
-                let processed_data = processor::process_mcx_tickers(tickers.clone());
-                return Ok(Json(ApiResponse {
-                    success: true,
-                    data: Some(processed_data),
-                    error: None,
-                    processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
-                }));
-            }
-        }
-    }
-
-    // Fetch from web scraping
-    match app_state.client.fetch_ticker_list().await {
+    match app_state.tickers().await {
         Ok(tickers) => {
-            // Update cache
-            {
-                let mut cache = app_state.cache.write().await;
-                cache.ticker_list = Some((tickers.clone(), Instant::now()));
-            }
-
             let processed_data = processor::process_mcx_tickers(tickers);
             Ok(Json(ApiResponse {
                 success: true,
@@ -279,15 +693,15 @@ async fn get_ticker_list(State(app_state): State<AppState>) -> Result<Json<ApiRe
 }
 
 /// GET /api/mcx/option-chain?commodity=COPPER&expiry=23DEC2025 - Get processed option chain for specific commodity and expiry
+#[utoipa::path(get, path = "/api/mcx/option-chain", tag = "mcx", params(OptionChainQuery), responses((status = 200, body = ApiResponse<EnhancedSingleAnalysisResponse>)))]
 async fn get_option_chain(
     Query(query): Query<OptionChainQuery>,
     State(app_state): State<AppState>,
 ) -> Result<Json<ApiResponse<EnhancedSingleAnalysisResponse>>, StatusCode> {
     let start_time = Instant::now();
-    let cache_key = format!("{}_{}", query.commodity, query.expiry);
 
-    // Step 1: Fetch future symbols to get latest expiry date
-    let latest_future_expiry = match app_state.client.fetch_future_symbols().await {
+    // Step 1: Future symbols (cached) give the latest futures expiry
+    let latest_future_expiry = match app_state.future_symbols().await {
         Ok(symbols_data) => {
             // Process the symbols data to find matching commodity
             match processor::process_mcx_future_symbols(symbols_data) {
@@ -314,71 +728,9 @@ async fn get_option_chain(
         Err(_) => None,
     };
 
-    // Step 2: Check cache for option chain (existing logic)
-    {
-        let cache = app_state.cache.read().await;
-        if let Some((option_chain, cached_at)) = cache.option_chains.get(&cache_key) {
-            if cached_at.elapsed() < CACHE_DURATION {
-                // Process the cached data
-                // Get underlying value from first available data point
-                let underlying_value = option_chain.d.data.iter()
-                    .find_map(|d| d.underlying_value)
-                    .unwrap_or(0.0);
-                    
-                match processor::process_mcx_option_data(
-                    option_chain.d.data.clone(),
-                    underlying_value,
-                    &query.expiry,
-                ) {
-                    Ok((processed_data, spread, days_to_expiry, ce_oi, pe_oi)) => {
-                        let analysis_response = processor::create_single_analysis_response(
-                            query.commodity.clone(),
-                            option_chain.d.summary.as_on.clone().unwrap_or_else(|| "".to_string()),
-                            underlying_value,
-                            processed_data,
-                            spread,
-                            days_to_expiry,
-                            ce_oi,
-                            pe_oi,
-                        );
-                        
-                        let enhanced_response = EnhancedSingleAnalysisResponse {
-                            analysis: analysis_response,
-                            latest_future_expiry: latest_future_expiry.clone(),
-                        };
-                        
-                        return Ok(Json(ApiResponse {
-                            success: true,
-                            data: Some(enhanced_response),
-                            error: None,
-                            processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
-                        }));
-                    }
-                    Err(e) => {
-                        return Ok(Json(ApiResponse {
-                            success: false,
-                            data: None,
-                            error: Some(format!("Failed to process option chain data: {}", e)),
-                            processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
-                        }));
-                    }
-                }
-            }
-        }
-    }
-
-    // Step 3: Fetch from API (existing logic)
-    match app_state.client.fetch_option_chain(&query.commodity, &query.expiry).await {
+    // Step 2: Option chain (cached)
+    match app_state.option_chain(&query.commodity, &query.expiry).await {
         Ok(option_chain) => {
-            // Update cache
-            {
-                let mut cache = app_state.cache.write().await;
-                cache.option_chains.insert(
-                    cache_key,
-                    (option_chain.clone(), Instant::now()),
-                );
-            }
-
             // Process the data
             // Get underlying value from first available data point
             let underlying_value = option_chain.d.data.iter()
@@ -402,10 +754,14 @@ async fn get_option_chain(
                         pe_oi,
                     );
                     
-                    let enhanced_response = EnhancedSingleAnalysisResponse {
-                        analysis: analysis_response,
+                    let contract_spec = app_state.contracts.read().await.mcx(&query.commodity).cloned();
+                    let iv_rank = app_state.analysis_iv_rank(&analysis_response).await;
+                    let enhanced_response = EnhancedSingleAnalysisResponse::new(
+                        analysis_response,
                         latest_future_expiry,
-                    };
+                        contract_spec,
+                    )
+                    .with_iv_rank(iv_rank);
                     
                     Ok(Json(ApiResponse {
                         success: true,
@@ -431,14 +787,77 @@ async fn get_option_chain(
     }
 }
 
-/// POST /api/mcx/batch-analysis - Run batch analysis for latest expiry per symbol only
+/// POST /api/mcx/batch-analysis - Start a batch analysis job (latest expiry per symbol); poll GET /jobs/{id}
+#[utoipa::path(post, path = "/api/mcx/batch-analysis", tag = "mcx", responses((status = 200, body = ApiResponse<JobView<BatchAnalysisResponse>>)))]
 async fn run_batch_analysis(
     State(app_state): State<AppState>,
+) -> Result<Json<ApiResponse<JobView<BatchAnalysisResponse>>>, StatusCode> {
+    let start_time = Instant::now();
+
+    let state = app_state.clone();
+    let id = app_state.jobs
+        .spawn(BATCH_JOB_KIND, |progress| async move { state.batch_analysis(progress).await })
+        .await;
+
+    Ok(Json(match app_state.jobs.get(&id).await {
+        Some(job) => ApiResponse {
+            success: true,
+            data: Some(job),
+            error: None,
+            processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
+        },
+        None => ApiResponse {
+            success: false,
+            data: None,
+            error: Some(format!("Job '{}' not found", id)),
+            processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
+        },
+    }))
+}
+
+/// GET /api/mcx/batch-analysis - Result of the most recent completed batch job
+#[utoipa::path(get, path = "/api/mcx/batch-analysis", tag = "mcx", responses((status = 200, body = ApiResponse<BatchAnalysisResponse>)))]
+async fn get_latest_batch_analysis(
+    State(app_state): State<AppState>,
 ) -> Result<Json<ApiResponse<BatchAnalysisResponse>>, StatusCode> {
     let start_time = Instant::now();
 
-    // Step 1: Fetch all MCX tickers
-    let all_tickers = match app_state.client.fetch_ticker_list().await {
+    Ok(Json(match app_state.jobs.latest_result(BATCH_JOB_KIND).await {
+        Some(response) => ApiResponse {
+            success: true,
+            data: Some(response),
+            error: None,
+            processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
+        },
+        None => ApiResponse {
+            success: false,
+            data: None,
+            error: Some("No completed batch analysis yet".to_string()),
+            processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
+        },
+    }))
+}
+
+/// GET /api/mcx/volatility-surface?commodity=CRUDEOIL - IV smile, skew and term structure across expiries
+#[utoipa::path(get, path = "/api/mcx/volatility-surface", tag = "mcx", params(VolatilitySurfaceQuery), responses((status = 200, body = ApiResponse<VolatilitySurface>)))]
+async fn get_volatility_surface(
+    Query(query): Query<VolatilitySurfaceQuery>,
+    State(app_state): State<AppState>,
+) -> Result<Json<ApiResponse<VolatilitySurface>>, StatusCode> {
+    let start_time = Instant::now();
+    let commodity = query.commodity.to_uppercase();
+    let max_expiries = match app_state.settings().surface_expiries(query.max_expiries) {
+        Ok(max_expiries) => max_expiries,
+        Err(e) => return Ok(Json(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(e.to_string()),
+            processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
+        })),
+    };
+
+    // Step 1: Ticker list (cached) gives every listed expiry for the commodity
+    let tickers = match app_state.tickers().await {
         Ok(tickers) => tickers,
         Err(e) => {
             return Ok(Json(ApiResponse {
@@ -450,183 +869,153 @@ async fn run_batch_analysis(
         }
     };
 
-    let total_tickers = all_tickers.len();
-    let unique_symbols = MCXClient::get_unique_symbols(&all_tickers);
-    let total_unique_symbols = unique_symbols.len();
-
-    // Step 2: Filter to only latest expiry per symbol
-    let filtered_tickers = MCXClient::filter_latest_expiry_per_symbol(all_tickers);
-    let filtered_count = filtered_tickers.len();
-
-    println!("📊 Batch Analysis Summary:");
-    println!("   Total tickers from MCX: {}", total_tickers);
-    println!("   Unique symbols: {}", total_unique_symbols);
-    println!("   Latest expiry filtered: {}", filtered_count);
-    println!("   Reduction: {:.1}%", 
-             (1.0 - (filtered_count as f64 / total_tickers as f64)) * 100.0);
-
-    let max_concurrent = if config::is_ci_environment() {
-        config::CI_MAX_CONCURRENT
-    } else {
-        config::DEFAULT_MAX_CONCURRENT
-    };
+    // Step 2: Keep live expiries, nearest first
+    let mut expiries: Vec<(String, i32)> = MCXClient::get_expiries_for_symbol(&tickers, &commodity)
+        .into_iter()
+        .filter_map(|expiry| {
+            processor::calculate_days_to_expiry(&expiry)
+                .ok()
+                .map(|days| (expiry, days))
+        })
+        .collect();
+    expiries.sort_by_key(|(_, days)| *days);
+    expiries.dedup();
+    expiries.truncate(max_expiries);
+
+    // Step 3: Fetch the chains concurrently, then build each smile in expiry order
+    let semaphore = Arc::new(Semaphore::new(app_state.settings().max_concurrent));
+    let mut tasks = FetchTasks::new();
+    for (expiry, _) in &expiries {
+        let (state, sem, commodity, expiry) = (app_state.clone(), Arc::clone(&semaphore), commodity.clone(), expiry.clone());
+        tasks.spawn(async move {
+            let _permit = sem.acquire_owned().await
+                .map_err(|e| anyhow!("Semaphore error: {}", e))?;
+            state.option_chain(&commodity, &expiry).await
+        });
+    }
+    let chains = tasks.join_all().await;
 
-    // Step 3: Bulk process filtered tickers (only latest expiry per symbol)
-    let results = app_state.client.clone()
-        .fetch_all_option_chains(filtered_tickers.clone(), max_concurrent)
-        .await;
+    let model = PricingModel::mcx();
+    let mut smiles = Vec::new();
+    let mut underlying_value = 0.0;
 
-    // Step 4: Process results and apply processor + rules
-    let mut batch_results = Vec::new();
-    let mut successful_count = 0;
-    let mut failed_count = 0;
-    let mut batch_for_rules = Vec::new();
-
-    for (ticker, result) in filtered_tickers.iter().zip(results.iter()) {
-        match result {
-            Ok((_, chain)) => {
-                // Process the MCX option chain data
-                // Get underlying value from first available data point  
-                let underlying_value = chain.d.data.iter()
+    for ((expiry, days_to_expiry), chain) in expiries.into_iter().zip(chains) {
+        match chain {
+            Ok(chain) => {
+                let expiry_underlying = chain.d.data.iter()
                     .find_map(|d| d.underlying_value)
                     .unwrap_or(0.0);
-                    
-                match processor::process_mcx_option_data(
-                    chain.d.data.clone(),
-                    underlying_value,
-                    &ticker.expiry_date,
-                ) {
-                    Ok((processed_data, spread, _days_to_expiry, _ce_oi, _pe_oi)) => {
-                        // Store for rules processing
-                        batch_for_rules.push((
-                            ticker.symbol.clone(),
-                            chain.d.summary.as_on.clone().unwrap_or_else(|| "".to_string()),
-                            underlying_value,
-                            processed_data,
-                            spread,
-                        ));
-                        
-                        successful_count += 1;
-                    }
-                    Err(e) => {
-                        failed_count += 1;
-                        batch_results.push(BatchResult {
-                            ticker: ticker.clone(),
-                            rules_output: None,
-                            error: Some(format!("Processing error: {}", e)),
-                        });
-                    }
+                if expiry_underlying <= 0.0 {
+                    continue;
                 }
+                if smiles.is_empty() {
+                    underlying_value = expiry_underlying;
+                }
+
+                let quotes = processor::smile_quotes(&chain.d.data);
+                smiles.push(analytics::build_expiry_smile(
+                    &model,
+                    &processor::convert_mcx_expiry_format(&expiry),
+                    days_to_expiry,
+                    expiry_underlying,
+                    &quotes,
+                ));
             }
             Err(e) => {
-                failed_count += 1;
-                batch_results.push(BatchResult {
-                    ticker: ticker.clone(),
-                    rules_output: None,
-                    error: Some(e.to_string()),
-                });
+                warn!("Failed to fetch {} chain for {}: {}", commodity, expiry, e);
             }
         }
     }
-    
-    // Step 5: Run rules on all successfully processed securities
-    let rules_outputs = super::rules::run_mcx_batch_rules(batch_for_rules);
-    
-    // Step 6: Add rules outputs to batch results (only securities with alerts)
-    for rules_output in rules_outputs {
-        // Find the corresponding ticker for this symbol
-        if let Some(ticker) = filtered_tickers.iter().find(|t| t.symbol == rules_output.symbol) {
-            batch_results.push(BatchResult {
-                ticker: ticker.clone(),
-                rules_output: Some(rules_output),
-                error: None,
-            });
-        }
-    }
-
-    // Calculate alerts count
-    let securities_with_alerts = batch_results.iter()
-        .filter(|r| r.rules_output.is_some())
-        .count();
-        
-    let total_alerts: usize = batch_results.iter()
-        .filter_map(|r| r.rules_output.as_ref())
-        .map(|r| r.alerts.len())
-        .sum();
-
-    let summary = BatchSummary {
-        total_tickers,
-        total_unique_symbols,
-        filtered_latest_expiry: filtered_count,
-        successful: successful_count,
-        failed: failed_count,
-        securities_with_alerts,
-        total_alerts,
-        processing_time_ms: start_time.elapsed().as_millis() as u64,
-    };
-
-    // Extract only the rules outputs (alerts) for the response
-    let rules_outputs: Vec<super::rules::McxRulesOutput> = batch_results
-        .into_iter()
-        .filter_map(|r| r.rules_output)
-        .collect();
 
-    println!("✅ Batch analysis completed:");
-    println!("   Successful: {}/{}", successful_count, filtered_count);
-    println!("   Failed: {}/{}", failed_count, filtered_count);
-    println!("   Securities with alerts: {}", securities_with_alerts);
-    println!("   Total alerts: {}", total_alerts);
-    println!("   Processing time: {}ms", summary.processing_time_ms);
+    if smiles.is_empty() {
+        return Ok(Json(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(format!("No option chains available for {}", commodity)),
+            processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
+        }));
+    }
 
     Ok(Json(ApiResponse {
         success: true,
-        data: Some(BatchAnalysisResponse {
-            summary,
-            rules_output: rules_outputs,
-        }),
+        data: Some(analytics::build_volatility_surface(&commodity, underlying_value, smiles)),
         error: None,
         processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
     }))
 }
 
+/// POST /api/mcx/strategy - Payoff, P&L curve, breakevens and greeks for a multi-leg strategy
+#[utoipa::path(post, path = "/api/mcx/strategy", tag = "mcx", request_body = StrategyRequest, responses((status = 200, body = ApiResponse<StrategyAnalysis>)))]
+async fn analyze_strategy(
+    State(app_state): State<AppState>,
+    Json(request): Json<StrategyRequest>,
+) -> Result<Json<ApiResponse<StrategyAnalysis>>, StatusCode> {
+    let start_time = Instant::now();
+    let commodity = request.commodity.to_uppercase();
+
+    let result = async {
+        let days_to_expiry = processor::calculate_days_to_expiry(&request.expiry)?;
+
+        let chain = app_state.option_chain(&commodity, &request.expiry).await?;
+
+        let underlying_value = chain.d.data.iter()
+            .find_map(|d| d.underlying_value)
+            .unwrap_or(0.0);
+        let quotes = processor::smile_quotes(&chain.d.data);
+        let lot_size = match request.lot_size {
+            Some(lot_size) => lot_size,
+            None => app_state.contracts.read().await
+                .mcx(&commodity)
+                .map(|spec| spec.point_value())
+                .unwrap_or(1.0),
+        };
+
+        analytics::analyze_strategy(
+            &PricingModel::mcx(),
+            &request.legs,
+            &quotes,
+            underlying_value,
+            days_to_expiry,
+            lot_size,
+        )
+    }
+    .await;
+
+    match result {
+        Ok(analysis) => Ok(Json(ApiResponse {
+            success: true,
+            data: Some(analysis),
+            error: None,
+            processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
+        })),
+        Err(e) => Ok(Json(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(e.to_string()),
+            processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
+        })),
+    }
+}
+
 /// GET /api/mcx/future-quote?commodity=ALUMINI&expiry=31DEC2025 - Get future quote for specific commodity and expiry
+#[utoipa::path(get, path = "/api/mcx/future-quote", tag = "mcx", params(OptionQuoteQuery), responses((status = 200, description = "MCX futures quote payload", body = ApiResponse<serde_json::Value>)))]
 async fn get_future_quote(
     Query(query): Query<OptionQuoteQuery>,
     State(app_state): State<AppState>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, StatusCode> {
     let start_time = Instant::now();
-    let cache_key = format!("quote_{}_{}", query.commodity, query.expiry);
-
-    // Check cache first
-    {
-        let cache = app_state.cache.read().await;
-        if let Some((quote_data, cached_at)) = cache.future_quotes.get(&cache_key) {
-            if cached_at.elapsed() < CACHE_DURATION {
-                return Ok(Json(ApiResponse {
-                    success: true,
-                    data: Some(quote_data.clone()),
-                    error: None,
-                    processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
-                }));
-            }
-        }
-    }
 
-    // Fetch from MCX API
-    match app_state.client.fetch_future_quote(&query.commodity, &query.expiry).await {
-        Ok(mut quote_data) => {
-
-             processor::enrich_mcx_future_quote(&mut quote_data);
-             
-            // Update cache
-            {
-                let mut cache = app_state.cache.write().await;
-                cache.future_quotes.insert(
-                    cache_key,
-                    (quote_data.clone(), Instant::now()),
-                );
-            }
+    let client = Arc::clone(&app_state.client);
+    let key = (query.commodity.clone(), query.expiry.clone());
+    let (commodity, expiry) = key.clone();
+    let quote = app_state.cache.future_quotes.get_or_fetch(key, move || async move {
+        let mut quote_data = client.fetch_future_quote(&commodity, &expiry).await?;
+        processor::enrich_mcx_future_quote(&mut quote_data);
+        Ok(quote_data)
+    });
 
+    match quote.await {
+        Ok(quote_data) => {
             Ok(Json(ApiResponse {
                 success: true,
                 data: Some(quote_data),
@@ -644,6 +1033,7 @@ async fn get_future_quote(
 }
 
 /// GET /api/mcx/option-quote?commodity=COPPER&expiry=23DEC2025&option_type=CE&strike_price=1120.00 - Get specific option quote
+#[utoipa::path(get, path = "/api/mcx/option-quote", tag = "mcx", params(SpecificOptionQuoteQuery), responses((status = 200, description = "MCX option quote payload", body = ApiResponse<serde_json::Value>)))]
 async fn get_option_quote(
     Query(query): Query<SpecificOptionQuoteQuery>,
     State(app_state): State<AppState>,
@@ -652,38 +1042,16 @@ async fn get_option_quote(
     let cache_key = format!("option_{}_{}_{}_{}",
         query.commodity, query.expiry, query.option_type, query.strike_price);
 
-    // Check cache first
-    {
-        let cache = app_state.cache.read().await;
-        if let Some((quote_data, cached_at)) = cache.option_quotes.get(&cache_key) {
-            if cached_at.elapsed() < CACHE_DURATION {
-                return Ok(Json(ApiResponse {
-                    success: true,
-                    data: Some(quote_data.clone()),
-                    error: None,
-                    processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
-                }));
-            }
-        }
-    }
+    let client = Arc::clone(&app_state.client);
+    let (commodity, expiry, option_type, strike_price) = (
+        query.commodity.clone(), query.expiry.clone(), query.option_type.clone(), query.strike_price.clone(),
+    );
+    let quote = app_state.cache.option_quotes.get_or_fetch(cache_key, move || async move {
+        client.fetch_option_quote(&commodity, &expiry, &option_type, &strike_price).await
+    });
 
-    // Fetch from MCX API
-    match app_state.client.fetch_option_quote(
-        &query.commodity,
-        &query.expiry,
-        &query.option_type,
-        &query.strike_price,
-    ).await {
+    match quote.await {
         Ok(quote_data) => {
-            // Update cache
-            {
-                let mut cache = app_state.cache.write().await;
-                cache.option_quotes.insert(
-                    cache_key,
-                    (quote_data.clone(), Instant::now()),
-                );
-            }
-
             Ok(Json(ApiResponse {
                 success: true,
                 data: Some(quote_data),
@@ -701,46 +1069,12 @@ async fn get_option_quote(
 }
 
 /// GET /api/mcx/future-symbols - Get available future symbols and expiry dates
+#[utoipa::path(get, path = "/api/mcx/future-symbols", tag = "mcx", responses((status = 200, description = "Future symbols with their expiry dates", body = ApiResponse<serde_json::Value>)))]
 async fn get_future_symbols(State(app_state): State<AppState>) -> Result<Json<ApiResponse<serde_json::Value>>, StatusCode> {
     let start_time = Instant::now();
 
-    // Check cache first
-    {
-        let cache = app_state.cache.read().await;
-        if let Some((data, cached_at)) = &cache.future_symbols {
-            if cached_at.elapsed() < CACHE_DURATION {
-                // Process the cached data
-                match processor::process_mcx_future_symbols(data.clone()) {
-                    Ok(processed_data) => {
-                        return Ok(Json(ApiResponse {
-                            success: true,
-                            data: Some(processed_data),
-                            error: None,
-                            processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
-                        }));
-                    }
-                    Err(e) => {
-                        return Ok(Json(ApiResponse {
-                            success: false,
-                            data: None,
-                            error: Some(format!("Failed to process future symbols data: {}", e)),
-                            processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
-                        }));
-                    }
-                }
-            }
-        }
-    }
-
-    // Fetch from MCX API
-    match app_state.client.fetch_future_symbols().await {
+    match app_state.future_symbols().await {
         Ok(data) => {
-            // Update cache with raw data
-            {
-                let mut cache = app_state.cache.write().await;
-                cache.future_symbols = Some((data.clone(), Instant::now()));
-            }
-
             // Process the data (parse JSON string and convert timestamps)
             match processor::process_mcx_future_symbols(data) {
                 Ok(processed_data) => {
@@ -771,87 +1105,50 @@ async fn get_future_symbols(State(app_state): State<AppState>) -> Result<Json<Ap
 }
 
 /// GET /api/mcx/historic-data?symbol=COPPER&expiry=23DEC2025&from_date=20251215&to_date=20251219&instrument_name=OPTFUT&option_type=CE&strike=1120.00 - Get historic data
+#[utoipa::path(get, path = "/api/mcx/historic-data", tag = "mcx", params(HistoricDataQuery), responses((status = 200, description = "Normalized OHLC/OI series with any requested indicators", body = ApiResponse<HistoricalSeries>)))]
 async fn get_historic_data(
     Query(query): Query<HistoricDataQuery>,
     State(app_state): State<AppState>,
-) -> Result<Json<ApiResponse<serde_json::Value>>, StatusCode> {
+) -> Result<Json<ApiResponse<HistoricalSeries>>, StatusCode> {
     let start_time = Instant::now();
-    
-    // Create cache key including optional parameters
-    let cache_key = if let (Some(option_type), Some(strike)) = (&query.option_type, &query.strike) {
-        format!("historic_{}_{}_{}_{}_{}_{}_{}",
-            query.symbol, query.expiry, query.from_date, query.to_date,
-            query.instrument_name, option_type, strike)
-    } else {
-        format!("historic_{}_{}_{}_{}_{}", 
-            query.symbol, query.expiry, query.from_date, query.to_date, query.instrument_name)
-    };
-
-    // Check cache first
-    {
-        let cache = app_state.cache.read().await;
-        if let Some((data, cached_at)) = cache.historic_data.get(&cache_key) {
-            if cached_at.elapsed() < CACHE_DURATION {
-                // Process cached data if filtering is needed
-                match processor::process_historic_data_response(data.clone(), &query.option_type, &query.strike) {
-                    Ok(processed_data) => {
-                        return Ok(Json(ApiResponse {
-                            success: true,
-                            data: Some(processed_data),
-                            error: None,
-                            processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
-                        }));
-                    }
-                    Err(e) => {
-                        return Ok(Json(ApiResponse {
-                            success: false,
-                            data: None,
-                            error: Some(format!("Failed to process cached historic data: {}", e)),
-                            processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
-                        }));
-                    }
-                }
-            }
-        }
-    }
 
-    // Fetch from MCX API
-    match app_state.client.fetch_historic_data(
-        &query.symbol, 
-        &query.expiry, 
-        &query.from_date, 
-        &query.to_date, 
-        &query.instrument_name
-    ).await {
-        Ok(data) => {
-            // Process the response data
-            match processor::process_historic_data_response(data.clone(), &query.option_type, &query.strike) {
-                Ok(processed_data) => {
-                    // Update cache with processed data
-                    {
-                        let mut cache = app_state.cache.write().await;
-                        cache.historic_data.insert(
-                            cache_key,
-                            (processed_data.clone(), Instant::now()),
-                        );
-                    }
+    let indicators = match IndicatorSpec::parse_list(query.indicators.as_deref().unwrap_or_default()) {
+        Ok(indicators) => indicators,
+        Err(e) => return Ok(Json(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(e.to_string()),
+            processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
+        })),
+    };
 
-                    Ok(Json(ApiResponse {
-                        success: true,
-                        data: Some(processed_data),
-                        error: None,
-                        processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
-                    }))
-                }
-                Err(e) => {
-                    Ok(Json(ApiResponse {
-                        success: false,
-                        data: None,
-                        error: Some(format!("Failed to process historic data: {}", e)),
-                        processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
-                    }))
-                }
-            }
+    let meta = SeriesMeta {
+        exchange: "MCX".to_string(),
+        symbol: query.symbol.clone(),
+        instrument: query.instrument_name.clone(),
+        expiry: query.expiry.clone(),
+        strike: query.strike.as_deref().and_then(|s| s.parse().ok()),
+        option_type: query.option_type.clone(),
+    };
+    let window = query.window.unwrap_or(DEFAULT_ROLLING_WINDOW);
+    let cache_key = format!("{}_{}_{}_{}_{}_{}_{}_{}",
+        meta.symbol, meta.expiry, query.from_date, query.to_date, meta.instrument,
+        meta.option_type.as_deref().unwrap_or_default(), meta.strike.unwrap_or_default(), window);
+    let client = Arc::clone(&app_state.client);
+    let (from_date, to_date) = (query.from_date.clone(), query.to_date.clone());
+    let series = app_state.cache.historic_data.get_or_fetch(cache_key, move || async move {
+        client.fetch_historic_series(meta, &from_date, &to_date, window).await
+    });
+
+    match series.await {
+        Ok(mut series) => {
+            analytics::apply_indicators(&mut series, &indicators);
+            Ok(Json(ApiResponse {
+                success: true,
+                data: Some(series),
+                error: None,
+                processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
+            }))
         }
         Err(e) => Ok(Json(ApiResponse {
             success: false,
@@ -862,6 +1159,59 @@ async fn get_historic_data(
     }
 }
 
+/// GET /api/mcx/cache-stats - Entries, hit rates and evictions per server cache
+#[utoipa::path(get, path = "/api/mcx/cache-stats", tag = "mcx", responses((status = 200, body = ApiResponse<Vec<CacheStats>>)))]
+async fn get_cache_stats(State(app_state): State<AppState>) -> Result<Json<ApiResponse<Vec<CacheStats>>>, StatusCode> {
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(app_state.cache.stats()),
+        error: None,
+        processing_time_ms: None,
+    }))
+}
+
+// -----------------------------------------------
+// OPENAPI
+// -----------------------------------------------
+
+/// OpenAPI 3 document for the MCX routes, served at /openapi.json
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "MCX Analyzer API", description = "MCX option chain analysis, batch jobs and analytics"),
+    paths(
+        health,
+        ready,
+        get_ticker_list,
+        get_option_chain,
+        get_future_quote,
+        get_option_quote,
+        run_batch_analysis,
+        get_latest_batch_analysis,
+        get_future_symbols,
+        get_historic_data,
+        get_volatility_surface,
+        analyze_strategy,
+        get_cache_stats,
+        jobs::jobs_api::list_jobs,
+        jobs::jobs_api::get_job,
+        jobs::jobs_api::cancel_job,
+        portfolio::portfolio_api::get_summary,
+        portfolio::portfolio_api::get_risk_alerts,
+        portfolio::portfolio_api::list_positions,
+        portfolio::portfolio_api::open_position,
+        portfolio::portfolio_api::close_position,
+        portfolio::portfolio_api::delete_position,
+        utility::shutdown::request_shutdown,
+    ),
+    tags(
+        (name = "mcx", description = "MCX option chain analysis"),
+        (name = "jobs", description = "Background batch jobs"),
+        (name = "portfolio", description = "Paper positions shared by both servers"),
+        (name = "server", description = "Server lifecycle"),
+    )
+)]
+pub struct McxApiDoc;
+
 // -----------------------------------------------
 // SERVER SETUP
 // -----------------------------------------------
@@ -869,38 +1219,100 @@ async fn get_historic_data(
 pub async fn start_mcx_server(port: u16) -> Result<()> {
     let app_state = AppState::new()?;
 
+    // Keep the hot commodities warm so the first requests skip the upstream round trip
+    if app_state.settings().prewarm.enabled {
+        let symbols = prewarm::hot_symbols(app_state.settings());
+        info!(symbols = symbols.len(), "Pre-warming MCX caches");
+        let prewarm_state = app_state.clone();
+        prewarm::spawn("mcx", app_state.settings().prewarm_schedule(), move || {
+            let (state, symbols) = (prewarm_state.clone(), symbols.clone());
+            async move { state.prewarm(&symbols).await }
+        });
+    }
+
     let mcx_routes = Router::new()
         .route("/api/mcx/tickers", get(get_ticker_list))
         .route("/api/mcx/option-chain", get(get_option_chain))
         .route("/api/mcx/future-quote", get(get_future_quote))
         .route("/api/mcx/option-quote", get(get_option_quote))
-        .route("/api/mcx/batch-analysis", post(run_batch_analysis))
+        .route("/api/mcx/batch-analysis", post(run_batch_analysis).get(get_latest_batch_analysis))
         .route("/api/mcx/future-symbols", get(get_future_symbols))
-        .route("/api/mcx/historic-data", get(get_historic_data));
+        .route("/api/mcx/historic-data", get(get_historic_data))
+        .route("/api/mcx/volatility-surface", get(get_volatility_surface))
+        .route("/api/mcx/strategy", post(analyze_strategy))
+        .route("/api/mcx/cache-stats", get(get_cache_stats));
+
+    let portfolio_routes = portfolio::get_portfolio_routes(app_state.contracts.clone(), app_state.market.clone());
+    let job_routes = jobs::get_job_routes(app_state.jobs.clone());
+    let openapi_routes = utility::openapi_routes(&McxApiDoc::openapi())?;
+    let guard = utility::ApiGuard::new("mcx", &app_state.settings().server);
+    let addr = app_state.settings().server_addr(port);
+    let loopback_only = app_state.settings().server.is_loopback();
+
+    // Ctrl+C / SIGTERM / POST /shutdown: cancel running batch jobs (aborting their
+    // in-flight fetches), stop accepting connections and drain open requests
+    let shutdown = utility::Shutdown::new();
+    shutdown.listen_for_signals();
+    let (jobs_on_shutdown, shutdown_signal) = (app_state.jobs.clone(), shutdown.clone());
+    tokio::spawn(async move {
+        shutdown_signal.triggered().await;
+        let cancelled = jobs_on_shutdown.cancel_all().await;
+        if cancelled > 0 {
+            info!(cancelled, "Cancelled {} running job(s) for shutdown", cancelled);
+        }
+    });
 
     let app = Router::new()
         .route("/mcx_health", get(health))
+        .route("/ready", get(ready))
         .merge(mcx_routes)
-        .layer(CorsLayer::permissive())
-        .with_state(app_state);
+        .with_state(app_state)
+        .merge(portfolio_routes)
+        .merge(job_routes)
+        .merge(openapi_routes)
+        .merge(utility::shutdown::get_shutdown_routes(shutdown.clone()))
+        .route("/metrics", get(utility::metrics_handler))
+        .layer(middleware::from_fn_with_state(guard.clone(), utility::api_guard))
+        .layer(utility::logging::http_trace_layer())
+        .layer(CorsLayer::permissive());
 
-    let addr = format!("127.0.0.1:{}", port);
     let listener = tokio::net::TcpListener::bind(&addr).await?;
     
-    println!("🚀 MCX API Server running on http://{}", addr);
+    info!("🚀 MCX API Server running on http://{}", addr);
+    if guard.auth_enabled() {
+        info!("🔑 API key required (X-API-Key or Authorization: Bearer)");
+    } else if !loopback_only {
+        warn!("MCX API Server is reachable on {} without API keys", addr);
+    }
     println!("📋 Available MCX endpoints:");
     println!("   GET  /mcx_health");
+    println!("   GET  /ready (upstream session, circuit and cache freshness)");
+    println!("   GET  /metrics (Prometheus)");
     println!("   GET  /api/mcx/tickers");
     println!("   GET  /api/mcx/option-chain?commodity=COPPER&expiry=23DEC2025 (Processed Data + Latest Expiry)");
     println!("   GET  /api/mcx/future-quote?commodity=ALUMINI&expiry=31DEC2025");
     println!("   GET  /api/mcx/option-quote?commodity=COPPER&expiry=23DEC2025&option_type=CE&strike_price=1120.00");
-    println!("   POST /api/mcx/batch-analysis (Latest Expiry Only - Processed Data)");
+    println!("   POST /api/mcx/batch-analysis (Latest Expiry Only - returns a job id)");
+    println!("   GET  /api/mcx/batch-analysis (latest completed result)");
     println!("   GET  /api/mcx/future-symbols");
     println!("   GET  /api/mcx/historic-data?symbol=COPPER&expiry=23DEC2025&from_date=20251215&to_date=20251219&instrument_name=FUTCOM");
     println!("   GET  /api/mcx/historic-data?symbol=COPPER&expiry=23DEC2025&from_date=20251215&to_date=20251219&instrument_name=OPTFUT&option_type=CE&strike=1120.00");
+    println!("   GET  /api/mcx/historic-data?...&window=5&indicators=sma:20,ema:9,rsi:14,atr:14,bb:20:2,vwap,oi_sma:5");
+    println!("   GET  /api/mcx/volatility-surface?commodity=CRUDEOIL&max_expiries=4");
+    println!("   POST /api/mcx/strategy");
+    println!("   GET  /api/mcx/cache-stats");
+    utility::print_openapi_endpoints();
+    utility::shutdown::print_shutdown_endpoints();
+    jobs::print_job_endpoints();
+    portfolio::print_portfolio_endpoints();
     println!();
 
-    axum::serve(listener, app).await?;
+    let server = axum::serve(listener, app).with_graceful_shutdown(shutdown.clone().triggered());
+    tokio::select! {
+        result = server => result?,
+        _ = shutdown.drain_deadline() => {}
+    }
+    info!("MCX API Server stopped");
     Ok(())
 }
 
@@ -911,9 +1323,12 @@ pub fn get_mcx_routes() -> Router<AppState> {
         .route("/api/mcx/option-chain", get(get_option_chain))
         .route("/api/mcx/future-quote", get(get_future_quote))
         .route("/api/mcx/option-quote", get(get_option_quote))
-        .route("/api/mcx/batch-analysis", post(run_batch_analysis))
+        .route("/api/mcx/batch-analysis", post(run_batch_analysis).get(get_latest_batch_analysis))
         .route("/api/mcx/future-symbols", get(get_future_symbols))
         .route("/api/mcx/historic-data", get(get_historic_data))
+        .route("/api/mcx/volatility-surface", get(get_volatility_surface))
+        .route("/api/mcx/strategy", post(analyze_strategy))
+        .route("/api/mcx/cache-stats", get(get_cache_stats))
 }
 
 /// Get MCX app state for merging with existing server
@@ -1,15 +1,21 @@
 use super::config::{*};
 use super::models::{Ticker, OptionChainResponse};
+use super::processor;
+use super::rules;
+use crate::analytics::{HistoricalSeries, SeriesMeta};
+use crate::contracts::Exchange;
+use crate::jobs::{FetchTasks, JobProgress};
+use crate::settings::{self, ExchangeSettings};
+use crate::utility::{metrics, upstream};
 use anyhow::{anyhow, Result};
 use chrono::{Datelike, NaiveDate, Utc, Weekday};
 use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use std::future::Future;
 use std::sync::Arc;
-use std::time::Duration;
 use tokio::sync::Semaphore;
-use tokio_retry::strategy::ExponentialBackoff;
-use tokio_retry::Retry;
+use tracing::{debug, debug_span, info, instrument, warn, Instrument};
 
 // -----------------------------------------------
 // BHAVCOPY API STRUCTURES
@@ -87,19 +93,33 @@ struct BhavCopyPayload {
 // -----------------------------------------------
 pub struct MCXClient {
     client: Client,
+    settings: ExchangeSettings,
 }
 
 impl MCXClient {
     pub fn new() -> Result<Self> {
+        Self::with_settings(settings::current().mcx.clone())
+    }
+
+    pub fn with_settings(settings: ExchangeSettings) -> Result<Self> {
         let client = Client::builder()
             .cookie_store(true)
             .user_agent(USER_AGENT)
-            .timeout(HTTP_TIMEOUT)
+            .timeout(settings.http_timeout())
             .redirect(reqwest::redirect::Policy::limited(10))
             .gzip(true)
             .build()?;
         
-        Ok(Self { client })
+        Ok(Self { client, settings })
+    }
+
+    pub fn settings(&self) -> &ExchangeSettings {
+        &self.settings
+    }
+
+    /// One upstream fetch (retries included) through the MCX circuit breaker
+    async fn guarded<T>(&self, fetch: impl Future<Output = Result<T>>) -> Result<T> {
+        upstream::health(Exchange::Mcx).call(&self.settings.circuit, fetch).await
     }
 
     /// Get the most recent weekday date for fetching data
@@ -142,24 +162,22 @@ impl MCXClient {
 
 
     /// Fetch bhav copy data with automatic date fallback
+    #[instrument(skip_all)]
     async fn fetch_bhav_copy_with_fallback(&self) -> Result<BhavCopyResponse> {
         let mut data_date = Self::get_data_date();
         let max_attempts = 5; // Don't go back more than 5 days
         
         for attempt in 0..max_attempts {
-            println!("🔍 Attempt {}/{}: Trying to fetch MCX data for date: {}", attempt + 1, max_attempts, data_date);
+            info!("Attempt {}/{}: Trying to fetch MCX data for date: {}", attempt + 1, max_attempts, data_date);
             
             let payload = BhavCopyPayload {
                 date: data_date.clone(),
                 instrument_name: "OPTFUT".to_string(),
             };
 
-            let backoff = ExponentialBackoff::from_millis(RETRY_BASE_DELAY_MS)
-                .factor(RETRY_FACTOR)
-                .max_delay(Duration::from_secs(RETRY_MAX_DELAY_SECS))
-                .take(RETRY_MAX_ATTEMPTS);
+            let backoff = self.settings.retry.backoff();
 
-            let result = Retry::spawn(backoff, || async {
+            let result = self.guarded(metrics::retry_tracked(&metrics::endpoint_label(MCX_BHAVCOPY_API), backoff, || async {
                 let res = apply_standard_post_headers(
                     self.client.post(MCX_BHAVCOPY_API),
                     REFERER_BHAVCOPY
@@ -180,21 +198,21 @@ impl MCXClient {
                     
                     Ok(response)
                 } else if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
-                    anyhow::bail!("Retryable error: {}", status)
+                    anyhow::bail!(upstream::StatusError::new(status, ""))
                 } else {
                     let body = res.text().await.unwrap_or_default();
                     let preview: String = body.chars().take(200).collect();
-                    anyhow::bail!("Client error {}: {}", status, preview)
+                    anyhow::bail!(upstream::StatusError::new(status, preview))
                 }
-            }).await;
+            })).await;
 
             match result {
                 Ok(response) => {
                     if response.d.summary.count > 0 {
-                        println!("✅ Found {} OPTFUT entries for date {}", response.d.summary.count, data_date);
+                        info!(entries = response.d.summary.count, "✓ Found {} OPTFUT entries for date {}", response.d.summary.count, data_date);
                         return Ok(response);
                     } else {
-                        println!("⚠️  No data found for date {} (count: 0)", data_date);
+                        warn!("No data found for date {} (count: 0)", data_date);
                         
                         if attempt == max_attempts - 1 {
                             return Err(anyhow!("No data found after {} attempts", max_attempts));
@@ -207,16 +225,16 @@ impl MCXClient {
                         
                         let prev_date = Self::get_previous_weekday(current_date);
                         
-                        println!("📅 Moving from {} to previous weekday {}", 
-                                current_date.format("%Y-%m-%d"), 
-                                prev_date.format("%Y-%m-%d"));
-                        
                         data_date = prev_date.format("%Y%m%d").to_string();
-                        println!("🔄 Next attempt will use date: {}", data_date);
+                        info!(
+                            "Moving from {} to previous weekday {}",
+                            current_date.format("%Y-%m-%d"),
+                            prev_date.format("%Y-%m-%d")
+                        );
                     }
                 }
                 Err(e) => {
-                    println!("❌ Failed to fetch data for date {}: {}", data_date, e);
+                    warn!("Failed to fetch data for date {}: {}", data_date, e);
                     
                     if attempt == max_attempts - 1 {
                         return Err(anyhow!("Failed to fetch MCX data after {} attempts. Last error: {}", max_attempts, e));
@@ -228,12 +246,12 @@ impl MCXClient {
                     
                     let prev_date = Self::get_previous_weekday(current_date);
                     
-                    println!("📅 After error, moving from {} to previous weekday {}", 
-                            current_date.format("%Y-%m-%d"), 
-                            prev_date.format("%Y-%m-%d"));
-                    
                     data_date = prev_date.format("%Y%m%d").to_string();
-                    println!("🔄 Next attempt will use date: {}", data_date);
+                    info!(
+                        "After error, moving from {} to previous weekday {}",
+                        current_date.format("%Y-%m-%d"),
+                        prev_date.format("%Y-%m-%d")
+                    );
                 }
             }
         }
@@ -282,6 +300,7 @@ impl MCXClient {
     }
 
     /// Fetch option chain data
+    #[instrument(level = "debug", skip(self))]
     pub async fn fetch_option_chain(
         &self,
         commodity: &str,
@@ -292,12 +311,9 @@ impl MCXClient {
             "Expiry": expiry
         });
         
-        let backoff = ExponentialBackoff::from_millis(RETRY_BASE_DELAY_MS)
-            .factor(RETRY_FACTOR)
-            .max_delay(Duration::from_secs(RETRY_MAX_DELAY_SECS))
-            .take(RETRY_MAX_ATTEMPTS);
+        let backoff = self.settings.retry.backoff();
 
-        let result = Retry::spawn(backoff, || async {
+        let result = self.guarded(metrics::retry_tracked(&metrics::endpoint_label(MCX_OPTION_CHAIN_API), backoff, || async {
             let res = apply_standard_post_headers(
                 self.client.post(MCX_OPTION_CHAIN_API),
                 REFERER_OPTION_CHAIN
@@ -361,9 +377,9 @@ impl MCXClient {
                 
                 Ok(legacy_response)
             } else {
-                anyhow::bail!("HTTP error: {}", status)
+                anyhow::bail!(upstream::StatusError::new(status, ""))
             }
-        }).await;
+        })).await;
         
         match result {
             Ok(response) => {
@@ -371,7 +387,7 @@ impl MCXClient {
                 Ok(response)
             }
             Err(e) => {
-                println!("❌ Failed to fetch option chain for {}: {} - {}", commodity,expiry, e);
+                warn!("Failed to fetch option chain for {}: {} - {}", commodity, expiry, e);
                 Err(e)
             }
         }
@@ -383,36 +399,52 @@ impl MCXClient {
         tickers: Vec<Ticker>,
         max_concurrent: usize,
     ) -> Vec<Result<(Ticker, OptionChainResponse)>> {
-        println!("📈 Batch fetching {} option chains with {} max concurrent", 
-                 tickers.len(), max_concurrent);
+        self.fetch_all_option_chains_tracked(tickers, max_concurrent, JobProgress::new()).await
+    }
+
+    /// Same as `fetch_all_option_chains`, reporting each ticker to `progress`
+    /// and skipping fetches not yet started once it is cancelled.
+    /// Dropping the returned future aborts the fetches still in flight.
+    #[instrument(skip_all, fields(tickers = tickers.len(), max_concurrent))]
+    pub async fn fetch_all_option_chains_tracked(
+        self: Arc<Self>,
+        tickers: Vec<Ticker>,
+        max_concurrent: usize,
+        progress: Arc<JobProgress>,
+    ) -> Vec<Result<(Ticker, OptionChainResponse)>> {
+        progress.add_total(tickers.len());
+        info!("Batch fetching {} option chains with {} max concurrent", tickers.len(), max_concurrent);
         
         let semaphore = Arc::new(Semaphore::new(max_concurrent));
-        let mut handles = vec![];
+        let mut tasks = FetchTasks::new();
 
         for ticker in tickers {
             let client = Arc::clone(&self);
             let sem = Arc::clone(&semaphore);
+            let progress = Arc::clone(&progress);
+            let span = debug_span!("fetch_symbol", symbol = %ticker.symbol, expiry = %ticker.expiry_date);
 
-            let handle = tokio::spawn(async move {
+            tasks.spawn(async move {
                 let _permit = sem.acquire_owned().await
                     .map_err(|e| anyhow::anyhow!("Semaphore error: {}", e))?;
+                progress.check_cancelled()?;
 
-                let chain = client.fetch_option_chain(&ticker.symbol, &ticker.expiry_date).await?;
-                Ok((ticker, chain))
-            });
-
-            handles.push(handle);
-        }
-
-        let mut results = vec![];
-        for handle in handles {
-            match handle.await {
-                Ok(res) => results.push(res),
-                Err(e) => results.push(Err(anyhow::anyhow!("Task error: {}", e))),
-            }
+                match client.fetch_option_chain(&ticker.symbol, &ticker.expiry_date).await {
+                    Ok(chain) => {
+                        progress.record_success(&ticker.symbol);
+                        record_partial_alerts(&progress, &ticker, &chain);
+                        Ok((ticker, chain))
+                    }
+                    Err(e) => {
+                        debug!(error = %e, "fetch failed");
+                        progress.record_failure(&ticker.symbol, &e);
+                        Err(e)
+                    }
+                }
+            }.instrument(span));
         }
 
-        results
+        tasks.join_all().await
     }
 
     /// Fetch available future symbols and expiry dates
@@ -421,7 +453,7 @@ impl MCXClient {
         match self.fetch_future_symbols_direct().await {
             Ok(data) => return Ok(data),
             Err(e) => {
-                println!("🔄 Direct API failed ({}), trying with session establishment...", e);
+                warn!("Direct API failed ({}), trying with session establishment...", e);
                 // Fallback to session-based approach
                 return self.fetch_future_symbols_with_session().await;
             }
@@ -430,12 +462,9 @@ impl MCXClient {
 
     /// Direct API call approach
     async fn fetch_future_symbols_direct(&self) -> Result<serde_json::Value> {
-        let backoff = ExponentialBackoff::from_millis(RETRY_BASE_DELAY_MS)
-            .factor(RETRY_FACTOR)
-            .max_delay(Duration::from_secs(RETRY_MAX_DELAY_SECS))
-            .take(RETRY_MAX_ATTEMPTS);
+        let backoff = self.settings.retry.backoff();
 
-        let result = Retry::spawn(backoff, || async {
+        let result = self.guarded(metrics::retry_tracked(&metrics::endpoint_label(MCX_FUTURE_SYMBOLS_API), backoff, || async {
             let res = apply_standard_get_headers(
                 self.client.get(MCX_FUTURE_SYMBOLS_API),
                 REFERER_OPTION_CHAIN
@@ -456,13 +485,13 @@ impl MCXClient {
                 
                 Ok(data)
             } else if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
-                anyhow::bail!("Retryable error: {}", status)
+                anyhow::bail!(upstream::StatusError::new(status, ""))
             } else {
                 let body = res.text().await.unwrap_or_default();
                 let preview: String = body.chars().take(500).collect();
-                anyhow::bail!("Client error {}: {}", status, preview)
+                anyhow::bail!(upstream::StatusError::new(status, preview))
             }
-        }).await;
+        })).await;
         
         result
     }
@@ -507,8 +536,28 @@ impl MCXClient {
         }
     }
 
+    /// Daily history of the contract `meta` names as a normalized OHLC/OI series.
+    /// OPTFUT rows are narrowed to the meta's option type and strike.
+    #[instrument(skip(self))]
+    pub async fn fetch_historic_series(
+        &self,
+        meta: SeriesMeta,
+        from_date: &str,
+        to_date: &str,
+        window: usize,
+    ) -> Result<HistoricalSeries> {
+        let data = self.fetch_historic_data(&meta.symbol, &meta.expiry, from_date, to_date, &meta.instrument).await?;
+        let strike = meta.strike.map(|s| s.to_string());
+        let data = processor::process_historic_data_response(data, &meta.option_type, &strike)
+            .map_err(|e| anyhow!("Failed to process historic data: {}", e))?;
+
+        let meta = SeriesMeta { exchange: "MCX".to_string(), ..meta };
+        Ok(processor::build_historic_series(&data, meta, window))
+    }
+
     /// Fetch historic commodity data
-    pub async fn fetch_historic_data(
+    #[instrument(skip(self))]
+    async fn fetch_historic_data(
         &self,
         symbol: &str,
         expiry: &str,
@@ -524,12 +573,9 @@ impl MCXClient {
             "InstrumentName": instrument_name
         });
         
-        let backoff = ExponentialBackoff::from_millis(RETRY_BASE_DELAY_MS)
-            .factor(RETRY_FACTOR)
-            .max_delay(Duration::from_secs(RETRY_MAX_DELAY_SECS))
-            .take(RETRY_MAX_ATTEMPTS);
+        let backoff = self.settings.retry.backoff();
 
-        let result = Retry::spawn(backoff, || async {
+        let result = self.guarded(metrics::retry_tracked(&metrics::endpoint_label(MCX_HISTORIC_DATA_API), backoff, || async {
             let res = apply_standard_post_headers(
                 self.client.post(MCX_HISTORIC_DATA_API),
                 REFERER_BHAVCOPY
@@ -550,13 +596,13 @@ impl MCXClient {
                 
                 Ok(data)
             } else if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
-                anyhow::bail!("Retryable error: {}", status)
+                anyhow::bail!(upstream::StatusError::new(status, ""))
             } else {
                 let body = res.text().await.unwrap_or_default();
                 let preview: String = body.chars().take(200).collect();
-                anyhow::bail!("Client error {}: {}", status, preview)
+                anyhow::bail!(upstream::StatusError::new(status, preview))
             }
-        }).await;
+        })).await;
         
         match result {
             Ok(data) => {
@@ -564,13 +610,14 @@ impl MCXClient {
                 Ok(data)
             }
             Err(e) => {
-                println!("❌ Failed to fetch historic data for {} {}: {}", symbol, expiry, e);
+                warn!("Failed to fetch historic data for {} {}: {}", symbol, expiry, e);
                 Err(e)
             }
         }
     }
 
     /// Fetch futures quote for specific commodity and expiry (existing method)
+    #[instrument(skip(self))]
     pub async fn fetch_future_quote(
         &self,
         commodity: &str,
@@ -581,12 +628,9 @@ impl MCXClient {
             "Expiry": expiry
         });
         
-        let backoff = ExponentialBackoff::from_millis(RETRY_BASE_DELAY_MS)
-            .factor(RETRY_FACTOR)
-            .max_delay(Duration::from_secs(RETRY_MAX_DELAY_SECS))
-            .take(RETRY_MAX_ATTEMPTS);
+        let backoff = self.settings.retry.backoff();
 
-        let result = Retry::spawn(backoff, || async {
+        let result = self.guarded(metrics::retry_tracked(&metrics::endpoint_label(MCX_FUTURE_QUOTE_API), backoff, || async {
             let res = apply_standard_post_headers(
                 self.client.post(MCX_FUTURE_QUOTE_API),
                 REFERER_OPTION_CHAIN
@@ -607,9 +651,9 @@ impl MCXClient {
                 
                 Ok(data)
             } else {
-                anyhow::bail!("HTTP error: {}", status)
+                anyhow::bail!(upstream::StatusError::new(status, ""))
             }
-        }).await;
+        })).await;
         
         match result {
             Ok(data) => {
@@ -617,13 +661,14 @@ impl MCXClient {
                 Ok(data)
             }
             Err(e) => {
-                println!("❌ Failed to fetch future quote for {} {}: {}", commodity, expiry, e);
+                warn!("Failed to fetch future quote for {} {}: {}", commodity, expiry, e);
                 Err(e)
             }
         }
     }
 
     /// Fetch option quote for specific commodity, expiry, option type, and strike price
+    #[instrument(skip(self))]
     pub async fn fetch_option_quote(
         &self,
         commodity: &str,
@@ -638,12 +683,9 @@ impl MCXClient {
             "StrikPrice": strike_price
         });
         
-        let backoff = ExponentialBackoff::from_millis(RETRY_BASE_DELAY_MS)
-            .factor(RETRY_FACTOR)
-            .max_delay(Duration::from_secs(RETRY_MAX_DELAY_SECS))
-            .take(RETRY_MAX_ATTEMPTS);
+        let backoff = self.settings.retry.backoff();
 
-        let result = Retry::spawn(backoff, || async {
+        let result = self.guarded(metrics::retry_tracked(&metrics::endpoint_label(MCX_OPTION_QUOTE_API), backoff, || async {
             let res = apply_standard_post_headers(
                 self.client.post(MCX_OPTION_QUOTE_API),
                 REFERER_OPTION_CHAIN
@@ -664,13 +706,13 @@ impl MCXClient {
                 
                 Ok(data)
             } else if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
-                anyhow::bail!("Retryable error: {}", status)
+                anyhow::bail!(upstream::StatusError::new(status, ""))
             } else {
                 let body = res.text().await.unwrap_or_default();
                 let preview: String = body.chars().take(200).collect();
-                anyhow::bail!("Client error {}: {}", status, preview)
+                anyhow::bail!(upstream::StatusError::new(status, preview))
             }
-        }).await;
+        })).await;
         
         match result {
             Ok(data) => {
@@ -679,8 +721,7 @@ impl MCXClient {
                 Ok(data)
             }
             Err(e) => {
-                println!("❌ Failed to fetch option quote for {} {} {} {}: {}", 
-                         commodity, expiry, option_type, strike_price, e);
+                warn!("Failed to fetch option quote for {} {} {} {}: {}", commodity, expiry, option_type, strike_price, e);
                 Err(e)
             }
         }
@@ -786,7 +827,7 @@ impl MCXClient {
         
         // println!("🔍 Filtered to {} nearest future expiry tickers", result.len());
         if total_removed_past > 0 {
-            println!("📅 Removed {} past expiries", total_removed_past);
+            info!("Removed {} past expiries", total_removed_past);
         }
         
         result
@@ -818,4 +859,28 @@ impl Default for MCXClient {
     fn default() -> Self {
         Self::new().unwrap()
     }
-}
\ No newline at end of file
+}
+/// Strike rules for one chain as it arrives, so a running or cancelled job shows alerts so far
+fn record_partial_alerts(progress: &JobProgress, ticker: &Ticker, chain: &OptionChainResponse) {
+    if !progress.wants_partial_results() {
+        return;
+    }
+    let underlying_value = chain.d.data.iter()
+        .find_map(|d| d.underlying_value)
+        .unwrap_or(0.0);
+    let Ok((processed_data, spread, _, _, _)) =
+        processor::process_mcx_option_data(chain.d.data.clone(), underlying_value, &ticker.expiry_date)
+    else {
+        return;
+    };
+    let output = rules::run_mcx_rules(
+        &processed_data,
+        ticker.symbol.clone(),
+        chain.d.summary.as_on.clone().unwrap_or_default(),
+        underlying_value,
+        spread,
+    );
+    if let Some(output) = output {
+        progress.record_partial(&output);
+    }
+}
@@ -3,10 +3,17 @@ use super::MCXClient;
 use super::config;
 use super::mcx_api_server;
 use super::rules;
+use crate::analytics;
+use crate::backtest::snapshots;
+use crate::contracts::{ContractRegistry, Exchange};
+use crate::portfolio;
+use crate::report::BatchReportBuilder;
+use crate::settings;
+use crate::utility::{self, watchlist};
 
 use anyhow::Result;
-use colored::Colorize;
 use std::sync::Arc;
+use tracing::{error, info, info_span, warn, Instrument};
 
 /// MCX Command Handler - encapsulates all MCX-related operations
 pub struct MCXCommands;
@@ -14,76 +21,97 @@ pub struct MCXCommands;
 impl MCXCommands {
     /// Run batch fetch for all MCX tickers (nearest future expiry per symbol only)
     pub async fn run_batch() -> Result<()> {
-        println!("{}", "=".repeat(60).blue());
-        println!("{}", "MCX Batch Processor (Nearest Future Expiry Only)".green().bold());
-        println!("{}", "=".repeat(60).blue());
-        println!();
+        Self::run_batch_for(None).await
+    }
+
+    /// Run batch fetch, restricted to the watchlist symbols when one is given
+    pub async fn run_batch_for(watchlist: Option<&[String]>) -> Result<()> {
+        Self::batch(watchlist).instrument(info_span!("mcx_batch")).await
+    }
+
+    async fn batch(watchlist: Option<&[String]>) -> Result<()> {
+        info!("MCX Batch Processor (Nearest Future Expiry Only)");
 
         let client = Arc::new(MCXClient::new()?);
+        let mut report = BatchReportBuilder::new(Exchange::Mcx);
 
         // Step 1: Fetch all MCX tickers from web scraping
-        println!("{}", "Step 1: Scraping MCX tickers from option chain page...".cyan());
-        let all_tickers = client.fetch_ticker_list().await?;
-        println!("{} Found {} total tickers", "✓".green(), all_tickers.len());
+        report.step("fetch_ticker_list");
+        info!("Step 1: Scraping MCX tickers from option chain page...");
+        let mut all_tickers = client.fetch_ticker_list().await?;
+        info!(count = all_tickers.len(), "✓ Found {} total tickers", all_tickers.len());
+
+        if let Some(watchlist) = watchlist {
+            let (kept, missing) = watchlist::filter(all_tickers, watchlist, |t| &t.symbol);
+            if !missing.is_empty() {
+                warn!("Not in the MCX ticker list, skipping: {}", missing.join(", "));
+            }
+            if kept.is_empty() {
+                return Err(anyhow::anyhow!("No watchlist symbols found in the MCX ticker list"));
+            }
+            info!(count = kept.len(), "✓ Watchlist: {} tickers", kept.len());
+            all_tickers = kept;
+        }
         
         let unique_symbols = MCXClient::get_unique_symbols(&all_tickers);
-        println!("{} Unique symbols: {}", "ℹ".blue(), unique_symbols.len());
-        println!("{} Symbols: {}", "ℹ".blue(), unique_symbols.join(", ").yellow());
-        println!();
+        info!(count = unique_symbols.len(), "Unique symbols: {} ({})", unique_symbols.len(), unique_symbols.join(", "));
 
         // Step 1.5: Filter to only nearest future expiry per symbol
-        println!("{}", "Step 1.5: Filtering to nearest future expiry per symbol...".cyan());
+        info!("Step 1.5: Filtering to nearest future expiry per symbol...");
         let tickers = MCXClient::filter_latest_expiry_per_symbol(all_tickers.clone());
         let reduction_percent = ((all_tickers.len() - tickers.len()) as f64 / all_tickers.len() as f64) * 100.0;
         
-        println!("{} Filtered to {} tickers (nearest future expiry only)", "✓".green(), tickers.len());
-        println!("{} Reduction: {:.1}% fewer API calls", "📉".yellow(), reduction_percent);
-        println!("{} Processing {} symbols instead of {} total combinations", 
-                 "ℹ".blue(), tickers.len(), all_tickers.len());
-        println!();
+        info!(
+            "✓ Filtered to {} tickers (nearest future expiry only), {:.1}% fewer API calls than {} total combinations",
+            tickers.len(), reduction_percent, all_tickers.len()
+        );
 
         // Step 2: Bulk process filtered tickers with timeout handling
-        println!("{}", "Step 2: Processing nearest future expiry tickers only...".cyan());
+        info!("Step 2: Processing nearest future expiry tickers only...");
         
-        let max_concurrent = if config::is_ci_environment() {
-            println!("{} CI Mode: Using lower concurrency ({})", "ℹ".blue(), config::CI_MAX_CONCURRENT);
-            config::CI_MAX_CONCURRENT
+        let settings = client.settings().clone();
+        let max_concurrent = settings.concurrency(config::is_ci_environment());
+        if config::is_ci_environment() {
+            info!("CI Mode: Using lower concurrency ({})", max_concurrent);
         } else {
-            println!("{} Max concurrent requests: {}", "ℹ".blue(), config::DEFAULT_MAX_CONCURRENT);
-            config::DEFAULT_MAX_CONCURRENT
-        };
-        
-        println!();
+            info!("Max concurrent requests: {}", max_concurrent);
+        }
 
         let start_time = std::time::Instant::now();
+        report.step("fetch_option_chains");
         
         // Wrap the batch processing with a timeout for CI environments
-        let results = if config::is_ci_environment() {
-            println!("{} CI timeout enabled: {} seconds", "⏱".yellow(), config::GITHUB_ACTIONS_TIMEOUT_SECS);
-            
-            let timeout_duration = std::time::Duration::from_secs(config::GITHUB_ACTIONS_TIMEOUT_SECS);
-            
-            match tokio::time::timeout(
-                timeout_duration,
-                client.fetch_all_option_chains(tickers.clone(), max_concurrent)
-            ).await {
-                Ok(results) => results,
-                Err(_) => {
-                    println!("{} Timeout reached after {} seconds - stopping analysis", "⚠".red(), config::GITHUB_ACTIONS_TIMEOUT_SECS);
-                    println!("{} This may indicate MCX API issues or network problems", "ℹ".blue());
-                    
-                    // Create empty results vector matching the tickers count
-                    tickers.iter().map(|_| Err(anyhow::anyhow!("Timeout"))).collect()
+        let results = async {
+            if config::is_ci_environment() {
+                info!("CI timeout enabled: {} seconds", settings.ci_timeout_secs);
+                
+                let timeout_duration = settings.ci_timeout();
+                
+                match tokio::time::timeout(
+                    timeout_duration,
+                    client.fetch_all_option_chains(tickers.clone(), max_concurrent)
+                ).await {
+                    Ok(results) => results,
+                    Err(_) => {
+                        error!("Timeout reached after {} seconds - stopping analysis", settings.ci_timeout_secs);
+                        warn!("This may indicate MCX API issues or network problems");
+                        
+                        // Create empty results vector matching the tickers count
+                        tickers.iter().map(|_| Err(anyhow::anyhow!("Timeout"))).collect()
+                    }
                 }
+            } else {
+                // No timeout for local development
+                client.fetch_all_option_chains(tickers.clone(), max_concurrent).await
             }
-        } else {
-            // No timeout for local development
-            client.fetch_all_option_chains(tickers.clone(), max_concurrent).await
-        };
+        }
+        .instrument(info_span!("fetch_option_chains", tickers = tickers.len(), max_concurrent))
+        .await;
 
         let elapsed = start_time.elapsed();
         
         // Step 3: Process results
+        report.step("collect_results");
         let mut successful = Vec::new();
         let mut failed = Vec::new();
         let mut timeout_count = 0;
@@ -91,71 +119,72 @@ impl MCXCommands {
         for (ticker, result) in tickers.iter().zip(results.iter()) {
             match result {
                 Ok((_, chain)) => {
+                    report.symbol_success(&ticker.symbol, Some(&ticker.expiry_date));
                     successful.push((ticker.clone(), chain.clone()));
-                    print!("{}", ".".green());
                 }
                 Err(e) => {
+                    report.symbol_failure(&ticker.symbol, Some(&ticker.expiry_date), &e.to_string());
                     if e.to_string().contains("Timeout") {
                         timeout_count += 1;
-                        print!("{}", "⏱".yellow());
                     } else {
                         failed.push((ticker.symbol.clone(), e.to_string()));
-                        print!("{}", "✗".red());
                     }
                 }
             }
         }
-        
-        println!("\n");
 
         // Step 4: Display summary
         Self::display_batch_summary(&successful, &failed, timeout_count, elapsed, &tickers, &all_tickers);
 
         // Step 5: Process data and run rules (similar to NSE)
-        Self::process_batch_data_and_rules(successful).await?;
+        report.step("process_and_rules");
+        let rules_outputs = Self::process_batch_data_and_rules(successful)
+            .instrument(info_span!("process_and_rules"))
+            .await?;
+
+        // Step 6: Machine-readable run report
+        for output in &rules_outputs {
+            for alert in &output.alerts {
+                report.alert(&output.symbol, &alert.alert_type, &alert.option_type);
+            }
+        }
+        if let Err(e) = report.finish().publish() {
+            warn!("Failed to write batch report: {}", e);
+        }
 
-        println!();
-        println!("{}", "=".repeat(60).blue());
-        println!("{}", "Done!".green().bold());
-        println!("{}", "=".repeat(60).blue());
+        info!("Done!");
 
         Ok(())
     }
 
     /// Run single ticker fetch (for API endpoints only - not used in GitHub Actions)
     pub async fn run_single(symbol: &str, expiry: &str) -> Result<()> {
-        println!("{}", "=".repeat(60).blue());
-        println!("{}", "MCX Single Ticker Fetch".green().bold());
-        println!("{}", "=".repeat(60).blue());
-        println!();
-
-        let client = MCXClient::new()?;
+        let span = info_span!("mcx_single", symbol, expiry);
+        async {
+            info!("MCX Single Ticker Fetch");
 
-        println!("{} Fetching option chain for {}...", "→".cyan(), symbol.yellow());
-        println!("{} Expiry: {}", "→".cyan(), expiry.yellow());
-        println!();
+            let client = MCXClient::new()?;
 
-        let chain = client.fetch_option_chain(symbol, expiry).await?;
+            info!("→ Fetching option chain for {} (expiry {})...", symbol, expiry);
 
-        // Display results
-        Self::display_single_results(symbol, &chain, expiry);
+            let chain = client.fetch_option_chain(symbol, expiry).await?;
 
-        println!("{}", "=".repeat(60).blue());
+            // Display results
+            Self::display_single_results(symbol, &chain, expiry);
 
-        Ok(())
+            Ok(())
+        }
+        .instrument(span)
+        .await
     }
 
     /// Run API server mode
     pub async fn run_server(port: u16) -> Result<()> {
-        println!("{}", "=".repeat(60).blue());
-        println!("{}", "MCX API Server".green().bold());
-        println!("{}", "=".repeat(60).blue());
-        println!();
-
+        info!("MCX API Server");
         mcx_api_server::start_mcx_server(port).await
     }
 
-    /// Display batch processing summary with filtering information
+    /// Log batch processing summary with filtering information
     fn display_batch_summary(
         successful: &[(super::models::Ticker, super::models::OptionChainResponse)],
         failed: &[(String, String)],
@@ -164,75 +193,67 @@ impl MCXCommands {
         filtered_tickers: &[super::models::Ticker],
         all_tickers: &[super::models::Ticker],
     ) {
-        println!("{}", "=".repeat(60).blue());
-        println!("{}", "Summary".cyan().bold());
-        println!("{}", "=".repeat(60).blue());
+        info!("Summary");
         
         // Filtering summary
-        println!("{} Total tickers available: {}", "📊".blue(), all_tickers.len());
-        println!("{} Nearest future expiry filtered: {}", "🔍".blue(), filtered_tickers.len());
         let reduction_percent = ((all_tickers.len() - filtered_tickers.len()) as f64 / all_tickers.len() as f64) * 100.0;
-        println!("{} API call reduction: {:.1}%", "📉".green(), reduction_percent);
-        println!();
+        info!(
+            total_tickers = all_tickers.len(),
+            filtered = filtered_tickers.len(),
+            "Total tickers available: {}, nearest future expiry filtered: {} ({:.1}% API call reduction)",
+            all_tickers.len(), filtered_tickers.len(), reduction_percent
+        );
         
         // Processing summary
-        println!("{} Successful: {}/{}", "✓".green(), successful.len(), filtered_tickers.len());
-        println!("{} Failed: {}/{}", "✗".red(), failed.len(), filtered_tickers.len());
+        info!(successful = successful.len(), "✓ Successful: {}/{}", successful.len(), filtered_tickers.len());
+        info!(failed = failed.len(), "✗ Failed: {}/{}", failed.len(), filtered_tickers.len());
         
         if timeout_count > 0 {
-            println!("{} Timed out: {} (due to {} second limit)", "⏱".yellow(), timeout_count, config::GITHUB_ACTIONS_TIMEOUT_SECS);
+            warn!(timed_out = timeout_count, "Timed out: {} (due to {} second limit)", timeout_count, settings::current().mcx.ci_timeout_secs);
         }
         
-        println!("{} Time taken: {:.2}s", "⏱".yellow(), elapsed.as_secs_f64());
+        info!(elapsed_secs = elapsed.as_secs_f64(), "Time taken: {:.2}s", elapsed.as_secs_f64());
         
-        if filtered_tickers.len() > 0 {
-            println!("{} Avg time per ticker: {:.2}s", "⏱".yellow(), elapsed.as_secs_f64() / filtered_tickers.len() as f64);
+        if !filtered_tickers.is_empty() {
+            info!("Avg time per ticker: {:.2}s", elapsed.as_secs_f64() / filtered_tickers.len() as f64);
         }
-        
-        println!();
 
         // Show failed tickers
-        if !failed.is_empty() {
-            println!("{}", "Failed Tickers:".red());
-            for (symbol, error) in failed.iter().take(10) {
-                println!("  {} {} → {}", "✗".red(), symbol.yellow(), error.chars().take(80).collect::<String>());
-            }
-            if failed.len() > 10 {
-                println!("  ... and {} more", failed.len() - 10);
-            }
-            println!();
+        for (symbol, error) in failed.iter().take(10) {
+            warn!(symbol = %symbol, "✗ {} → {}", symbol, error.chars().take(80).collect::<String>());
+        }
+        if failed.len() > 10 {
+            warn!("... and {} more failed tickers", failed.len() - 10);
         }
     }
 
-    /// Display single ticker fetch results
+    /// Log single ticker fetch results
     fn display_single_results(symbol: &str, chain: &super::models::OptionChainResponse, expiry: &str) {
-        println!("{}", "=".repeat(60).blue());
-        println!("{}", "Results".cyan().bold());
-        println!("{}", "=".repeat(60).blue());
-        println!("{} Symbol: {}", "✓".green(), symbol.yellow());
-        println!("{} Expiry: {}", "✓".green(), expiry);
-        
-        if let Some(as_on) = &chain.d.summary.as_on {
-            println!("{} As On: {}", "✓".green(), as_on);
-        }
-        
-        if let Some(count) = chain.d.summary.count {
-            println!("{} Total strikes: {}", "✓".green(), count);
-        }
-        
-        println!();
-        println!("{} Data points processed: {}", "✓".green(), chain.d.data.len());
-        println!();
+        info!(
+            symbol,
+            expiry,
+            as_on = chain.d.summary.as_on.as_deref().unwrap_or("-"),
+            strikes = chain.d.summary.count,
+            data_points = chain.d.data.len(),
+            "✓ {} expiry {}: {} data points processed",
+            symbol,
+            expiry,
+            chain.d.data.len()
+        );
     }
 
     /// Process batch data and apply rules (similar to NSE implementation)
     async fn process_batch_data_and_rules(
         successful: Vec<(super::models::Ticker, super::models::OptionChainResponse)>
-    ) -> Result<()> {
-        println!("{}", "Processing data and applying rules...".cyan());
+    ) -> Result<Vec<rules::McxRulesOutput>> {
+        info!("Processing data and applying rules...");
+        
+        // Archive records for backtesting when SNAPSHOT_DIR is set
+        let snapshot_dir = snapshots::snapshot_run_dir(Exchange::Mcx);
         
         // Process each ticker's data through the processor and rules
-        let mut batch_for_rules = Vec::new();
+        let mut processed = Vec::new();
+        let mut iv_entries = Vec::new();
         
         for (ticker, chain) in successful.iter() {
             // Get underlying value from first available data point
@@ -246,28 +267,84 @@ impl MCXCommands {
                 underlying_value,
                 &ticker.expiry_date,
             ) {
-                Ok((processed_data, spread, _days_to_expiry, _ce_oi, _pe_oi)) => {
-                    // Store for rules processing
-                    batch_for_rules.push((
-                        ticker.symbol.clone(),
-                        processor::convert_mcx_timestamp(&chain.d.summary.as_on.clone().unwrap_or_else(|| "".to_string())),
-                        underlying_value,
-                        processed_data,
-                        spread,
-                    ));
+                Ok((processed_data, spread, days_to_expiry, _ce_oi, _pe_oi)) => {
+                    let timestamp = processor::convert_mcx_timestamp(&chain.d.summary.as_on.clone().unwrap_or_else(|| "".to_string()));
+                    
+                    if let Some(atm_iv) = processor::calculate_expected_move(&processed_data, underlying_value).and_then(|m| m.atm_iv) {
+                        iv_entries.push((ticker.symbol.clone(), analytics::observation_date(&timestamp), atm_iv));
+                    }
+                    processed.push((ticker, timestamp, underlying_value, processed_data, spread, days_to_expiry));
                 }
                 Err(e) => {
-                    println!("{} Failed to process {}: {}", "⚠".yellow(), ticker.symbol, e);
+                    warn!(symbol = %ticker.symbol, "Failed to process {}: {}", ticker.symbol, e);
+                }
+            }
+        }
+        
+        // Daily ATM IV history for IV rank/percentile, updated once for the whole batch
+        let iv_ranks = analytics::record_daily_atm_ivs(Exchange::Mcx, iv_entries).await;
+        
+        let mut batch_for_rules = Vec::new();
+        let mut iv_alerts = Vec::new();
+        
+        for (ticker, timestamp, underlying_value, processed_data, spread, days_to_expiry) in processed {
+            let iv_rank = iv_ranks.get(&ticker.symbol).cloned();
+            if let Some(iv) = &iv_rank {
+                let expiry = processed_data.first().and_then(|opt| opt.expiry_date.clone()).unwrap_or_default();
+                if let Some(alert) = rules::check_mcx_iv_percentile_rule(&ticker.symbol, &expiry, iv, spread, days_to_expiry) {
+                    iv_alerts.push((timestamp.clone(), underlying_value, alert));
+                }
+            }
+            
+            if let Some(dir) = &snapshot_dir {
+                let record = serde_json::json!({
+                    "record": {
+                        "symbol": ticker.symbol,
+                        "timestamp": timestamp,
+                        "underlying_value": underlying_value,
+                        "spread": spread,
+                        "days_to_expiry": days_to_expiry,
+                        "iv_rank": iv_rank,
+                    },
+                    "data": processed_data,
+                });
+                let filename = format!("{}.json", ticker.symbol);
+                if let Err(e) = utility::write_atomic(dir.join(&filename), serde_json::to_string_pretty(&record)?) {
+                    warn!("Failed to archive snapshot {}: {}", filename, e);
                 }
             }
+            
+            // Store for rules processing
+            batch_for_rules.push((
+                ticker.symbol.clone(),
+                timestamp,
+                underlying_value,
+                processed_data,
+                spread,
+            ));
         }
         
         // Run rules on all processed securities
-        let rules_outputs = rules::run_mcx_batch_rules(batch_for_rules);
+        let mut rules_outputs = rules::run_mcx_batch_rules(batch_for_rules);
+        rules::merge_mcx_symbol_alerts(&mut rules_outputs, iv_alerts);
+        let contracts = ContractRegistry::load();
+        for output in rules_outputs.iter_mut() {
+            output.contract_spec = contracts.mcx(&output.symbol).cloned();
+        }
+
+        // Risk alerts for open paper positions join the market alerts
+        let risk = match portfolio::MarketData::new() {
+            Ok(market) => portfolio::book_risk_alerts(&market, Exchange::Mcx).await,
+            Err(e) => Err(e),
+        };
+        match risk {
+            Ok(risk) => portfolio::merge_mcx_risk_alerts(&mut rules_outputs, risk.mcx),
+            Err(e) => warn!("Skipped portfolio risk alerts: {}", e),
+        }
         
         // Save only the rules output (alerts) - similar to NSE
         if !rules_outputs.is_empty() {
-            std::fs::write(
+            utility::write_atomic(
                 "mcx_batch_results.json",
                 serde_json::to_string_pretty(&rules_outputs)?,
             )?;
@@ -276,17 +353,16 @@ impl MCXCommands {
                 .map(|r| r.alerts.len())
                 .sum();
             
-            println!("{} Saved alerts to mcx_batch_results.json", "✓".green());
-            println!("{} Securities with alerts: {}", "ℹ".blue(), rules_outputs.len());
-            println!("{} Total alerts: {}", "ℹ".blue(), total_alerts);
+            info!("✓ Saved alerts to mcx_batch_results.json");
+            info!(securities_with_alerts = rules_outputs.len(), total_alerts, "Securities with alerts: {}, total alerts: {}", rules_outputs.len(), total_alerts);
         } else {
             // Create empty file for consistency
-            std::fs::write("mcx_batch_results.json", "[]")?;
-            println!("{} No alerts found across all securities", "ℹ".blue());
-            println!("{} Created empty results file: mcx_batch_results.json", "✓".green());
+            utility::write_atomic("mcx_batch_results.json", "[]")?;
+            info!("No alerts found across all securities");
+            info!("✓ Created empty results file: mcx_batch_results.json");
         }
 
-        Ok(())
+        Ok(rules_outputs)
     }
 
     /// Print usage instructions
@@ -301,7 +377,7 @@ impl MCXCommands {
     /// Handle CI environment mode switching
     pub fn handle_ci_mode_override(mode: &str) -> bool {
         if config::is_ci_environment() && (mode == "server") {
-            println!("{} GitHub Actions only supports batch mode, running batch instead", "ℹ".blue());
+            info!("GitHub Actions only supports batch mode, running batch instead");
             true
         } else {
             false
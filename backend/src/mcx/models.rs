@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use utoipa::ToSchema;
 
 // -----------------------------------------------
 // NEW MCX DATA STRUCTURES (from working implementation)
@@ -250,7 +251,7 @@ pub struct HistoricSummary {
 // LEGACY TICKER DATA FROM INITIAL SCRAPE (for compatibility)
 // -----------------------------------------------
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Ticker {
     #[serde(rename = "ExpiryDate")]
     pub expiry_date: String,
@@ -1,5 +1,10 @@
-use super::models::{ OptionData as McxOptionData};
+use super::models::{ OptionData as McxOptionData, HistoricRecord};
+use crate::analytics::{
+    classify_buildup, compute_expected_move, normalize_series, parse_series_date, Buildup,
+    BuildupCounts, ExpectedMove, HistoricalSeries, OhlcBar, PricingModel, SeriesMeta, SmileQuote,
+};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use chrono::{DateTime, Local, NaiveDate};
 use anyhow::{Result, anyhow};
 use serde_json::Value;
@@ -37,7 +42,7 @@ pub fn convert_mcx_expiry_format(mcx_expiry: &str) -> String {
 }
 
 /// Enhanced MCX option detail with computed fields
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ProcessedMcxOptionDetail {
     #[serde(rename = "strikePrice")]
     pub strike_price: f64,
@@ -66,13 +71,15 @@ pub struct ProcessedMcxOptionDetail {
     pub tambu: Option<String>,  // "TMJ", "TMG", or None
     pub time_val: f64,
     pub days_to_expiry: i32,
+    #[serde(default)]
+    pub buildup: Buildup,  // Price change vs OI change classification
     
     #[serde(rename = "oiRank")]
     pub oi_rank: Option<u32>,
 }
 
 /// Processed MCX option data with enhanced CE and PE
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ProcessedMcxOptionData {
     #[serde(rename = "strikePrice")]
     pub strike_price: f64,
@@ -90,7 +97,7 @@ pub struct ProcessedMcxOptionData {
 }
 
 /// Single Analysis Response for MCX (matching NSE structure)
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct McxSingleAnalysisResponse {
     pub symbol: String,
     pub timestamp: String,
@@ -186,6 +193,7 @@ pub fn process_mcx_option_data(
                 tambu: calculate_tambu(pchange_in_oi, opt.ce_net_change),
                 time_val: calculate_time_value(opt.ce_ltp, strike, underlying_value, true),
                 days_to_expiry,
+                buildup: classify_buildup(opt.ce_absolute_change, change_in_oi),
                 oi_rank: None, // Will be calculated separately if needed
             })
         } else {
@@ -212,6 +220,7 @@ pub fn process_mcx_option_data(
                 tambu: calculate_tambu(pchange_in_oi, opt.pe_net_change),
                 time_val: calculate_time_value(opt.pe_ltp, strike, underlying_value, false),
                 days_to_expiry,
+                buildup: classify_buildup(opt.pe_absolute_change, change_in_oi),
                 oi_rank: None, // Will be calculated separately if needed
             })
         } else {
@@ -247,6 +256,34 @@ pub fn process_mcx_option_data(
     Ok((processed, spread, days_to_expiry, ce_oi, pe_oi))
 }
 
+/// Count buildups across all CE and PE legs of processed MCX data
+pub fn count_buildups(data: &[ProcessedMcxOptionData]) -> BuildupCounts {
+    data.iter()
+        .flat_map(|opt| [opt.call.as_ref(), opt.put.as_ref()])
+        .flatten()
+        .map(|detail| detail.buildup)
+        .collect()
+}
+
+/// Extract per-strike CE/PE prices for IV smile computation (one entry per strike)
+pub fn smile_quotes(data: &[McxOptionData]) -> Vec<SmileQuote> {
+    let mut seen = std::collections::HashSet::new();
+
+    data.iter()
+        .filter_map(|opt| {
+            let strike = opt.ce_strike_price?;
+            if !seen.insert(strike as i64) {
+                return None;
+            }
+            Some(SmileQuote {
+                strike,
+                call_price: opt.ce_ltp,
+                put_price: opt.pe_ltp,
+            })
+        })
+        .collect()
+}
+
 /// Find ATM strike (closest to underlying, prefer floor)
 pub fn find_atm_strike(data: &[McxOptionData], underlying_value: f64) -> f64 {
     let mut closest_strike = 0.0;
@@ -641,6 +678,22 @@ pub fn create_single_analysis_response(
     }
 }
 
+/// Expected move (and ATM IV) from the ATM strike's call and put prices
+pub fn calculate_expected_move(data: &[ProcessedMcxOptionData], underlying_value: f64) -> Option<ExpectedMove> {
+    let atm = data.iter().find(|opt| {
+        opt.call.as_ref().map(|ce| ce.the_money == "ATM").unwrap_or(false)
+    })?;
+
+    compute_expected_move(
+        &PricingModel::mcx(),
+        atm.strike_price,
+        atm.call.as_ref()?.last_price?,
+        atm.put.as_ref()?.last_price?,
+        underlying_value,
+        atm.days_to_expiry,
+    )
+}
+
 // Helper function to process historic data response
 pub fn process_historic_data_response(
     mut data: serde_json::Value,
@@ -794,17 +847,7 @@ pub fn enrich_mcx_future_quote(quote: &mut Value) {
             };
 
             if let (Some(pchange), Some(poi)) = (pchange, pchangein_oi) {
-                let action = if pchange > 0.0 && poi > 0.0 {
-                    "Long Buildup"
-                } else if pchange > 0.0 && poi < 0.0 {
-                    "Short Covering"
-                } else if pchange < 0.0 && poi > 0.0 {
-                    "Short Buildup"
-                } else if pchange < 0.0 && poi < 0.0 {
-                    "Long Covering"
-                } else {
-                    "Neutral"
-                };
+                let action = classify_buildup(Some(pchange), Some(poi));
 
                 if let Some(num) = serde_json::Number::from_f64(poi) {
                     obj.insert(
@@ -815,9 +858,37 @@ pub fn enrich_mcx_future_quote(quote: &mut Value) {
 
                 obj.insert(
                     "action".to_string(),
-                    Value::String(action.to_string()),
+                    Value::String(action.as_str().to_string()),
                 );
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Normalized OHLC/OI series from a (processed) MCX historic data response.
+/// Records that do not match the typed `HistoricRecord` are skipped.
+pub fn build_historic_series(data: &Value, meta: SeriesMeta, window: usize) -> HistoricalSeries {
+    let records = data
+        .pointer("/d/Data")
+        .and_then(|v| v.as_array())
+        .map(|items| items.as_slice())
+        .unwrap_or_default();
+
+    let bars = records
+        .iter()
+        .filter_map(|item| serde_json::from_value::<HistoricRecord>(item.clone()).ok())
+        .filter_map(|record| {
+            let date = parse_series_date(record.date.as_deref()?)?;
+            let close = record.close.or(record.ltp).filter(|p| *p > 0.0)?;
+
+            Some(OhlcBar {
+                volume: record.volume.map(|v| v as f64),
+                open_interest: record.open_interest.map(|oi| oi as f64),
+                ..OhlcBar::new(date, record.open, record.high, record.low, close)
+            })
+        })
+        .collect();
+
+    normalize_series(meta, bars, window)
+}
+
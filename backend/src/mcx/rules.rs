@@ -1,8 +1,16 @@
 use super::processor::{ProcessedMcxOptionData, ProcessedMcxOptionDetail};
+use crate::analytics::{Buildup, IvRankStats};
+use crate::contracts::ContractSpec;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Fresh buildup alert thresholds (above this OI change HUGE_OI_INCREASE takes over)
+const BUILDUP_MIN_PCHANGE_IN_OI: f64 = 100.0;
+const BUILDUP_MAX_PCHANGE_IN_OI: f64 = 1000.0;
+const BUILDUP_MIN_ABS_PCHANGE: f64 = 20.0;
 
 /// Alert types for MCX option strikes
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct McxAlert {
     pub symbol: String,
     #[serde(rename = "strikePrice")]
@@ -16,7 +24,7 @@ pub struct McxAlert {
     pub values: McxAlertValues,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct McxAlertValues {
     #[serde(rename = "pchangeinOpenInterest")]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -35,16 +43,79 @@ pub struct McxAlertValues {
 
     pub time_val: f64,
     pub days_to_expiry: i32,  // Days remaining until expiry
+
+    #[serde(default)]
+    pub buildup: Buildup,
 }
 
 /// MCX Rules output structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct McxRulesOutput {
     pub symbol: String,
     pub timestamp: String,
     #[serde(rename = "underlyingValue")]
     pub underlying_value: f64,
     pub alerts: Vec<McxAlert>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract_spec: Option<ContractSpec>,
+}
+
+/// Rule 6: ATM IV at an extreme percentile of its own history
+pub fn check_mcx_iv_percentile_rule(
+    symbol: &str,
+    expiry: &str,
+    iv: &IvRankStats,
+    spread: f64,
+    days_to_expiry: i32,
+) -> Option<McxAlert> {
+    let extreme = iv.extreme()?;
+
+    Some(McxAlert {
+        symbol: symbol.to_string(),
+        strike_price: 0.0,
+        expiry_date: expiry.to_string(),
+        option_type: "IV".to_string(),
+        alert_type: extreme.alert_type.to_string(),
+        description: extreme.description,
+        spread,
+        values: McxAlertValues {
+            pchange_in_oi: None,
+            last_price: Some(iv.atm_iv),
+            open_interest: None,
+            the_money: Some("ATM".to_string()),
+            time_val: 0.0,
+            days_to_expiry,
+            buildup: Buildup::Neutral,
+        },
+    })
+}
+
+/// Append a symbol-level alert, creating the output when no strike alerts fired
+pub fn append_mcx_alert(
+    output: Option<McxRulesOutput>,
+    alert: McxAlert,
+    timestamp: &str,
+    underlying_value: f64,
+) -> McxRulesOutput {
+    let mut output = output.unwrap_or_else(|| McxRulesOutput {
+        symbol: alert.symbol.clone(),
+        timestamp: timestamp.to_string(),
+        underlying_value,
+        alerts: Vec::new(),
+        contract_spec: None,
+    });
+    output.alerts.push(alert);
+    output
+}
+
+/// Merge symbol-level alerts (timestamp, underlying, alert) into batch rules outputs
+pub fn merge_mcx_symbol_alerts(outputs: &mut Vec<McxRulesOutput>, alerts: Vec<(String, f64, McxAlert)>) {
+    for (timestamp, underlying_value, alert) in alerts {
+        match outputs.iter().position(|r| r.symbol == alert.symbol) {
+            Some(idx) => outputs[idx].alerts.push(alert),
+            None => outputs.push(append_mcx_alert(None, alert, &timestamp, underlying_value)),
+        }
+    }
 }
 
 /// Calculate OI percentage change with infinity handling
@@ -122,6 +193,7 @@ pub fn run_mcx_rules(
         timestamp: converted_timestamp,
         underlying_value,
         alerts,
+        contract_spec: None,
     })
 }
 
@@ -162,6 +234,7 @@ pub fn check_mcx_option_rules(
                 the_money: Some(detail.the_money.clone()),
                 time_val: detail.time_val,
                 days_to_expiry,
+                buildup: detail.buildup,
             },
         });
     }
@@ -186,6 +259,7 @@ pub fn check_mcx_option_rules(
                 the_money: Some(detail.the_money.clone()),
                 time_val: detail.time_val,
                 days_to_expiry,
+                buildup: detail.buildup,
             },
         });
     }
@@ -210,6 +284,7 @@ pub fn check_mcx_option_rules(
                 the_money: Some(detail.the_money.clone()),
                 time_val: detail.time_val,
                 days_to_expiry,
+                buildup: detail.buildup,
             },
         });
     }
@@ -243,6 +318,7 @@ pub fn check_mcx_option_rules(
                 the_money: Some(detail.the_money.clone()),
                 time_val: detail.time_val,
                 days_to_expiry,
+                buildup: detail.buildup,
             },
         });
     }
@@ -267,6 +343,38 @@ pub fn check_mcx_option_rules(
                 the_money: Some(detail.the_money.clone()),
                 time_val: detail.time_val,
                 days_to_expiry,
+                buildup: detail.buildup,
+            },
+        });
+    }
+    
+    // Rule 5: Fresh long/short buildup
+    let pchange = detail.pchange.unwrap_or(0.0);
+    let is_fresh_buildup = matches!(detail.buildup, Buildup::LongBuildup | Buildup::ShortBuildup)
+        && safe_pchange_in_oi > BUILDUP_MIN_PCHANGE_IN_OI
+        && safe_pchange_in_oi <= BUILDUP_MAX_PCHANGE_IN_OI
+        && pchange.abs() >= BUILDUP_MIN_ABS_PCHANGE;
+
+    if is_fresh_buildup {
+        alerts.push(McxAlert {
+            symbol: symbol.to_string(),
+            strike_price: strike,
+            expiry_date: expiry.to_string(),
+            option_type: option_type.to_string(),
+            alert_type: detail.buildup.alert_type().to_string(),
+            description: format!(
+                "{} {} {} strike shows {} (OI {:+.2}%, price {:+.2}%) ({} days to expiry)",
+                symbol, option_type, strike, detail.buildup, safe_pchange_in_oi, pchange, days_to_expiry
+            ),
+            spread,
+            values: McxAlertValues {
+                pchange_in_oi: Some(safe_pchange_in_oi),
+                last_price,
+                open_interest,
+                the_money: Some(detail.the_money.clone()),
+                time_val: detail.time_val,
+                days_to_expiry,
+                buildup: detail.buildup,
             },
         });
     }
@@ -1,5 +1,9 @@
 use std::time::Duration;
 
+// Concurrency, retry, timeout, cache, market hours and index constants below are the
+// built-in defaults; nse-analyzer.toml and NSE_* env vars override them at
+// runtime (see crate::settings).
+
 // -----------------------------------------------
 // NSE API ENDPOINTS
 // -----------------------------------------------
@@ -24,11 +28,26 @@ pub fn nse_option_chain_url(typ: &str, symbol: &str, expiry: &str) -> String {
     )
 }
 
+// NSE F&O contract master (market lot per underlying, per expiry month)
+pub const NSE_FO_MARKET_LOTS_URL: &str = "https://nsearchives.nseindia.com/content/fo/fo_mktlots.csv";
+
 // -----------------------------------------------
 // INDICES TO ADD
 // -----------------------------------------------
 pub const NSE_INDICES: &[&str] = &["NIFTY", "BANKNIFTY", "FINNIFTY", "NIFTYNXT50", "MIDCPNIFTY"];
 
+// -----------------------------------------------
+// CONTRACT SPECS (fallbacks when the contract master is unavailable)
+// -----------------------------------------------
+pub const NSE_INDEX_LOT_SIZES: &[(&str, f64)] = &[
+    ("NIFTY", 75.0),
+    ("BANKNIFTY", 35.0),
+    ("FINNIFTY", 65.0),
+    ("MIDCPNIFTY", 140.0),
+    ("NIFTYNXT50", 25.0),
+];
+pub const NSE_OPTION_TICK_SIZE: f64 = 0.05;
+
 // -----------------------------------------------
 // HTTP CLIENT CONFIG
 // -----------------------------------------------
@@ -57,6 +76,12 @@ pub const RETRY_FACTOR: u64 = 3;
 pub const RETRY_MAX_DELAY_SECS: u64 = 5;
 pub const RETRY_MAX_ATTEMPTS: usize = 5;
 
+// -----------------------------------------------
+// CIRCUIT BREAKER (consecutive failed fetches, after retries)
+// -----------------------------------------------
+pub const CIRCUIT_FAILURE_THRESHOLD: u32 = 5;  // 0 = never open
+pub const CIRCUIT_COOLDOWN_SECS: u64 = 30;     // Fail fast this long before probing again
+
 // -----------------------------------------------
 // GITHUB ACTIONS TIMEOUT CONFIG
 // -----------------------------------------------
@@ -67,6 +92,46 @@ pub const GITHUB_ACTIONS_TIMEOUT_SECS: u64 = 700;
 // -----------------------------------------------
 pub const DEFAULT_MAX_CONCURRENT: usize = 5;
 pub const CI_MAX_CONCURRENT: usize = 5; 
+
+// -----------------------------------------------
+// MARKET HOURS (IST, Monday to Friday)
+// -----------------------------------------------
+pub const MARKET_OPEN_IST: &str = "09:15";
+pub const MARKET_CLOSE_IST: &str = "15:30";
+
+// Paper-portfolio margin as a fraction of underlying notional (rough SPAN + exposure proxy)
+pub const MARGIN_RATE: f64 = 0.12;
+
+// -----------------------------------------------
+// SERVER CACHE
+// -----------------------------------------------
+// (resource, ttl secs, market-hours ttl secs, stale secs, max entries)
+pub const CACHE_POLICIES: &[(&str, u64, u64, u64, usize)] = &[
+    ("securities_list", 21_600, 3_600, 3_600, 1),
+    ("contract_info", 21_600, 21_600, 3_600, 500),
+    ("option_chains", 900, 30, 30, 300),
+];
+
+// -----------------------------------------------
+// CACHE PRE-WARM (hot symbols = indices + these + WATCHLIST)
+// -----------------------------------------------
+pub const PREWARM_INTERVAL_SECS: u64 = 10;  // Market hours; below the shortest market TTL
+pub const PREWARM_IDLE_INTERVAL_SECS: u64 = 1_800;
+pub const PREWARM_SYMBOLS: &[&str] = &[];
+
+// -----------------------------------------------
+// VOLATILITY SURFACE
+// -----------------------------------------------
+pub const SURFACE_MAX_EXPIRIES: usize = 6;
+pub const SURFACE_EXPIRIES_LIMIT: usize = 12;
+
+// -----------------------------------------------
+// API SERVER ACCESS (API keys are configured only in settings)
+// -----------------------------------------------
+pub const SERVER_BIND: &str = "127.0.0.1";
+pub const SERVER_REQUESTS_PER_MINUTE: u32 = 300;     // Per API key; 0 = unlimited
+pub const SERVER_BATCH_REQUESTS_PER_HOUR: u32 = 20;  // POST batch-analysis per API key
+
 // -----------------------------------------------
 // RATE LIMITING (if needed)
 // -----------------------------------------------
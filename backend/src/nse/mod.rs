@@ -8,7 +8,7 @@ pub mod nse_commands;
 
 // Re-exports (public API)
 pub use nse_client::NSEClient;
-pub use models::{Security, SecurityType, OptionChain, OptionData, OptionDetail};
+pub use models::{Security, SecurityType, OptionChain, OptionData, OptionDetail, NseFuturesData, NseFuturesQuote, NseHistoricalRecord, NseHistoricalResponse};
 pub use processor::{
     calculate_days_to_expiry, 
     find_atm_strike, 
@@ -16,7 +16,8 @@ pub use processor::{
     classify_money_with_distance, 
     calculate_time_value,
     calculate_oi_rankings,
+    enrich_nse_futures_data,
     ProcessedOptionData, 
     ProcessedOptionDetail,
     };
-pub use rules::{run_rules, check_option_rules, Alert, AlertValues, RulesOutput};
\ No newline at end of file
+pub use rules::{run_rules, check_option_rules, check_gamma_flip_rule, Alert, AlertValues, RulesOutput};
\ No newline at end of file
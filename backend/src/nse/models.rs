@@ -1,4 +1,6 @@
+use crate::analytics::Buildup;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Security {
@@ -91,7 +93,7 @@ pub struct OptionData {
 }
 
 /// Detailed option information (CE or PE)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct OptionDetail {
     // #[serde(default)]
     // pub identifier: Option<String>,  
@@ -122,4 +124,129 @@ pub struct OptionDetail {
 
      #[serde(rename = "oiRank")]
     pub oi_rank: Option<u32>,
-}
\ No newline at end of file
+}
+/// Response from NSE getSymbolDerivativesData for one futures expiry
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct NseFuturesData {
+    #[serde(default)]
+    pub data: Vec<NseFuturesQuote>,
+    #[serde(default)]
+    pub timestamp: Option<String>,
+}
+
+/// One futures contract quote with the buildup implied by its price and OI change
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NseFuturesQuote {
+    #[serde(rename = "lastPrice", default, deserialize_with = "lenient_f64")]
+    pub last_price: Option<f64>,
+
+    #[serde(rename = "prevClose", default, deserialize_with = "lenient_f64")]
+    pub prev_close: Option<f64>,
+
+    #[serde(rename = "change", default, deserialize_with = "lenient_f64")]
+    pub price_change: Option<f64>,
+
+    #[serde(rename = "pchange", default, deserialize_with = "lenient_f64")]
+    pub per_chg_price: Option<f64>,
+
+    #[serde(rename = "openInterest", default, deserialize_with = "lenient_f64")]
+    pub open_interest: Option<f64>,
+
+    #[serde(rename = "changeinOpenInterest", default, deserialize_with = "lenient_f64")]
+    pub change_in_oi: Option<f64>,
+
+    #[serde(rename = "pchangeinOpenInterest", default, deserialize_with = "lenient_f64")]
+    pub per_chg_oi: Option<f64>,
+
+    #[serde(rename = "underlyingValue", default, deserialize_with = "lenient_f64")]
+    pub underlying_value: Option<f64>,
+
+    #[serde(default)]
+    pub action: Buildup,
+
+    // Remaining exchange fields, passed through as received
+    #[serde(flatten)]
+    #[schema(ignore)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Response from NSE getDerivativesHistoricalData
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NseHistoricalResponse {
+    #[serde(default)]
+    pub data: Vec<NseHistoricalRecord>,
+}
+
+/// One day of NSE derivatives history (numeric fields may arrive as strings)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NseHistoricalRecord {
+    #[serde(rename = "FH_TIMESTAMP")]
+    pub timestamp: String,
+
+    #[serde(rename = "FH_SYMBOL", default)]
+    pub symbol: Option<String>,
+
+    #[serde(rename = "FH_INSTRUMENT", default)]
+    pub instrument: Option<String>,
+
+    #[serde(rename = "FH_EXPIRY_DT", default)]
+    pub expiry_date: Option<String>,
+
+    #[serde(rename = "FH_STRIKE_PRICE", default, deserialize_with = "lenient_f64")]
+    pub strike_price: Option<f64>,
+
+    #[serde(rename = "FH_OPTION_TYPE", default)]
+    pub option_type: Option<String>,
+
+    #[serde(rename = "FH_OPENING_PRICE", default, deserialize_with = "lenient_f64")]
+    pub open: Option<f64>,
+
+    #[serde(rename = "FH_TRADE_HIGH_PRICE", default, deserialize_with = "lenient_f64")]
+    pub high: Option<f64>,
+
+    #[serde(rename = "FH_TRADE_LOW_PRICE", default, deserialize_with = "lenient_f64")]
+    pub low: Option<f64>,
+
+    #[serde(rename = "FH_CLOSING_PRICE", default, deserialize_with = "lenient_f64")]
+    pub close: Option<f64>,
+
+    #[serde(rename = "FH_LAST_TRADED_PRICE", default, deserialize_with = "lenient_f64")]
+    pub last_traded_price: Option<f64>,
+
+    #[serde(rename = "FH_PREV_CLS", default, deserialize_with = "lenient_f64")]
+    pub previous_close: Option<f64>,
+
+    #[serde(rename = "FH_SETTLE_PRICE", default, deserialize_with = "lenient_f64")]
+    pub settle_price: Option<f64>,
+
+    #[serde(rename = "FH_TOT_TRADED_QTY", default, deserialize_with = "lenient_f64")]
+    pub volume: Option<f64>,
+
+    #[serde(rename = "FH_TOT_TRADED_VAL", default, deserialize_with = "lenient_f64")]
+    pub traded_value: Option<f64>,
+
+    #[serde(rename = "FH_OPEN_INT", default, deserialize_with = "lenient_f64")]
+    pub open_interest: Option<f64>,
+
+    #[serde(rename = "FH_CHANGE_IN_OI", default, deserialize_with = "lenient_f64")]
+    pub change_in_oi: Option<f64>,
+
+    #[serde(rename = "FH_MARKET_LOT", default, deserialize_with = "lenient_f64")]
+    pub market_lot: Option<f64>,
+
+    #[serde(rename = "FH_UNDERLYING_VALUE", default, deserialize_with = "lenient_f64")]
+    pub underlying_value: Option<f64>,
+}
+
+/// Accept numbers, numeric strings ("1,234.50") and "-"/"" (as None)
+fn lenient_f64<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = Option::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(match value {
+        Some(serde_json::Value::Number(n)) => n.as_f64(),
+        Some(serde_json::Value::String(s)) => s.trim().replace(',', "").parse().ok(),
+        _ => None,
+    })
+}
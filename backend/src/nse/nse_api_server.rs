@@ -1,45 +1,78 @@
-use super::config;
-use super::models::{Security, SecurityType};
+use super::models::{ContractInfo, NseFuturesData, OptionChain, Security, SecurityType};
 use super::nse_client::NSEClient;
+use crate::mcx::MCXClient;
 use super::{processor, rules};
-use anyhow::Result;
+use crate::cache::{prewarm, CacheStats, PrewarmRound, TtlCache};
+use crate::analytics::{
+    self, ExpectedMove, GammaExposure, IndicatorSpec, IvHistoryStore, IvRankStats, PricingModel,
+    HistoricalSeries, StrategyAnalysis, StrategyLegRequest, SymbolBuildup, SymbolIvRank,
+    VolatilitySurface, DEFAULT_ROLLING_WINDOW,
+};
+use crate::contracts::{ContractRegistry, ContractSpec, Exchange};
+use crate::jobs::{self, FetchTasks, JobProgress, JobRegistry, JobView};
+use crate::portfolio::{self, MarketData};
+use crate::screener::{self, ScreenerQuery, ScreenerResult, SymbolMetrics};
+use crate::settings::ExchangeSettings;
+use crate::utility::{self, metrics, upstream, ApiResponse, Readiness};
+use anyhow::{anyhow, Result};
 use axum::{
     extract::{Query, State},
     http::StatusCode,
+    middleware,
     response::Json,
     routing::{get, post},
     Router,
 };
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
+use std::time::Instant;
+use tokio::sync::{RwLock, Semaphore};
 use tower_http::cors::CorsLayer;
+use tracing::{info, instrument, warn};
+use utoipa::{IntoParams, OpenApi, ToSchema};
 
 // -----------------------------------------------
 // API REQUEST/RESPONSE MODELS
 // -----------------------------------------------
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ContractInfoQuery {
     pub symbol: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SingleAnalysisQuery {
     pub symbol: String,
     pub expiry: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct FuturesDataQuery {
     pub symbol: String,
     pub expiry: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct VolatilitySurfaceQuery {
+    pub symbol: String,
+    pub max_expiries: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct StrategyRequest {
+    pub symbol: String,
+    pub expiry: String,
+    pub legs: Vec<StrategyLegRequest>,
+    pub lot_size: Option<f64>,  // Defaults to the registry point value
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct DerivativesHistoricalQuery {
     pub symbol: String,
     pub instrument_type: String, // "OPTIONS" or "FUTURES"
@@ -49,55 +82,67 @@ pub struct DerivativesHistoricalQuery {
     pub option_type: Option<String>, // "CE" or "PE"
     pub from_date: String,
     pub to_date: String,
+    pub window: Option<usize>, // Rolling window for the normalized series
+    pub indicators: Option<String>, // e.g. "sma:20,ema:9,rsi:14,atr:14,bb:20:2,vwap,oi_sma:5"
 }
 
-#[derive(Debug, Serialize)]
-pub struct ApiResponse<T> {
-    pub success: bool,
-    pub data: Option<T>,
-    pub error: Option<String>,
-    pub processing_time_ms: Option<u64>,
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ScreenerParams {
+    pub filter: Option<String>, // e.g. "pcr > 1.2 and alerts > 0"
+    pub sort: Option<String>,   // e.g. "-iv_rank,spread"
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub refresh: bool,          // Start a background batch job; the last metrics are served until it completes
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct SecurityListResponse {
     pub indices: Vec<SecurityInfo>,
     pub equities: HashMap<String, Vec<SecurityInfo>>,  // Grouped by first letter
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Clone, ToSchema)]
 pub struct SecurityInfo {
     pub symbol: String,
     pub security_type: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ContractInfoResponse {
     pub symbol: String,
     pub expiry_dates: Vec<String>,
     pub strike_prices: Vec<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct SingleAnalysisResponse {
     pub symbol: String,
     pub timestamp: String,
     pub underlying_value: f64,
     pub spread: f64,
     pub days_to_expiry: i32,
+    pub expected_move: Option<ExpectedMove>,
     pub ce_oi: f64,
     pub pe_oi: f64,
     pub processed_data: Vec<processor::ProcessedOptionData>,
     pub alerts: Option<rules::RulesOutput>,
+    pub contract_spec: Option<ContractSpec>,
+    pub ce_oi_notional: Option<f64>,
+    pub pe_oi_notional: Option<f64>,
+    pub gamma_exposure: Option<GammaExposure>,
+    pub iv_rank: Option<IvRankStats>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct BatchAnalysisResponse {
     pub summary: BatchSummary,
     pub rules_output: Vec<rules::RulesOutput>,
+    pub buildup_summary: Vec<SymbolBuildup>,
+    pub iv_ranks: Vec<SymbolIvRank>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct BatchSummary {
     pub total_securities: usize,
     pub successful: usize,
@@ -114,22 +159,378 @@ pub struct BatchSummary {
 #[derive(Clone)]
 pub struct AppState {
     client: Arc<NSEClient>,
-    cache: Arc<RwLock<Cache>>,
+    cache: Cache,
+    last_spot: Arc<RwLock<HashMap<(String, String), f64>>>,  // Previous underlying per (symbol, expiry) chain (gamma flip rule)
+    screener_metrics: Arc<RwLock<Option<Vec<SymbolMetrics>>>>,  // From the last batch run
+    contracts: Arc<RwLock<ContractRegistry>>,
+    jobs: JobRegistry<BatchAnalysisResponse>,
+    market: Arc<MarketData>,  // Paper portfolio marks (shares this server's client)
 }
 
-#[derive(Default)]
+const BATCH_JOB_KIND: &str = "nse-batch";
+
+/// Upstream responses, one cache per resource (policies from `[nse.cache]` settings)
+#[derive(Clone)]
 struct Cache {
-    securities_list: Option<(Vec<Security>, Instant)>,
-    contract_info: HashMap<String, (super::models::ContractInfo, Instant)>,
+    securities_list: TtlCache<(), Vec<Security>>,
+    contract_info: TtlCache<String, ContractInfo>,
+    option_chains: TtlCache<(String, String), OptionChain>,  // (symbol, expiry)
 }
 
-const CACHE_DURATION: Duration = Duration::from_secs(300); // 5 minutes
+impl Cache {
+    fn new(settings: &ExchangeSettings) -> Self {
+        Self {
+            securities_list: TtlCache::new("nse_securities_list", settings.cache_policy("securities_list")),
+            contract_info: TtlCache::new("nse_contract_info", settings.cache_policy("contract_info")),
+            option_chains: TtlCache::new("nse_option_chains", settings.cache_policy("option_chains")),
+        }
+    }
+
+    fn stats(&self) -> Vec<CacheStats> {
+        vec![self.securities_list.stats(), self.contract_info.stats(), self.option_chains.stats()]
+    }
+}
 
 impl AppState {
     pub fn new() -> Result<Self> {
+        let client = Arc::new(NSEClient::new()?);
         Ok(Self {
-            client: Arc::new(NSEClient::new()?),
-            cache: Arc::new(RwLock::new(Cache::default())),
+            cache: Cache::new(client.settings()),
+            client: Arc::clone(&client),
+            last_spot: Arc::new(RwLock::new(HashMap::new())),
+            screener_metrics: Arc::new(RwLock::new(None)),
+            contracts: Arc::new(RwLock::new(ContractRegistry::load())),
+            jobs: JobRegistry::new(),
+            market: Arc::new(MarketData::with_clients(client, Arc::new(MCXClient::new()?))),
+        })
+    }
+
+    pub fn settings(&self) -> &ExchangeSettings {
+        self.client.settings()
+    }
+
+    async fn securities(&self) -> Result<Vec<Security>> {
+        let client = Arc::clone(&self.client);
+        self.cache.securities_list.get_or_fetch((), move || async move { client.fetch_fno_list().await }).await
+    }
+
+    async fn contract_info(&self, symbol: &str) -> Result<ContractInfo> {
+        let client = Arc::clone(&self.client);
+        let owned = symbol.to_string();
+        self.cache.contract_info
+            .get_or_fetch(symbol.to_string(), move || async move { client.fetch_contract_info(&owned).await })
+            .await
+    }
+
+    async fn option_chain(&self, security: &Security, expiry: &str) -> Result<OptionChain> {
+        let client = Arc::clone(&self.client);
+        let (owned, expiry_owned) = (security.clone(), expiry.to_string());
+        self.cache.option_chains
+            .get_or_fetch((security.symbol.clone(), expiry.to_string()), move || async move {
+                client.fetch_option_chain(&owned, &expiry_owned).await
+            })
+            .await
+    }
+
+    /// Refresh whatever is about to expire for the hot symbols: the securities list, their
+    /// contract info and the nearest-expiry chain
+    async fn prewarm(&self, symbols: &[String]) -> PrewarmRound {
+        let margin = self.settings().prewarm_schedule().interval;
+        let mut round = PrewarmRound::default();
+
+        if self.cache.securities_list.expires_within(&(), margin) {
+            let client = Arc::clone(&self.client);
+            round.record(self.cache.securities_list.refresh((), || async move { client.fetch_fno_list().await }).await);
+        }
+
+        for symbol in symbols {
+            let contract_info = if self.cache.contract_info.expires_within(symbol, margin) {
+                let client = Arc::clone(&self.client);
+                round.record(self.cache.contract_info.refresh(symbol.clone(), || async move {
+                    client.fetch_contract_info(symbol).await
+                }).await)
+            } else {
+                self.contract_info(symbol).await.ok()
+            };
+
+            let nearest_expiry = contract_info.and_then(|info| {
+                info.expiry_dates.into_iter()
+                    .filter_map(|expiry| processor::calculate_days_to_expiry(&expiry).ok().map(|days| (days, expiry)))
+                    .filter(|(days, _)| *days >= 0)
+                    .min_by_key(|(days, _)| *days)
+                    .map(|(_, expiry)| expiry)
+            });
+            let Some(expiry) = nearest_expiry else {
+                continue;
+            };
+
+            let key = (symbol.clone(), expiry.clone());
+            if self.cache.option_chains.expires_within(&key, margin) {
+                let security = if self.settings().is_index(symbol) {
+                    Security::index(symbol.clone())
+                } else {
+                    Security::equity(symbol.clone())
+                };
+                let client = Arc::clone(&self.client);
+                round.record(self.cache.option_chains.refresh(key, || async move {
+                    client.fetch_option_chain(&security, &expiry).await
+                }).await);
+            }
+        }
+        round
+    }
+
+    /// Refresh NSE lot sizes from the F&O contract master
+    pub async fn refresh_contract_specs(&self) -> Result<usize> {
+        let lots = self.client.fetch_market_lots().await?;
+        let mut contracts = self.contracts.write().await;
+        Ok(contracts.merge_nse_lot_sizes(&lots))
+    }
+
+    /// IV rank/percentile of an ATM IV against the recorded history, without storing it
+    async fn lookup_iv_rank(&self, symbol: &str, date: chrono::NaiveDate, atm_iv: f64) -> Option<IvRankStats> {
+        let path = IvHistoryStore::path();
+        let symbol = symbol.to_string();
+        let ranked = tokio::task::spawn_blocking(move || {
+            IvHistoryStore::load_file(&path).map(|store| store.rank(Exchange::Nse, &symbol, date, atm_iv))
+        })
+        .await;
+        match ranked.map_err(anyhow::Error::from).and_then(|result| result) {
+            Ok(ranks) => ranks,
+            Err(e) => {
+                warn!("Failed to read IV history: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Gamma exposure for a chain, plus a flip alert if spot crossed the
+    /// zero-gamma level since this symbol and expiry were last analyzed
+    async fn gamma_exposure(
+        &self,
+        symbol: &str,
+        expiry: &str,
+        chain: &OptionChain,
+        days_to_expiry: i32,
+        spread: f64,
+    ) -> (Option<GammaExposure>, Option<rules::Alert>) {
+        let underlying_value = chain.records.underlying_value;
+        let point_value = self.contracts.read().await
+            .nse(symbol)
+            .map(|spec| spec.point_value())
+            .unwrap_or(1.0);
+
+        let exposure = analytics::compute_gamma_exposure(
+            &PricingModel::nse(),
+            &processor::exposure_quotes(&chain.filtered.data),
+            underlying_value,
+            days_to_expiry,
+            point_value,
+        );
+
+        // Spot is remembered per expiry (each chain has its own zero-gamma level) and only
+        // when there is a flip level, so the next run compares like with like
+        let alert = match exposure.as_ref().filter(|e| e.zero_gamma_level.is_some()) {
+            Some(flip_exposure) => {
+                let previous_spot = self.last_spot.write().await
+                    .insert((symbol.to_string(), expiry.to_string()), underlying_value);
+                previous_spot.and_then(|previous_spot| {
+                    rules::check_gamma_flip_rule(symbol, expiry, previous_spot, flip_exposure, spread, days_to_expiry)
+                })
+            }
+            None => None,
+        };
+
+        (exposure, alert)
+    }
+
+    /// Start a background batch job, or return the id of the one already running
+    async fn start_batch_job(&self) -> String {
+        let state = self.clone();
+        self.jobs
+            .spawn(BATCH_JOB_KIND, |progress| async move { state.batch_analysis(progress).await })
+            .await
+    }
+
+    /// Fetch and analyze every F&O security, caching per-symbol screener metrics
+    #[instrument(skip_all, fields(exchange = "nse"))]
+    async fn batch_analysis(&self, progress: Arc<JobProgress>) -> Result<BatchAnalysisResponse> {
+        let start_time = Instant::now();
+
+        // Step 1: Fetch all FNO securities
+        progress.set_stage("securities");
+        let securities = self.client.fetch_fno_list().await
+            .map_err(|e| {
+                metrics::registry().record_batch_failure("nse");
+                anyhow!("Failed to fetch securities list: {}", e)
+            })?;
+
+        let total_securities = securities.len();
+        let max_concurrent = self.settings().max_concurrent;
+
+        // Step 2: Bulk process all securities
+        progress.set_stage("fetching");
+        let results = self.client.clone()
+            .fetch_all_option_chains_tracked(securities.clone(), max_concurrent, Arc::clone(&progress))
+            .await;
+        progress.check_cancelled()?;
+        progress.set_stage("processing");
+
+        // Step 3: Process results
+        let mut successful = Vec::new();
+        let mut failed_count = 0;
+
+        for (security, result) in securities.iter().zip(results.iter()) {
+            match result {
+                Ok((_, chain)) => {
+                    successful.push((security.clone(), chain.clone()));
+                }
+                Err(_) => {
+                    failed_count += 1;
+                }
+            }
+        }
+
+        // Step 4: Process data and run rules
+        let mut batch_for_rules = Vec::new();
+        let mut buildup_summary = Vec::new();
+        let mut symbol_alerts = Vec::new();
+        let mut iv_entries = Vec::new();
+        let mut iv_context = HashMap::new();
+        let mut screener_metrics = Vec::new();
+
+        for (security, chain) in successful.iter() {
+            let (processed_data, spread) = processor::process_option_data(
+                chain.filtered.data.clone(),
+                chain.records.underlying_value
+            );
+
+            screener_metrics.push(SymbolMetrics::from_chain(
+                &security.symbol,
+                &chain.records.timestamp,
+                chain.records.underlying_value,
+                spread,
+                chain.filtered.ce_totals.total_oi,
+                chain.filtered.pe_totals.total_oi,
+                &processed_data,
+            ));
+
+            buildup_summary.push(SymbolBuildup {
+                symbol: security.symbol.clone(),
+                counts: processor::count_buildups(&processed_data),
+            });
+
+            // Gamma flip rule (indices only - dealer positioning matters most there)
+            if security.security_type == SecurityType::Indices {
+                let first = processed_data.first();
+                let expiry = first.and_then(|opt| opt.expiry_date.clone()).unwrap_or_default();
+                let days_to_expiry = first.map(|opt| opt.days_to_expiry).unwrap_or(0);
+
+                let (_, flip_alert) = self
+                    .gamma_exposure(&security.symbol, &expiry, chain, days_to_expiry, spread)
+                    .await;
+                if let Some(alert) = flip_alert {
+                    symbol_alerts.push((chain.records.timestamp.clone(), chain.records.underlying_value, alert));
+                }
+            }
+
+            if let Some(atm_iv) = processor::calculate_expected_move(&processed_data, chain.records.underlying_value)
+                .and_then(|m| m.atm_iv)
+            {
+                let first = processed_data.first();
+                iv_entries.push((security.symbol.clone(), analytics::observation_date(&chain.records.timestamp), atm_iv));
+                iv_context.insert(security.symbol.clone(), (
+                    first.and_then(|opt| opt.expiry_date.clone()).unwrap_or_default(),
+                    first.map(|opt| opt.days_to_expiry).unwrap_or(0),
+                    spread,
+                    chain.records.timestamp.clone(),
+                    chain.records.underlying_value,
+                ));
+            }
+
+            batch_for_rules.push((
+                security.symbol.clone(),
+                chain.records.timestamp.clone(),
+                chain.records.underlying_value,
+                processed_data,
+                spread,
+            ));
+        }
+
+        // IV rank/percentile from the daily ATM IV history (only batch runs record: one nearest-expiry IV per symbol and day)
+        let mut iv_ranks: Vec<SymbolIvRank> = analytics::record_daily_atm_ivs(Exchange::Nse, iv_entries)
+            .await
+            .into_iter()
+            .map(|(symbol, stats)| SymbolIvRank { symbol, stats })
+            .collect();
+        iv_ranks.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        for row in &iv_ranks {
+            if let Some((expiry, days_to_expiry, spread, timestamp, underlying_value)) = iv_context.remove(&row.symbol)
+                && let Some(alert) = rules::check_iv_percentile_rule(&row.symbol, &expiry, &row.stats, spread, days_to_expiry)
+            {
+                symbol_alerts.push((timestamp, underlying_value, alert));
+            }
+        }
+
+        // Run rules on all securities
+        let mut rules_outputs = rules::run_batch_rules(batch_for_rules);
+        rules::merge_symbol_alerts(&mut rules_outputs, symbol_alerts);
+        {
+            let contracts = self.contracts.read().await;
+            for output in rules_outputs.iter_mut() {
+                output.contract_spec = contracts.nse(&output.symbol).cloned();
+            }
+        }
+
+        // Alert counts and IV ranks complete the screener metrics
+        let alert_counts: HashMap<&str, usize> = rules_outputs.iter()
+            .map(|r| (r.symbol.as_str(), r.alerts.len()))
+            .collect();
+        for metrics in screener_metrics.iter_mut() {
+            metrics.alerts = alert_counts.get(metrics.symbol.as_str()).copied().unwrap_or(0);
+            if let Some(row) = iv_ranks.iter().find(|row| row.symbol == metrics.symbol) {
+                metrics.set_iv_rank(&row.stats);
+            }
+        }
+        *self.screener_metrics.write().await = Some(screener_metrics);
+
+        // Risk alerts for open paper positions join the market alerts (after the screener counts)
+        match portfolio::book_risk_alerts(&self.market, Exchange::Nse).await {
+            Ok(risk) => portfolio::merge_nse_risk_alerts(&mut rules_outputs, risk.nse),
+            Err(e) => warn!("Skipped portfolio risk alerts: {}", e),
+        }
+
+        let total_alerts: usize = rules_outputs.iter()
+            .map(|r| r.alerts.len())
+            .sum();
+
+        let summary = BatchSummary {
+            total_securities,
+            successful: successful.len(),
+            failed: failed_count,
+            securities_with_alerts: rules_outputs.len(),
+            total_alerts,
+            processing_time_ms: start_time.elapsed().as_millis() as u64,
+        };
+
+        info!(
+            successful = summary.successful,
+            failed = summary.failed,
+            total_alerts = summary.total_alerts,
+            processing_time_ms = summary.processing_time_ms,
+            "Batch analysis completed"
+        );
+        metrics::registry().record_batch("nse", summary.successful, summary.failed);
+        metrics::registry().record_alerts(
+            "nse",
+            rules_outputs.iter().flat_map(|r| r.alerts.iter().map(|a| a.alert_type.as_str())),
+        );
+
+        Ok(BatchAnalysisResponse {
+            summary,
+            rules_output: rules_outputs,
+            buildup_summary,
+            iv_ranks,
         })
     }
 }
@@ -139,35 +540,28 @@ impl AppState {
 // -----------------------------------------------
 
 /// GET /nse_health - Health check endpoint
+#[utoipa::path(get, path = "/nse_health", tag = "nse", responses((status = 200, description = "Server is up", body = String)))]
 async fn health() -> &'static str {
     "NSE server -> OK"
 }
 
+/// GET /ready - Readiness: upstream session, circuit breaker and cache freshness
+#[utoipa::path(get, path = "/ready", tag = "nse", responses(
+    (status = 200, description = "Ready, starting or degraded", body = Readiness),
+    (status = 503, description = "Server is up but NSE is blocking requests (circuit open)", body = Readiness),
+))]
+async fn ready(State(app_state): State<AppState>) -> Readiness {
+    let upstream = upstream::health(Exchange::Nse).status(&app_state.settings().circuit);
+    Readiness::evaluate(Exchange::Nse, upstream, &app_state.cache.stats())
+}
+
 /// GET /api/nse/securities - Get all FNO securities list
+#[utoipa::path(get, path = "/api/nse/securities", tag = "nse", responses((status = 200, body = ApiResponse<SecurityListResponse>)))]
 async fn get_securities(State(app_state): State<AppState>) -> Result<Json<ApiResponse<SecurityListResponse>>, StatusCode> {
     let start_time = Instant::now();
 
-    // Check cache first
-    {
-        let cache = app_state.cache.read().await;
-        if let Some((securities, cached_at)) = &cache.securities_list {
-            if cached_at.elapsed() < CACHE_DURATION {
-                return Ok(Json(format_securities_response(securities.clone(), start_time)));
-            }
-        }
-    }
-
-    // Fetch from API
-    match app_state.client.fetch_fno_list().await {
-        Ok(securities) => {
-            // Update cache
-            {
-                let mut cache = app_state.cache.write().await;
-                cache.securities_list = Some((securities.clone(), Instant::now()));
-            }
-
-            Ok(Json(format_securities_response(securities, start_time)))
-        }
+    match app_state.securities().await {
+        Ok(securities) => Ok(Json(format_securities_response(securities, start_time))),
         Err(e) => Ok(Json(ApiResponse {
             success: false,
             data: None,
@@ -178,6 +572,7 @@ async fn get_securities(State(app_state): State<AppState>) -> Result<Json<ApiRes
 }
 
 /// GET /api/nse/contract-info?symbol=NIFTY - Get contract info for a symbol
+#[utoipa::path(get, path = "/api/nse/contract-info", tag = "nse", params(ContractInfoQuery), responses((status = 200, body = ApiResponse<ContractInfoResponse>)))]
 async fn get_contract_info(
     Query(query): Query<ContractInfoQuery>,
     State(app_state): State<AppState>,
@@ -185,37 +580,8 @@ async fn get_contract_info(
     let start_time = Instant::now();
     let symbol = &query.symbol;
 
-    // Check cache first
-    {
-        let cache = app_state.cache.read().await;
-        if let Some((contract_info, cached_at)) = cache.contract_info.get(symbol) {
-            if cached_at.elapsed() < CACHE_DURATION {
-                return Ok(Json(ApiResponse {
-                    success: true,
-                    data: Some(ContractInfoResponse {
-                        symbol: symbol.to_string(),
-                        expiry_dates: contract_info.expiry_dates.clone(),
-                        strike_prices: contract_info.strike_prices.clone(),
-                    }),
-                    error: None,
-                    processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
-                }));
-            }
-        }
-    }
-
-    // Fetch from API
-    match app_state.client.fetch_contract_info(symbol).await {
+    match app_state.contract_info(symbol).await {
         Ok(contract_info) => {
-            // Update cache
-            {
-                let mut cache = app_state.cache.write().await;
-                cache.contract_info.insert(
-                    symbol.to_string(),
-                    (contract_info.clone(), Instant::now()),
-                );
-            }
-
             Ok(Json(ApiResponse {
                 success: true,
                 data: Some(ContractInfoResponse {
@@ -237,6 +603,7 @@ async fn get_contract_info(
 }
 
 /// GET /api/nse/single-analysis?symbol=NIFTY&expiry=30-Dec-2025 - Get single security analysis
+#[utoipa::path(get, path = "/api/nse/single-analysis", tag = "nse", params(SingleAnalysisQuery), responses((status = 200, body = ApiResponse<SingleAnalysisResponse>)))]
 async fn get_single_analysis(
     Query(query): Query<SingleAnalysisQuery>,
     State(app_state): State<AppState>,
@@ -246,13 +613,13 @@ async fn get_single_analysis(
     let expiry = &query.expiry;
 
     // Determine security type
-    let security = if config::NSE_INDICES.contains(&symbol.as_str()) {
+    let security = if app_state.settings().is_index(symbol) {
         Security::index(symbol.to_string())
     } else {
         Security::equity(symbol.to_string())
     };
 
-    match app_state.client.fetch_option_chain(&security, expiry).await {
+    match app_state.option_chain(&security, expiry).await {
         Ok(chain) => {
             // Process the data
             let (processed_data, spread) = processor::process_option_data(
@@ -266,13 +633,53 @@ async fn get_single_analysis(
                 .unwrap_or(0);
 
             // Run rules on processed data
+            let contract_spec = app_state.contracts.read().await.nse(symbol).cloned();
             let alerts = rules::run_rules(
                 &processed_data,
                 symbol.to_string(),
                 chain.records.timestamp.clone(),
                 chain.records.underlying_value,
                 spread,
-            );
+            )
+            .map(|output| rules::RulesOutput { contract_spec: contract_spec.clone(), ..output });
+
+            // Gamma exposure and flip rule
+            let (gamma_exposure, flip_alert) = app_state
+                .gamma_exposure(symbol, expiry, &chain, days_to_expiry, spread)
+                .await;
+            let alerts = match flip_alert {
+                Some(alert) => Some(rules::RulesOutput {
+                    contract_spec: contract_spec.clone(),
+                    ..rules::append_alert(alerts, alert, &chain.records.timestamp, chain.records.underlying_value)
+                }),
+                None => alerts,
+            };
+
+            // IV rank/percentile against the batch-recorded history and extreme percentile rule
+            let expected_move = processor::calculate_expected_move(&processed_data, chain.records.underlying_value);
+            let iv_rank = match expected_move.as_ref().and_then(|m| m.atm_iv) {
+                Some(atm_iv) => {
+                    let date = analytics::observation_date(&chain.records.timestamp);
+                    app_state.lookup_iv_rank(symbol, date, atm_iv).await
+                }
+                None => None,
+            };
+            let iv_alert = iv_rank.as_ref().and_then(|iv| {
+                rules::check_iv_percentile_rule(symbol, expiry, iv, spread, days_to_expiry)
+            });
+            let alerts = match iv_alert {
+                Some(alert) => Some(rules::RulesOutput {
+                    contract_spec: contract_spec.clone(),
+                    ..rules::append_alert(alerts, alert, &chain.records.timestamp, chain.records.underlying_value)
+                }),
+                None => alerts,
+            };
+
+            let oi_notional = |oi: f64| {
+                contract_spec.as_ref().map(|spec| spec.notional(chain.records.underlying_value, oi))
+            };
+            let ce_oi_notional = oi_notional(chain.filtered.ce_totals.total_oi);
+            let pe_oi_notional = oi_notional(chain.filtered.pe_totals.total_oi);
 
             Ok(Json(ApiResponse {
                 success: true,
@@ -282,10 +689,16 @@ async fn get_single_analysis(
                     underlying_value: chain.records.underlying_value,
                     spread,
                     days_to_expiry,
+                    expected_move,
                     ce_oi: chain.filtered.ce_totals.total_oi,
                     pe_oi: chain.filtered.pe_totals.total_oi,
                     processed_data,
                     alerts,
+                    contract_spec,
+                    ce_oi_notional,
+                    pe_oi_notional,
+                    gamma_exposure,
+                    iv_rank,
                 }),
                 error: None,
                 processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
@@ -301,21 +714,26 @@ async fn get_single_analysis(
 }
 
 /// GET /api/nse/futures-data?symbol=NIFTY&expiry=30-Dec-2025 - Get futures data
+#[utoipa::path(get, path = "/api/nse/futures-data", tag = "nse", params(FuturesDataQuery), responses((status = 200, description = "NSE futures quotes with their buildup", body = ApiResponse<NseFuturesData>)))]
 async fn get_futures_data(
     Query(query): Query<FuturesDataQuery>,
     State(app_state): State<AppState>,
-) -> Result<Json<ApiResponse<Value>>, StatusCode> {
+) -> Result<Json<ApiResponse<NseFuturesData>>, StatusCode> {
     let start_time = Instant::now();
     let symbol = &query.symbol;
     let expiry = &query.expiry;
 
     match app_state.client.fetch_futures_data(symbol, expiry).await {
-        Ok(data) => Ok(Json(ApiResponse {
-            success: true,
-            data: Some(data),
-            error: None,
-            processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
-        })),
+        Ok(mut data) => {
+            processor::enrich_nse_futures_data(&mut data);
+
+            Ok(Json(ApiResponse {
+                success: true,
+                data: Some(data),
+                error: None,
+                processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
+            }))
+        }
         Err(e) => Ok(Json(ApiResponse {
             success: false,
             data: None,
@@ -326,14 +744,25 @@ async fn get_futures_data(
 }
 
 /// GET /api/nse/derivatives-historical - Get derivatives historical data
+#[utoipa::path(get, path = "/api/nse/derivatives-historical", tag = "nse", params(DerivativesHistoricalQuery), responses((status = 200, description = "Normalized OHLC/OI series with any requested indicators", body = ApiResponse<HistoricalSeries>)))]
 async fn get_derivatives_historical_data(
     Query(query): Query<DerivativesHistoricalQuery>,
     State(app_state): State<AppState>,
-) -> Result<Json<ApiResponse<Value>>, StatusCode> {
+) -> Result<Json<ApiResponse<HistoricalSeries>>, StatusCode> {
     let start_time = Instant::now();
-    
+
+    let indicators = match IndicatorSpec::parse_list(query.indicators.as_deref().unwrap_or_default()) {
+        Ok(indicators) => indicators,
+        Err(e) => return Ok(Json(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(e.to_string()),
+            processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
+        })),
+    };
+
     // Determine security type
-    let security_type = if config::NSE_INDICES.contains(&query.symbol.as_str()) {
+    let security_type = if app_state.settings().is_index(&query.symbol) {
         SecurityType::Indices
     } else {
         SecurityType::Equity
@@ -349,13 +778,17 @@ async fn get_derivatives_historical_data(
         query.option_type.as_deref(),
         &query.from_date,
         &query.to_date,
+        query.window.unwrap_or(DEFAULT_ROLLING_WINDOW),
     ).await {
-        Ok(data) => Ok(Json(ApiResponse {
-            success: true,
-            data: Some(data),
-            error: None,
-            processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
-        })),
+        Ok(mut series) => {
+            analytics::apply_indicators(&mut series, &indicators);
+            Ok(Json(ApiResponse {
+                success: true,
+                data: Some(series),
+                error: None,
+                processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
+            }))
+        }
         Err(e) => Ok(Json(ApiResponse {
             success: false,
             data: None,
@@ -365,86 +798,261 @@ async fn get_derivatives_historical_data(
     }
 }
 
-/// POST /api/nse/batch-analysis - Run batch analysis
-async fn run_batch_analysis(
+/// GET /api/nse/volatility-surface?symbol=NIFTY - IV smile, skew and term structure across expiries
+#[utoipa::path(get, path = "/api/nse/volatility-surface", tag = "nse", params(VolatilitySurfaceQuery), responses((status = 200, body = ApiResponse<VolatilitySurface>)))]
+async fn get_volatility_surface(
+    Query(query): Query<VolatilitySurfaceQuery>,
     State(app_state): State<AppState>,
-) -> Result<Json<ApiResponse<BatchAnalysisResponse>>, StatusCode> {
+) -> Result<Json<ApiResponse<VolatilitySurface>>, StatusCode> {
     let start_time = Instant::now();
+    let symbol = &query.symbol;
+    let max_expiries = match app_state.settings().surface_expiries(query.max_expiries) {
+        Ok(max_expiries) => max_expiries,
+        Err(e) => return Ok(Json(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(e.to_string()),
+            processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
+        })),
+    };
+
+    let security = if app_state.settings().is_index(symbol) {
+        Security::index(symbol.to_string())
+    } else {
+        Security::equity(symbol.to_string())
+    };
 
-    // Step 1: Fetch all FNO securities
-    let securities = match app_state.client.fetch_fno_list().await {
-        Ok(securities) => securities,
+    // Step 1: All listed expiries for the symbol
+    let contract_info = match app_state.contract_info(symbol).await {
+        Ok(info) => info,
         Err(e) => {
             return Ok(Json(ApiResponse {
                 success: false,
                 data: None,
-                error: Some(format!("Failed to fetch securities list: {}", e)),
+                error: Some(format!("Failed to fetch contract info: {}", e)),
                 processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
             }));
         }
     };
 
-    let total_securities = securities.len();
-    let max_concurrent = config::DEFAULT_MAX_CONCURRENT;
+    // Step 2: Keep live expiries, nearest first
+    let mut expiries: Vec<(String, i32)> = contract_info.expiry_dates
+        .iter()
+        .filter_map(|expiry| {
+            processor::calculate_days_to_expiry(expiry)
+                .ok()
+                .map(|days| (expiry.clone(), days))
+        })
+        .collect();
+    expiries.sort_by_key(|(_, days)| *days);
+    expiries.truncate(max_expiries);
 
-    // Step 2: Bulk process all securities  
-    let results = app_state.client.clone().fetch_all_option_chains(securities.clone(), max_concurrent).await;
+    // Step 3: Fetch the chains concurrently, then build each smile in expiry order
+    let semaphore = Arc::new(Semaphore::new(app_state.settings().max_concurrent));
+    let mut tasks = FetchTasks::new();
+    for (expiry, _) in &expiries {
+        let (state, sem, security, expiry) = (app_state.clone(), Arc::clone(&semaphore), security.clone(), expiry.clone());
+        tasks.spawn(async move {
+            let _permit = sem.acquire_owned().await
+                .map_err(|e| anyhow!("Semaphore error: {}", e))?;
+            state.option_chain(&security, &expiry).await
+        });
+    }
+    let chains = tasks.join_all().await;
 
-    // Step 3: Process results
-    let mut successful = Vec::new();
-    let mut failed_count = 0;
+    let model = PricingModel::nse();
+    let mut smiles = Vec::new();
+    let mut underlying_value = 0.0;
 
-    for (security, result) in securities.iter().zip(results.iter()) {
-        match result {
-            Ok((_, chain)) => {
-                successful.push((security.clone(), chain.clone()));
+    for ((expiry, days_to_expiry), chain) in expiries.into_iter().zip(chains) {
+        match chain {
+            Ok(chain) => {
+                if chain.records.underlying_value <= 0.0 {
+                    continue;
+                }
+                if smiles.is_empty() {
+                    underlying_value = chain.records.underlying_value;
+                }
+                let quotes = processor::smile_quotes(&chain.filtered.data);
+                smiles.push(analytics::build_expiry_smile(
+                    &model,
+                    &expiry,
+                    days_to_expiry,
+                    chain.records.underlying_value,
+                    &quotes,
+                ));
             }
-            Err(_) => {
-                failed_count += 1;
+            Err(e) => {
+                warn!("Failed to fetch {} chain for {}: {}", symbol, expiry, e);
             }
         }
     }
 
-    // Step 4: Process data and run rules
-    let mut batch_for_rules = Vec::new();
-    
-    for (security, chain) in successful.iter() {
-        let (processed_data, spread) = processor::process_option_data(
-            chain.filtered.data.clone(),
-            chain.records.underlying_value
-        );
-        
-        batch_for_rules.push((
-            security.symbol.clone(),
-            chain.records.timestamp.clone(),
+    if smiles.is_empty() {
+        return Ok(Json(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(format!("No option chains available for {}", symbol)),
+            processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
+        }));
+    }
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(analytics::build_volatility_surface(symbol, underlying_value, smiles)),
+        error: None,
+        processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
+    }))
+}
+
+/// POST /api/nse/strategy - Payoff, P&L curve, breakevens and greeks for a multi-leg strategy
+#[utoipa::path(post, path = "/api/nse/strategy", tag = "nse", request_body = StrategyRequest, responses((status = 200, body = ApiResponse<StrategyAnalysis>)))]
+async fn analyze_strategy(
+    State(app_state): State<AppState>,
+    Json(request): Json<StrategyRequest>,
+) -> Result<Json<ApiResponse<StrategyAnalysis>>, StatusCode> {
+    let start_time = Instant::now();
+    let symbol = &request.symbol;
+
+    let security = if app_state.settings().is_index(symbol) {
+        Security::index(symbol.to_string())
+    } else {
+        Security::equity(symbol.to_string())
+    };
+
+    let result = async {
+        let days_to_expiry = processor::calculate_days_to_expiry(&request.expiry)?;
+        let chain = app_state.option_chain(&security, &request.expiry).await?;
+        let quotes = processor::smile_quotes(&chain.filtered.data);
+        let lot_size = match request.lot_size {
+            Some(lot_size) => lot_size,
+            None => app_state.contracts.read().await
+                .nse(symbol)
+                .map(|spec| spec.point_value())
+                .unwrap_or(1.0),
+        };
+
+        analytics::analyze_strategy(
+            &PricingModel::nse(),
+            &request.legs,
+            &quotes,
             chain.records.underlying_value,
-            processed_data,
-            spread,
-        ));
+            days_to_expiry,
+            lot_size,
+        )
     }
-    
-    // Run rules on all securities
-    let rules_outputs = rules::run_batch_rules(batch_for_rules);
-    
-    let total_alerts: usize = rules_outputs.iter()
-        .map(|r| r.alerts.len())
-        .sum();
-
-    let summary = BatchSummary {
-        total_securities,
-        successful: successful.len(),
-        failed: failed_count,
-        securities_with_alerts: rules_outputs.len(),
-        total_alerts,
-        processing_time_ms: start_time.elapsed().as_millis() as u64,
+    .await;
+
+    match result {
+        Ok(analysis) => Ok(Json(ApiResponse {
+            success: true,
+            data: Some(analysis),
+            error: None,
+            processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
+        })),
+        Err(e) => Ok(Json(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(e.to_string()),
+            processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
+        })),
+    }
+}
+
+/// POST /api/nse/batch-analysis - Start a batch analysis job; poll GET /jobs/{id} for progress
+#[utoipa::path(post, path = "/api/nse/batch-analysis", tag = "nse", responses((status = 200, body = ApiResponse<JobView<BatchAnalysisResponse>>)))]
+async fn run_batch_analysis(
+    State(app_state): State<AppState>,
+) -> Result<Json<ApiResponse<JobView<BatchAnalysisResponse>>>, StatusCode> {
+    let start_time = Instant::now();
+
+    let id = app_state.start_batch_job().await;
+
+    Ok(Json(match app_state.jobs.get(&id).await {
+        Some(job) => ApiResponse {
+            success: true,
+            data: Some(job),
+            error: None,
+            processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
+        },
+        None => ApiResponse {
+            success: false,
+            data: None,
+            error: Some(format!("Job '{}' not found", id)),
+            processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
+        },
+    }))
+}
+
+/// GET /api/nse/batch-analysis - Result of the most recent completed batch job
+#[utoipa::path(get, path = "/api/nse/batch-analysis", tag = "nse", responses((status = 200, body = ApiResponse<BatchAnalysisResponse>)))]
+async fn get_latest_batch_analysis(
+    State(app_state): State<AppState>,
+) -> Result<Json<ApiResponse<BatchAnalysisResponse>>, StatusCode> {
+    let start_time = Instant::now();
+
+    Ok(Json(match app_state.jobs.latest_result(BATCH_JOB_KIND).await {
+        Some(response) => ApiResponse {
+            success: true,
+            data: Some(response),
+            error: None,
+            processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
+        },
+        None => ApiResponse {
+            success: false,
+            data: None,
+            error: Some("No completed batch analysis yet".to_string()),
+            processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
+        },
+    }))
+}
+
+/// GET /api/nse/screener - Filter and rank symbols on the last batch's metrics
+#[utoipa::path(get, path = "/api/nse/screener", tag = "nse", params(ScreenerParams), responses((status = 200, body = ApiResponse<ScreenerResult>)))]
+async fn get_screener(
+    State(app_state): State<AppState>,
+    Query(params): Query<ScreenerParams>,
+) -> Result<Json<ApiResponse<ScreenerResult>>, StatusCode> {
+    let start_time = Instant::now();
+    let failure = |error: String| Ok(Json(ApiResponse {
+        success: false,
+        data: None,
+        error: Some(error),
+        processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
+    }));
+
+    let query = match ScreenerQuery::parse(params.filter.as_deref(), params.sort.as_deref(), params.limit) {
+        Ok(query) => query,
+        Err(e) => return failure(e.to_string()),
+    };
+
+    // The screener never runs a batch inline: refresh starts (or joins) a batch job in the
+    // registry, with progress and cancellation, and the last metrics are served meanwhile
+    let batch_job = match params.refresh {
+        true => Some(app_state.start_batch_job().await),
+        false => None,
+    };
+    let mut metrics = match app_state.screener_metrics.read().await.clone() {
+        Some(metrics) => metrics,
+        None => return failure(match &batch_job {
+            Some(id) => format!("No screener metrics yet; batch job {} is running (poll GET /jobs/{})", id, id),
+            None => "No screener metrics yet; start a batch with POST /api/nse/batch-analysis or refresh=true".to_string(),
+        }),
     };
 
+    // Basis needs a futures quote per symbol, so only fetch it when asked for
+    if query.needs_futures() {
+        let max_concurrent = app_state.settings().max_concurrent;
+        let errors = screener::fill_futures_prices(&app_state.market, &mut metrics, max_concurrent).await;
+        if !errors.is_empty() {
+            warn!("No futures price for {} symbol(s)", errors.len());
+        }
+    }
+
+    let result = screener::run_screener(metrics, &query, params.filter.as_deref(), params.sort.as_deref());
     Ok(Json(ApiResponse {
         success: true,
-        data: Some(BatchAnalysisResponse {
-            summary,
-            rules_output: rules_outputs,
-        }),
+        data: Some(screener::ScreenerResult { batch_job, ..result }),
         error: None,
         processing_time_ms: Some(start_time.elapsed().as_millis() as u64),
     }))
@@ -504,6 +1112,59 @@ fn format_securities_response(securities: Vec<Security>, start_time: Instant) ->
     }
 }
 
+/// GET /api/nse/cache-stats - Entries, hit rates and evictions per server cache
+#[utoipa::path(get, path = "/api/nse/cache-stats", tag = "nse", responses((status = 200, body = ApiResponse<Vec<CacheStats>>)))]
+async fn get_cache_stats(State(app_state): State<AppState>) -> Result<Json<ApiResponse<Vec<CacheStats>>>, StatusCode> {
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(app_state.cache.stats()),
+        error: None,
+        processing_time_ms: None,
+    }))
+}
+
+// -----------------------------------------------
+// OPENAPI
+// -----------------------------------------------
+
+/// OpenAPI 3 document for the NSE routes, served at /openapi.json
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "NSE Analyzer API", description = "NSE option chain analysis, batch jobs and analytics"),
+    paths(
+        health,
+        ready,
+        get_securities,
+        get_contract_info,
+        get_single_analysis,
+        run_batch_analysis,
+        get_latest_batch_analysis,
+        get_futures_data,
+        get_derivatives_historical_data,
+        get_volatility_surface,
+        analyze_strategy,
+        get_screener,
+        get_cache_stats,
+        jobs::jobs_api::list_jobs,
+        jobs::jobs_api::get_job,
+        jobs::jobs_api::cancel_job,
+        portfolio::portfolio_api::get_summary,
+        portfolio::portfolio_api::get_risk_alerts,
+        portfolio::portfolio_api::list_positions,
+        portfolio::portfolio_api::open_position,
+        portfolio::portfolio_api::close_position,
+        portfolio::portfolio_api::delete_position,
+        utility::shutdown::request_shutdown,
+    ),
+    tags(
+        (name = "nse", description = "NSE option chain analysis"),
+        (name = "jobs", description = "Background batch jobs"),
+        (name = "portfolio", description = "Paper positions shared by both servers"),
+        (name = "server", description = "Server lifecycle"),
+    )
+)]
+pub struct NseApiDoc;
+
 // -----------------------------------------------
 // SERVER SETUP
 // -----------------------------------------------
@@ -511,32 +1172,105 @@ fn format_securities_response(securities: Vec<Security>, start_time: Instant) ->
 pub async fn start_server(port: u16) -> Result<()> {
     let app_state = AppState::new()?;
 
+    // Lot sizes from the contract master (falls back to built-in/file specs)
+    let refresh_state = app_state.clone();
+    tokio::spawn(async move {
+        match refresh_state.refresh_contract_specs().await {
+            Ok(count) => info!("Loaded {} NSE lot sizes from contract master", count),
+            Err(e) => warn!("Failed to load NSE lot sizes: {}", e),
+        }
+    });
+
+    // Keep the hot symbols warm so the first requests skip the upstream round trip
+    if app_state.settings().prewarm.enabled {
+        let symbols = prewarm::hot_symbols(app_state.settings());
+        info!(symbols = symbols.len(), "Pre-warming NSE caches");
+        let prewarm_state = app_state.clone();
+        prewarm::spawn("nse", app_state.settings().prewarm_schedule(), move || {
+            let (state, symbols) = (prewarm_state.clone(), symbols.clone());
+            async move { state.prewarm(&symbols).await }
+        });
+    }
+
+    let portfolio_routes = portfolio::get_portfolio_routes(app_state.contracts.clone(), app_state.market.clone());
+    let job_routes = jobs::get_job_routes(app_state.jobs.clone());
+    let openapi_routes = utility::openapi_routes(&NseApiDoc::openapi())?;
+    let guard = utility::ApiGuard::new("nse", &app_state.settings().server);
+    let addr = app_state.settings().server_addr(port);
+    let loopback_only = app_state.settings().server.is_loopback();
+
+    // Ctrl+C / SIGTERM / POST /shutdown: cancel running batch jobs (aborting their
+    // in-flight fetches), stop accepting connections and drain open requests
+    let shutdown = utility::Shutdown::new();
+    shutdown.listen_for_signals();
+    let (jobs_on_shutdown, shutdown_signal) = (app_state.jobs.clone(), shutdown.clone());
+    tokio::spawn(async move {
+        shutdown_signal.triggered().await;
+        let cancelled = jobs_on_shutdown.cancel_all().await;
+        if cancelled > 0 {
+            info!(cancelled, "Cancelled {} running job(s) for shutdown", cancelled);
+        }
+    });
+
     let app = Router::new()
         .route("/nse_health", get(health))
+        .route("/ready", get(ready))
         .route("/api/nse/securities", get(get_securities))
         .route("/api/nse/contract-info", get(get_contract_info))
         .route("/api/nse/single-analysis", get(get_single_analysis))
-        .route("/api/nse/batch-analysis", post(run_batch_analysis))
+        .route("/api/nse/batch-analysis", post(run_batch_analysis).get(get_latest_batch_analysis))
         .route("/api/nse/futures-data", get(get_futures_data))
         .route("/api/nse/derivatives-historical", get(get_derivatives_historical_data))
-        .layer(CorsLayer::permissive())
-        .with_state(app_state);
+        .route("/api/nse/volatility-surface", get(get_volatility_surface))
+        .route("/api/nse/strategy", post(analyze_strategy))
+        .route("/api/nse/screener", get(get_screener))
+        .route("/api/nse/cache-stats", get(get_cache_stats))
+        .with_state(app_state)
+        .merge(portfolio_routes)
+        .merge(job_routes)
+        .merge(openapi_routes)
+        .merge(utility::shutdown::get_shutdown_routes(shutdown.clone()))
+        .route("/metrics", get(utility::metrics_handler))
+        .layer(middleware::from_fn_with_state(guard.clone(), utility::api_guard))
+        .layer(utility::logging::http_trace_layer())
+        .layer(CorsLayer::permissive());
 
-    let addr = format!("127.0.0.1:{}", port);
     let listener = tokio::net::TcpListener::bind(&addr).await?;
     
-    println!("🚀 NSE API Server running on http://{}", addr);
+    info!("🚀 NSE API Server running on http://{}", addr);
+    if guard.auth_enabled() {
+        info!("🔑 API key required (X-API-Key or Authorization: Bearer)");
+    } else if !loopback_only {
+        warn!("NSE API Server is reachable on {} without API keys", addr);
+    }
     println!("📋 Available endpoints:");
     println!("   GET  /nse_health");
+    println!("   GET  /ready (upstream session, circuit and cache freshness)");
+    println!("   GET  /metrics (Prometheus)");
     println!("   GET  /api/nse/securities");
     println!("   GET  /api/nse/contract-info?symbol=NIFTY");
     println!("   GET  /api/nse/single-analysis?symbol=NIFTY&expiry=30-Dec-2025");
     println!("   GET  /api/nse/futures-data?symbol=NIFTY&expiry=30-Dec-2025");
     println!("   GET  /api/nse/derivatives-historical?symbol=NIFTY&instrument_type=FUTURES&expiry=30-Dec-2025&from_date=06-11-2025&to_date=06-12-2025");
     println!("   GET  /api/nse/derivatives-historical?symbol=NIFTY&instrument_type=OPTIONS&expiry=30-Dec-2025&from_date=06-11-2025&to_date=06-12-2025&strike_price=18000&option_type=CE");
-    println!("   POST /api/nse/batch-analysis");
+    println!("   GET  /api/nse/derivatives-historical?...&window=5&indicators=sma:20,ema:9,rsi:14,atr:14,bb:20:2,vwap,oi_sma:5");
+    println!("   GET  /api/nse/volatility-surface?symbol=NIFTY&max_expiries=6");
+    println!("   GET  /api/nse/screener?filter=pcr>1.2 and alerts>0&sort=-iv_rank,spread&limit=20");
+    println!("   GET  /api/nse/cache-stats");
+    println!("   POST /api/nse/batch-analysis (returns a job id)");
+    println!("   GET  /api/nse/batch-analysis (latest completed result)");
+    println!("   POST /api/nse/strategy");
+    utility::print_openapi_endpoints();
+    utility::shutdown::print_shutdown_endpoints();
+    jobs::print_job_endpoints();
+    portfolio::print_portfolio_endpoints();
     println!();
 
-    axum::serve(listener, app).await?;
+    let server = axum::serve(listener, app).with_graceful_shutdown(shutdown.clone().triggered());
+    tokio::select! {
+        result = server => result?,
+        _ = shutdown.drain_deadline() => {}
+    }
+    info!("NSE API Server stopped");
     Ok(())
 }
\ No newline at end of file
@@ -1,21 +1,23 @@
 use super::config;
-use super::models::{ContractInfo, OptionChain, Security, SecurityType};
+use super::models::{ContractInfo, NseFuturesData, NseHistoricalResponse, OptionChain, Security, SecurityType};
+use super::processor;
+use super::rules;
+use crate::analytics::{HistoricalSeries, SeriesMeta};
+use crate::contracts::{parse_nse_market_lots, Exchange};
+use crate::jobs::{FetchTasks, JobProgress};
+use crate::settings::{self, ExchangeSettings};
 use anyhow::{anyhow, Context, Result};
 use rand::{seq::SliceRandom, thread_rng};
 use reqwest::{header, Client, StatusCode};
-use serde_json::Value;
+use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Duration;
 use tokio::sync::{Semaphore, RwLock};
-use tokio_retry::strategy::ExponentialBackoff;
-use tokio_retry::Retry;
 use chrono::{NaiveDate, NaiveTime, Local};
-use colored::Colorize;
 use std::sync::atomic::{AtomicUsize, Ordering};
-
+use tracing::{debug, debug_span, error, info, info_span, instrument, warn, Instrument};
 
 // Import timing utilities
-use crate::utility::Timer;
+use crate::utility::{metrics, upstream, Timer};
 
 // -----------------------------------------------
 // CLIENT WRAPPER WITH SESSION STATE
@@ -23,6 +25,7 @@ use crate::utility::Timer;
 pub struct NSEClient {
     client: Client,
     warmed_up: Arc<RwLock<bool>>,
+    settings: ExchangeSettings,
 }
 
 fn select_expiry<'a>(expiry_dates: &'a [String]) -> Result<&'a String> {
@@ -79,12 +82,21 @@ fn select_expiry<'a>(expiry_dates: &'a [String]) -> Result<&'a String> {
 
 impl NSEClient {
     pub fn new() -> Result<Self> {
+        Self::with_settings(settings::current().nse.clone())
+    }
+
+    pub fn with_settings(settings: ExchangeSettings) -> Result<Self> {
         Ok(Self {
-            client: build_client()?,
+            client: build_client(&settings)?,
             warmed_up: Arc::new(RwLock::new(false)),
+            settings,
         })
     }
 
+    pub fn settings(&self) -> &ExchangeSettings {
+        &self.settings
+    }
+
     /// Warmup NSE session (only once per client)
     async fn warmup_if_needed(&self) -> Result<()> {
         // Check if already warmed up
@@ -95,7 +107,7 @@ impl NSEClient {
         // Acquire write lock and warmup
         let mut warmed = self.warmed_up.write().await;
         if !*warmed {
-            let timer = Timer::start("NSE Warmup");
+            let timer = Timer::silent("NSE Warmup");
             
             let response = self.client
                 .get(config::NSE_BASE_URL)
@@ -105,37 +117,38 @@ impl NSEClient {
                 .context("Failed to warm up NSE session")?;
             
             let status = response.status();
-            let elapsed = timer.elapsed_ms();
-            
-            println!(
-                "🌐 {} | {} | {}ms",
-                config::NSE_BASE_URL.bright_blue(),
-                format_status(status),
-                elapsed
+            debug!(
+                url = config::NSE_BASE_URL,
+                status = status.as_u16(),
+                elapsed_ms = timer.elapsed_ms() as u64,
+                "NSE session warmup"
             );
             
-            tokio::time::sleep(Duration::from_millis(config::WARMUP_DELAY_MS)).await;
+            tokio::time::sleep(self.settings.warmup_delay()).await;
             *warmed = true;
+            upstream::health(Exchange::Nse).mark_warmed_up();
         }
         
         Ok(())
     }
 
     
-    /// Generic retry fetch with better error handling and timing
+    /// Fetch through the NSE circuit breaker: fails fast while NSE keeps refusing requests
     async fn fetch_json(&self, url: &str) -> Result<String> {
+        upstream::health(Exchange::Nse).call(&self.settings.circuit, self.fetch_json_with_retry(url)).await
+    }
+
+    /// Generic retry fetch with better error handling and timing
+    async fn fetch_json_with_retry(&self, url: &str) -> Result<String> {
         self.warmup_if_needed().await?;
 
-        let backoff = ExponentialBackoff::from_millis(config::RETRY_BASE_DELAY_MS)
-            .factor(config::RETRY_FACTOR)
-            .max_delay(Duration::from_secs(config::RETRY_MAX_DELAY_SECS))
-            .take(config::RETRY_MAX_ATTEMPTS);
+        let backoff = self.settings.retry.backoff();
 
         // Use Arc<AtomicUsize> for thread-safe attempt counting
         let attempt = Arc::new(AtomicUsize::new(0));
         let url_owned = url.to_string(); // Clone URL for closure
         
-        Retry::spawn(backoff, || {
+        metrics::retry_tracked(&metrics::endpoint_label(url), backoff, || {
             let attempt = Arc::clone(&attempt);
             let url = url_owned.clone();
             let client = self.client.clone();
@@ -153,22 +166,15 @@ impl NSEClient {
                     .context("Request send failed")?;
 
                 let status = res.status();
-                let elapsed = timer.elapsed_ms();
 
                 // Log request with status and timing
-                let retry_indicator = if current_attempt > 1 {
-                    format!(" (retry {})", current_attempt).yellow().to_string()
+                let url_label = truncate_url(&url, 80);
+                let elapsed_ms = timer.elapsed_ms() as u64;
+                if status.is_success() {
+                    debug!(url = %url_label, status = status.as_u16(), elapsed_ms, attempt = current_attempt, "NSE request");
                 } else {
-                    String::new()
-                };
-
-                println!(
-                    "🌐 {} | {} | {}ms{}",
-                    truncate_url(&url, 80).bright_blue(),
-                    format_status(status),
-                    elapsed,
-                    retry_indicator
-                );
+                    warn!(url = %url_label, status = status.as_u16(), elapsed_ms, attempt = current_attempt, "NSE request failed");
+                }
 
                 // Handle different status codes
                 if status.is_success() {
@@ -184,12 +190,12 @@ impl NSEClient {
                     Ok(text)
                 } else if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
                     // Retry on server errors and rate limits
-                    anyhow::bail!("Retryable error: {}", status)
+                    anyhow::bail!(upstream::StatusError::new(status, ""))
                 } else {
                     // Fail fast on client errors
                     let body = res.text().await.unwrap_or_default();
                     let preview: String = body.chars().take(200).collect();
-                    anyhow::bail!("Client error {}: {}", status, preview)
+                    anyhow::bail!(upstream::StatusError::new(status, preview))
                 }
             }
         })
@@ -200,9 +206,8 @@ impl NSEClient {
     // -----------------------------------------------
     // STEP 1: FETCH FNO LIST
     // -----------------------------------------------
+    #[instrument(skip_all)]
     pub async fn fetch_fno_list(&self) -> Result<Vec<Security>> {
-        let _timer = Timer::start("1. Fetch FNO List");
-        
         let text = self.fetch_json(config::NSE_API_MASTER_QUOTE).await?;
         
         let symbols: Vec<String> = serde_json::from_str(&text)
@@ -214,8 +219,8 @@ impl NSEClient {
             .collect();
         
         // Add indices
-        for index in config::NSE_INDICES {
-            securities.push(Security::index(index.to_string()));
+        for index in &self.settings.indices {
+            securities.push(Security::index(index.clone()));
         }
         
         Ok(securities)
@@ -224,9 +229,8 @@ impl NSEClient {
     // -----------------------------------------------
     // STEP 2: FETCH CONTRACT INFO
     // -----------------------------------------------
+    #[instrument(level = "debug", skip(self))]
     pub async fn fetch_contract_info(&self, symbol: &str) -> Result<ContractInfo> {
-        // let _timer = Timer::start(format!("2. Fetch Contract Info: {}", symbol));
-        
         let url = config::nse_contract_info_url(symbol);
         let text = self.fetch_json(&url).await?;
         let info: ContractInfo = serde_json::from_str(&text)
@@ -238,13 +242,12 @@ impl NSEClient {
     // -----------------------------------------------
     // STEP 3: FETCH OPTION CHAIN
     // -----------------------------------------------
+    #[instrument(level = "debug", skip_all, fields(symbol = %security.symbol, expiry))]
     pub async fn fetch_option_chain(
         &self,
         security: &Security,
         expiry: &str,
     ) -> Result<OptionChain> {
-        // let _timer = Timer::start(format!("3. Fetch Option Chain: {} {}", security.symbol, expiry));
-        
         let typ = match security.security_type {
             SecurityType::Equity => "Equity",
             SecurityType::Indices => "Indices",
@@ -261,13 +264,12 @@ impl NSEClient {
     // -----------------------------------------------
     // NEW API A: FETCH FUTURES DATA
     // -----------------------------------------------
+    #[instrument(skip(self))]
     pub async fn fetch_futures_data(
         &self,
         symbol: &str,
         expiry: &str,
-    ) -> Result<Value> {
-        let _timer = Timer::start(format!("Fetch Futures: {} {}", symbol, expiry));
-        
+    ) -> Result<NseFuturesData> {
         let url = format!(
             "{}/api/NextApi/apiClient/GetQuoteApi?functionName=getSymbolDerivativesData&symbol={}&instrumentType=FUT&expiryDt={}",
             config::NSE_BASE_URL,
@@ -276,15 +278,14 @@ impl NSEClient {
         );
         
         let text = self.fetch_json(&url).await?;
-        let data: Value = serde_json::from_str(&text)
-            .context("Failed to parse futures data")?;
-        
-        Ok(data)
+        serde_json::from_str(&text).context("Failed to parse futures data")
     }
 
     // -----------------------------------------------
     // NEW API B: FETCH DERIVATIVES HISTORICAL DATA
     // -----------------------------------------------
+    /// Daily history of one contract as a normalized OHLC/OI series
+    #[instrument(skip(self, security_type, year, strike_price, option_type))]
     pub async fn fetch_derivatives_historical_data(
         &self,
         symbol: &str,
@@ -296,9 +297,8 @@ impl NSEClient {
         option_type: Option<&str>,
         from_date: &str,
         to_date: &str,
-    ) -> Result<Value> {
-        let _timer = Timer::start(format!("Fetch Historical: {} {}", symbol, instrument_type));
-        
+        window: usize,
+    ) -> Result<HistoricalSeries> {
         // Determine instrument type based on security type and instrument
         let instype = match (security_type, instrument_type) {
             (SecurityType::Equity, "OPTIONS") => "OPTSTK",
@@ -338,10 +338,44 @@ impl NSEClient {
         }
 
         let text = self.fetch_json(&url).await?;
-        let data: Value = serde_json::from_str(&text)
+        let response: NseHistoricalResponse = serde_json::from_str(&text)
             .context("Failed to parse derivatives historical data")?;
-        
-        Ok(data)
+
+        let meta = SeriesMeta {
+            exchange: "NSE".to_string(),
+            symbol: symbol.to_string(),
+            instrument: instype.to_string(),
+            expiry: expiry.to_string(),
+            strike: strike_price.and_then(|s| s.parse().ok()),
+            option_type: option_type.filter(|t| !t.is_empty()).map(str::to_string),
+        };
+        Ok(processor::build_historical_series(&response.data, meta, window))
+    }
+
+    // -----------------------------------------------
+    // FETCH F&O MARKET LOTS (CONTRACT MASTER)
+    // -----------------------------------------------
+    #[instrument(skip_all)]
+    pub async fn fetch_market_lots(&self) -> Result<HashMap<String, f64>> {
+        let res = self.client
+            .get(config::NSE_FO_MARKET_LOTS_URL)
+            .header("Referer", config::HEADER_REFERER)
+            .send()
+            .await
+            .context("Failed to fetch market lots")?;
+
+        let status = res.status();
+        if !status.is_success() {
+            anyhow::bail!("Market lots request failed: {}", status);
+        }
+
+        let text = res.text().await.context("Failed to read market lots")?;
+        let lots = parse_nse_market_lots(&text);
+        if lots.is_empty() {
+            anyhow::bail!("No lot sizes found in market lots file");
+        }
+
+        Ok(lots)
     }
 
     // -----------------------------------------------
@@ -352,41 +386,50 @@ impl NSEClient {
         securities: Vec<Security>,
         max_concurrent: usize,
     ) -> Vec<Result<(Security, OptionChain)>> {
-        let _timer = Timer::start(format!(
-            "Batch Fetch {} Option Chains (concurrency: {})",
-            securities.len(),
-            max_concurrent
-        ));
+        self.fetch_all_option_chains_tracked(securities, max_concurrent, JobProgress::new()).await
+    }
+
+    /// Same as `fetch_all_option_chains`, reporting each symbol to `progress`
+    /// and skipping fetches not yet started once it is cancelled.
+    /// Dropping the returned future aborts the fetches still in flight.
+    #[instrument(skip_all, fields(securities = securities.len(), max_concurrent))]
+    pub async fn fetch_all_option_chains_tracked(
+        self: Arc<Self>,
+        securities: Vec<Security>,
+        max_concurrent: usize,
+        progress: Arc<JobProgress>,
+    ) -> Vec<Result<(Security, OptionChain)>> {
+        progress.add_total(securities.len());
         
         // Separate equities from indices
         let (equities, indices): (Vec<_>, Vec<_>) = securities
             .into_iter()
             .partition(|s| matches!(s.security_type, SecurityType::Equity));
 
-        println!("{} Equities: {}, Indices: {}", "ℹ".blue(), equities.len(), indices.len());
+        info!(equities = equities.len(), indices = indices.len(), "Equities: {}, Indices: {}", equities.len(), indices.len());
 
         // Step 1: Fetch equity expiry once (using any equity as representative)
         let equity_expiry = if !equities.is_empty() {
-            let _expiry_timer = Timer::start("Fetch Equity Expiry (shared)");
-            
             // Use first equity to get standard expiry dates
             let sample_symbol = &equities[0].symbol;
-            match self.fetch_contract_info(sample_symbol).await {
+            let contract_info = self.fetch_contract_info(sample_symbol)
+                .instrument(info_span!("fetch_equity_expiry"))
+                .await;
+            match contract_info {
                 Ok(contract_info) => {
                     match select_expiry(&contract_info.expiry_dates) {
                         Ok(expiry) => {
-                            println!("{} Using equity expiry: {} (applies to all {} equities)", 
-                                "✓".green(), expiry.yellow(), equities.len());
+                            info!(expiry = %expiry, "✓ Using equity expiry: {} (applies to all {} equities)", expiry, equities.len());
                             Some(expiry.clone())
                         }
                         Err(e) => {
-                            println!("{} Failed to select equity expiry: {}", "✗".red(), e);
+                            error!("Failed to select equity expiry: {}", e);
                             None
                         }
                     }
                 }
                 Err(e) => {
-                    println!("{} Failed to fetch equity contract info: {}", "✗".red(), e);
+                    error!("Failed to fetch equity contract info: {}", e);
                     None
                 }
             }
@@ -396,12 +439,17 @@ impl NSEClient {
 
         // Step 2: Process equities (no contract info fetch needed)
         let equity_results = if let Some(expiry) = equity_expiry {
-            let _equity_timer = Timer::start(format!("Fetch {} Equity Chains", equities.len()));
-            Arc::clone(&self).fetch_option_chains_with_expiry(equities, &expiry, max_concurrent).await
+            let span = info_span!("fetch_equity_chains", count = equities.len(), expiry = %expiry);
+            Arc::clone(&self).fetch_option_chains_with_expiry(equities, &expiry, max_concurrent, &progress)
+                .instrument(span)
+                .await
         } else if !equities.is_empty() {
-            println!("{} Skipping equities - no valid expiry found", "⚠".yellow());
+            warn!("Skipping equities - no valid expiry found");
             equities.into_iter()
-                .map(|_sec| Err(anyhow!("No valid equity expiry")))
+                .map(|sec| {
+                    progress.record_failure(&sec.symbol, "No valid equity expiry");
+                    Err(anyhow!("No valid equity expiry"))
+                })
                 .collect()
         } else {
             Vec::new()
@@ -409,8 +457,10 @@ impl NSEClient {
 
         // Step 3: Process indices (each needs individual contract info)
         let index_results = if !indices.is_empty() {
-            let _index_timer = Timer::start(format!("Fetch {} Index Chains", indices.len()));
-            Arc::clone(&self).fetch_option_chains_with_contract_info(indices, max_concurrent).await
+            let span = info_span!("fetch_index_chains", count = indices.len());
+            Arc::clone(&self).fetch_option_chains_with_contract_info(indices, max_concurrent, &progress)
+                .instrument(span)
+                .await
         } else {
             Vec::new()
         };
@@ -428,38 +478,41 @@ impl NSEClient {
         securities: Vec<Security>,
         expiry: &str,
         max_concurrent: usize,
+        progress: &Arc<JobProgress>,
     ) -> Vec<Result<(Security, OptionChain)>> {
         let semaphore = Arc::new(Semaphore::new(max_concurrent));
         let expiry = expiry.to_string();
-        let mut handles = vec![];
+        let mut tasks = FetchTasks::new();
 
         for security in securities {
             let client = Arc::clone(&self);
             let sem = Arc::clone(&semaphore);
             let expiry = expiry.clone();
+            let progress = Arc::clone(progress);
+            let span = debug_span!("fetch_symbol", symbol = %security.symbol);
 
-            let handle = tokio::spawn(async move {
+            tasks.spawn(async move {
                 let _permit = sem.acquire_owned().await
                     .map_err(|e| anyhow::anyhow!("Semaphore error: {}", e))?;
+                progress.check_cancelled()?;
 
                 // Direct fetch - no contract info needed
-                let chain = client.fetch_option_chain(&security, &expiry).await?;
-
-                Ok((security, chain))
-            });
-
-            handles.push(handle);
-        }
-
-        let mut results = vec![];
-        for handle in handles {
-            match handle.await {
-                Ok(res) => results.push(res),
-                Err(e) => results.push(Err(anyhow::anyhow!("Task error: {}", e))),
-            }
+                match client.fetch_option_chain(&security, &expiry).await {
+                    Ok(chain) => {
+                        progress.record_success(&security.symbol);
+                        record_partial_alerts(&progress, &security, &chain);
+                        Ok((security, chain))
+                    }
+                    Err(e) => {
+                        debug!(error = %e, "fetch failed");
+                        progress.record_failure(&security.symbol, &e);
+                        Err(e)
+                    }
+                }
+            }.instrument(span));
         }
 
-        results
+        tasks.join_all().await
     }
 
     /// Fetch option chains with individual contract info (indices)
@@ -467,45 +520,73 @@ impl NSEClient {
         self: Arc<Self>,
         securities: Vec<Security>,
         max_concurrent: usize,
+        progress: &Arc<JobProgress>,
     ) -> Vec<Result<(Security, OptionChain)>> {
         let semaphore = Arc::new(Semaphore::new(max_concurrent));
-        let mut handles = vec![];
+        let mut tasks = FetchTasks::new();
 
         for security in securities {
             let client = Arc::clone(&self);
             let sem = Arc::clone(&semaphore);
+            let progress = Arc::clone(progress);
+            let span = debug_span!("fetch_symbol", symbol = %security.symbol);
 
-            let handle = tokio::spawn(async move {
+            tasks.spawn(async move {
                 let _permit = sem.acquire_owned().await
                     .map_err(|e| anyhow::anyhow!("Semaphore error: {}", e))?;
+                progress.check_cancelled()?;
 
                 // Fetch contract info to get expiry
-                let contract_info = client.fetch_contract_info(&security.symbol).await?;
-                let expiry = select_expiry(&contract_info.expiry_dates)?;
-                let chain = client.fetch_option_chain(&security, expiry).await?;
-
-                Ok((security, chain))
-            });
-
-            handles.push(handle);
+                let fetched = async {
+                    let contract_info = client.fetch_contract_info(&security.symbol).await?;
+                    let expiry = select_expiry(&contract_info.expiry_dates)?;
+                    client.fetch_option_chain(&security, expiry).await
+                }.await;
+
+                match fetched {
+                    Ok(chain) => {
+                        progress.record_success(&security.symbol);
+                        record_partial_alerts(&progress, &security, &chain);
+                        Ok((security, chain))
+                    }
+                    Err(e) => {
+                        debug!(error = %e, "fetch failed");
+                        progress.record_failure(&security.symbol, &e);
+                        Err(e)
+                    }
+                }
+            }.instrument(span));
         }
 
-        let mut results = vec![];
-        for handle in handles {
-            match handle.await {
-                Ok(res) => results.push(res),
-                Err(e) => results.push(Err(anyhow::anyhow!("Task error: {}", e))),
-            }
-        }
+        tasks.join_all().await
+    }
+}
 
-        results
+/// Strike rules for one chain as it arrives, so a running or cancelled job shows alerts so far
+fn record_partial_alerts(progress: &JobProgress, security: &Security, chain: &OptionChain) {
+    if !progress.wants_partial_results() {
+        return;
+    }
+    let (processed_data, spread) = processor::process_option_data(
+        chain.filtered.data.clone(),
+        chain.records.underlying_value
+    );
+    let output = rules::run_rules(
+        &processed_data,
+        security.symbol.clone(),
+        chain.records.timestamp.clone(),
+        chain.records.underlying_value,
+        spread,
+    );
+    if let Some(output) = output {
+        progress.record_partial(&output);
     }
 }
 
 // -----------------------------------------------
 // HTTP CLIENT BUILDER
 // -----------------------------------------------
-fn build_client() -> Result<Client> {
+fn build_client(settings: &ExchangeSettings) -> Result<Client> {
     let mut headers = header::HeaderMap::new();
     
     let lang = config::ACCEPT_LANGUAGES.choose(&mut thread_rng()).unwrap();
@@ -519,31 +600,15 @@ fn build_client() -> Result<Client> {
         .default_headers(headers)
         .cookie_store(true)
         .user_agent(config::USER_AGENT)
-        .timeout(config::HTTP_TIMEOUT)
+        .timeout(settings.http_timeout())
         .build()
         .context("Failed to build HTTP client")?)
 }
 
 // -----------------------------------------------
-// HELPER FUNCTIONS FOR DISPLAY
+// HELPER FUNCTIONS FOR LOGGING
 // -----------------------------------------------
 
-/// Format HTTP status code with color
-fn format_status(status: StatusCode) -> String {
-    let code = status.as_u16();
-    let status_str = format!("{}", code);
-    
-    if status.is_success() {
-        status_str.green().to_string()
-    } else if status.is_client_error() {
-        status_str.yellow().to_string()
-    } else if status.is_server_error() {
-        status_str.red().to_string()
-    } else {
-        status_str.to_string()
-    }
-}
-
 /// Truncate URL for display
 fn truncate_url(url: &str, max_len: usize) -> String {
     let trimmed: String = url.chars().skip(24).collect();
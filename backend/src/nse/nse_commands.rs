@@ -6,9 +6,15 @@ use super::rules;
 use super::nse_api_server;
 
 use anyhow::{Result, Context};
-use colored::Colorize;
 use std::sync::Arc;
-use crate::utility::{Timer, AggregateTimer};
+use tracing::{error, info, info_span, warn, Instrument};
+use crate::analytics;
+use crate::backtest::snapshots;
+use crate::contracts::{ContractRegistry, Exchange};
+use crate::portfolio;
+use crate::report::BatchReportBuilder;
+use crate::settings;
+use crate::utility::{self, watchlist, Timer, AggregateTimer};
 
 /// NSE Command Handler - encapsulates all NSE-related operations
 pub struct NSECommands;
@@ -16,182 +22,201 @@ pub struct NSECommands;
 impl NSECommands {
     /// Run batch fetch for all FNO securities
     pub async fn run_batch() -> Result<()> {
-        let _total_timer = Timer::start("Total Batch Processing");
-        
-        println!("{}", "=".repeat(60).blue());
-        println!("{}", "NSE Batch Processor".green().bold());
-        println!("{}", "=".repeat(60).blue());
-        println!();
+        Self::run_batch_for(None).await
+    }
+
+    /// Run batch fetch, restricted to the watchlist symbols when one is given
+    pub async fn run_batch_for(watchlist: Option<&[String]>) -> Result<()> {
+        Self::batch(watchlist).instrument(info_span!("nse_batch")).await
+    }
+
+    async fn batch(watchlist: Option<&[String]>) -> Result<()> {
+        info!("NSE Batch Processor");
 
         let client = Arc::new(NSEClient::new()?);
+        let mut report = BatchReportBuilder::new(Exchange::Nse);
 
         // Step 1: Fetch all FNO securities
-        let securities = {
-            let _step1_timer = Timer::start("Step 1: Fetch FNO List");
-            println!("{}", "Step 1: Fetching all FNO securities...".cyan());
+        report.step("fetch_fno_list");
+        let securities = async {
+            info!("Step 1: Fetching all FNO securities...");
             let securities = client.fetch_fno_list().await?;
-            println!("{} Found {} securities", "✓".green(), securities.len());
-            println!();
-            securities
-        };
+            info!(count = securities.len(), "✓ Found {} securities", securities.len());
+
+            let Some(watchlist) = watchlist else {
+                return Ok::<_, anyhow::Error>(securities);
+            };
+            let (securities, missing) = watchlist::filter(securities, watchlist, |s| &s.symbol);
+            if !missing.is_empty() {
+                warn!("Not in the FNO list, skipping: {}", missing.join(", "));
+            }
+            if securities.is_empty() {
+                return Err(anyhow::anyhow!("No watchlist symbols found in the FNO list"));
+            }
+            info!(count = securities.len(), "✓ Watchlist: {} securities", securities.len());
+            Ok(securities)
+        }
+        .instrument(info_span!("fetch_fno_list"))
+        .await?;
+
+        // Lot sizes for notional values in the output
+        report.step("load_contract_specs");
+        let contracts = Self::load_contract_specs(&client).await;
 
         // Step 2: Bulk process all securities
-        println!("{}", "Step 2: Processing all securities...".cyan());
+        info!("Step 2: Processing all securities...");
         
-        let max_concurrent = if config::is_ci_environment() {
-            println!("{} CI Mode: Using concurrency ({})", "ℹ".blue(), config::CI_MAX_CONCURRENT);
-            config::CI_MAX_CONCURRENT
+        let settings = client.settings().clone();
+        let max_concurrent = settings.concurrency(config::is_ci_environment());
+        if config::is_ci_environment() {
+            info!("CI Mode: Using concurrency ({})", max_concurrent);
         } else {
-            println!("{} Max concurrent requests: {}", "ℹ".blue(), config::DEFAULT_MAX_CONCURRENT);
-            config::DEFAULT_MAX_CONCURRENT
-        };
-        
-        println!();
+            info!("Max concurrent requests: {}", max_concurrent);
+        }
 
-        let (results, step2_elapsed) = {
+        report.step("fetch_option_chains");
+        let (results, step2_elapsed) = async {
             let step2_timer = Timer::silent("Step 2");
             
-        let results = if config::is_ci_environment() {
-            println!("{} CI timeout enabled: {} seconds", "⏱".yellow(), config::GITHUB_ACTIONS_TIMEOUT_SECS);
-            
-            let timeout_duration = std::time::Duration::from_secs(config::GITHUB_ACTIONS_TIMEOUT_SECS);
-            
-            match tokio::time::timeout(
-                timeout_duration,
-                client.fetch_all_option_chains(securities.clone(), max_concurrent)
-            ).await {
-                Ok(results) => results,
-                Err(_) => {
-                    println!("{} Timeout reached after {} seconds - stopping analysis", "⚠".red(), config::GITHUB_ACTIONS_TIMEOUT_SECS);
-                    println!("{} This may indicate NSE API issues or network problems", "ℹ".blue());
-                    securities.iter().map(|_| Err(anyhow::anyhow!("Timeout"))).collect()
+            let results = if config::is_ci_environment() {
+                info!("CI timeout enabled: {} seconds", settings.ci_timeout_secs);
+                
+                let timeout_duration = settings.ci_timeout();
+                
+                match tokio::time::timeout(
+                    timeout_duration,
+                    client.fetch_all_option_chains(securities.clone(), max_concurrent)
+                ).await {
+                    Ok(results) => results,
+                    Err(_) => {
+                        error!("Timeout reached after {} seconds - stopping analysis", settings.ci_timeout_secs);
+                        warn!("This may indicate NSE API issues or network problems");
+                        securities.iter().map(|_| Err(anyhow::anyhow!("Timeout"))).collect()
+                    }
                 }
-            }
-        } else {
-            client.fetch_all_option_chains(securities.clone(), max_concurrent).await
-        };
-        
-        let elapsed = step2_timer.elapsed();
-        println!("⏱️  Step 2: Fetch All Option Chains - {:.2}s", elapsed.as_secs_f64());
-        (results, elapsed)
-    };
+            } else {
+                client.fetch_all_option_chains(securities.clone(), max_concurrent).await
+            };
+            
+            (results, step2_timer.elapsed())
+        }
+        .instrument(info_span!("fetch_option_chains", securities = securities.len(), max_concurrent))
+        .await;
     
-    // Step 3: Process results
-    let (successful, failed, timeout_count) = {
-        let _step3_timer = Timer::start("Step 3: Collect Results");
-        let mut successful = Vec::new();
-        let mut failed = Vec::new();
-        let mut timeout_count = 0;
-
-        for (security, result) in securities.iter().zip(results.iter()) {
-            match result {
-                Ok((_, chain)) => {
-                    successful.push((security.clone(), chain.clone()));
-                    // print!("{}", ".".green());
-                }
-                Err(e) => {
-                    if e.to_string().contains("Timeout") {
-                        timeout_count += 1;
-                        print!("{}", "⏱".yellow());
-                    } else {
-                        failed.push((security.symbol.clone(), e.to_string()));
-                        print!("{}", "✗".red());
+        // Step 3: Process results
+        report.step("collect_results");
+        let (successful, failed, timeout_count) = info_span!("collect_results").in_scope(|| {
+            let mut successful = Vec::new();
+            let mut failed = Vec::new();
+            let mut timeout_count = 0;
+
+            for (security, result) in securities.iter().zip(results.iter()) {
+                match result {
+                    Ok((_, chain)) => {
+                        report.symbol_success(&security.symbol, None);
+                        successful.push((security.clone(), chain.clone()));
+                    }
+                    Err(e) => {
+                        report.symbol_failure(&security.symbol, None, &e.to_string());
+                        if e.to_string().contains("Timeout") {
+                            timeout_count += 1;
+                        } else {
+                            failed.push((security.symbol.clone(), e.to_string()));
+                        }
                     }
                 }
             }
-        }
-        println!("\n");
-        (successful, failed, timeout_count)
-    };
+            (successful, failed, timeout_count)
+        });
 
         // Step 4: Display summary
         Self::display_batch_summary(&successful, &failed, timeout_count, step2_elapsed, &securities);
 
         // Step 5: Process data and run rules
-        Self::process_batch_data_and_rules(successful).await?;
+        report.step("process_and_rules");
+        let rules_outputs = Self::process_batch_data_and_rules(successful, &contracts)
+            .instrument(info_span!("process_and_rules"))
+            .await?;
 
-        println!();
-        println!("{}", "=".repeat(60).blue());
-        println!("{}", "Done!".green().bold());
-        println!("{}", "=".repeat(60).blue());
+        // Step 6: Machine-readable run report
+        for output in &rules_outputs {
+            for alert in &output.alerts {
+                report.alert(&output.symbol, &alert.alert_type, &alert.option_type);
+            }
+        }
+        if let Err(e) = report.finish().publish() {
+            warn!("Failed to write batch report: {}", e);
+        }
+
+        info!("Done!");
 
         Ok(())
     }
     /// Run single security fetch (for API endpoints only - not used in GitHub Actions)
     pub async fn run_single(symbol: &str, expiry: &str) -> Result<()> {
-        let _total_timer = Timer::start(format!("Single Security: {}", symbol));
-        
-        println!("{}", "=".repeat(60).blue());
-        println!("{}", "NSE Single Security Fetch".green().bold());
-        println!("{}", "=".repeat(60).blue());
-        println!();
+        Self::single(symbol, expiry)
+            .instrument(info_span!("nse_single", symbol, expiry))
+            .await
+    }
+
+    async fn single(symbol: &str, expiry: &str) -> Result<()> {
+        info!("NSE Single Security Fetch");
 
         let client = NSEClient::new()?;
 
-        let security = if config::NSE_INDICES.contains(&symbol) {
+        let security = if client.settings().is_index(symbol) {
             models::Security::index(symbol.to_string())
         } else {
             models::Security::equity(symbol.to_string())
         };
 
-        println!("{} Fetching option chain for {}...", "→".cyan(), symbol.yellow());
-        println!("{} Expiry: {}", "→".cyan(), expiry.yellow());
-        println!();
+        info!("→ Fetching option chain for {} (expiry {})...", symbol, expiry);
 
-        let chain = {
-            let _fetch_timer = Timer::start("Fetch Option Chain");
-            client.fetch_option_chain(&security, expiry).await?
-        };
+        let chain = client.fetch_option_chain(&security, expiry)
+            .instrument(info_span!("fetch_option_chain"))
+            .await?;
 
         Self::display_single_results(symbol, &chain, expiry);
 
-        let (processed_data, spread) = {
-            let _process_timer = Timer::start("Process Option Data");
+        let (processed_data, spread) = info_span!("process_option_data").in_scope(|| {
             processor::process_option_data(
                 chain.filtered.data.clone(),
                 chain.records.underlying_value
             )
-        };
+        });
 
         let days_to_expiry = processed_data.first()
             .map(|opt| opt.days_to_expiry)
             .unwrap_or(0);
 
-        println!("{} Days to expiry: {}", "ℹ".blue(), days_to_expiry);
+        info!(days_to_expiry, "Days to expiry: {}", days_to_expiry);
         
-        let rules_output = {
-            let _rules_timer = Timer::start("Run Rules Engine");
+        let rules_output = info_span!("run_rules").in_scope(|| {
             rules::run_rules(
                 &processed_data,
-            symbol.to_string(),
-            chain.records.timestamp.clone(),
-            chain.records.underlying_value,
-            spread,
+                symbol.to_string(),
+                chain.records.timestamp.clone(),
+                chain.records.underlying_value,
+                spread,
             )
-        };
+        });
         
         if let Some(output) = rules_output {
-            println!("{} Total alerts: {}", "ℹ".blue(), output.alerts.len());
+            info!(alerts = output.alerts.len(), "Total alerts: {}", output.alerts.len());
         } else {
-            println!("{} No alerts found", "ℹ".blue());
+            info!("No alerts found");
         }
-        
-        println!("{}", "=".repeat(60).blue());
 
         Ok(())
     }
 
     /// Run API server mode
     pub async fn run_server(port: u16) -> Result<()> {
-        println!("{}", "=".repeat(60).blue());
-        println!("{}", "NSE API Server".green().bold());
-        println!("{}", "=".repeat(60).blue());
-        println!();
-
+        info!("NSE API Server");
         nse_api_server::start_server(port).await
     }
 
-    /// Display batch processing summary
+    /// Log batch processing summary (CI parses the "✓ Successful:" / "✗ Failed:" lines)
     fn display_batch_summary(
         successful: &[(models::Security, models::OptionChain)],
         failed: &[(String, String)],
@@ -199,59 +224,72 @@ impl NSECommands {
         elapsed: std::time::Duration,
         _securities: &[models::Security],
     ) {
-        println!("{}", "=".repeat(60).blue());
-        println!("{}", "Summary".cyan().bold());
-        println!("{}", "=".repeat(60).blue());
-        println!("{} Successful: {}", "✓".green(), successful.len());
-        println!("{} Failed: {}", "✗".red(), failed.len());
+        info!("Summary");
+        info!(successful = successful.len(), "✓ Successful: {}", successful.len());
+        info!(failed = failed.len(), "✗ Failed: {}", failed.len());
         
         if timeout_count > 0 {
-            println!("{} Timed out: {} (due to {} second limit)", "⏱".yellow(), timeout_count, config::GITHUB_ACTIONS_TIMEOUT_SECS);
+            warn!(timed_out = timeout_count, "Timed out: {} (due to {} second limit)", timeout_count, settings::current().nse.ci_timeout_secs);
         }
         
-        println!("{} Time taken: {:.2}s", "⏱".yellow(), elapsed.as_secs_f64());
+        info!(elapsed_secs = elapsed.as_secs_f64(), "Time taken: {:.2}s", elapsed.as_secs_f64());
         
-        if successful.len() > 0 {
-            println!("{} Avg time per security: {:.2}s", "📊".to_string(), elapsed.as_secs_f64() / successful.len() as f64);
+        if !successful.is_empty() {
             let requests_per_sec = successful.len() as f64 / elapsed.as_secs_f64();
-            println!("{} Throughput: {:.2} securities/sec", "⚡".to_string(), requests_per_sec);
+            info!(
+                "Avg time per security: {:.2}s, throughput: {:.2} securities/sec",
+                elapsed.as_secs_f64() / successful.len() as f64,
+                requests_per_sec
+            );
         }
-        
-        println!();
 
         // Show failed securities
-        if !failed.is_empty() {
-            println!("{}", "Failed Securities:".red());
-            for (symbol, error) in failed.iter().take(10) {
-                println!("  {} {} → {}", "✗".red(), symbol.yellow(), error.chars().take(80).collect::<String>());
-            }
-            if failed.len() > 10 {
-                println!("  ... and {} more", failed.len() - 10);
-            }
-            println!();
+        for (symbol, error) in failed.iter().take(10) {
+            warn!(symbol = %symbol, "✗ {} → {}", symbol, error.chars().take(80).collect::<String>());
+        }
+        if failed.len() > 10 {
+            warn!("... and {} more failed securities", failed.len() - 10);
         }
     }
 
-    /// Display single security fetch results
+    /// Log single security fetch results
     fn display_single_results(symbol: &str, chain: &models::OptionChain, expiry: &str) {
-        println!("{}", "=".repeat(60).blue());
-        println!("{}", "Results".cyan().bold());
-        println!("{}", "=".repeat(60).blue());
-        println!("{} Symbol: {}", "✓".green(), symbol.yellow());
-        println!("{} Timestamp: {}", "✓".green(), chain.records.timestamp);
-        println!("{} Underlying: {:.2}", "✓".green(), chain.records.underlying_value);
-        println!("{} Expiry: {}", "✓".green(), expiry);
-        println!();
-        println!("{} Total strikes processed: {}", "✓".green(), chain.filtered.data.len());
-        println!();
+        info!(
+            symbol,
+            timestamp = %chain.records.timestamp,
+            underlying = chain.records.underlying_value,
+            expiry,
+            strikes = chain.filtered.data.len(),
+            "✓ {} @ {:.2} ({}), expiry {}, {} strikes processed",
+            symbol,
+            chain.records.underlying_value,
+            chain.records.timestamp,
+            expiry,
+            chain.filtered.data.len()
+        );
+    }
+
+    /// Load contract specs, refreshing NSE lot sizes from the contract master
+    async fn load_contract_specs(client: &NSEClient) -> ContractRegistry {
+        let mut contracts = ContractRegistry::load();
+        match client.fetch_market_lots().await {
+            Ok(lots) => {
+                let count = contracts.merge_nse_lot_sizes(&lots);
+                info!("✓ Loaded {} NSE lot sizes", count);
+            }
+            Err(e) => {
+                warn!("Using built-in lot sizes ({})", e);
+            }
+        }
+        contracts
     }
 
     /// Process batch data and apply rules
     async fn process_batch_data_and_rules(
-        successful: Vec<(models::Security, models::OptionChain)>
-    ) -> Result<()> {
-        let _total_timer = Timer::start("Step 4: Process Data & Apply Rules");
-        println!("{}", "Processing data and applying rules...".cyan());
+        successful: Vec<(models::Security, models::OptionChain)>,
+        contracts: &ContractRegistry,
+    ) -> Result<Vec<rules::RulesOutput>> {
+        info!("Processing data and applying rules...");
         
         let mut process_timer = AggregateTimer::new("Option Data Processing");
         let mut write_timer = AggregateTimer::new("File Write Operations");
@@ -263,8 +301,11 @@ impl NSECommands {
                 .context("Failed to create processed_data directory")?;
         }
         
-        let mut processed_batch = Vec::new();
-        let mut batch_for_rules = Vec::new();
+        // Archive records for backtesting when SNAPSHOT_DIR is set
+        let snapshot_dir = snapshots::snapshot_run_dir(Exchange::Nse);
+        
+        let mut processed = Vec::new();
+        let mut iv_entries = Vec::new();
         
         for (security, chain) in successful.iter() {
             let item_timer = Timer::silent("process_item");
@@ -276,10 +317,34 @@ impl NSECommands {
             
             process_timer.record(item_timer.elapsed());
             
+            let expected_move = processor::calculate_expected_move(&processed_data, chain.records.underlying_value);
+            if let Some(atm_iv) = expected_move.as_ref().and_then(|m| m.atm_iv) {
+                let date = analytics::observation_date(&chain.records.timestamp);
+                iv_entries.push((security.symbol.clone(), date, atm_iv));
+            }
+            processed.push((security, chain, processed_data, spread, expected_move));
+        }
+        
+        // Daily ATM IV history for IV rank/percentile, updated once for the whole batch
+        let iv_ranks = analytics::record_daily_atm_ivs(Exchange::Nse, iv_entries).await;
+        
+        let mut processed_batch = Vec::new();
+        let mut batch_for_rules = Vec::new();
+        let mut symbol_alerts = Vec::new();
+        
+        for (security, chain, processed_data, spread, expected_move) in processed {
             let days_to_expiry = processed_data.first()
                 .map(|opt| opt.days_to_expiry)
                 .unwrap_or(0);
             
+            let iv_rank = iv_ranks.get(&security.symbol).cloned();
+            if let Some(iv) = &iv_rank {
+                let expiry = processed_data.first().and_then(|opt| opt.expiry_date.clone()).unwrap_or_default();
+                if let Some(alert) = rules::check_iv_percentile_rule(&security.symbol, &expiry, iv, spread, days_to_expiry) {
+                    symbol_alerts.push((chain.records.timestamp.clone(), chain.records.underlying_value, alert));
+                }
+            }
+            
             // Create record structure
             let record = serde_json::json!({
                 "record": {
@@ -292,8 +357,12 @@ impl NSECommands {
                     "underlying_value": chain.records.underlying_value,
                     "spread": spread,
                     "days_to_expiry": days_to_expiry,
+                    "expected_move": expected_move,
+                    "iv_rank": iv_rank,
                     "ce_oi": chain.filtered.ce_totals.total_oi,
                     "pe_oi": chain.filtered.pe_totals.total_oi,
+                    "buildup_counts": processor::count_buildups(&processed_data),
+                    "contract_spec": contracts.nse(&security.symbol),
                     // "ce_change_in_oi": chain.filtered.ce_totals.total_change_in_oi,
                     // "pe_change_in_oi": chain.filtered.pe_totals.total_change_in_oi,
                 },
@@ -305,10 +374,15 @@ impl NSECommands {
             let filename = format!("{}.json", security.symbol);
             let filepath = output_dir.join(&filename);
             
-            std::fs::write(
-                &filepath,
-                serde_json::to_string_pretty(&record)?,
-            ).with_context(|| format!("Failed to write {}", filename))?;
+            let contents = serde_json::to_string_pretty(&record)?;
+            utility::write_atomic(&filepath, &contents)
+                .with_context(|| format!("Failed to write {}", filename))?;
+            
+            if let Some(dir) = &snapshot_dir
+                && let Err(e) = utility::write_atomic(dir.join(&filename), &contents)
+            {
+                warn!("Failed to archive snapshot {}: {}", filename, e);
+            }
             
             write_timer.record(write_item_timer.elapsed());
             
@@ -325,26 +399,32 @@ impl NSECommands {
             ));
         }
         
-        println!();
         process_timer.summary();
         write_timer.summary();
         
-        println!("{} Written {} ticker files to {}/", 
-            "✓".green(), 
-            successful.len(), 
-            output_dir.display().to_string().yellow()
-        );
-        println!();
+        info!("✓ Written {} ticker files to {}/", successful.len(), output_dir.display());
         
-        let rules_outputs = {
-            let _rules_timer = Timer::start("Run Rules Engine");
-            rules::run_batch_rules(batch_for_rules)
+        let mut rules_outputs = info_span!("run_rules").in_scope(|| {
+            let mut outputs = rules::run_batch_rules(batch_for_rules);
+            rules::merge_symbol_alerts(&mut outputs, symbol_alerts);
+            for output in outputs.iter_mut() {
+                output.contract_spec = contracts.nse(&output.symbol).cloned();
+            }
+            outputs
+        });
+
+        // Risk alerts for open paper positions join the market alerts
+        let risk = match portfolio::MarketData::new() {
+            Ok(market) => portfolio::book_risk_alerts(&market, Exchange::Nse).await,
+            Err(e) => Err(e),
         };
+        match risk {
+            Ok(risk) => portfolio::merge_nse_risk_alerts(&mut rules_outputs, risk.nse),
+            Err(e) => warn!("Skipped portfolio risk alerts: {}", e),
+        }
         
-        {
-            let _save_timer = Timer::start("Save Rules Output");
         if !rules_outputs.is_empty() {
-            std::fs::write(
+            utility::write_atomic(
                 "batch_rules.json",
                 serde_json::to_string_pretty(&rules_outputs)?,
             )?;
@@ -353,17 +433,15 @@ impl NSECommands {
                 .map(|r| r.alerts.len())
                 .sum();
             
-            println!("{} Saved rules to batch_rules.json", "✓".green());
-            println!("{} Securities with alerts: {}", "ℹ".blue(), rules_outputs.len());
-            println!("{} Total alerts: {}", "ℹ".blue(), total_alerts);
+            info!("✓ Saved rules to batch_rules.json");
+            info!(securities_with_alerts = rules_outputs.len(), total_alerts, "Securities with alerts: {}, total alerts: {}", rules_outputs.len(), total_alerts);
         } else {
-            std::fs::write("batch_rules.json", "[]")?;
-            println!("{} No alerts found across all securities", "ℹ".blue());
-            println!("{} Created empty rules file: batch_rules.json", "✓".green());
-        }
+            utility::write_atomic("batch_rules.json", "[]")?;
+            info!("No alerts found across all securities");
+            info!("✓ Created empty rules file: batch_rules.json");
         }
 
-        Ok(())
+        Ok(rules_outputs)
     }
    
     /// Print usage instructions
@@ -378,7 +456,7 @@ impl NSECommands {
     /// Handle CI environment mode switching
     pub fn handle_ci_mode_override(mode: &str) -> bool {
         if config::is_ci_environment() && (mode == "server") {
-            println!("{} GitHub Actions only supports batch mode, running batch instead", "ℹ".blue());
+            info!("GitHub Actions only supports batch mode, running batch instead");
             true
         } else {
             false
@@ -1,10 +1,16 @@
-use super::models::{OptionData, OptionDetail};
+use super::models::{NseFuturesData, NseHistoricalRecord, OptionData, OptionDetail};
+use crate::analytics::{
+    classify_buildup, compute_expected_move, normalize_series, parse_series_date,
+    strike_probabilities, Buildup, BuildupCounts, ExpectedMove, ExposureQuote, HistoricalSeries,
+    OhlcBar, OptionKind, PricingModel, SeriesMeta, SmileQuote, StrikeProbabilities,
+};
 use serde::{Deserialize, Serialize};
 use chrono::{NaiveDate, Local};
 use anyhow::{Result, anyhow};
+use utoipa::ToSchema;
 
 /// Enhanced option detail with computed fields
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ProcessedOptionDetail {
     #[serde(flatten)]
     pub base: OptionDetail,
@@ -13,10 +19,14 @@ pub struct ProcessedOptionDetail {
     pub tambu: Option<String>,  // "TMJ", "TMG", or None
     pub time_val: f64,
     pub days_to_expiry: i32,  // Days remaining until expiry (0 on expiry day)
+    #[serde(default)]
+    pub buildup: Buildup,  // Price change vs OI change classification
+    #[serde(default)]
+    pub probabilities: Option<StrikeProbabilities>,  // IV, prob ITM / touch at expiry
 }
 
 /// Processed option data with enhanced CE and PE
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ProcessedOptionData {
     #[serde(rename = "expiryDates")]
     pub expiry_date: Option<String>,
@@ -130,7 +140,7 @@ pub fn process_option_data(
             let days_to_expiry = match calculate_days_to_expiry(expiry_date_str) {
                 Ok(days) => days,
                 Err(e) => {
-                    eprintln!("Warning: Failed to calculate days to expiry for {}: {}", expiry_date_str, e);
+                    tracing::warn!("Failed to calculate days to expiry for {}: {}", expiry_date_str, e);
                     return None; // Skip this option if expiry calculation fails
                 }
             };
@@ -237,12 +247,28 @@ pub fn process_option_detail(
         is_call,
     );
     
+    // Step 5: Classify buildup from price change vs OI change
+    let buildup = classify_buildup(detail.price_change, detail.change_in_oi);
+    
+    // Step 6: IV and probability of expiring ITM / touching the strike
+    let kind = if is_call { OptionKind::Call } else { OptionKind::Put };
+    let probabilities = strike_probabilities(
+        &PricingModel::nse(),
+        kind,
+        detail.last_price,
+        underlying_value,
+        strike,
+        days_to_expiry,
+    );
+    
     ProcessedOptionDetail {
         base: detail,
         the_money,
         tambu,
         time_val,
         days_to_expiry,
+        buildup,
+        probabilities,
     }
 }
 
@@ -394,3 +420,96 @@ pub fn get_max_oi(opt: &ProcessedOptionData) -> f64 {
 
     ce_oi.max(pe_oi)
 }
+
+/// Count buildups across all CE and PE legs of processed data
+pub fn count_buildups(data: &[ProcessedOptionData]) -> BuildupCounts {
+    data.iter()
+        .flat_map(|opt| [opt.call.as_ref(), opt.put.as_ref()])
+        .flatten()
+        .map(|detail| detail.buildup)
+        .collect()
+}
+
+/// Expected move and 1σ/2σ bands from the ATM straddle of processed data
+pub fn calculate_expected_move(data: &[ProcessedOptionData], underlying_value: f64) -> Option<ExpectedMove> {
+    let atm = data.iter().find(|opt| {
+        opt.call.as_ref().map(|ce| ce.the_money == "ATM").unwrap_or(false)
+    })?;
+
+    compute_expected_move(
+        &PricingModel::nse(),
+        atm.strike_price?,
+        atm.call.as_ref()?.base.last_price?,
+        atm.put.as_ref()?.base.last_price?,
+        underlying_value,
+        atm.days_to_expiry,
+    )
+}
+
+/// Extract per-strike CE/PE prices for IV smile computation
+pub fn smile_quotes(data: &[OptionData]) -> Vec<SmileQuote> {
+    data.iter()
+        .filter_map(|opt| {
+            Some(SmileQuote {
+                strike: opt.strike_price?,
+                call_price: opt.call.as_ref().and_then(|c| c.last_price),
+                put_price: opt.put.as_ref().and_then(|p| p.last_price),
+            })
+        })
+        .collect()
+}
+
+/// Extract per-strike OI and prices for gamma exposure computation
+pub fn exposure_quotes(data: &[OptionData]) -> Vec<ExposureQuote> {
+    data.iter()
+        .filter_map(|opt| {
+            Some(ExposureQuote {
+                strike: opt.strike_price?,
+                call_oi: opt.call.as_ref().and_then(|c| c.open_interest).unwrap_or(0.0),
+                put_oi: opt.put.as_ref().and_then(|p| p.open_interest).unwrap_or(0.0),
+                call_price: opt.call.as_ref().and_then(|c| c.last_price),
+                put_price: opt.put.as_ref().and_then(|p| p.last_price),
+            })
+        })
+        .collect()
+}
+
+/// Set each futures quote's buildup from its price and OI change
+pub fn enrich_nse_futures_data(futures: &mut NseFuturesData) {
+    for quote in &mut futures.data {
+        let price_change = quote.per_chg_price.or(quote.price_change);
+        let oi_change = quote.change_in_oi.or(quote.per_chg_oi);
+        quote.action = classify_buildup(price_change, oi_change);
+    }
+}
+
+/// Normalized OHLC/OI series from NSE derivatives history.
+/// Days without a trade fall back to the settle price, then the last traded price.
+pub fn build_historical_series(
+    records: &[NseHistoricalRecord],
+    meta: SeriesMeta,
+    window: usize,
+) -> HistoricalSeries {
+    let positive = |v: Option<f64>| v.filter(|p| *p > 0.0);
+
+    let bars = records
+        .iter()
+        .filter_map(|record| {
+            let date = parse_series_date(&record.timestamp)?;
+            let close = positive(record.close)
+                .or(positive(record.settle_price))
+                .or(positive(record.last_traded_price))?;
+
+            Some(OhlcBar {
+                volume: record.volume,
+                open_interest: record.open_interest,
+                change_in_oi: record.change_in_oi,
+                underlying_value: record.underlying_value,
+                ..OhlcBar::new(date, record.open, record.high, record.low, close)
+            })
+        })
+        .collect();
+
+    normalize_series(meta, bars, window)
+}
+
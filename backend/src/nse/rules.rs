@@ -1,8 +1,16 @@
 use super::processor::{ProcessedOptionData, ProcessedOptionDetail};
+use crate::analytics::{Buildup, GammaExposure, GammaRegime, IvRankStats};
+use crate::contracts::ContractSpec;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Fresh buildup alert thresholds (above this OI change HUGE_OI_INCREASE takes over)
+const BUILDUP_MIN_PCHANGE_IN_OI: f64 = 100.0;
+const BUILDUP_MAX_PCHANGE_IN_OI: f64 = 1000.0;
+const BUILDUP_MIN_ABS_PCHANGE: f64 = 20.0;
 
 /// Alert types for option strikes
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Alert {
     pub symbol: String,
     pub strike_price: f64,
@@ -14,7 +22,7 @@ pub struct Alert {
     pub values: AlertValues,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct AlertValues {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pchange_in_oi: Option<f64>,
@@ -30,15 +38,20 @@ pub struct AlertValues {
 
     pub time_val: f64,
     pub days_to_expiry: i32,  // Days remaining until expiry
+
+    #[serde(default)]
+    pub buildup: Buildup,
 }
 
 /// Rules output structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct RulesOutput {
     pub symbol: String,
     pub timestamp: String,
     pub underlying_value: f64,
     pub alerts: Vec<Alert>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract_spec: Option<ContractSpec>,
 }
 
 /// Run rules on processed option data
@@ -97,9 +110,116 @@ pub fn run_rules(
         timestamp,
         underlying_value,
         alerts,
+        contract_spec: None,
+    })
+}
+
+/// Rule 6: Spot crossed the zero-gamma flip level since the previous observation
+pub fn check_gamma_flip_rule(
+    symbol: &str,
+    expiry: &str,
+    previous_spot: f64,
+    exposure: &GammaExposure,
+    spread: f64,
+    days_to_expiry: i32,
+) -> Option<Alert> {
+    let flip = exposure.zero_gamma_level?;
+    let spot = exposure.underlying_value;
+
+    let (alert_type, direction) = if previous_spot < flip && spot >= flip {
+        ("GAMMA_FLIP_UP", "above")
+    } else if previous_spot > flip && spot <= flip {
+        ("GAMMA_FLIP_DOWN", "below")
+    } else {
+        return None;
+    };
+
+    let regime = match exposure.regime {
+        GammaRegime::Positive => "dealers long gamma",
+        GammaRegime::Negative => "dealers short gamma",
+    };
+
+    Some(Alert {
+        symbol: symbol.to_string(),
+        strike_price: flip,
+        expiry_date: expiry.to_string(),
+        option_type: "GEX".to_string(),
+        alert_type: alert_type.to_string(),
+        description: format!(
+            "Spot crossed {} zero-gamma level {:.2} ({:.2} -> {:.2}), {}",
+            direction, flip, previous_spot, spot, regime
+        ),
+        spread,
+        values: AlertValues {
+            pchange_in_oi: None,
+            last_price: Some(spot),
+            open_interest: None,
+            the_money: None,
+            time_val: 0.0,
+            days_to_expiry,
+            buildup: Buildup::Neutral,
+        },
     })
 }
 
+/// Rule 7: ATM IV at an extreme percentile of its own history
+pub fn check_iv_percentile_rule(
+    symbol: &str,
+    expiry: &str,
+    iv: &IvRankStats,
+    spread: f64,
+    days_to_expiry: i32,
+) -> Option<Alert> {
+    let extreme = iv.extreme()?;
+
+    Some(Alert {
+        symbol: symbol.to_string(),
+        strike_price: 0.0,
+        expiry_date: expiry.to_string(),
+        option_type: "IV".to_string(),
+        alert_type: extreme.alert_type.to_string(),
+        description: extreme.description,
+        spread,
+        values: AlertValues {
+            pchange_in_oi: None,
+            last_price: Some(iv.atm_iv),
+            open_interest: None,
+            the_money: Some("ATM".to_string()),
+            time_val: 0.0,
+            days_to_expiry,
+            buildup: Buildup::Neutral,
+        },
+    })
+}
+
+/// Append a symbol-level alert, creating the output when no strike alerts fired
+pub fn append_alert(
+    output: Option<RulesOutput>,
+    alert: Alert,
+    timestamp: &str,
+    underlying_value: f64,
+) -> RulesOutput {
+    let mut output = output.unwrap_or_else(|| RulesOutput {
+        symbol: alert.symbol.clone(),
+        timestamp: timestamp.to_string(),
+        underlying_value,
+        alerts: Vec::new(),
+        contract_spec: None,
+    });
+    output.alerts.push(alert);
+    output
+}
+
+/// Merge symbol-level alerts (timestamp, underlying, alert) into batch rules outputs
+pub fn merge_symbol_alerts(outputs: &mut Vec<RulesOutput>, alerts: Vec<(String, f64, Alert)>) {
+    for (timestamp, underlying_value, alert) in alerts {
+        match outputs.iter().position(|r| r.symbol == alert.symbol) {
+            Some(idx) => outputs[idx].alerts.push(alert),
+            None => outputs.push(append_alert(None, alert, &timestamp, underlying_value)),
+        }
+    }
+}
+
 /// Check rules for a single option (CE or PE)
 pub fn check_option_rules(
     symbol: &str,
@@ -137,6 +257,7 @@ pub fn check_option_rules(
                 the_money: Some(detail.the_money.clone()),
                 time_val: detail.time_val.clone(),
                 days_to_expiry,
+                buildup: detail.buildup,
             },
         });
     }
@@ -161,6 +282,7 @@ pub fn check_option_rules(
                 the_money: Some(detail.the_money.clone()),
                 time_val: detail.time_val.clone(),
                 days_to_expiry,
+                buildup: detail.buildup,
             },
         });
     }
@@ -194,6 +316,7 @@ pub fn check_option_rules(
                 the_money: Some(detail.the_money.clone()),
                 time_val: detail.time_val.clone(),
                 days_to_expiry,
+                buildup: detail.buildup,
             },
         });
         }
@@ -218,6 +341,38 @@ pub fn check_option_rules(
                 the_money: Some(detail.the_money.clone()),
                 time_val: detail.time_val.clone(),
                 days_to_expiry,
+                buildup: detail.buildup,
+            },
+        });
+    }
+    
+    // Rule 5: Fresh long/short buildup
+    let pchange = detail.base.per_chg_price.unwrap_or(0.0);
+    let is_fresh_buildup = matches!(detail.buildup, Buildup::LongBuildup | Buildup::ShortBuildup)
+        && pchange_in_oi > BUILDUP_MIN_PCHANGE_IN_OI
+        && pchange_in_oi <= BUILDUP_MAX_PCHANGE_IN_OI
+        && pchange.abs() >= BUILDUP_MIN_ABS_PCHANGE;
+
+    if is_fresh_buildup {
+        alerts.push(Alert {
+            symbol: symbol.to_string(),
+            strike_price: strike,
+            expiry_date: expiry.to_string(),
+            option_type: option_type.to_string(),
+            alert_type: detail.buildup.alert_type().to_string(),
+            description: format!(
+                "{} {} {} strike shows {} (OI {:+.2}%, price {:+.2}%) ({} days to expiry)",
+                symbol, option_type, strike, detail.buildup, pchange_in_oi, pchange, days_to_expiry
+            ),
+            spread,
+            values: AlertValues {
+                pchange_in_oi: Some(pchange_in_oi),
+                last_price,
+                open_interest,
+                the_money: Some(detail.the_money.clone()),
+                time_val: detail.time_val,
+                days_to_expiry,
+                buildup: detail.buildup,
             },
         });
     }
@@ -0,0 +1,193 @@
+use super::models::{Instrument, Position};
+use super::valuation::Mark;
+use crate::analytics::OptionKind;
+use crate::contracts::Exchange;
+use crate::mcx::{processor as mcx_processor, MCXClient};
+use crate::nse::models::Security;
+use crate::nse::{processor as nse_processor, NSEClient};
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+// Strikes are matched to the chain within this tolerance
+const STRIKE_EPSILON: f64 = 0.01;
+
+/// Positions sharing one chain/quote fetch
+type GroupKey = (Exchange, Instrument, String, String);
+
+/// Live prices for paper positions from the NSE and MCX public APIs
+pub struct MarketData {
+    nse: Arc<NSEClient>,
+    mcx: Arc<MCXClient>,
+}
+
+impl MarketData {
+    pub fn new() -> Result<Self> {
+        Ok(Self::with_clients(Arc::new(NSEClient::new()?), Arc::new(MCXClient::new()?)))
+    }
+
+    /// Share a server's clients (and their sessions and circuit breakers)
+    pub fn with_clients(nse: Arc<NSEClient>, mcx: Arc<MCXClient>) -> Self {
+        Self { nse, mcx }
+    }
+
+    /// Mark every position, fetching each (exchange, symbol, expiry) once.
+    /// Returns marks by position id and a message per failed fetch.
+    pub async fn mark_positions(&self, positions: &[&Position]) -> (HashMap<u64, Mark>, Vec<String>) {
+        let mut groups: HashMap<GroupKey, Vec<&Position>> = HashMap::new();
+        for position in positions {
+            groups
+                .entry((position.exchange, position.instrument, position.symbol.clone(), position.expiry.clone()))
+                .or_default()
+                .push(position);
+        }
+
+        let mut marks = HashMap::new();
+        let mut errors = Vec::new();
+
+        for ((exchange, instrument, symbol, expiry), group) in groups {
+            let result = match (exchange, instrument) {
+                (Exchange::Nse, Instrument::Option) => self.nse_option_marks(&symbol, &expiry, &group).await,
+                (Exchange::Nse, Instrument::Future) => self.nse_future_mark(&symbol, &expiry, &group).await,
+                (Exchange::Mcx, Instrument::Option) => self.mcx_option_marks(&symbol, &expiry, &group).await,
+                (Exchange::Mcx, Instrument::Future) => self.mcx_future_mark(&symbol, &expiry, &group).await,
+            };
+
+            match result {
+                Ok(group_marks) => marks.extend(group_marks),
+                Err(e) => errors.push(format!("{} {}: {}", symbol, expiry, e)),
+            }
+        }
+
+        (marks, errors)
+    }
+
+    /// Mark a single position (used to fill entries/exits at market)
+    pub async fn mark_position(&self, position: &Position) -> Result<Mark> {
+        let (marks, errors) = self.mark_positions(&[position]).await;
+        marks.get(&position.id).copied().ok_or_else(|| match errors.first() {
+            Some(error) => anyhow!("{}", error),
+            None => anyhow!("No live price for {}", position.label()),
+        })
+    }
+
+    async fn nse_option_marks(&self, symbol: &str, expiry: &str, group: &[&Position]) -> Result<HashMap<u64, Mark>> {
+        let security = if self.nse.settings().is_index(symbol) {
+            Security::index(symbol.to_string())
+        } else {
+            Security::equity(symbol.to_string())
+        };
+        let chain = self.nse.fetch_option_chain(&security, expiry).await?;
+        let days_to_expiry = nse_processor::calculate_days_to_expiry(expiry).ok();
+
+        let mut marks = HashMap::new();
+        for position in group {
+            let row = chain.records.data.iter().find(|row| {
+                row.expiry_date.as_deref().is_none_or(|e| e.eq_ignore_ascii_case(expiry))
+                    && strike_matches(row.strike_price, position.strike)
+            });
+            let detail = row.and_then(|row| match position.option_type {
+                Some(OptionKind::Call) => row.call.as_ref(),
+                Some(OptionKind::Put) => row.put.as_ref(),
+                None => None,
+            });
+
+            if let Some(price) = detail.and_then(|d| d.last_price).filter(|p| *p > 0.0) {
+                marks.insert(position.id, Mark {
+                    price,
+                    underlying_value: Some(chain.records.underlying_value),
+                    days_to_expiry,
+                });
+            }
+        }
+        Ok(marks)
+    }
+
+    /// Last traded price of an NSE future
+    pub async fn nse_futures_price(&self, symbol: &str, expiry: &str) -> Result<f64> {
+        let data = self.nse.fetch_futures_data(symbol, expiry).await?;
+        data.data.iter().find_map(|quote| quote.last_price).ok_or_else(|| anyhow!("No futures price in response"))
+    }
+
+    async fn nse_future_mark(&self, symbol: &str, expiry: &str, group: &[&Position]) -> Result<HashMap<u64, Mark>> {
+        let price = self.nse_futures_price(symbol, expiry).await?;
+
+        let mark = Mark {
+            price,
+            underlying_value: Some(price),
+            days_to_expiry: nse_processor::calculate_days_to_expiry(expiry).ok(),
+        };
+        Ok(group.iter().map(|p| (p.id, mark)).collect())
+    }
+
+    async fn mcx_option_marks(&self, symbol: &str, expiry: &str, group: &[&Position]) -> Result<HashMap<u64, Mark>> {
+        let chain = self.mcx.fetch_option_chain(symbol, expiry).await?;
+        let days_to_expiry = mcx_processor::calculate_days_to_expiry(expiry).ok();
+        let chain_underlying = chain.d.data.iter().find_map(|row| row.underlying_value);
+
+        let mut marks = HashMap::new();
+        for position in group {
+            let row = chain.d.data.iter().find(|row| strike_matches(row.ce_strike_price, position.strike));
+            let price = row.and_then(|row| match position.option_type {
+                Some(OptionKind::Call) => row.ce_ltp,
+                Some(OptionKind::Put) => row.pe_ltp,
+                None => None,
+            });
+
+            // Strikes missing from the chain fall back to a single-contract quote
+            let price = match (price.filter(|p| *p > 0.0), position.option_type, position.strike) {
+                (Some(price), _, _) => Some(price),
+                (None, Some(kind), Some(strike)) => self.mcx
+                    .fetch_option_quote(symbol, expiry, kind.code(), &format_mcx_strike(strike))
+                    .await
+                    .ok()
+                    .and_then(|quote| find_number(&quote, &["LTP", "lastPrice"])),
+                _ => None,
+            };
+
+            if let Some(price) = price.filter(|p| *p > 0.0) {
+                marks.insert(position.id, Mark {
+                    price,
+                    underlying_value: row.and_then(|r| r.underlying_value).or(chain_underlying),
+                    days_to_expiry,
+                });
+            }
+        }
+        Ok(marks)
+    }
+
+    async fn mcx_future_mark(&self, symbol: &str, expiry: &str, group: &[&Position]) -> Result<HashMap<u64, Mark>> {
+        let quote = self.mcx.fetch_future_quote(symbol, expiry).await?;
+        let price = find_number(&quote, &["LTP", "lastPrice"])
+            .ok_or_else(|| anyhow!("No futures price in response"))?;
+
+        let mark = Mark {
+            price,
+            underlying_value: Some(price),
+            days_to_expiry: mcx_processor::calculate_days_to_expiry(expiry).ok(),
+        };
+        Ok(group.iter().map(|p| (p.id, mark)).collect())
+    }
+}
+
+/// MCX quotes take the strike with two decimals ("1120.00")
+pub fn format_mcx_strike(strike: f64) -> String {
+    format!("{:.2}", strike)
+}
+
+fn strike_matches(row_strike: Option<f64>, strike: Option<f64>) -> bool {
+    matches!((row_strike, strike), (Some(a), Some(b)) if (a - b).abs() < STRIKE_EPSILON)
+}
+
+/// First positive number under any of `keys`, searching depth-first
+fn find_number(value: &Value, keys: &[&str]) -> Option<f64> {
+    match value {
+        Value::Object(map) => keys
+            .iter()
+            .find_map(|key| map.get(*key).and_then(|v| v.as_f64()).filter(|n| *n > 0.0))
+            .or_else(|| map.values().find_map(|v| find_number(v, keys))),
+        Value::Array(items) => items.iter().find_map(|v| find_number(v, keys)),
+        _ => None,
+    }
+}
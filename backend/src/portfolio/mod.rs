@@ -0,0 +1,16 @@
+pub mod market;
+pub mod models;
+pub mod portfolio_api;
+pub mod risk;
+pub mod valuation;
+
+pub use market::MarketData;
+pub use models::{
+    ClosePositionRequest, Instrument, OpenPositionRequest, PortfolioStore, Position, PositionStatus,
+};
+pub use portfolio_api::{get_portfolio_routes, print_portfolio_endpoints};
+pub use risk::{
+    book_risk_alerts, check_position_risk, merge_mcx_risk_alerts, merge_nse_risk_alerts, run_risk_rules, RiskAlerts,
+    RiskConfig,
+};
+pub use valuation::{summarize, value_position, Mark, PortfolioSummary, PositionValuation};
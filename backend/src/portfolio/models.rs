@@ -0,0 +1,249 @@
+use crate::analytics::{OptionKind, Side};
+use crate::contracts::Exchange;
+use crate::settings;
+use crate::utility;
+use anyhow::{anyhow, Context, Result};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use utoipa::ToSchema;
+
+pub const DEFAULT_PORTFOLIO_FILE: &str = "portfolio.json";
+const TIMESTAMP_FORMAT: &str = "%d-%b-%Y %H:%M:%S";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Instrument {
+    #[serde(alias = "OPTIONS", alias = "option")]
+    Option,
+    #[serde(alias = "FUTURES", alias = "future")]
+    Future,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum PositionStatus {
+    Open,
+    Closed,
+}
+
+/// A paper position in one option or futures contract
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Position {
+    pub id: u64,
+    pub exchange: Exchange,
+    pub symbol: String,
+    pub expiry: String,  // As the exchange formats it ("30-Dec-2025" / "23DEC2025")
+    pub instrument: Instrument,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strike: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub option_type: Option<OptionKind>,
+    pub side: Side,
+    pub lots: f64,
+    pub point_value: f64,  // Lot size x multiplier at entry
+    pub entry_price: f64,
+    pub entry_time: String,
+    pub status: PositionStatus,
+    #[serde(default)]
+    pub exit_price: Option<f64>,
+    #[serde(default)]
+    pub exit_time: Option<String>,
+    #[serde(default)]
+    pub realized_pnl: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,  // e.g. the alert type that prompted the trade
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry_delta: Option<f64>,  // Per-unit delta at entry (options), for drift alerts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_loss: Option<f64>,    // P&L (rupees, negative) that triggers a stop alert
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub take_profit: Option<f64>,  // P&L (rupees) that triggers a target alert
+}
+
+impl Position {
+    pub fn new(id: u64, request: OpenPositionRequest, entry_price: f64, point_value: f64) -> Self {
+        Self {
+            id,
+            exchange: request.exchange,
+            symbol: request.symbol.trim().to_uppercase(),
+            expiry: request.expiry.trim().to_string(),
+            instrument: request.instrument,
+            strike: request.strike,
+            option_type: request.option_type,
+            side: request.side,
+            lots: request.lots,
+            point_value,
+            entry_price,
+            entry_time: Local::now().format(TIMESTAMP_FORMAT).to_string(),
+            status: PositionStatus::Open,
+            exit_price: None,
+            exit_time: None,
+            realized_pnl: None,
+            tag: request.tag,
+            entry_delta: None,
+            stop_loss: request.stop_loss,
+            take_profit: request.take_profit,
+        }
+    }
+
+    /// Units of the underlying controlled by the position
+    pub fn quantity(&self) -> f64 {
+        self.lots * self.point_value
+    }
+
+    /// P&L if the position were closed at `price`
+    pub fn pnl_at(&self, price: f64) -> f64 {
+        self.side.sign() * (price - self.entry_price) * self.quantity()
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.status == PositionStatus::Open
+    }
+
+    /// Short label, e.g. "NIFTY 30-Dec-2025 26000 CE" or "CRUDEOIL 19DEC2025 FUT"
+    pub fn label(&self) -> String {
+        match (self.instrument, self.strike, self.option_type) {
+            (Instrument::Option, Some(strike), Some(kind)) => {
+                format!("{} {} {} {}", self.symbol, self.expiry, strike, kind.code())
+            }
+            _ => format!("{} {} FUT", self.symbol, self.expiry),
+        }
+    }
+}
+
+fn default_lots() -> f64 {
+    1.0
+}
+
+/// Body of POST /api/portfolio/positions
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct OpenPositionRequest {
+    pub exchange: Exchange,
+    pub symbol: String,
+    pub expiry: String,
+    pub instrument: Instrument,
+    pub strike: Option<f64>,
+    pub option_type: Option<OptionKind>,
+    pub side: Side,
+    #[serde(default = "default_lots")]
+    pub lots: f64,
+    pub entry_price: Option<f64>,  // Defaults to the live mark
+    pub point_value: Option<f64>,  // Defaults to the contract registry
+    pub tag: Option<String>,
+    pub stop_loss: Option<f64>,
+    pub take_profit: Option<f64>,
+}
+
+impl OpenPositionRequest {
+    pub fn validate(&self) -> Result<()> {
+        if self.symbol.trim().is_empty() || self.expiry.trim().is_empty() {
+            return Err(anyhow!("symbol and expiry are required"));
+        }
+        if self.lots <= 0.0 {
+            return Err(anyhow!("lots must be positive"));
+        }
+        if self.stop_loss.is_some_and(|sl| sl >= 0.0) || self.take_profit.is_some_and(|tp| tp <= 0.0) {
+            return Err(anyhow!("stop_loss must be negative and take_profit positive"));
+        }
+        match self.instrument {
+            Instrument::Option if self.strike.is_none() || self.option_type.is_none() => {
+                Err(anyhow!("Option positions need strike and option_type"))
+            }
+            Instrument::Future if self.strike.is_some() || self.option_type.is_some() => {
+                Err(anyhow!("Futures positions take no strike or option_type"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Body of POST /api/portfolio/positions/{id}/close
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct ClosePositionRequest {
+    pub exit_price: Option<f64>,  // Defaults to the live mark
+}
+
+/// All paper positions, persisted as JSON
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PortfolioStore {
+    next_id: u64,
+    pub positions: Vec<Position>,
+}
+
+impl PortfolioStore {
+    /// Store location from the portfolio_file setting (PORTFOLIO_FILE, default "portfolio.json")
+    pub fn path() -> PathBuf {
+        PathBuf::from(&settings::current().portfolio_file)
+    }
+
+    /// Load the store, starting empty if the file does not exist yet
+    pub fn load_file(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&text).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Write via a temp file so a crash never leaves a truncated store
+    pub fn save_file(&self, path: &Path) -> Result<()> {
+        utility::write_atomic(path, serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Position> {
+        self.positions.iter().find(|p| p.id == id)
+    }
+
+    /// Record a new open position and return it
+    pub fn open(
+        &mut self,
+        request: OpenPositionRequest,
+        entry_price: f64,
+        point_value: f64,
+        entry_delta: Option<f64>,
+    ) -> Result<Position> {
+        request.validate()?;
+        if entry_price <= 0.0 || point_value <= 0.0 {
+            return Err(anyhow!("entry_price and point_value must be positive"));
+        }
+
+        self.next_id += 1;
+        let position = Position {
+            entry_delta,
+            ..Position::new(self.next_id, request, entry_price, point_value)
+        };
+        self.positions.push(position.clone());
+        Ok(position)
+    }
+
+    /// Close an open position at `exit_price`, realizing its P&L
+    pub fn close(&mut self, id: u64, exit_price: f64) -> Result<Position> {
+        if exit_price <= 0.0 {
+            return Err(anyhow!("exit_price must be positive"));
+        }
+        let position = self.positions
+            .iter_mut()
+            .find(|p| p.id == id)
+            .ok_or_else(|| anyhow!("Position {} not found", id))?;
+        if !position.is_open() {
+            return Err(anyhow!("Position {} is already closed", id));
+        }
+
+        position.status = PositionStatus::Closed;
+        position.exit_price = Some(exit_price);
+        position.exit_time = Some(Local::now().format(TIMESTAMP_FORMAT).to_string());
+        position.realized_pnl = Some(position.pnl_at(exit_price));
+        Ok(position.clone())
+    }
+
+    /// Delete a position (open or closed) from the book
+    pub fn remove(&mut self, id: u64) -> Result<Position> {
+        let idx = self.positions
+            .iter()
+            .position(|p| p.id == id)
+            .ok_or_else(|| anyhow!("Position {} not found", id))?;
+        Ok(self.positions.remove(idx))
+    }
+}
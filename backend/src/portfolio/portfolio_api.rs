@@ -0,0 +1,219 @@
+use super::market::MarketData;
+use super::models::{ClosePositionRequest, Instrument, OpenPositionRequest, PortfolioStore, Position};
+use super::risk::{run_risk_rules, RiskAlerts, RiskConfig};
+use super::valuation::{summarize, value_position, PortfolioSummary};
+use crate::contracts::{ContractRegistry, Exchange};
+use crate::settings;
+use crate::utility::{self, ApiResponse};
+use anyhow::{anyhow, Result};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    routing::{get, post},
+    Router,
+};
+use chrono::Local;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::RwLock;
+
+// -----------------------------------------------
+// PORTFOLIO STATE
+// -----------------------------------------------
+
+/// Both servers mount these routes over one store file: it is re-read on every request
+/// and every load-modify-save holds a file lock, so the two processes never lose updates
+#[derive(Clone)]
+pub struct PortfolioState {
+    path: PathBuf,
+    market: Arc<MarketData>,
+    contracts: Arc<RwLock<ContractRegistry>>,
+    risk: RiskConfig,
+}
+
+impl PortfolioState {
+    pub fn new(contracts: Arc<RwLock<ContractRegistry>>, market: Arc<MarketData>) -> Self {
+        Self {
+            path: PortfolioStore::path(),
+            market,
+            contracts,
+            risk: RiskConfig::from_settings(&settings::current().risk),
+        }
+    }
+
+    /// Load, modify and save the store under the cross-process file lock
+    async fn update<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut PortfolioStore) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        utility::with_file_lock(self.path.clone(), move |path| {
+            let mut store = PortfolioStore::load_file(path)?;
+            let result = f(&mut store)?;
+            store.save_file(path)?;
+            Ok(result)
+        })
+        .await
+    }
+
+    async fn load(&self) -> Result<PortfolioStore> {
+        utility::with_file_lock(self.path.clone(), PortfolioStore::load_file).await
+    }
+
+    async fn default_point_value(&self, exchange: Exchange, symbol: &str) -> Option<f64> {
+        let contracts = self.contracts.read().await;
+        let spec = match exchange {
+            Exchange::Nse => contracts.nse(symbol),
+            Exchange::Mcx => contracts.mcx(symbol),
+        };
+        spec.map(|s| s.point_value())
+    }
+
+    async fn summary(&self) -> Result<PortfolioSummary> {
+        let store = self.load().await?;
+        let open: Vec<&Position> = store.positions.iter().filter(|p| p.is_open()).collect();
+        let (marks, mark_errors) = self.market.mark_positions(&open).await;
+
+        let mut summary = summarize(&store.positions, &marks);
+        let timestamp = Local::now().format("%d-%b-%Y %H:%M:%S").to_string();
+        summary.risk_alerts = run_risk_rules(&summary, &self.risk, &timestamp);
+        summary.mark_errors = mark_errors;
+        Ok(summary)
+    }
+
+    async fn open_position(&self, request: OpenPositionRequest) -> Result<Position> {
+        request.validate()?;
+
+        let point_value = match request.point_value {
+            Some(value) => value,
+            None => self
+                .default_point_value(request.exchange, &request.symbol.trim().to_uppercase())
+                .await
+                .ok_or_else(|| anyhow!("No contract spec for {}; pass point_value", request.symbol))?,
+        };
+
+        // Fill at market unless an entry price was given. Options are marked
+        // either way so the entry delta can be recorded for drift alerts.
+        let preview = Position::new(0, request.clone(), request.entry_price.unwrap_or(0.0), point_value);
+        let mark = match (request.entry_price, request.instrument) {
+            (None, _) => Some(self.market.mark_position(&preview).await?),
+            (Some(_), Instrument::Option) => self.market.mark_position(&preview).await.ok(),
+            (Some(_), Instrument::Future) => None,
+        };
+        let entry_price = request.entry_price.or(mark.map(|m| m.price)).unwrap_or(0.0);
+        let entry_delta = mark.and_then(|m| value_position(&preview, Some(&m)).unit_delta());
+
+        self.update(move |store| store.open(request, entry_price, point_value, entry_delta)).await
+    }
+
+    async fn close_position(&self, id: u64, request: ClosePositionRequest) -> Result<Position> {
+        let exit_price = match request.exit_price {
+            Some(price) => price,
+            None => {
+                let store = self.load().await?;
+                let position = store.get(id).ok_or_else(|| anyhow!("Position {} not found", id))?;
+                self.market.mark_position(position).await?.price
+            }
+        };
+
+        self.update(move |store| store.close(id, exit_price)).await
+    }
+}
+
+// -----------------------------------------------
+// API HANDLERS
+// -----------------------------------------------
+
+fn respond<T>(result: Result<T>, start_time: Instant) -> Json<ApiResponse<T>> {
+    let processing_time_ms = Some(start_time.elapsed().as_millis() as u64);
+    match result {
+        Ok(data) => Json(ApiResponse { success: true, data: Some(data), error: None, processing_time_ms }),
+        Err(e) => Json(ApiResponse { success: false, data: None, error: Some(e.to_string()), processing_time_ms }),
+    }
+}
+
+/// GET /api/portfolio/summary - Open positions marked to market with P&L, Greeks and margin
+#[utoipa::path(get, path = "/api/portfolio/summary", tag = "portfolio", responses((status = 200, body = ApiResponse<PortfolioSummary>)))]
+pub(crate) async fn get_summary(
+    State(state): State<PortfolioState>,
+) -> Result<Json<ApiResponse<PortfolioSummary>>, StatusCode> {
+    let start_time = Instant::now();
+    Ok(respond(state.summary().await, start_time))
+}
+
+/// GET /api/portfolio/alerts - Position risk alerts in each exchange's rules output format
+#[utoipa::path(get, path = "/api/portfolio/alerts", tag = "portfolio", responses((status = 200, body = ApiResponse<RiskAlerts>)))]
+pub(crate) async fn get_risk_alerts(
+    State(state): State<PortfolioState>,
+) -> Result<Json<ApiResponse<RiskAlerts>>, StatusCode> {
+    let start_time = Instant::now();
+    Ok(respond(state.summary().await.map(|summary| summary.risk_alerts), start_time))
+}
+
+/// GET /api/portfolio/positions - All positions as stored
+#[utoipa::path(get, path = "/api/portfolio/positions", tag = "portfolio", responses((status = 200, body = ApiResponse<Vec<Position>>)))]
+pub(crate) async fn list_positions(
+    State(state): State<PortfolioState>,
+) -> Result<Json<ApiResponse<Vec<Position>>>, StatusCode> {
+    let start_time = Instant::now();
+    Ok(respond(state.load().await.map(|store| store.positions), start_time))
+}
+
+/// POST /api/portfolio/positions - Open a paper position
+#[utoipa::path(post, path = "/api/portfolio/positions", tag = "portfolio", request_body = OpenPositionRequest, responses((status = 200, body = ApiResponse<Position>)))]
+pub(crate) async fn open_position(
+    State(state): State<PortfolioState>,
+    Json(request): Json<OpenPositionRequest>,
+) -> Result<Json<ApiResponse<Position>>, StatusCode> {
+    let start_time = Instant::now();
+    Ok(respond(state.open_position(request).await, start_time))
+}
+
+/// POST /api/portfolio/positions/{id}/close - Close at exit_price or the live mark
+#[utoipa::path(post, path = "/api/portfolio/positions/{id}/close", tag = "portfolio", params(("id" = u64, Path, description = "Position id")), request_body(content = Option<ClosePositionRequest>), responses((status = 200, body = ApiResponse<Position>)))]
+pub(crate) async fn close_position(
+    State(state): State<PortfolioState>,
+    Path(id): Path<u64>,
+    request: Option<Json<ClosePositionRequest>>,
+) -> Result<Json<ApiResponse<Position>>, StatusCode> {
+    let start_time = Instant::now();
+    let request = request.map(|Json(r)| r).unwrap_or_default();
+    Ok(respond(state.close_position(id, request).await, start_time))
+}
+
+/// DELETE /api/portfolio/positions/{id} - Remove a position from the book
+#[utoipa::path(delete, path = "/api/portfolio/positions/{id}", tag = "portfolio", params(("id" = u64, Path, description = "Position id")), responses((status = 200, body = ApiResponse<Position>)))]
+pub(crate) async fn delete_position(
+    State(state): State<PortfolioState>,
+    Path(id): Path<u64>,
+) -> Result<Json<ApiResponse<Position>>, StatusCode> {
+    let start_time = Instant::now();
+    Ok(respond(state.update(move |store| store.remove(id)).await, start_time))
+}
+
+// -----------------------------------------------
+// ROUTES
+// -----------------------------------------------
+
+/// Portfolio routes, mounted by both the NSE and MCX servers
+pub fn get_portfolio_routes(contracts: Arc<RwLock<ContractRegistry>>, market: Arc<MarketData>) -> Router {
+    Router::new()
+        .route("/api/portfolio/summary", get(get_summary))
+        .route("/api/portfolio/alerts", get(get_risk_alerts))
+        .route("/api/portfolio/positions", get(list_positions).post(open_position))
+        .route("/api/portfolio/positions/{id}/close", post(close_position))
+        .route("/api/portfolio/positions/{id}", axum::routing::delete(delete_position))
+        .with_state(PortfolioState::new(contracts, market))
+}
+
+/// Endpoint list for the server startup banner
+pub fn print_portfolio_endpoints() {
+    println!("   GET  /api/portfolio/summary");
+    println!("   GET  /api/portfolio/alerts");
+    println!("   GET  /api/portfolio/positions");
+    println!("   POST /api/portfolio/positions");
+    println!("   POST /api/portfolio/positions/{{id}}/close");
+    println!("   DEL  /api/portfolio/positions/{{id}}");
+}
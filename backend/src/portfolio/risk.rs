@@ -0,0 +1,240 @@
+use super::market::MarketData;
+use super::models::{Instrument, PortfolioStore, Position};
+use super::valuation::{summarize, PortfolioSummary, PositionValuation};
+use crate::analytics::{OptionKind, Side};
+use crate::contracts::Exchange;
+use crate::mcx::rules::{self as mcx_rules, McxAlert, McxAlertValues, McxRulesOutput};
+use crate::nse::rules::{self as nse_rules, Alert, AlertValues, RulesOutput};
+use crate::settings::{self, RiskSettings};
+use anyhow::Result;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Defaults for the [risk] settings
+pub const DEFAULT_STRIKE_PROXIMITY_PCT: f64 = 2.0;
+pub const DEFAULT_DELTA_DRIFT: f64 = 0.20;
+pub const DEFAULT_THETA_TARGET_PCT: f64 = 50.0;
+
+/// Thresholds for the position risk rules
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskConfig {
+    pub strike_proximity_pct: f64,      // Spot within this % of a short strike
+    pub delta_drift: f64,               // Per-unit delta change since entry
+    pub theta_target_pct: f64,          // Share of short premium decayed
+    pub stop_loss_pct: Option<f64>,     // Loss as % of entry value (if no per-position stop)
+    pub take_profit_pct: Option<f64>,   // Gain as % of entry value (if no per-position target)
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            strike_proximity_pct: DEFAULT_STRIKE_PROXIMITY_PCT,
+            delta_drift: DEFAULT_DELTA_DRIFT,
+            theta_target_pct: DEFAULT_THETA_TARGET_PCT,
+            stop_loss_pct: None,
+            take_profit_pct: None,
+        }
+    }
+}
+
+impl RiskConfig {
+    /// Thresholds from the validated [risk] settings (RISK_* env vars override them)
+    pub fn from_settings(settings: &RiskSettings) -> Self {
+        Self {
+            strike_proximity_pct: settings.strike_proximity_pct,
+            delta_drift: settings.delta_drift,
+            theta_target_pct: settings.theta_target_pct,
+            stop_loss_pct: Some(settings.stop_loss_pct).filter(|pct| *pct > 0.0),
+            take_profit_pct: Some(settings.take_profit_pct).filter(|pct| *pct > 0.0),
+        }
+    }
+}
+
+/// Evaluate the risk rules for one marked position
+pub fn check_position_risk(valuation: &PositionValuation, config: &RiskConfig) -> Vec<Alert> {
+    let mut alerts = Vec::new();
+    let position = &valuation.position;
+    let Some(mark) = valuation.mark_price else {
+        return alerts;
+    };
+
+    // Unknown expiry: the expiry rules are skipped and the alert shows 0 days
+    let days_to_expiry = valuation.days_to_expiry;
+    let is_short = position.side == Side::Sell;
+    let option = match (position.instrument, position.option_type, position.strike) {
+        (Instrument::Option, Some(kind), Some(strike)) => Some((kind, strike)),
+        _ => None,
+    };
+    let intrinsic = match (option, valuation.underlying_value) {
+        (Some((kind, strike)), Some(spot)) => Some(kind.intrinsic(spot, strike)),
+        _ => None,
+    };
+
+    let alert = |alert_type: &str, description: String| Alert {
+        symbol: position.symbol.clone(),
+        strike_price: position.strike.unwrap_or(mark),
+        expiry_date: position.expiry.clone(),
+        option_type: position.option_type.map(|k| k.code()).unwrap_or("FUT").to_string(),
+        alert_type: alert_type.to_string(),
+        description,
+        spread: 0.0,
+        values: AlertValues {
+            pchange_in_oi: None,
+            last_price: Some(mark),
+            open_interest: None,
+            the_money: intrinsic.map(|i| if i > 0.0 { "ITM" } else { "OTM" }.to_string()),
+            time_val: intrinsic.map(|i| mark - i).unwrap_or(0.0),
+            days_to_expiry: days_to_expiry.unwrap_or_default(),
+            buildup: Default::default(),
+        },
+    };
+
+    // Rule 1: Spot approaching (or through) a short strike
+    if let (true, Some((kind, strike)), Some(spot)) = (is_short, option, valuation.underlying_value) {
+        let cushion_pct = match kind {
+            OptionKind::Call => (strike - spot) / spot * 100.0,
+            OptionKind::Put => (spot - strike) / spot * 100.0,
+        };
+        if cushion_pct <= config.strike_proximity_pct {
+            alerts.push(alert(
+                "SHORT_STRIKE_PROXIMITY",
+                format!("Spot {:.2} is {:.2}% from short {} strike {}", spot, cushion_pct, kind.code(), strike),
+            ));
+        }
+    }
+
+    // Rule 2: Delta drifted from entry
+    if let (Some(entry_delta), Some(current_delta)) = (position.entry_delta, valuation.unit_delta()) {
+        let drift = current_delta - entry_delta;
+        if drift.abs() >= config.delta_drift {
+            alerts.push(alert(
+                "DELTA_DRIFT",
+                format!("Delta moved {:+.2} since entry ({:.2} -> {:.2})", drift, entry_delta, current_delta),
+            ));
+        }
+    }
+
+    // Rule 3: Short premium decayed to target
+    if is_short && option.is_some() && position.entry_price > 0.0 {
+        let decayed_pct = (position.entry_price - mark) / position.entry_price * 100.0;
+        if decayed_pct >= config.theta_target_pct {
+            alerts.push(alert(
+                "THETA_TARGET",
+                format!("{:.1}% of short premium captured ({:.2} -> {:.2})", decayed_pct, position.entry_price, mark),
+            ));
+        }
+    }
+
+    // Rule 4: P&L stop / target (per position, else % of entry value)
+    if let Some(pnl) = valuation.unrealized_pnl {
+        let entry_value = position.entry_price * position.quantity();
+        let stop = position.stop_loss.or(config.stop_loss_pct.map(|pct| -entry_value * pct / 100.0));
+        let target = position.take_profit.or(config.take_profit_pct.map(|pct| entry_value * pct / 100.0));
+
+        if let Some(stop) = stop.filter(|stop| pnl <= *stop) {
+            alerts.push(alert("PNL_STOP", format!("P&L {:.2} hit stop {:.2}", pnl, stop)));
+        }
+        if let Some(target) = target.filter(|target| pnl >= *target) {
+            alerts.push(alert("PNL_TARGET", format!("P&L {:.2} hit target {:.2}", pnl, target)));
+        }
+    }
+
+    // Rule 5: Short leg in the money on expiry day
+    if is_short && days_to_expiry == Some(0) && intrinsic.is_some_and(|i| i > 0.0) {
+        alerts.push(alert(
+            "EXPIRY_ITM_SHORT",
+            format!("Short leg is ITM by {:.2} on expiry day", intrinsic.unwrap_or(0.0)),
+        ));
+    }
+
+    alerts
+}
+
+/// Position risk alerts in each exchange's rules output format, one output per symbol
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct RiskAlerts {
+    pub nse: Vec<RulesOutput>,
+    pub mcx: Vec<McxRulesOutput>,
+}
+
+impl RiskAlerts {
+    pub fn is_empty(&self) -> bool {
+        self.nse.is_empty() && self.mcx.is_empty()
+    }
+}
+
+/// Risk alerts for every open position
+pub fn run_risk_rules(summary: &PortfolioSummary, config: &RiskConfig, timestamp: &str) -> RiskAlerts {
+    let mut nse_alerts = Vec::new();
+    let mut mcx_alerts = Vec::new();
+
+    for valuation in &summary.positions {
+        let underlying_value = valuation.underlying_value.unwrap_or(0.0);
+        for alert in check_position_risk(valuation, config) {
+            match valuation.position.exchange {
+                Exchange::Nse => nse_alerts.push((timestamp.to_string(), underlying_value, alert)),
+                Exchange::Mcx => mcx_alerts.push((timestamp.to_string(), underlying_value, to_mcx_alert(alert))),
+            }
+        }
+    }
+
+    let mut alerts = RiskAlerts::default();
+    nse_rules::merge_symbol_alerts(&mut alerts.nse, nse_alerts);
+    mcx_rules::merge_mcx_symbol_alerts(&mut alerts.mcx, mcx_alerts);
+    alerts.nse.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+    alerts.mcx.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+    alerts
+}
+
+fn to_mcx_alert(alert: Alert) -> McxAlert {
+    McxAlert {
+        symbol: alert.symbol,
+        strike_price: alert.strike_price,
+        expiry_date: alert.expiry_date,
+        option_type: alert.option_type,
+        alert_type: alert.alert_type,
+        description: alert.description,
+        spread: alert.spread,
+        values: McxAlertValues {
+            pchange_in_oi: alert.values.pchange_in_oi,
+            last_price: alert.values.last_price,
+            open_interest: alert.values.open_interest,
+            the_money: alert.values.the_money,
+            time_val: alert.values.time_val,
+            days_to_expiry: alert.values.days_to_expiry,
+            buildup: alert.values.buildup,
+        },
+    }
+}
+
+/// Mark the stored book's open positions on `exchange` and run the risk rules (for batch runs)
+pub async fn book_risk_alerts(market: &MarketData, exchange: Exchange) -> Result<RiskAlerts> {
+    let store = PortfolioStore::load_file(&PortfolioStore::path())?;
+    let positions: Vec<Position> = store.positions.into_iter().filter(|p| p.is_open() && p.exchange == exchange).collect();
+    if positions.is_empty() {
+        return Ok(RiskAlerts::default());
+    }
+
+    let (marks, _) = market.mark_positions(&positions.iter().collect::<Vec<_>>()).await;
+    let timestamp = Local::now().format("%d-%b-%Y %H:%M:%S").to_string();
+    Ok(run_risk_rules(&summarize(&positions, &marks), &RiskConfig::from_settings(&settings::current().risk), &timestamp))
+}
+
+/// Add position risk alerts to a batch's NSE rules outputs (joining the symbol's entry if present)
+pub fn merge_nse_risk_alerts(outputs: &mut Vec<RulesOutput>, risk: Vec<RulesOutput>) {
+    let entries = risk.into_iter().flat_map(|output| {
+        let (timestamp, underlying_value) = (output.timestamp, output.underlying_value);
+        output.alerts.into_iter().map(move |alert| (timestamp.clone(), underlying_value, alert))
+    });
+    nse_rules::merge_symbol_alerts(outputs, entries.collect());
+}
+
+/// Add position risk alerts to a batch's MCX rules outputs (joining the symbol's entry if present)
+pub fn merge_mcx_risk_alerts(outputs: &mut Vec<McxRulesOutput>, risk: Vec<McxRulesOutput>) {
+    let entries = risk.into_iter().flat_map(|output| {
+        let (timestamp, underlying_value) = (output.timestamp, output.underlying_value);
+        output.alerts.into_iter().map(move |alert| (timestamp.clone(), underlying_value, alert))
+    });
+    mcx_rules::merge_mcx_symbol_alerts(outputs, entries.collect());
+}
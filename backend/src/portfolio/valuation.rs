@@ -0,0 +1,165 @@
+use super::models::{Instrument, Position};
+use crate::analytics::{years_to_expiry, Greeks, PricingModel, Side};
+use crate::contracts::Exchange;
+use crate::settings;
+use super::risk::RiskAlerts;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+/// Live price for one position
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Mark {
+    pub price: f64,
+    pub underlying_value: Option<f64>,
+    pub days_to_expiry: Option<i32>,
+}
+
+/// One open position marked to market
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PositionValuation {
+    pub position: Position,
+    pub mark_price: Option<f64>,  // None when no live price was available
+    pub underlying_value: Option<f64>,
+    pub days_to_expiry: Option<i32>,
+    pub unrealized_pnl: Option<f64>,
+    pub notional: Option<f64>,    // Underlying value x quantity
+    pub margin: Option<f64>,
+    pub greeks: Option<Greeks>,   // Position Greeks (signed, scaled by quantity)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PortfolioSummary {
+    pub open_positions: usize,
+    pub closed_positions: usize,
+    pub unpriced_positions: usize,
+    pub realized_pnl: f64,
+    pub unrealized_pnl: f64,
+    pub total_pnl: f64,
+    pub net_greeks: Greeks,
+    pub gross_notional: f64,
+    pub margin_exposure: f64,
+    pub positions: Vec<PositionValuation>,
+    pub closed: Vec<Position>,
+    #[serde(default)]
+    pub mark_errors: Vec<String>,  // Chains/quotes that could not be fetched
+    #[serde(default)]
+    pub risk_alerts: RiskAlerts,
+}
+
+impl PositionValuation {
+    /// Delta of one unit of the contract (the position delta without side or size)
+    pub fn unit_delta(&self) -> Option<f64> {
+        let scale = self.position.side.sign() * self.position.quantity();
+        self.greeks.filter(|_| scale != 0.0).map(|g| g.delta / scale)
+    }
+}
+
+fn margin_rate(exchange: Exchange) -> f64 {
+    match exchange {
+        Exchange::Nse => settings::current().nse.margin_rate,
+        Exchange::Mcx => settings::current().mcx.margin_rate,
+    }
+}
+
+fn pricing_model(exchange: Exchange) -> PricingModel {
+    match exchange {
+        Exchange::Nse => PricingModel::nse(),
+        Exchange::Mcx => PricingModel::mcx(),
+    }
+}
+
+/// Mark one position. Long options tie up their premium; futures and short
+/// options tie up a fraction of notional (plus the premium for short options).
+pub fn value_position(position: &Position, mark: Option<&Mark>) -> PositionValuation {
+    let Some(mark) = mark.filter(|m| m.price > 0.0) else {
+        return PositionValuation {
+            position: position.clone(),
+            mark_price: None,
+            underlying_value: None,
+            days_to_expiry: None,
+            unrealized_pnl: None,
+            notional: None,
+            margin: None,
+            greeks: None,
+        };
+    };
+
+    let quantity = position.quantity();
+    let signed_quantity = position.side.sign() * quantity;
+    let underlying = match position.instrument {
+        Instrument::Future => mark.underlying_value.or(Some(mark.price)),
+        Instrument::Option => mark.underlying_value,
+    };
+    let notional = underlying.map(|u| u * quantity);
+    let rate = margin_rate(position.exchange);
+
+    let (greeks, margin) = match position.instrument {
+        Instrument::Future => (
+            Some(Greeks { delta: signed_quantity, ..Default::default() }),
+            notional.map(|n| n * rate),
+        ),
+        Instrument::Option => {
+            let greeks = match (position.option_type, position.strike, underlying) {
+                (Some(kind), Some(strike), Some(spot)) => {
+                    let model = pricing_model(position.exchange);
+                    let t = years_to_expiry(mark.days_to_expiry.unwrap_or(0));
+                    model
+                        .implied_volatility(kind, mark.price, spot, strike, t)
+                        .map(|vol| model.greeks(kind, spot, strike, t, vol) * signed_quantity)
+                }
+                _ => None,
+            };
+            let premium = mark.price * quantity;
+            let margin = match position.side {
+                Side::Buy => Some(premium),
+                Side::Sell => notional.map(|n| n * rate + premium),
+            };
+            (greeks, margin)
+        }
+    };
+
+    PositionValuation {
+        position: position.clone(),
+        mark_price: Some(mark.price),
+        underlying_value: underlying,
+        days_to_expiry: mark.days_to_expiry,
+        unrealized_pnl: Some(position.pnl_at(mark.price)),
+        notional,
+        margin,
+        greeks,
+    }
+}
+
+/// Value the whole book. `marks` is keyed by position id.
+pub fn summarize(positions: &[Position], marks: &HashMap<u64, Mark>) -> PortfolioSummary {
+    let (open, closed): (Vec<&Position>, Vec<&Position>) = positions.iter().partition(|p| p.is_open());
+
+    let valuations: Vec<PositionValuation> = open
+        .iter()
+        .map(|p| value_position(p, marks.get(&p.id)))
+        .collect();
+
+    let realized_pnl: f64 = closed.iter().filter_map(|p| p.realized_pnl).sum();
+    let unrealized_pnl: f64 = valuations.iter().filter_map(|v| v.unrealized_pnl).sum();
+    let net_greeks = valuations
+        .iter()
+        .filter_map(|v| v.greeks)
+        .fold(Greeks::default(), |acc, g| acc + g);
+
+    PortfolioSummary {
+        open_positions: valuations.len(),
+        closed_positions: closed.len(),
+        unpriced_positions: valuations.iter().filter(|v| v.mark_price.is_none()).count(),
+        realized_pnl,
+        unrealized_pnl,
+        total_pnl: realized_pnl + unrealized_pnl,
+        net_greeks,
+        gross_notional: valuations.iter().filter_map(|v| v.notional).sum(),
+        margin_exposure: valuations.iter().filter_map(|v| v.margin).sum(),
+        positions: valuations,
+        closed: closed.into_iter().cloned().collect(),
+        mark_errors: Vec::new(),
+        risk_alerts: RiskAlerts::default(),
+    }
+}
@@ -0,0 +1,367 @@
+use crate::contracts::Exchange;
+use crate::utility;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::io::Write as _;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+pub const NSE_BATCH_REPORT_FILE: &str = "batch_report.json";
+pub const NSE_BATCH_SUMMARY_FILE: &str = "batch_summary.md";
+pub const MCX_BATCH_REPORT_FILE: &str = "mcx_batch_report.json";
+pub const MCX_BATCH_SUMMARY_FILE: &str = "mcx_batch_summary.md";
+
+// Failures listed individually in the Markdown summary (all are kept in the JSON)
+const MARKDOWN_MAX_FAILURES: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolStatus {
+    Success,
+    Failed,
+    TimedOut,
+}
+
+/// Fetch outcome and alert count for one symbol
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolReport {
+    pub symbol: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiry: Option<String>,
+    pub status: SymbolStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub alerts: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepTiming {
+    pub name: String,
+    pub duration_ms: u64,
+}
+
+/// Alerts of one type on one side (CE/PE)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertGroup {
+    pub alert_type: String,
+    pub side: String,
+    pub count: usize,
+    pub symbols: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AlertSummary {
+    pub total: usize,
+    pub securities_with_alerts: usize,
+    pub by_type: BTreeMap<String, usize>,
+    pub by_side: BTreeMap<String, usize>,
+    pub groups: Vec<AlertGroup>,  // Largest first
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReportTotals {
+    pub symbols: usize,
+    pub successful: usize,
+    pub failed: usize,
+    pub timed_out: usize,
+}
+
+/// Machine-readable summary of one batch run (batch_report.json)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchReport {
+    pub run_id: String,
+    pub exchange: Exchange,
+    pub started_at: String,
+    pub finished_at: String,
+    pub duration_ms: u64,
+    pub totals: ReportTotals,
+    pub steps: Vec<StepTiming>,
+    pub alerts: AlertSummary,
+    pub symbols: Vec<SymbolReport>,
+}
+
+/// Collects step timings, symbol outcomes and alerts while a batch runs
+#[derive(Debug)]
+pub struct BatchReportBuilder {
+    exchange: Exchange,
+    started_at: DateTime<Local>,
+    started: Instant,
+    current_step: Option<(String, Instant)>,
+    steps: Vec<StepTiming>,
+    symbols: Vec<SymbolReport>,
+    alerts: Vec<(String, String, String)>,  // (symbol, alert_type, side)
+}
+
+impl BatchReportBuilder {
+    pub fn new(exchange: Exchange) -> Self {
+        Self {
+            exchange,
+            started_at: Local::now(),
+            started: Instant::now(),
+            current_step: None,
+            steps: Vec::new(),
+            symbols: Vec::new(),
+            alerts: Vec::new(),
+        }
+    }
+
+    /// Start timing a step; the previous step (if any) ends here
+    pub fn step(&mut self, name: &str) {
+        self.end_step();
+        self.current_step = Some((name.to_string(), Instant::now()));
+    }
+
+    /// Record a step timed elsewhere
+    pub fn record_step(&mut self, name: &str, duration: Duration) {
+        self.steps.push(StepTiming {
+            name: name.to_string(),
+            duration_ms: duration.as_millis() as u64,
+        });
+    }
+
+    fn end_step(&mut self) {
+        if let Some((name, started)) = self.current_step.take() {
+            self.record_step(&name, started.elapsed());
+        }
+    }
+
+    pub fn symbol_success(&mut self, symbol: &str, expiry: Option<&str>) {
+        self.push_symbol(symbol, expiry, SymbolStatus::Success, None);
+    }
+
+    /// A failed fetch; errors mentioning "Timeout" count as timed out
+    pub fn symbol_failure(&mut self, symbol: &str, expiry: Option<&str>, error: &str) {
+        let status = if error.contains("Timeout") { SymbolStatus::TimedOut } else { SymbolStatus::Failed };
+        self.push_symbol(symbol, expiry, status, Some(error.to_string()));
+    }
+
+    fn push_symbol(&mut self, symbol: &str, expiry: Option<&str>, status: SymbolStatus, error: Option<String>) {
+        self.symbols.push(SymbolReport {
+            symbol: symbol.to_string(),
+            expiry: expiry.map(str::to_string),
+            status,
+            error,
+            alerts: 0,
+        });
+    }
+
+    /// Add one fired alert
+    pub fn alert(&mut self, symbol: &str, alert_type: &str, side: &str) {
+        self.alerts.push((symbol.to_string(), alert_type.to_string(), side.to_string()));
+    }
+
+    pub fn finish(mut self) -> BatchReport {
+        self.end_step();
+        let finished_at = Local::now();
+
+        let mut alert_counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for (symbol, _, _) in &self.alerts {
+            *alert_counts.entry(symbol).or_insert(0) += 1;
+        }
+        for report in self.symbols.iter_mut() {
+            report.alerts = alert_counts.get(report.symbol.as_str()).copied().unwrap_or(0);
+        }
+
+        let count = |status| self.symbols.iter().filter(|s| s.status == status).count();
+        let totals = ReportTotals {
+            symbols: self.symbols.len(),
+            successful: count(SymbolStatus::Success),
+            failed: count(SymbolStatus::Failed),
+            timed_out: count(SymbolStatus::TimedOut),
+        };
+
+        BatchReport {
+            run_id: format!(
+                "{}-{}",
+                exchange_label(self.exchange).to_lowercase(),
+                self.started_at.format("%Y%m%dT%H%M%S")
+            ),
+            exchange: self.exchange,
+            started_at: self.started_at.to_rfc3339(),
+            finished_at: finished_at.to_rfc3339(),
+            duration_ms: self.started.elapsed().as_millis() as u64,
+            totals,
+            steps: self.steps,
+            alerts: summarize_alerts(&self.alerts, alert_counts.len()),
+            symbols: self.symbols,
+        }
+    }
+}
+
+fn summarize_alerts(alerts: &[(String, String, String)], securities_with_alerts: usize) -> AlertSummary {
+    let mut by_type = BTreeMap::new();
+    let mut by_side = BTreeMap::new();
+    let mut grouped: BTreeMap<(&str, &str), (usize, BTreeSet<&str>)> = BTreeMap::new();
+
+    for (symbol, alert_type, side) in alerts {
+        *by_type.entry(alert_type.clone()).or_insert(0) += 1;
+        *by_side.entry(side.clone()).or_insert(0) += 1;
+        let group = grouped.entry((alert_type, side)).or_default();
+        group.0 += 1;
+        group.1.insert(symbol);
+    }
+
+    let mut groups: Vec<AlertGroup> = grouped
+        .into_iter()
+        .map(|((alert_type, side), (count, symbols))| AlertGroup {
+            alert_type: alert_type.to_string(),
+            side: side.to_string(),
+            count,
+            symbols: symbols.into_iter().map(str::to_string).collect(),
+        })
+        .collect();
+    groups.sort_by_key(|g| std::cmp::Reverse(g.count));
+
+    AlertSummary {
+        total: alerts.len(),
+        securities_with_alerts,
+        by_type,
+        by_side,
+        groups,
+    }
+}
+
+fn exchange_label(exchange: Exchange) -> &'static str {
+    match exchange {
+        Exchange::Nse => "NSE",
+        Exchange::Mcx => "MCX",
+    }
+}
+
+impl BatchReport {
+    /// Default report and summary file names for an exchange
+    pub fn default_paths(exchange: Exchange) -> (&'static str, &'static str) {
+        match exchange {
+            Exchange::Nse => (NSE_BATCH_REPORT_FILE, NSE_BATCH_SUMMARY_FILE),
+            Exchange::Mcx => (MCX_BATCH_REPORT_FILE, MCX_BATCH_SUMMARY_FILE),
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&text).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Write the JSON report and the Markdown summary
+    pub fn save(&self, report_path: impl AsRef<Path>, summary_path: impl AsRef<Path>) -> Result<()> {
+        utility::write_atomic(report_path.as_ref(), serde_json::to_string_pretty(self)?)?;
+        utility::write_atomic(summary_path.as_ref(), self.to_markdown())?;
+        Ok(())
+    }
+
+    /// Save to the exchange's default files and append to $GITHUB_STEP_SUMMARY
+    pub fn publish(&self) -> Result<()> {
+        let (report_file, summary_file) = Self::default_paths(self.exchange);
+        self.save(report_file, summary_file)?;
+        info!("✓ Saved batch report to {} and {}", report_file, summary_file);
+        if self.append_step_summary()? {
+            info!("✓ Appended batch summary to GITHUB_STEP_SUMMARY");
+        }
+        Ok(())
+    }
+
+    /// Append the Markdown summary to $GITHUB_STEP_SUMMARY when running in GitHub Actions
+    pub fn append_step_summary(&self) -> Result<bool> {
+        let Ok(path) = std::env::var("GITHUB_STEP_SUMMARY") else { return Ok(false) };
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open {}", path))?;
+        writeln!(file, "{}", self.to_markdown())?;
+        Ok(true)
+    }
+
+    /// Markdown summary for $GITHUB_STEP_SUMMARY or email
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::new();
+        let totals = &self.totals;
+
+        let _ = writeln!(md, "## {} batch report", exchange_label(self.exchange));
+        let _ = writeln!(md);
+        let _ = writeln!(md, "| Run | Started | Duration | Symbols | ✓ Successful | ✗ Failed | Timed out | Alerts |");
+        let _ = writeln!(md, "|---|---|---|---:|---:|---:|---:|---:|");
+        let _ = writeln!(
+            md,
+            "| `{}` | {} | {} | {} | {} | {} | {} | {} |",
+            self.run_id,
+            self.started_at,
+            format_duration_ms(self.duration_ms),
+            totals.symbols,
+            totals.successful,
+            totals.failed,
+            totals.timed_out,
+            self.alerts.total,
+        );
+
+        if !self.steps.is_empty() {
+            let _ = writeln!(md);
+            let _ = writeln!(md, "### Steps");
+            let _ = writeln!(md);
+            let _ = writeln!(md, "| Step | Duration |");
+            let _ = writeln!(md, "|---|---:|");
+            for step in &self.steps {
+                let _ = writeln!(md, "| {} | {} |", step.name, format_duration_ms(step.duration_ms));
+            }
+        }
+
+        let _ = writeln!(md);
+        let _ = writeln!(
+            md,
+            "### Alerts ({} across {} securities)",
+            self.alerts.total, self.alerts.securities_with_alerts
+        );
+        let _ = writeln!(md);
+        if self.alerts.groups.is_empty() {
+            let _ = writeln!(md, "No alerts fired.");
+        } else {
+            let _ = writeln!(md, "| Alert type | Side | Count | Securities |");
+            let _ = writeln!(md, "|---|---|---:|---|");
+            for group in &self.alerts.groups {
+                let _ = writeln!(
+                    md,
+                    "| {} | {} | {} | {} |",
+                    group.alert_type,
+                    group.side,
+                    group.count,
+                    group.symbols.join(", ")
+                );
+            }
+        }
+
+        let failures: Vec<&SymbolReport> = self.symbols.iter().filter(|s| s.status != SymbolStatus::Success).collect();
+        if !failures.is_empty() {
+            let _ = writeln!(md);
+            let _ = writeln!(md, "### Failures ({})", failures.len());
+            let _ = writeln!(md);
+            let _ = writeln!(md, "| Symbol | Status | Error |");
+            let _ = writeln!(md, "|---|---|---|");
+            for failure in failures.iter().take(MARKDOWN_MAX_FAILURES) {
+                let status = match failure.status {
+                    SymbolStatus::TimedOut => "timed out",
+                    _ => "failed",
+                };
+                let error: String = failure.error.as_deref().unwrap_or("").chars().take(120).collect();
+                let _ = writeln!(md, "| {} | {} | {} |", failure.symbol, status, error.replace('|', "\\|"));
+            }
+            if failures.len() > MARKDOWN_MAX_FAILURES {
+                let _ = writeln!(md, "\n…and {} more", failures.len() - MARKDOWN_MAX_FAILURES);
+            }
+        }
+
+        md
+    }
+}
+
+fn format_duration_ms(ms: u64) -> String {
+    if ms < 1000 {
+        format!("{}ms", ms)
+    } else {
+        format!("{:.1}s", ms as f64 / 1000.0)
+    }
+}
@@ -0,0 +1,7 @@
+pub mod batch_report;
+
+pub use batch_report::{
+    AlertGroup, AlertSummary, BatchReport, BatchReportBuilder, ReportTotals, StepTiming,
+    SymbolReport, SymbolStatus, MCX_BATCH_REPORT_FILE, MCX_BATCH_SUMMARY_FILE,
+    NSE_BATCH_REPORT_FILE, NSE_BATCH_SUMMARY_FILE,
+};
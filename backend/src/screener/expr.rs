@@ -0,0 +1,342 @@
+use super::metrics::{SymbolMetrics, METRIC_FIELDS};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Alternative names accepted in filters and sorts
+const FIELD_ALIASES: [(&str, &str); 6] = [
+    ("iv", "atm_iv"),
+    ("oi", "total_oi"),
+    ("spot", "underlying_value"),
+    ("dte", "days_to_expiry"),
+    ("ivr", "iv_rank"),
+    ("ivp", "iv_percentile"),
+];
+
+// Filters arrive in query strings; bound them so parsing cannot exhaust the stack
+const MAX_FILTER_LENGTH: usize = 1_024;
+const MAX_FILTER_DEPTH: usize = 32;  // Nested parentheses and `not`s
+
+/// Canonical metric name for a field or alias (case-insensitive)
+pub fn resolve_field(name: &str) -> Result<&'static str> {
+    let lower = name.trim().to_lowercase();
+    if let Some((_, target)) = FIELD_ALIASES.iter().find(|(alias, _)| *alias == lower) {
+        return Ok(target);
+    }
+    METRIC_FIELDS
+        .iter()
+        .find(|field| **field == lower)
+        .copied()
+        .ok_or_else(|| anyhow!("Unknown field '{}' (expected one of: {})", name.trim(), METRIC_FIELDS.join(", ")))
+}
+
+// -----------------------------------------------
+// Filter expressions
+// -----------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompareOp {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+    Ne,
+}
+
+impl CompareOp {
+    fn apply(self, left: f64, right: f64) -> bool {
+        match self {
+            CompareOp::Gt => left > right,
+            CompareOp::Ge => left >= right,
+            CompareOp::Lt => left < right,
+            CompareOp::Le => left <= right,
+            CompareOp::Eq => (left - right).abs() < 1e-9,
+            CompareOp::Ne => (left - right).abs() >= 1e-9,
+        }
+    }
+}
+
+/// Parsed filter, e.g. `pcr > 1.2 and (spread >= 50 or alerts > 0)`
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterExpr {
+    Compare { field: &'static str, op: CompareOp, value: f64 },
+    And(Box<FilterExpr>, Box<FilterExpr>),
+    Or(Box<FilterExpr>, Box<FilterExpr>),
+    Not(Box<FilterExpr>),
+}
+
+impl FilterExpr {
+    /// Parse a filter; `and`/`&&`, `or`/`||`, `not`/`!`, parentheses and
+    /// `> >= < <= == = !=` comparisons against numbers (a trailing `%` is ignored)
+    pub fn parse(raw: &str) -> Result<Self> {
+        if raw.len() > MAX_FILTER_LENGTH {
+            return Err(anyhow!("Filter is longer than {} characters", MAX_FILTER_LENGTH));
+        }
+        let tokens = tokenize(raw)?;
+        if tokens.is_empty() {
+            return Err(anyhow!("Empty filter expression"));
+        }
+        let mut parser = Parser { tokens, pos: 0, depth: 0 };
+        let expr = parser.parse_or()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some(token) => Err(anyhow!("Unexpected '{}' in filter", token)),
+        }
+    }
+
+    /// A comparison on a metric the symbol does not have is false
+    pub fn matches(&self, metrics: &SymbolMetrics) -> bool {
+        match self {
+            FilterExpr::Compare { field, op, value } => {
+                metrics.field(field).is_some_and(|left| op.apply(left, *value))
+            }
+            FilterExpr::And(a, b) => a.matches(metrics) && b.matches(metrics),
+            FilterExpr::Or(a, b) => a.matches(metrics) || b.matches(metrics),
+            FilterExpr::Not(inner) => !inner.matches(metrics),
+        }
+    }
+
+    /// Metric names referenced anywhere in the expression
+    pub fn fields(&self) -> Vec<&'static str> {
+        match self {
+            FilterExpr::Compare { field, .. } => vec![field],
+            FilterExpr::And(a, b) | FilterExpr::Or(a, b) => {
+                let mut fields = a.fields();
+                fields.extend(b.fields());
+                fields
+            }
+            FilterExpr::Not(inner) => inner.fields(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(f64),
+    Op(CompareOp),
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Ident(name) => write!(f, "{}", name),
+            Token::Number(n) => write!(f, "{}", n),
+            Token::Op(op) => write!(f, "{:?}", op),
+            Token::And => write!(f, "and"),
+            Token::Or => write!(f, "or"),
+            Token::Not => write!(f, "not"),
+            Token::LParen => write!(f, "("),
+            Token::RParen => write!(f, ")"),
+        }
+    }
+}
+
+fn tokenize(raw: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = raw.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            ' ' | '\t' | '\n' => i += 1,
+            '(' => { tokens.push(Token::LParen); i += 1; }
+            ')' => { tokens.push(Token::RParen); i += 1; }
+            '>' | '<' | '=' | '!' => {
+                let (token, len) = match (c, next) {
+                    ('>', Some('=')) => (Token::Op(CompareOp::Ge), 2),
+                    ('>', _) => (Token::Op(CompareOp::Gt), 1),
+                    ('<', Some('=')) => (Token::Op(CompareOp::Le), 2),
+                    ('<', _) => (Token::Op(CompareOp::Lt), 1),
+                    ('=', Some('=')) => (Token::Op(CompareOp::Eq), 2),
+                    ('=', _) => (Token::Op(CompareOp::Eq), 1),
+                    ('!', Some('=')) => (Token::Op(CompareOp::Ne), 2),
+                    _ => (Token::Not, 1),
+                };
+                tokens.push(token);
+                i += len;
+            }
+            '&' | '|' => {
+                if next != Some(c) {
+                    return Err(anyhow!("Expected '{}{}' in filter", c, c));
+                }
+                tokens.push(if c == '&' { Token::And } else { Token::Or });
+                i += 2;
+            }
+            c if c.is_ascii_digit() || c == '.' || (c == '-' && next.is_some_and(|n| n.is_ascii_digit() || n == '.')) => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                let value = text.parse::<f64>().map_err(|_| anyhow!("Invalid number '{}' in filter", text))?;
+                if chars.get(i) == Some(&'%') {
+                    i += 1;
+                }
+                tokens.push(Token::Number(value));
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                tokens.push(match word.to_lowercase().as_str() {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    _ => Token::Ident(word),
+                });
+            }
+            other => return Err(anyhow!("Unexpected character '{}' in filter", other)),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent: or > and > not > comparison / parenthesised group
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    /// Parse a nested `not` or group, failing past `MAX_FILTER_DEPTH`
+    fn nested(&mut self, parse: fn(&mut Self) -> Result<FilterExpr>) -> Result<FilterExpr> {
+        if self.depth >= MAX_FILTER_DEPTH {
+            return Err(anyhow!("Filter nests deeper than {} levels", MAX_FILTER_DEPTH));
+        }
+        self.depth += 1;
+        let expr = parse(self);
+        self.depth -= 1;
+        expr
+    }
+
+    fn parse_or(&mut self) -> Result<FilterExpr> {
+        let mut left = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            left = FilterExpr::Or(Box::new(left), Box::new(self.parse_and()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<FilterExpr> {
+        let mut left = self.parse_not()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            left = FilterExpr::And(Box::new(left), Box::new(self.parse_not()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<FilterExpr> {
+        if self.peek() == Some(&Token::Not) {
+            self.pos += 1;
+            return Ok(FilterExpr::Not(Box::new(self.nested(Self::parse_not)?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<FilterExpr> {
+        match self.next() {
+            Some(Token::LParen) => {
+                let expr = self.nested(Self::parse_or)?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(expr),
+                    _ => Err(anyhow!("Missing ')' in filter")),
+                }
+            }
+            Some(Token::Ident(name)) => {
+                let field = resolve_field(&name)?;
+                let op = match self.next() {
+                    Some(Token::Op(op)) => op,
+                    _ => return Err(anyhow!("Expected a comparison after '{}'", name)),
+                };
+                match self.next() {
+                    Some(Token::Number(value)) => Ok(FilterExpr::Compare { field, op, value }),
+                    _ => Err(anyhow!("Expected a number after '{} {:?}'", name, op)),
+                }
+            }
+            Some(token) => Err(anyhow!("Unexpected '{}' in filter", token)),
+            None => Err(anyhow!("Filter ends unexpectedly")),
+        }
+    }
+}
+
+// -----------------------------------------------
+// Sorting
+// -----------------------------------------------
+
+/// One sort key; symbols missing the metric always sort last
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SortKey {
+    pub field: &'static str,
+    pub descending: bool,
+}
+
+impl SortKey {
+    /// Parse `-pcr,spread` or `pcr:desc,spread:asc`
+    pub fn parse_list(raw: &str) -> Result<Vec<Self>> {
+        raw.split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .map(|part| {
+                let (name, descending) = match part.split_once(':') {
+                    Some((name, dir)) => match dir.trim().to_lowercase().as_str() {
+                        "desc" => (name, true),
+                        "asc" => (name, false),
+                        other => return Err(anyhow!("Invalid sort direction '{}' (use asc or desc)", other)),
+                    },
+                    None => match part.strip_prefix('-') {
+                        Some(name) => (name, true),
+                        None => (part.strip_prefix('+').unwrap_or(part), false),
+                    },
+                };
+                Ok(SortKey { field: resolve_field(name)?, descending })
+            })
+            .collect()
+    }
+
+    fn compare(&self, a: &SymbolMetrics, b: &SymbolMetrics) -> Ordering {
+        match (a.field(self.field), b.field(self.field)) {
+            (Some(x), Some(y)) => {
+                let ordering = x.total_cmp(&y);
+                if self.descending { ordering.reverse() } else { ordering }
+            }
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+}
+
+/// Sort by each key in turn, then by symbol for a stable ranking
+pub fn sort_metrics(metrics: &mut [SymbolMetrics], keys: &[SortKey]) {
+    metrics.sort_by(|a, b| {
+        keys.iter()
+            .map(|key| key.compare(a, b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| a.symbol.cmp(&b.symbol))
+    });
+}
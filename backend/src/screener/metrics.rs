@@ -0,0 +1,226 @@
+use crate::analytics::IvRankStats;
+use crate::nse::processor::{self, ProcessedOptionData};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Metric names usable in screener filters and sorts
+pub const METRIC_FIELDS: [&str; 22] = [
+    "underlying_value",
+    "spread",
+    "days_to_expiry",
+    "ce_oi",
+    "pe_oi",
+    "total_oi",
+    "pcr",
+    "ce_oi_change",
+    "pe_oi_change",
+    "ce_oi_change_pct",
+    "pe_oi_change_pct",
+    "oi_change_pct",
+    "alerts",
+    "max_pain",
+    "max_pain_distance_pct",
+    "atm_iv",
+    "iv_rank",
+    "iv_percentile",
+    "futures_price",
+    "basis",
+    "basis_pct",
+    "expected_move_pct",
+];
+
+/// Metrics that need a futures quote (fetched only when a query uses them)
+pub const FUTURES_FIELDS: [&str; 3] = ["futures_price", "basis", "basis_pct"];
+
+/// Per-symbol batch metrics the screener filters and ranks on
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct SymbolMetrics {
+    pub symbol: String,
+    pub timestamp: String,
+    pub expiry: Option<String>,
+    pub underlying_value: f64,
+    pub spread: f64,
+    pub days_to_expiry: i32,
+    pub ce_oi: f64,
+    pub pe_oi: f64,
+    pub pcr: Option<f64>,                   // Put/call OI ratio
+    pub ce_oi_change: f64,
+    pub pe_oi_change: f64,
+    pub ce_oi_change_pct: Option<f64>,      // Change vs previous day's OI
+    pub pe_oi_change_pct: Option<f64>,
+    pub oi_change_pct: Option<f64>,
+    pub alerts: usize,
+    pub max_pain: Option<f64>,
+    pub max_pain_distance_pct: Option<f64>, // |spot - max pain| / spot
+    pub atm_iv: Option<f64>,
+    pub expected_move_pct: Option<f64>,
+    pub iv_rank: Option<f64>,               // 252-day lookback
+    pub iv_percentile: Option<f64>,
+    pub futures_price: Option<f64>,
+    pub basis: Option<f64>,                 // Futures - spot
+    pub basis_pct: Option<f64>,
+}
+
+impl SymbolMetrics {
+    /// Metrics from one processed option chain (alerts, IV rank and basis are filled in later)
+    pub fn from_chain(
+        symbol: &str,
+        timestamp: &str,
+        underlying_value: f64,
+        spread: f64,
+        ce_oi: f64,
+        pe_oi: f64,
+        data: &[ProcessedOptionData],
+    ) -> Self {
+        let first = data.first();
+        let oi_change = |side: fn(&ProcessedOptionData) -> Option<f64>| data.iter().filter_map(side).sum::<f64>();
+        let ce_oi_change = oi_change(|opt| opt.call.as_ref().and_then(|ce| ce.base.change_in_oi));
+        let pe_oi_change = oi_change(|opt| opt.put.as_ref().and_then(|pe| pe.base.change_in_oi));
+
+        let max_pain = max_pain(data);
+        let expected_move = processor::calculate_expected_move(data, underlying_value);
+
+        Self {
+            symbol: symbol.to_string(),
+            timestamp: timestamp.to_string(),
+            expiry: first.and_then(|opt| opt.expiry_date.clone()),
+            underlying_value,
+            spread,
+            days_to_expiry: first.map(|opt| opt.days_to_expiry).unwrap_or(0),
+            ce_oi,
+            pe_oi,
+            pcr: (ce_oi > 0.0).then(|| pe_oi / ce_oi),
+            ce_oi_change,
+            pe_oi_change,
+            ce_oi_change_pct: change_pct(ce_oi_change, ce_oi),
+            pe_oi_change_pct: change_pct(pe_oi_change, pe_oi),
+            oi_change_pct: change_pct(ce_oi_change + pe_oi_change, ce_oi + pe_oi),
+            alerts: 0,
+            max_pain,
+            max_pain_distance_pct: max_pain
+                .filter(|_| underlying_value > 0.0)
+                .map(|mp| (underlying_value - mp).abs() / underlying_value * 100.0),
+            atm_iv: expected_move.as_ref().and_then(|m| m.atm_iv),
+            expected_move_pct: expected_move.as_ref().map(|m| m.straddle_move_pct),
+            iv_rank: None,
+            iv_percentile: None,
+            futures_price: None,
+            basis: None,
+            basis_pct: None,
+        }
+    }
+
+    pub fn set_iv_rank(&mut self, stats: &IvRankStats) {
+        self.iv_rank = stats.iv_rank_252;
+        self.iv_percentile = stats.iv_percentile_252;
+    }
+
+    pub fn set_futures_price(&mut self, price: f64) {
+        self.futures_price = Some(price);
+        self.basis = Some(price - self.underlying_value);
+        self.basis_pct = (self.underlying_value > 0.0)
+            .then(|| (price - self.underlying_value) / self.underlying_value * 100.0);
+    }
+
+    /// Look up a metric by name (see `METRIC_FIELDS`); None when missing for this symbol
+    pub fn field(&self, name: &str) -> Option<f64> {
+        match name {
+            "underlying_value" => Some(self.underlying_value),
+            "spread" => Some(self.spread),
+            "days_to_expiry" => Some(self.days_to_expiry as f64),
+            "ce_oi" => Some(self.ce_oi),
+            "pe_oi" => Some(self.pe_oi),
+            "total_oi" => Some(self.ce_oi + self.pe_oi),
+            "pcr" => self.pcr,
+            "ce_oi_change" => Some(self.ce_oi_change),
+            "pe_oi_change" => Some(self.pe_oi_change),
+            "ce_oi_change_pct" => self.ce_oi_change_pct,
+            "pe_oi_change_pct" => self.pe_oi_change_pct,
+            "oi_change_pct" => self.oi_change_pct,
+            "alerts" => Some(self.alerts as f64),
+            "max_pain" => self.max_pain,
+            "max_pain_distance_pct" => self.max_pain_distance_pct,
+            "atm_iv" => self.atm_iv,
+            "iv_rank" => self.iv_rank,
+            "iv_percentile" => self.iv_percentile,
+            "futures_price" => self.futures_price,
+            "basis" => self.basis,
+            "basis_pct" => self.basis_pct,
+            "expected_move_pct" => self.expected_move_pct,
+            _ => None,
+        }
+    }
+}
+
+/// Current OI includes today's change, so the base is `current - change`
+fn change_pct(change: f64, current: f64) -> Option<f64> {
+    let previous = current - change;
+    (previous > 0.0).then(|| change / previous * 100.0)
+}
+
+/// Strike at which option writers pay out the least at expiry
+pub fn max_pain(data: &[ProcessedOptionData]) -> Option<f64> {
+    let strikes: Vec<(f64, f64, f64)> = data
+        .iter()
+        .filter_map(|opt| {
+            let strike = opt.strike_price?;
+            let ce_oi = opt.call.as_ref().and_then(|ce| ce.base.open_interest).unwrap_or(0.0);
+            let pe_oi = opt.put.as_ref().and_then(|pe| pe.base.open_interest).unwrap_or(0.0);
+            Some((strike, ce_oi, pe_oi))
+        })
+        .collect();
+
+    if strikes.iter().all(|(_, ce_oi, pe_oi)| *ce_oi + *pe_oi <= 0.0) {
+        return None;
+    }
+
+    let payout = |settle: f64| -> f64 {
+        strikes
+            .iter()
+            .map(|(strike, ce_oi, pe_oi)| {
+                ce_oi * (settle - strike).max(0.0) + pe_oi * (strike - settle).max(0.0)
+            })
+            .sum()
+    };
+
+    strikes
+        .iter()
+        .map(|(strike, _, _)| (*strike, payout(*strike)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(strike, _)| strike)
+}
+
+/// CSV table of metrics: symbol, timestamp, expiry, then every `METRIC_FIELDS` column
+pub fn metrics_to_csv(metrics: &[SymbolMetrics]) -> String {
+    let mut csv = String::from("symbol,timestamp,expiry");
+    for field in METRIC_FIELDS {
+        csv.push(',');
+        csv.push_str(field);
+    }
+    csv.push('\n');
+
+    for m in metrics {
+        csv.push_str(&csv_field(&m.symbol));
+        csv.push(',');
+        csv.push_str(&csv_field(&m.timestamp));
+        csv.push(',');
+        csv.push_str(&csv_field(m.expiry.as_deref().unwrap_or("")));
+        for field in METRIC_FIELDS {
+            csv.push(',');
+            if let Some(value) = m.field(field) {
+                csv.push_str(&value.to_string());
+            }
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Quote a CSV value when it contains a separator, quote or newline
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
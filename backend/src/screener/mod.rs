@@ -0,0 +1,143 @@
+pub mod expr;
+pub mod metrics;
+pub mod screener_commands;
+
+pub use expr::{resolve_field, sort_metrics, CompareOp, FilterExpr, SortKey};
+pub use metrics::{max_pain, metrics_to_csv, SymbolMetrics, FUTURES_FIELDS, METRIC_FIELDS};
+pub use screener_commands::ScreenerCommands;
+
+use crate::jobs::FetchTasks;
+use crate::portfolio::MarketData;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use utoipa::ToSchema;
+
+/// Default ordering when no sort is given: most alerts first, then highest spread
+pub const DEFAULT_SORT: &str = "-alerts,-spread";
+
+/// Parsed screener query (filter, sort keys and row limit)
+#[derive(Debug, Clone, Default)]
+pub struct ScreenerQuery {
+    pub filter: Option<FilterExpr>,
+    pub sort: Vec<SortKey>,
+    pub limit: Option<usize>,
+}
+
+impl ScreenerQuery {
+    /// Parse the raw filter/sort strings; blank values mean "none" / the default sort
+    pub fn parse(filter: Option<&str>, sort: Option<&str>, limit: Option<usize>) -> Result<Self> {
+        let filter = filter
+            .filter(|f| !f.trim().is_empty())
+            .map(FilterExpr::parse)
+            .transpose()?;
+        let sort = SortKey::parse_list(sort.filter(|s| !s.trim().is_empty()).unwrap_or(DEFAULT_SORT))?;
+        Ok(Self { filter, sort, limit })
+    }
+
+    /// True when the filter or sort references a futures-based metric (basis)
+    pub fn needs_futures(&self) -> bool {
+        let filter_fields = self.filter.as_ref().map(|f| f.fields()).unwrap_or_default();
+        filter_fields
+            .into_iter()
+            .chain(self.sort.iter().map(|key| key.field))
+            .any(|field| FUTURES_FIELDS.contains(&field))
+    }
+}
+
+/// One ranked row of screener output
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ScreenerRow {
+    pub rank: usize,
+    #[serde(flatten)]
+    pub metrics: SymbolMetrics,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ScreenerResult {
+    pub total_symbols: usize,
+    pub matched: usize,
+    pub filter: Option<String>,
+    pub sort: String,
+    pub timestamp: String,
+    pub results: Vec<ScreenerRow>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch_job: Option<String>,  // Batch job refreshing the metrics (poll GET /jobs/{id})
+}
+
+/// Filter, rank and truncate per-symbol metrics
+pub fn screen(metrics: Vec<SymbolMetrics>, query: &ScreenerQuery) -> Vec<ScreenerRow> {
+    let mut matched: Vec<SymbolMetrics> = metrics
+        .into_iter()
+        .filter(|m| query.filter.as_ref().is_none_or(|f| f.matches(m)))
+        .collect();
+    sort_metrics(&mut matched, &query.sort);
+
+    matched
+        .into_iter()
+        .take(query.limit.unwrap_or(usize::MAX))
+        .enumerate()
+        .map(|(i, metrics)| ScreenerRow { rank: i + 1, metrics })
+        .collect()
+}
+
+/// Run a query and wrap the rows with totals for API/CLI output
+pub fn run_screener(
+    metrics: Vec<SymbolMetrics>,
+    query: &ScreenerQuery,
+    raw_filter: Option<&str>,
+    raw_sort: Option<&str>,
+) -> ScreenerResult {
+    let total_symbols = metrics.len();
+    let matched = metrics
+        .iter()
+        .filter(|m| query.filter.as_ref().is_none_or(|f| f.matches(m)))
+        .count();
+
+    ScreenerResult {
+        total_symbols,
+        matched,
+        filter: raw_filter.map(str::trim).filter(|f| !f.is_empty()).map(str::to_string),
+        sort: raw_sort.map(str::trim).filter(|s| !s.is_empty()).unwrap_or(DEFAULT_SORT).to_string(),
+        timestamp: chrono::Local::now().format("%d-%b-%Y %H:%M:%S").to_string(),
+        results: screen(metrics, query),
+        batch_job: None,
+    }
+}
+
+/// Fill futures price and basis for each symbol's option expiry, at most `max_concurrent`
+/// quotes in flight. Symbols without a matching future (e.g. weekly index expiries) are left empty.
+pub async fn fill_futures_prices(
+    market: &Arc<MarketData>,
+    metrics: &mut [SymbolMetrics],
+    max_concurrent: usize,
+) -> Vec<String> {
+    let semaphore = Arc::new(Semaphore::new(max_concurrent.max(1)));
+    let mut tasks = FetchTasks::new();
+    let mut indices = Vec::new();
+
+    for (index, m) in metrics.iter().enumerate() {
+        let Some(expiry) = m.expiry.clone() else { continue };
+        let market = Arc::clone(market);
+        let sem = Arc::clone(&semaphore);
+        let symbol = m.symbol.clone();
+        indices.push(index);
+
+        tasks.spawn(async move {
+            let _permit = sem.acquire_owned().await
+                .map_err(|e| anyhow!("Semaphore error: {}", e))?;
+            market.nse_futures_price(&symbol, &expiry).await
+                .map_err(|e| anyhow!("{} {}: {}", symbol, expiry, e))
+        });
+    }
+
+    let mut errors = Vec::new();
+    for (index, result) in indices.into_iter().zip(tasks.join_all().await) {
+        match result {
+            Ok(price) => metrics[index].set_futures_price(price),
+            Err(e) => errors.push(e.to_string()),
+        }
+    }
+    errors
+}
@@ -0,0 +1,190 @@
+use super::{fill_futures_prices, run_screener, ScreenerQuery, ScreenerResult, SymbolMetrics};
+use crate::analytics::IvRankStats;
+use crate::nse::config;
+use crate::nse::processor::ProcessedOptionData;
+use crate::nse::rules::RulesOutput;
+use crate::portfolio::MarketData;
+use crate::settings;
+use anyhow::{anyhow, Context, Result};
+use colored::Colorize;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{info, info_span, warn, Instrument};
+
+pub const SCREENER_RESULTS_FILE: &str = "screener_results.json";
+pub const DEFAULT_PROCESSED_DIR: &str = "processed_data";
+pub const DEFAULT_RULES_FILE: &str = "batch_rules.json";
+
+/// processed_data/<SYMBOL>.json as written by the NSE batch
+#[derive(Debug, Deserialize)]
+pub(crate) struct StoredRecord {
+    pub(crate) record: RecordHeader,
+    pub(crate) data: Vec<ProcessedOptionData>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RecordHeader {
+    pub(crate) symbol: String,
+    pub(crate) timestamp: String,
+    pub(crate) underlying_value: f64,
+    #[serde(default)]
+    pub(crate) spread: f64,
+    #[serde(default)]
+    pub(crate) ce_oi: f64,
+    #[serde(default)]
+    pub(crate) pe_oi: f64,
+    #[serde(default)]
+    pub(crate) iv_rank: Option<IvRankStats>,
+}
+
+impl StoredRecord {
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&text).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+}
+
+/// Screener Command Handler - ranks the last batch run's symbols
+pub struct ScreenerCommands;
+
+impl ScreenerCommands {
+    /// Screen the stored batch output and write the ranked results
+    pub async fn run(exchange: &str) -> Result<()> {
+        Self::screener(exchange).instrument(info_span!("screener", exchange)).await
+    }
+
+    async fn screener(exchange: &str) -> Result<()> {
+        if exchange != "nse" {
+            return Err(anyhow!("Screener needs EXCHANGE=nse (got '{}')", exchange));
+        }
+
+        info!("Option Chain Screener");
+
+        let dir = std::env::var("SCREENER_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from(DEFAULT_PROCESSED_DIR));
+        let rules_file = std::env::var("SCREENER_RULES")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from(DEFAULT_RULES_FILE));
+        let filter = std::env::var("SCREENER_FILTER").ok();
+        let sort = std::env::var("SCREENER_SORT").ok();
+        let limit = match std::env::var("SCREENER_LIMIT") {
+            Ok(limit) => Some(limit.parse().map_err(|_| anyhow!("SCREENER_LIMIT must be a whole number"))?),
+            Err(_) => None,
+        };
+        let query = ScreenerQuery::parse(filter.as_deref(), sort.as_deref(), limit)?;
+
+        info!("Batch data: {}", dir.display());
+        if let Some(filter) = &filter {
+            info!("Filter: {}", filter);
+        }
+        if let Some(sort) = &sort {
+            info!("Sort: {}", sort);
+        }
+
+        let mut metrics = info_span!("load_batch_metrics").in_scope(|| load_batch_metrics(&dir, &rules_file))?;
+        if metrics.is_empty() {
+            return Err(anyhow!("No processed symbols found in {} (run MODE=batch first)", dir.display()));
+        }
+
+        if query.needs_futures() {
+            let market = Arc::new(MarketData::new()?);
+            let max_concurrent = settings::current().nse.concurrency(config::is_ci_environment());
+            let errors = fill_futures_prices(&market, &mut metrics, max_concurrent)
+                .instrument(info_span!("fetch_futures_prices"))
+                .await;
+            if !errors.is_empty() {
+                warn!("No futures price for {} symbol(s)", errors.len());
+            }
+        }
+
+        let result = run_screener(metrics, &query, filter.as_deref(), sort.as_deref());
+        Self::display_results(&result);
+
+        let output = std::env::var("SCREENER_OUTPUT").unwrap_or_else(|_| SCREENER_RESULTS_FILE.to_string());
+        std::fs::write(&output, serde_json::to_string_pretty(&result)?)?;
+        info!("✓ Saved results to {}", output);
+
+        Ok(())
+    }
+
+    fn display_results(result: &ScreenerResult) {
+        println!("{}", "=".repeat(60).blue());
+        println!("{}", "Screener Results".cyan().bold());
+        println!("{}", "=".repeat(60).blue());
+        println!("{} Matched {} of {} symbols", "ℹ".blue(), result.matched, result.total_symbols);
+        println!();
+
+        let fmt = |value: Option<f64>| value.map(|v| format!("{:.2}", v)).unwrap_or_else(|| "-".to_string());
+        println!(
+            "  {:>4}  {:<12} {:>10} {:>8} {:>6} {:>8} {:>7} {:>7} {:>8}",
+            "#", "Symbol", "Spot", "Spread", "PCR", "ATM IV", "IVR", "Alerts", "Basis%"
+        );
+        for row in &result.results {
+            let m = &row.metrics;
+            println!(
+                "  {:>4}  {:<12} {:>10.2} {:>8.2} {:>6} {:>8} {:>7} {:>7} {:>8}",
+                row.rank,
+                m.symbol.yellow(),
+                m.underlying_value,
+                m.spread,
+                fmt(m.pcr),
+                fmt(m.atm_iv),
+                fmt(m.iv_rank),
+                m.alerts,
+                fmt(m.basis_pct),
+            );
+        }
+        println!();
+    }
+}
+
+/// Metrics for every processed_data file, with alert counts from the batch rules file
+pub fn load_batch_metrics(dir: &Path, rules_file: &Path) -> Result<Vec<SymbolMetrics>> {
+    let alert_counts: HashMap<String, usize> = if rules_file.exists() {
+        let text = std::fs::read_to_string(rules_file)
+            .with_context(|| format!("Failed to read {}", rules_file.display()))?;
+        let outputs: Vec<RulesOutput> = serde_json::from_str(&text)
+            .with_context(|| format!("Failed to parse {}", rules_file.display()))?;
+        outputs.into_iter().map(|o| (o.symbol, o.alerts.len())).collect()
+    } else {
+        HashMap::new()
+    };
+
+    let mut metrics = Vec::new();
+    for entry in std::fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+
+        let stored = match StoredRecord::load(&path) {
+            Ok(stored) => stored,
+            Err(e) => {
+                warn!("Skipping {}: {}", path.display(), e);
+                continue;
+            }
+        };
+
+        let header = stored.record;
+        let mut m = SymbolMetrics::from_chain(
+            &header.symbol,
+            &header.timestamp,
+            header.underlying_value,
+            header.spread,
+            header.ce_oi,
+            header.pe_oi,
+            &stored.data,
+        );
+        m.alerts = alert_counts.get(&header.symbol).copied().unwrap_or(0);
+        if let Some(iv_rank) = &header.iv_rank {
+            m.set_iv_rank(iv_rank);
+        }
+        metrics.push(m);
+    }
+
+    Ok(metrics)
+}
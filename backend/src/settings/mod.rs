@@ -0,0 +1,5 @@
+pub mod runtime_settings;
+
+pub use runtime_settings::{
+    current, install, CacheSettings, CircuitSettings, ExchangeSettings, PrewarmSettings, RetrySettings, RiskSettings, ServerSettings, Settings, DEFAULT_SETTINGS_FILE, SETTINGS_FILE_ENV,
+};
@@ -0,0 +1,512 @@
+use crate::analytics::iv_rank;
+use crate::cache::{CachePolicy, MarketHours, PrewarmSchedule};
+use crate::mcx::config as mcx_config;
+use crate::nse::config as nse_config;
+use crate::portfolio::{models as portfolio_models, risk};
+use anyhow::{anyhow, Context, Result};
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio_retry::strategy::ExponentialBackoff;
+use toml::{Table, Value};
+
+pub const SETTINGS_FILE_ENV: &str = "ANALYZER_CONFIG";
+pub const DEFAULT_SETTINGS_FILE: &str = "nse-analyzer.toml";
+
+const MAX_CONCURRENT_LIMIT: usize = 64;
+// List settings whose env values keep their case (everything else is a symbol list)
+const CASE_SENSITIVE_LISTS: [&str; 1] = ["API_KEYS"];
+
+/// Runtime tunables for both exchanges
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Settings {
+    pub iv_history_file: String,  // Daily ATM IV history behind IV rank (both exchanges)
+    pub portfolio_file: String,   // Paper-trading positions
+    pub snapshot_dir: String,     // Batch runs archive records here for backtests; empty = off
+    pub risk: RiskSettings,
+    pub nse: ExchangeSettings,
+    pub mcx: ExchangeSettings,
+}
+
+/// Thresholds for the paper-portfolio risk alerts
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RiskSettings {
+    pub strike_proximity_pct: f64,  // Spot within this % of a short strike
+    pub delta_drift: f64,           // Per-unit delta change since entry
+    pub theta_target_pct: f64,      // Share of short premium decayed
+    pub stop_loss_pct: f64,         // Loss as % of entry value without a per-position stop; 0 = off
+    pub take_profit_pct: f64,       // Gain as % of entry value without a per-position target; 0 = off
+}
+
+/// Per-exchange tunables; the built-in defaults are the constants in `nse::config` / `mcx::config`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExchangeSettings {
+    pub max_concurrent: usize,
+    pub ci_max_concurrent: usize,
+    pub http_timeout_secs: u64,
+    pub warmup_delay_ms: u64,
+    pub ci_timeout_secs: u64,        // Whole-batch fetch limit in CI
+    pub surface_max_expiries: usize,
+    pub surface_expiries_limit: usize,  // Most expiries a request may ask for
+    pub indices: Vec<String>,        // Symbols fetched as indices (NSE only)
+    pub market_open: String,         // "HH:MM" IST, Monday to Friday
+    pub market_close: String,
+    pub margin_rate: f64,            // Paper-portfolio margin as a fraction of underlying notional
+    pub retry: RetrySettings,
+    pub circuit: CircuitSettings,
+    pub cache: BTreeMap<String, CacheSettings>,  // Server caches by resource
+    pub prewarm: PrewarmSettings,
+    pub server: ServerSettings,
+}
+
+/// API server listen address, API keys and request quotas
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ServerSettings {
+    pub bind: String,                  // Listen address; 0.0.0.0 exposes the server on the LAN
+    pub api_keys: Vec<String>,         // X-API-Key / Bearer tokens; empty disables auth
+    pub requests_per_minute: u32,      // Per key (all clients share one quota without auth); 0 = unlimited
+    pub batch_requests_per_hour: u32,  // POST batch-analysis per key; 0 = unlimited
+}
+
+/// One server cache; TTLs of 0 disable caching for that resource
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CacheSettings {
+    pub ttl_secs: u64,         // Outside market hours
+    pub market_ttl_secs: u64,  // During market hours
+    pub stale_secs: u64,       // Serve stale for this long while refreshing in the background
+    pub max_entries: usize,
+}
+
+/// Background refresh of hot symbols in the API server caches
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PrewarmSettings {
+    pub enabled: bool,
+    pub interval_secs: u64,       // During market hours
+    pub idle_interval_secs: u64,  // Outside market hours
+    pub symbols: Vec<String>,     // In addition to indices and WATCHLIST
+}
+
+/// Stop calling the exchange after repeated failures (e.g. NSE blocking the session)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CircuitSettings {
+    pub failure_threshold: u32,  // Consecutive failed fetches that open the circuit; 0 = never
+    pub cooldown_secs: u64,      // Fail fast this long while open before letting requests probe again
+}
+
+/// Exponential backoff for upstream requests
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RetrySettings {
+    pub base_delay_ms: u64,
+    pub factor: u64,
+    pub max_delay_secs: u64,
+    pub max_attempts: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            iv_history_file: iv_rank::DEFAULT_IV_HISTORY_FILE.to_string(),
+            portfolio_file: portfolio_models::DEFAULT_PORTFOLIO_FILE.to_string(),
+            snapshot_dir: String::new(),
+            risk: RiskSettings {
+                strike_proximity_pct: risk::DEFAULT_STRIKE_PROXIMITY_PCT,
+                delta_drift: risk::DEFAULT_DELTA_DRIFT,
+                theta_target_pct: risk::DEFAULT_THETA_TARGET_PCT,
+                stop_loss_pct: 0.0,
+                take_profit_pct: 0.0,
+            },
+            nse: ExchangeSettings::nse_defaults(),
+            mcx: ExchangeSettings::mcx_defaults(),
+        }
+    }
+}
+
+impl ExchangeSettings {
+    pub fn nse_defaults() -> Self {
+        Self {
+            max_concurrent: nse_config::DEFAULT_MAX_CONCURRENT,
+            ci_max_concurrent: nse_config::CI_MAX_CONCURRENT,
+            http_timeout_secs: nse_config::HTTP_TIMEOUT.as_secs(),
+            warmup_delay_ms: nse_config::WARMUP_DELAY_MS,
+            ci_timeout_secs: nse_config::GITHUB_ACTIONS_TIMEOUT_SECS,
+            surface_max_expiries: nse_config::SURFACE_MAX_EXPIRIES,
+            surface_expiries_limit: nse_config::SURFACE_EXPIRIES_LIMIT,
+            indices: nse_config::NSE_INDICES.iter().map(|s| s.to_string()).collect(),
+            market_open: nse_config::MARKET_OPEN_IST.to_string(),
+            market_close: nse_config::MARKET_CLOSE_IST.to_string(),
+            margin_rate: nse_config::MARGIN_RATE,
+            retry: RetrySettings {
+                base_delay_ms: nse_config::RETRY_BASE_DELAY_MS,
+                factor: nse_config::RETRY_FACTOR,
+                max_delay_secs: nse_config::RETRY_MAX_DELAY_SECS,
+                max_attempts: nse_config::RETRY_MAX_ATTEMPTS,
+            },
+            circuit: CircuitSettings {
+                failure_threshold: nse_config::CIRCUIT_FAILURE_THRESHOLD,
+                cooldown_secs: nse_config::CIRCUIT_COOLDOWN_SECS,
+            },
+            cache: nse_config::CACHE_POLICIES
+                .iter()
+                .map(|&(name, ttl_secs, market_ttl_secs, stale_secs, max_entries)| {
+                    (name.to_string(), CacheSettings { ttl_secs, market_ttl_secs, stale_secs, max_entries })
+                })
+                .collect(),
+            prewarm: PrewarmSettings {
+                enabled: true,
+                interval_secs: nse_config::PREWARM_INTERVAL_SECS,
+                idle_interval_secs: nse_config::PREWARM_IDLE_INTERVAL_SECS,
+                symbols: nse_config::PREWARM_SYMBOLS.iter().map(|s| s.to_string()).collect(),
+            },
+            server: ServerSettings {
+                bind: nse_config::SERVER_BIND.to_string(),
+                api_keys: Vec::new(),
+                requests_per_minute: nse_config::SERVER_REQUESTS_PER_MINUTE,
+                batch_requests_per_hour: nse_config::SERVER_BATCH_REQUESTS_PER_HOUR,
+            },
+        }
+    }
+
+    pub fn mcx_defaults() -> Self {
+        Self {
+            max_concurrent: mcx_config::DEFAULT_MAX_CONCURRENT,
+            ci_max_concurrent: mcx_config::CI_MAX_CONCURRENT,
+            http_timeout_secs: mcx_config::HTTP_TIMEOUT.as_secs(),
+            warmup_delay_ms: mcx_config::WARMUP_DELAY_MS,
+            ci_timeout_secs: mcx_config::GITHUB_ACTIONS_TIMEOUT_SECS,
+            surface_max_expiries: mcx_config::SURFACE_MAX_EXPIRIES,
+            surface_expiries_limit: mcx_config::SURFACE_EXPIRIES_LIMIT,
+            indices: Vec::new(),
+            market_open: mcx_config::MARKET_OPEN_IST.to_string(),
+            market_close: mcx_config::MARKET_CLOSE_IST.to_string(),
+            margin_rate: mcx_config::MARGIN_RATE,
+            retry: RetrySettings {
+                base_delay_ms: mcx_config::RETRY_BASE_DELAY_MS,
+                factor: mcx_config::RETRY_FACTOR,
+                max_delay_secs: mcx_config::RETRY_MAX_DELAY_SECS,
+                max_attempts: mcx_config::RETRY_MAX_ATTEMPTS,
+            },
+            circuit: CircuitSettings {
+                failure_threshold: mcx_config::CIRCUIT_FAILURE_THRESHOLD,
+                cooldown_secs: mcx_config::CIRCUIT_COOLDOWN_SECS,
+            },
+            cache: mcx_config::CACHE_POLICIES
+                .iter()
+                .map(|&(name, ttl_secs, market_ttl_secs, stale_secs, max_entries)| {
+                    (name.to_string(), CacheSettings { ttl_secs, market_ttl_secs, stale_secs, max_entries })
+                })
+                .collect(),
+            prewarm: PrewarmSettings {
+                enabled: true,
+                interval_secs: mcx_config::PREWARM_INTERVAL_SECS,
+                idle_interval_secs: mcx_config::PREWARM_IDLE_INTERVAL_SECS,
+                symbols: mcx_config::PREWARM_SYMBOLS.iter().map(|s| s.to_string()).collect(),
+            },
+            server: ServerSettings {
+                bind: mcx_config::SERVER_BIND.to_string(),
+                api_keys: Vec::new(),
+                requests_per_minute: mcx_config::SERVER_REQUESTS_PER_MINUTE,
+                batch_requests_per_hour: mcx_config::SERVER_BATCH_REQUESTS_PER_HOUR,
+            },
+        }
+    }
+
+    /// Concurrent upstream requests for a batch (lower limit in CI)
+    pub fn concurrency(&self, ci: bool) -> usize {
+        if ci { self.ci_max_concurrent } else { self.max_concurrent }
+    }
+
+    pub fn http_timeout(&self) -> Duration {
+        Duration::from_secs(self.http_timeout_secs)
+    }
+
+    pub fn warmup_delay(&self) -> Duration {
+        Duration::from_millis(self.warmup_delay_ms)
+    }
+
+    pub fn ci_timeout(&self) -> Duration {
+        Duration::from_secs(self.ci_timeout_secs)
+    }
+
+    /// Trading session; None if the configured times do not parse (rejected by validation)
+    pub fn market_hours(&self) -> Option<MarketHours> {
+        Some(MarketHours {
+            open: parse_hhmm(&self.market_open)?,
+            close: parse_hhmm(&self.market_close)?,
+        })
+    }
+
+    /// Cache policy for a server resource; unknown resources are not cached
+    pub fn cache_policy(&self, resource: &str) -> CachePolicy {
+        let Some(cache) = self.cache.get(resource) else {
+            return CachePolicy::fixed(Duration::ZERO, 1);
+        };
+        CachePolicy {
+            ttl: Duration::from_secs(cache.ttl_secs),
+            market_ttl: Duration::from_secs(cache.market_ttl_secs),
+            stale: Duration::from_secs(cache.stale_secs),
+            max_entries: cache.max_entries,
+            market_hours: self.market_hours(),
+        }
+    }
+
+    pub fn prewarm_schedule(&self) -> PrewarmSchedule {
+        PrewarmSchedule {
+            interval: Duration::from_secs(self.prewarm.interval_secs),
+            idle_interval: Duration::from_secs(self.prewarm.idle_interval_secs),
+            market_hours: self.market_hours(),
+        }
+    }
+
+    /// Listen address for the API server on `port`
+    pub fn server_addr(&self, port: u16) -> String {
+        format!("{}:{}", self.server.bind.trim(), port)
+    }
+
+    /// Expiries for a volatility surface: the default when not requested, capped at the limit
+    pub fn surface_expiries(&self, requested: Option<usize>) -> Result<usize> {
+        match requested {
+            Some(0) => Err(anyhow!("max_expiries must be greater than 0")),
+            Some(requested) => Ok(requested.min(self.surface_expiries_limit)),
+            None => Ok(self.surface_max_expiries),
+        }
+    }
+
+    pub fn is_index(&self, symbol: &str) -> bool {
+        self.indices.iter().any(|index| index.eq_ignore_ascii_case(symbol))
+    }
+
+    fn validate(&self, section: &str, errors: &mut Vec<String>) {
+        let mut check = |ok: bool, message: &str| {
+            if !ok {
+                errors.push(format!("{}.{}", section, message));
+            }
+        };
+        check((1..=MAX_CONCURRENT_LIMIT).contains(&self.max_concurrent),
+            &format!("max_concurrent must be between 1 and {}", MAX_CONCURRENT_LIMIT));
+        check((1..=MAX_CONCURRENT_LIMIT).contains(&self.ci_max_concurrent),
+            &format!("ci_max_concurrent must be between 1 and {}", MAX_CONCURRENT_LIMIT));
+        check(self.http_timeout_secs > 0, "http_timeout_secs must be greater than 0");
+        check(self.ci_timeout_secs > 0, "ci_timeout_secs must be greater than 0");
+        check(self.surface_max_expiries > 0, "surface_max_expiries must be greater than 0");
+        check(self.surface_expiries_limit >= self.surface_max_expiries,
+            "surface_expiries_limit must not be less than surface_max_expiries");
+        check(self.indices.iter().all(|s| !s.trim().is_empty()), "indices must not contain blank symbols");
+        check(self.retry.max_attempts > 0, "retry.max_attempts must be greater than 0");
+        check(self.retry.factor > 0, "retry.factor must be greater than 0");
+        check(self.retry.base_delay_ms <= self.retry.max_delay_secs * 1000,
+            "retry.base_delay_ms must not exceed retry.max_delay_secs");
+        check(self.circuit.failure_threshold == 0 || self.circuit.cooldown_secs > 0,
+            "circuit.cooldown_secs must be greater than 0");
+        check(self.margin_rate > 0.0 && self.margin_rate <= 1.0, "margin_rate must be greater than 0 and at most 1");
+        match (parse_hhmm(&self.market_open), parse_hhmm(&self.market_close)) {
+            (Some(open), Some(close)) => check(open < close, "market_open must be before market_close"),
+            _ => check(false, "market_open / market_close must be HH:MM"),
+        }
+        check(self.prewarm.interval_secs > 0, "prewarm.interval_secs must be greater than 0");
+        check(self.prewarm.idle_interval_secs >= self.prewarm.interval_secs,
+            "prewarm.idle_interval_secs must not be less than prewarm.interval_secs");
+        for (name, cache) in &self.cache {
+            check(cache.max_entries > 0, &format!("cache.{}.max_entries must be greater than 0", name));
+        }
+        check(self.server.bind.trim().parse::<IpAddr>().is_ok(), "server.bind must be an IP address");
+        check(self.server.api_keys.iter().all(|key| !key.trim().is_empty()), "server.api_keys must not contain blank keys");
+    }
+}
+
+impl RiskSettings {
+    fn validate(&self, errors: &mut Vec<String>) {
+        let mut check = |ok: bool, message: &str| {
+            if !ok {
+                errors.push(format!("risk.{}", message));
+            }
+        };
+        check(self.strike_proximity_pct > 0.0, "strike_proximity_pct must be greater than 0");
+        check(self.delta_drift > 0.0, "delta_drift must be greater than 0");
+        check(self.theta_target_pct > 0.0 && self.theta_target_pct <= 100.0,
+            "theta_target_pct must be greater than 0 and at most 100");
+        check(self.stop_loss_pct >= 0.0, "stop_loss_pct must not be negative");
+        check(self.take_profit_pct >= 0.0, "take_profit_pct must not be negative");
+    }
+}
+
+impl ServerSettings {
+    /// Whether the server only accepts connections from this machine
+    pub fn is_loopback(&self) -> bool {
+        self.bind.trim().parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+    }
+}
+
+impl CircuitSettings {
+    pub fn cooldown(&self) -> Duration {
+        Duration::from_secs(self.cooldown_secs)
+    }
+}
+
+impl RetrySettings {
+    /// Backoff schedule for `tokio_retry` / `metrics::retry_tracked`
+    pub fn backoff(&self) -> std::iter::Take<ExponentialBackoff> {
+        ExponentialBackoff::from_millis(self.base_delay_ms)
+            .factor(self.factor)
+            .max_delay(Duration::from_secs(self.max_delay_secs))
+            .take(self.max_attempts)
+    }
+}
+
+impl Settings {
+    /// Config file path: explicit path, then $ANALYZER_CONFIG, then ./nse-analyzer.toml
+    pub fn path(explicit: Option<&Path>) -> PathBuf {
+        explicit
+            .map(Path::to_path_buf)
+            .or_else(|| std::env::var(SETTINGS_FILE_ENV).ok().map(PathBuf::from))
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_SETTINGS_FILE))
+    }
+
+    /// Defaults, overlaid with the config file (if present) and then env vars (NSE_*, RISK_*, SNAPSHOT_DIR, ...).
+    /// An explicitly named file that does not exist is an error.
+    pub fn load(explicit: Option<&Path>) -> Result<Self> {
+        let path = Self::path(explicit);
+        let file = if path.exists() {
+            let text = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let table: Table = toml::from_str(&text)
+                .with_context(|| format!("Failed to parse {}", path.display()))?;
+            tracing::info!("Loaded settings from {}", path.display());
+            Some(table)
+        } else if explicit.is_some() || std::env::var(SETTINGS_FILE_ENV).is_ok() {
+            return Err(anyhow!("Config file {} not found", path.display()));
+        } else {
+            None
+        };
+
+        let env = |name: &str| std::env::var(name).ok();
+        let settings = Self::from_layers(file, &env)
+            .with_context(|| format!("Invalid settings (file: {})", path.display()))?;
+        Ok(settings)
+    }
+
+    /// Merge the layers: built-in defaults < file table < env lookups, then validate
+    pub fn from_layers(file: Option<Table>, env: &dyn Fn(&str) -> Option<String>) -> Result<Self> {
+        let mut merged = Table::try_from(Self::default())?;
+        if let Some(file) = file {
+            merge_table(&mut merged, file, "")?;
+        }
+        apply_env(&mut merged, "", env)?;
+
+        let settings: Settings = Value::Table(merged).try_into()?;
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    /// Check every section, reporting all problems at once
+    pub fn validate(&self) -> Result<()> {
+        let mut errors = Vec::new();
+        if self.iv_history_file.trim().is_empty() {
+            errors.push("iv_history_file must not be empty".to_string());
+        }
+        if self.portfolio_file.trim().is_empty() {
+            errors.push("portfolio_file must not be empty".to_string());
+        }
+        self.risk.validate(&mut errors);
+        self.nse.validate("nse", &mut errors);
+        self.mcx.validate("mcx", &mut errors);
+        if self.nse.indices.is_empty() {
+            errors.push("nse.indices must list at least one index".to_string());
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!("{}", errors.join("; ")))
+        }
+    }
+
+    /// Effective settings as TOML (for `nse-analyzer config`)
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+}
+
+fn parse_hhmm(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+/// Overlay `overlay` onto `base`; keys must already exist so typos fail loudly
+fn merge_table(base: &mut Table, overlay: Table, prefix: &str) -> Result<()> {
+    for (key, value) in overlay {
+        let name = format!("{}{}", prefix, key);
+        let Some(existing) = base.get_mut(&key) else {
+            return Err(anyhow!("Unknown setting '{}'", name));
+        };
+        match (existing, value) {
+            (Value::Table(existing), Value::Table(value)) => merge_table(existing, value, &format!("{}.", name))?,
+            (existing, value) if existing.same_type(&value) => *existing = value,
+            (Value::Float(existing), Value::Integer(value)) => *existing = value as f64,
+            (existing, value) => {
+                return Err(anyhow!("Setting '{}' must be {}, got {}", name, existing.type_str(), value.type_str()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Env override for every leaf: nse.retry.max_attempts <- NSE_RETRY_MAX_ATTEMPTS, lists are comma-separated
+fn apply_env(table: &mut Table, prefix: &str, env: &dyn Fn(&str) -> Option<String>) -> Result<()> {
+    for (key, value) in table.iter_mut() {
+        let name = format!("{}{}", prefix, key.to_uppercase());
+        if let Value::Table(nested) = value {
+            apply_env(nested, &format!("{}_", name), env)?;
+            continue;
+        }
+        let Some(raw) = env(&name) else {
+            continue;
+        };
+        let raw = raw.trim();
+        let keep_case = CASE_SENSITIVE_LISTS.iter().any(|list| name.ends_with(list));
+        *value = match value {
+            Value::Integer(_) => Value::Integer(raw.parse().map_err(|_| anyhow!("{} must be a whole number, got '{}'", name, raw))?),
+            Value::Float(_) => Value::Float(raw.parse().map_err(|_| anyhow!("{} must be a number, got '{}'", name, raw))?),
+            Value::Boolean(_) => Value::Boolean(raw.parse().map_err(|_| anyhow!("{} must be true or false, got '{}'", name, raw))?),
+            Value::Array(_) => Value::Array(
+                raw.split(',')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(|s| Value::String(if keep_case { s.to_string() } else { s.to_uppercase() }))
+                    .collect(),
+            ),
+            _ => Value::String(raw.to_string()),
+        };
+    }
+    Ok(())
+}
+
+// -----------------------------------------------
+// PROCESS-WIDE SETTINGS
+// -----------------------------------------------
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+
+/// Install the settings loaded at startup; returns false if already set
+pub fn install(settings: Settings) -> bool {
+    SETTINGS.set(settings).is_ok()
+}
+
+/// Settings installed at startup, or defaults + config file + env when nothing was installed
+pub fn current() -> &'static Settings {
+    SETTINGS.get_or_init(|| {
+        Settings::load(None).unwrap_or_else(|e| {
+            tracing::warn!("Using default settings: {:#}", e);
+            Settings::default()
+        })
+    })
+}
@@ -0,0 +1,227 @@
+// ============================================
+// API GUARD - API keys and per-key request quotas
+// ============================================
+// Usage:
+//   let guard = ApiGuard::new("nse", &settings.server);
+//   router.layer(axum::middleware::from_fn_with_state(guard, auth::api_guard))
+// ============================================
+
+use super::metrics;
+use crate::settings::ServerSettings;
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+// The desktop app holds no API key; it hands each backend it launches a per-launch
+// secret in this env var and sends it back in the header to stop that backend
+pub const SHUTDOWN_TOKEN_ENV: &str = "SHUTDOWN_TOKEN";
+pub const SHUTDOWN_TOKEN_HEADER: &str = "x-shutdown-token";
+
+// Quota identity for every client when no API keys are configured
+const ANONYMOUS: &str = "anonymous";
+// Reachable without a key or quota: health and readiness checks and API docs
+const OPEN_PATHS: [&str; 5] = ["/nse_health", "/mcx_health", "/ready", "/openapi.json", "/docs"];
+
+const REQUEST_WINDOW: Duration = Duration::from_secs(60);
+const BATCH_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+/// Token bucket per key: `limit` requests per `window`, refilled continuously
+pub struct RateLimiter {
+    limit: u32,
+    window: Duration,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    /// A limit of 0 never rejects
+    pub fn new(limit: u32, window: Duration) -> Self {
+        Self { limit, window, buckets: Mutex::new(HashMap::new()) }
+    }
+
+    /// Take one request from `key`'s quota; Err(retry after) once it is used up
+    pub fn check(&self, key: &str) -> Result<(), Duration> {
+        self.check_at(key, Instant::now())
+    }
+
+    pub fn check_at(&self, key: &str, now: Instant) -> Result<(), Duration> {
+        if self.limit == 0 {
+            return Ok(());
+        }
+        let capacity = self.limit as f64;
+        let per_sec = capacity / self.window.as_secs_f64();
+
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket { tokens: capacity, updated: now });
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * per_sec).min(capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / per_sec))
+        }
+    }
+}
+
+/// Why a request was turned away
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rejection {
+    Unauthorized,
+    RateLimited { retry_after: Duration },
+    BatchRateLimited { retry_after: Duration },
+}
+
+impl Rejection {
+    fn reason(&self) -> &'static str {
+        match self {
+            Rejection::Unauthorized => "unauthorized",
+            Rejection::RateLimited { .. } => "rate_limited",
+            Rejection::BatchRateLimited { .. } => "batch_rate_limited",
+        }
+    }
+}
+
+impl IntoResponse for Rejection {
+    fn into_response(self) -> Response {
+        let (status, error, retry_after) = match &self {
+            Rejection::Unauthorized => (
+                StatusCode::UNAUTHORIZED,
+                format!("Missing or invalid API key (send {} or Authorization: Bearer)", API_KEY_HEADER),
+                None,
+            ),
+            Rejection::RateLimited { retry_after } => {
+                (StatusCode::TOO_MANY_REQUESTS, "Request quota exceeded".to_string(), Some(*retry_after))
+            }
+            Rejection::BatchRateLimited { retry_after } => {
+                (StatusCode::TOO_MANY_REQUESTS, "Batch analysis quota exceeded".to_string(), Some(*retry_after))
+            }
+        };
+
+        // Same shape as ApiResponse so clients handle it like any other error
+        let body = json!({ "success": false, "data": null, "error": error, "processing_time_ms": null });
+        let mut response = (status, Json(body)).into_response();
+        if let Some(retry_after) = retry_after {
+            let secs = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+            response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(secs));
+        }
+        response
+    }
+}
+
+/// API key check and quotas for one server
+#[derive(Clone)]
+pub struct ApiGuard {
+    inner: Arc<GuardInner>,
+}
+
+struct GuardInner {
+    server: &'static str,
+    api_keys: HashSet<String>,
+    shutdown_token: Option<String>,
+    requests: RateLimiter,
+    batches: RateLimiter,
+}
+
+impl ApiGuard {
+    /// Accepts the shutdown token from `SHUTDOWN_TOKEN` when the process was given one
+    pub fn new(server: &'static str, settings: &ServerSettings) -> Self {
+        Self::with_shutdown_token(server, settings, std::env::var(SHUTDOWN_TOKEN_ENV).ok())
+    }
+
+    pub fn with_shutdown_token(server: &'static str, settings: &ServerSettings, shutdown_token: Option<String>) -> Self {
+        Self {
+            inner: Arc::new(GuardInner {
+                server,
+                api_keys: settings.api_keys.iter().map(|key| key.trim().to_string()).collect(),
+                shutdown_token: shutdown_token.map(|token| token.trim().to_string()).filter(|token| !token.is_empty()),
+                requests: RateLimiter::new(settings.requests_per_minute, REQUEST_WINDOW),
+                batches: RateLimiter::new(settings.batch_requests_per_hour, BATCH_WINDOW),
+            }),
+        }
+    }
+
+    pub fn auth_enabled(&self) -> bool {
+        !self.inner.api_keys.is_empty()
+    }
+
+    /// Check the key and charge the request to its quotas (`target` is the path plus any query)
+    pub fn authorize(&self, method: &Method, target: &str, headers: &HeaderMap) -> Result<(), Rejection> {
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        if method == Method::OPTIONS || OPEN_PATHS.contains(&path) {
+            return Ok(());
+        }
+        if method == Method::POST && path == "/shutdown" && self.is_shutdown_token(headers) {
+            return Ok(());
+        }
+
+        let key = if self.auth_enabled() {
+            match request_key(headers) {
+                Some(key) if self.inner.api_keys.contains(key) => key,
+                _ => return Err(Rejection::Unauthorized),
+            }
+        } else {
+            ANONYMOUS
+        };
+
+        self.inner.requests.check(key).map_err(|retry_after| Rejection::RateLimited { retry_after })?;
+        if is_batch_request(method, path, query) {
+            self.inner.batches.check(key).map_err(|retry_after| Rejection::BatchRateLimited { retry_after })?;
+        }
+        Ok(())
+    }
+
+    fn is_shutdown_token(&self, headers: &HeaderMap) -> bool {
+        let sent = headers.get(SHUTDOWN_TOKEN_HEADER).and_then(|v| v.to_str().ok()).map(str::trim);
+        self.inner.shutdown_token.as_deref().is_some_and(|token| sent == Some(token))
+    }
+}
+
+/// `X-API-Key: <key>` or `Authorization: Bearer <key>`
+fn request_key(headers: &HeaderMap) -> Option<&str> {
+    if let Some(key) = headers.get(API_KEY_HEADER).and_then(|v| v.to_str().ok()) {
+        return Some(key.trim());
+    }
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+}
+
+/// Starting a batch fans out hundreds of upstream requests, whether directly or
+/// through a screener refresh
+fn is_batch_request(method: &Method, path: &str, query: &str) -> bool {
+    match *method {
+        Method::POST => path.ends_with("/batch-analysis"),
+        Method::GET => path.ends_with("/screener") && query.split('&').any(|pair| pair == "refresh=true"),
+        _ => false,
+    }
+}
+
+/// Middleware rejecting requests without a valid key or over quota
+pub async fn api_guard(State(guard): State<ApiGuard>, request: Request, next: Next) -> Response {
+    let target = request.uri().path_and_query().map_or(request.uri().path(), |target| target.as_str());
+    match guard.authorize(request.method(), target, request.headers()) {
+        Ok(()) => next.run(request).await,
+        Err(rejection) => {
+            tracing::warn!(server = guard.inner.server, path = %request.uri().path(), reason = rejection.reason(), "Request rejected");
+            metrics::registry().record_rejection(guard.inner.server, rejection.reason());
+            rejection.into_response()
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use anyhow::{Context, Result};
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+
+/// Write via a temp file in the same directory, then rename over `path`,
+/// so readers and a killed process never see a truncated file
+pub fn write_atomic(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> Result<()> {
+    let path = path.as_ref();
+    let tmp = sibling_path(path, ".tmp");
+    std::fs::write(&tmp, contents).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    if let Err(e) = std::fs::rename(&tmp, path) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e).with_context(|| format!("Failed to replace {}", path.display()));
+    }
+    Ok(())
+}
+
+/// Exclusive lock on `<path>.lock`, released on drop. Unlike an in-process mutex it also
+/// serializes the NSE and MCX server processes that read-modify-write the same file.
+pub struct FileLock {
+    _file: File,
+}
+
+impl FileLock {
+    /// Blocks until the lock is free; async code goes through `with_file_lock`
+    pub fn exclusive(path: impl AsRef<Path>) -> Result<Self> {
+        let lock_path = sibling_path(path.as_ref(), ".lock");
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .with_context(|| format!("Failed to open {}", lock_path.display()))?;
+        file.lock().with_context(|| format!("Failed to lock {}", lock_path.display()))?;
+        Ok(Self { _file: file })
+    }
+}
+
+/// Run blocking file I/O on a worker thread while holding the lock on `path`
+pub async fn with_file_lock<T, F>(path: PathBuf, f: F) -> Result<T>
+where
+    F: FnOnce(&Path) -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(move || {
+        let _lock = FileLock::exclusive(&path)?;
+        f(&path)
+    })
+    .await
+    .context("File task panicked")?
+}
+
+/// `data.json` -> `data.json<suffix>`
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
@@ -0,0 +1,92 @@
+// ============================================
+// LOGGING - tracing subscriber setup
+// ============================================
+// Environment:
+//   RUST_LOG         - filter directives, e.g. "info" or "info,nse_analyzer::nse=debug"
+//   LOG_LEVEL        - plain level used when RUST_LOG is unset (default: info)
+//   LOG_FORMAT       - "text" (default), "compact" or "json"
+//   LOG_SPAN_EVENTS  - "close" (default) logs span durations, "none" disables them
+// ============================================
+
+use std::io::IsTerminal;
+use tower_http::classify::{ServerErrorsAsFailures, SharedClassifier};
+use tower_http::trace::{DefaultMakeSpan, DefaultOnRequest, DefaultOnResponse, TraceLayer};
+use tracing::Level;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::EnvFilter;
+
+const DEFAULT_LEVEL: &str = "info";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Text,
+    Compact,
+    Json,
+}
+
+impl LogFormat {
+    /// Parse a `LOG_FORMAT` value; unknown values fall back to text
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "json" => LogFormat::Json,
+            "compact" => LogFormat::Compact,
+            _ => LogFormat::Text,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        std::env::var("LOG_FORMAT").map(|v| Self::parse(&v)).unwrap_or(LogFormat::Text)
+    }
+}
+
+/// Filter from RUST_LOG, then LOG_LEVEL, then the default level
+pub fn env_filter() -> EnvFilter {
+    if let Ok(filter) = EnvFilter::try_from_default_env() {
+        return filter;
+    }
+    let level = std::env::var("LOG_LEVEL").unwrap_or_else(|_| DEFAULT_LEVEL.to_string());
+    EnvFilter::try_new(&level).unwrap_or_else(|_| EnvFilter::new(DEFAULT_LEVEL))
+}
+
+fn span_events() -> FmtSpan {
+    match std::env::var("LOG_SPAN_EVENTS").as_deref().map(str::trim) {
+        Ok("none") | Ok("off") => FmtSpan::NONE,
+        Ok("full") => FmtSpan::FULL,
+        _ => FmtSpan::CLOSE,
+    }
+}
+
+/// Install the global subscriber (logs go to stdout). Safe to call more than once.
+pub fn init() {
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(env_filter())
+        .with_span_events(span_events())
+        .with_writer(std::io::stdout);
+
+    // try_init fails only when a subscriber is already installed (e.g. by a test harness)
+    let _ = match LogFormat::from_env() {
+        LogFormat::Json => builder
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(false)
+            .try_init(),
+        LogFormat::Compact => builder
+            .compact()
+            .with_ansi(std::io::stdout().is_terminal())
+            .with_target(false)
+            .try_init(),
+        LogFormat::Text => builder
+            .with_ansi(std::io::stdout().is_terminal())
+            .with_target(false)
+            .try_init(),
+    };
+}
+
+/// Request span (method, path) and response log (status, latency) for every handler
+pub fn http_trace_layer() -> TraceLayer<SharedClassifier<ServerErrorsAsFailures>> {
+    TraceLayer::new_for_http()
+        .make_span_with(DefaultMakeSpan::new().level(Level::INFO))
+        .on_request(DefaultOnRequest::new().level(Level::DEBUG))
+        .on_response(DefaultOnResponse::new().level(Level::INFO))
+}
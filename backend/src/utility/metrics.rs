@@ -0,0 +1,244 @@
+// ============================================
+// METRICS - Prometheus text exposition
+// ============================================
+// Usage:
+//   1. Upstream calls: metrics::retry_tracked("/api/option-chain-v3", backoff, || async { ... }).await
+//   2. Caches: metrics::registry().record_cache("nse_contract_info", hit)
+//   3. Batches: metrics::registry().record_batch("nse", successful, failed)
+//   4. API guard: metrics::registry().record_rejection("nse", "rate_limited")
+//   5. Export: GET /metrics -> metrics::registry().render()
+// ============================================
+
+use anyhow::Result;
+use axum::http::header;
+use axum::response::IntoResponse;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio_retry::Retry;
+
+const PREFIX: &str = "nse_analyzer";
+
+/// Upstream latency buckets (seconds)
+const LATENCY_BUCKETS: [f64; 10] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 20.0, 30.0];
+
+// Metric names (without prefix)
+const UPSTREAM_DURATION: &str = "upstream_request_duration_seconds";
+const UPSTREAM_REQUESTS: &str = "upstream_requests_total";
+const UPSTREAM_RETRIES: &str = "upstream_retries_total";
+const CACHE_REQUESTS: &str = "cache_requests_total";
+const BATCH_RUNS: &str = "batch_runs_total";
+const BATCH_SYMBOLS: &str = "batch_symbols_total";
+const ALERTS_FIRED: &str = "alerts_fired_total";
+const LAST_BATCH_SUCCESS: &str = "last_successful_batch_timestamp_seconds";
+const API_REJECTIONS: &str = "api_requests_rejected_total";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+/// Export order, type and help text for every metric
+const DESCRIPTORS: [(&str, Kind, &str); 9] = [
+    (UPSTREAM_DURATION, Kind::Histogram, "Latency of upstream NSE/MCX API attempts by endpoint"),
+    (UPSTREAM_REQUESTS, Kind::Counter, "Upstream API attempts by endpoint and outcome"),
+    (UPSTREAM_RETRIES, Kind::Counter, "Upstream API retries by endpoint"),
+    (CACHE_REQUESTS, Kind::Counter, "Server cache lookups by cache and result (hit/miss)"),
+    (BATCH_RUNS, Kind::Counter, "Batch analysis runs by exchange and status"),
+    (BATCH_SYMBOLS, Kind::Counter, "Symbols processed by batch analysis by exchange and result"),
+    (ALERTS_FIRED, Kind::Counter, "Alerts fired by exchange and alert type"),
+    (LAST_BATCH_SUCCESS, Kind::Gauge, "Unix time of the last successful batch analysis"),
+    (API_REJECTIONS, Kind::Counter, "API requests rejected by server and reason (auth or quota)"),
+];
+
+type Labels = Vec<(&'static str, String)>;
+type SeriesKey = (&'static str, Labels);
+
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+#[derive(Default)]
+struct Series {
+    values: BTreeMap<SeriesKey, f64>,  // Counters and gauges
+    histograms: BTreeMap<SeriesKey, Histogram>,
+}
+
+/// Process-wide counters, gauges and histograms
+#[derive(Default)]
+pub struct MetricsRegistry {
+    series: Mutex<Series>,
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn add(&self, name: &'static str, labels: Labels, value: f64) {
+        let mut series = self.series.lock().unwrap();
+        *series.values.entry((name, labels)).or_insert(0.0) += value;
+    }
+
+    fn set(&self, name: &'static str, labels: Labels, value: f64) {
+        self.series.lock().unwrap().values.insert((name, labels), value);
+    }
+
+    fn observe(&self, name: &'static str, labels: Labels, value: f64) {
+        let mut series = self.series.lock().unwrap();
+        let histogram = series.histograms.entry((name, labels)).or_default();
+        for (bucket, bound) in histogram.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if value <= bound {
+                *bucket += 1;
+            }
+        }
+        histogram.count += 1;
+        histogram.sum += value;
+    }
+
+    /// One upstream attempt (including reading the body)
+    pub fn observe_upstream(&self, endpoint: &str, elapsed: Duration, ok: bool) {
+        self.observe(UPSTREAM_DURATION, vec![("endpoint", endpoint.to_string())], elapsed.as_secs_f64());
+        let outcome = if ok { "success" } else { "error" };
+        self.add(UPSTREAM_REQUESTS, vec![("endpoint", endpoint.to_string()), ("outcome", outcome.to_string())], 1.0);
+    }
+
+    pub fn record_retry(&self, endpoint: &str) {
+        self.add(UPSTREAM_RETRIES, vec![("endpoint", endpoint.to_string())], 1.0);
+    }
+
+    pub fn record_cache(&self, cache: &str, hit: bool) {
+        let result = if hit { "hit" } else { "miss" };
+        self.add(CACHE_REQUESTS, vec![("cache", cache.to_string()), ("result", result.to_string())], 1.0);
+    }
+
+    /// A finished batch run; successful runs also stamp the last-success gauge
+    pub fn record_batch(&self, exchange: &str, successful: usize, failed: usize) {
+        self.add(BATCH_RUNS, vec![("exchange", exchange.to_string()), ("status", "success".to_string())], 1.0);
+        self.add(BATCH_SYMBOLS, vec![("exchange", exchange.to_string()), ("result", "success".to_string())], successful as f64);
+        self.add(BATCH_SYMBOLS, vec![("exchange", exchange.to_string()), ("result", "failed".to_string())], failed as f64);
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64();
+        self.set(LAST_BATCH_SUCCESS, vec![("exchange", exchange.to_string())], now);
+    }
+
+    /// A batch run that aborted before producing results
+    pub fn record_batch_failure(&self, exchange: &str) {
+        self.add(BATCH_RUNS, vec![("exchange", exchange.to_string()), ("status", "failed".to_string())], 1.0);
+    }
+
+    pub fn record_alerts<'a>(&self, exchange: &str, alert_types: impl IntoIterator<Item = &'a str>) {
+        for alert_type in alert_types {
+            self.add(ALERTS_FIRED, vec![("exchange", exchange.to_string()), ("alert_type", alert_type.to_string())], 1.0);
+        }
+    }
+
+    pub fn record_rejection(&self, server: &str, reason: &str) {
+        self.add(API_REJECTIONS, vec![("server", server.to_string()), ("reason", reason.to_string())], 1.0);
+    }
+
+    /// Prometheus text format (version 0.0.4)
+    pub fn render(&self) -> String {
+        let series = self.series.lock().unwrap();
+        let mut out = String::new();
+
+        for (name, kind, help) in DESCRIPTORS {
+            let full_name = format!("{}_{}", PREFIX, name);
+            let type_name = match kind {
+                Kind::Counter => "counter",
+                Kind::Gauge => "gauge",
+                Kind::Histogram => "histogram",
+            };
+            let _ = writeln!(out, "# HELP {} {}", full_name, help);
+            let _ = writeln!(out, "# TYPE {} {}", full_name, type_name);
+
+            if kind == Kind::Histogram {
+                for ((_, labels), histogram) in series.histograms.iter().filter(|((n, _), _)| *n == name) {
+                    for (count, bound) in histogram.buckets.iter().zip(LATENCY_BUCKETS) {
+                        let le = [("le", bound.to_string())];
+                        let _ = writeln!(out, "{}_bucket{} {}", full_name, format_labels(labels, &le), count);
+                    }
+                    let le = [("le", "+Inf".to_string())];
+                    let _ = writeln!(out, "{}_bucket{} {}", full_name, format_labels(labels, &le), histogram.count);
+                    let _ = writeln!(out, "{}_sum{} {}", full_name, format_labels(labels, &[]), histogram.sum);
+                    let _ = writeln!(out, "{}_count{} {}", full_name, format_labels(labels, &[]), histogram.count);
+                }
+            } else {
+                for ((_, labels), value) in series.values.iter().filter(|((n, _), _)| *n == name) {
+                    let _ = writeln!(out, "{}{} {}", full_name, format_labels(labels, &[]), value);
+                }
+            }
+        }
+        out
+    }
+}
+
+fn format_labels(labels: &[(&'static str, String)], extra: &[(&'static str, String)]) -> String {
+    let pairs: Vec<String> = labels
+        .iter()
+        .chain(extra)
+        .map(|(key, value)| {
+            let escaped = value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+            format!("{}=\"{}\"", key, escaped)
+        })
+        .collect();
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+/// The registry shared by clients and servers in this process
+pub fn registry() -> &'static MetricsRegistry {
+    static REGISTRY: OnceLock<MetricsRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(MetricsRegistry::new)
+}
+
+/// Endpoint label for an upstream URL: its path without host or query string
+pub fn endpoint_label(url: &str) -> String {
+    let without_scheme = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let path = without_scheme.find('/').map(|i| &without_scheme[i..]).unwrap_or("/");
+    path.split('?').next().unwrap_or(path).to_string()
+}
+
+/// `Retry::spawn` that records latency per attempt and counts retries for `endpoint`
+pub async fn retry_tracked<T, F, Fut>(
+    endpoint: &str,
+    strategy: impl IntoIterator<Item = Duration>,
+    mut action: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let attempts = AtomicUsize::new(0);
+    Retry::start(strategy, || {
+        if attempts.fetch_add(1, Ordering::Relaxed) > 0 {
+            registry().record_retry(endpoint);
+        }
+        let attempt = action();
+        async move {
+            let start = Instant::now();
+            let result = attempt.await;
+            registry().observe_upstream(endpoint, start.elapsed(), result.is_ok());
+            result
+        }
+    })
+    .await
+}
+
+/// GET /metrics - Prometheus scrape endpoint (mounted by both servers)
+pub async fn metrics_handler() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")],
+        registry().render(),
+    )
+}
@@ -1,3 +1,21 @@
+pub mod api_response;
+pub mod auth;
+pub mod files;
+pub mod logging;
+pub mod metrics;
+pub mod openapi;
+pub mod readiness;
+pub mod shutdown;
 pub mod timing;
+pub mod upstream;
+pub mod watchlist;
 
-pub use timing::{Timer, AggregateTimer, timed, timed_async};
\ No newline at end of file
+pub use api_response::ApiResponse;
+pub use auth::{api_guard, ApiGuard};
+pub use files::{with_file_lock, write_atomic, FileLock};
+pub use metrics::{metrics_handler, MetricsRegistry};
+pub use openapi::{openapi_routes, print_openapi_endpoints};
+pub use readiness::Readiness;
+pub use shutdown::Shutdown;
+pub use timing::{Timer, AggregateTimer, timed, timed_async};
+pub use upstream::UpstreamHealth;
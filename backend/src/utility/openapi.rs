@@ -0,0 +1,42 @@
+use axum::{
+    http::header,
+    response::Html,
+    routing::get,
+    Router,
+};
+use utoipa::openapi::OpenApi;
+
+// Swagger UI assets are loaded from the CDN; only the spec is served locally
+const DOCS_PAGE: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8" />
+  <title>API docs</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css" />
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+  <script>
+    window.ui = SwaggerUIBundle({ url: "/openapi.json", dom_id: "#swagger-ui" });
+  </script>
+</body>
+</html>
+"##;
+
+/// GET /openapi.json (spec) and GET /docs (Swagger UI) for one server
+pub fn openapi_routes(spec: &OpenApi) -> anyhow::Result<Router> {
+    let body = spec.to_json()?;
+    Ok(Router::new()
+        .route("/openapi.json", get(move || {
+            let body = body.clone();
+            async move { ([(header::CONTENT_TYPE, "application/json")], body) }
+        }))
+        .route("/docs", get(|| async { Html(DOCS_PAGE) })))
+}
+
+/// Endpoint list for the server startup banner
+pub fn print_openapi_endpoints() {
+    println!("   GET  /openapi.json (OpenAPI 3 spec)");
+    println!("   GET  /docs (Swagger UI)");
+}
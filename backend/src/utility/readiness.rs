@@ -0,0 +1,99 @@
+// ============================================
+// READINESS - GET /ready for probes and the desktop app
+// ============================================
+// Usage:
+//   let upstream = upstream::health(Exchange::Nse).status(&settings.circuit);
+//   Readiness::evaluate(Exchange::Nse, upstream, &cache.stats())  // 503 while upstream is blocked
+// ============================================
+
+use super::upstream::{CircuitState, UpstreamStatus};
+use crate::cache::CacheStats;
+use crate::contracts::Exchange;
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadyStatus {
+    Ready,            // Last upstream fetch succeeded
+    Starting,         // No upstream fetch yet
+    Degraded,         // Last fetch failed but the circuit is not open
+    UpstreamBlocked,  // Circuit open: the server is up, the exchange is refusing or failing requests
+}
+
+/// How current one server cache is
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CacheFreshness {
+    pub name: String,
+    pub entries: usize,
+    pub ttl_secs: u64,
+    pub newest_age_secs: Option<u64>,
+    pub fresh: bool,  // Newest entry is within its TTL
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Readiness {
+    pub exchange: Exchange,
+    pub status: ReadyStatus,
+    pub ready: bool,
+    pub upstream: UpstreamStatus,
+    pub caches: Vec<CacheFreshness>,
+    pub data_age_secs: Option<u64>,  // Newest cached data, else the last successful fetch
+    pub checked_at: DateTime<Local>,
+}
+
+impl Readiness {
+    pub fn evaluate(exchange: Exchange, upstream: UpstreamStatus, caches: &[CacheStats]) -> Self {
+        let now = Local::now();
+        let failing = match (&upstream.last_error, upstream.last_success) {
+            (Some(error), Some(success)) => error.at > success,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+        let status = if upstream.circuit == CircuitState::Open {
+            ReadyStatus::UpstreamBlocked
+        } else if failing {
+            ReadyStatus::Degraded
+        } else if upstream.last_success.is_none() {
+            ReadyStatus::Starting
+        } else {
+            ReadyStatus::Ready
+        };
+
+        let caches: Vec<CacheFreshness> = caches
+            .iter()
+            .map(|stats| CacheFreshness {
+                name: stats.name.clone(),
+                entries: stats.entries,
+                ttl_secs: stats.ttl_secs,
+                newest_age_secs: stats.newest_age_secs,
+                fresh: stats.newest_age_secs.is_some_and(|age| age < stats.ttl_secs),
+            })
+            .collect();
+        let data_age_secs = caches.iter().filter_map(|cache| cache.newest_age_secs).min().or_else(|| {
+            upstream.last_success.map(|success| (now - success).num_seconds().max(0) as u64)
+        });
+
+        Self {
+            exchange,
+            status,
+            ready: status != ReadyStatus::UpstreamBlocked,
+            upstream,
+            caches,
+            data_age_secs,
+            checked_at: now,
+        }
+    }
+}
+
+impl IntoResponse for Readiness {
+    fn into_response(self) -> Response {
+        let status = if self.ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+        (status, Json(self)).into_response()
+    }
+}
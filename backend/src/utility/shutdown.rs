@@ -0,0 +1,121 @@
+// ============================================
+// SHUTDOWN - Ctrl+C, SIGTERM or POST /shutdown
+// ============================================
+// Usage:
+//   let shutdown = Shutdown::new();
+//   shutdown.listen_for_signals();
+//   axum::serve(listener, app).with_graceful_shutdown(shutdown.clone().triggered())
+// ============================================
+
+use super::ApiResponse;
+use axum::{extract::State, response::Json, routing::post, Router};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+/// In-flight requests get this long to finish once shutdown starts
+pub const DRAIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Process-wide shutdown flag that any number of tasks can wait on
+#[derive(Clone)]
+pub struct Shutdown {
+    tx: Arc<watch::Sender<bool>>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self { tx: Arc::new(watch::channel(false).0) }
+    }
+
+    pub fn trigger(&self) {
+        self.tx.send_replace(true);
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        *self.tx.borrow()
+    }
+
+    /// Resolves once shutdown has been triggered
+    pub async fn triggered(self) {
+        let mut rx = self.tx.subscribe();
+        let _ = rx.wait_for(|triggered| *triggered).await;
+    }
+
+    /// Resolves `DRAIN_TIMEOUT` after shutdown was triggered
+    pub async fn drain_deadline(self) {
+        self.triggered().await;
+        tokio::time::sleep(DRAIN_TIMEOUT).await;
+        warn!("Requests still in flight after {:?}, exiting anyway", DRAIN_TIMEOUT);
+    }
+
+    /// Trigger on Ctrl+C or SIGTERM
+    pub fn listen_for_signals(&self) {
+        let shutdown = self.clone();
+        tokio::spawn(async move {
+            signal().await;
+            info!("Shutdown signal received");
+            shutdown.trigger();
+        });
+    }
+}
+
+/// Resolves on Ctrl+C (SIGINT) or, on Unix, SIGTERM
+pub async fn signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            warn!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(e) => {
+                warn!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+/// POST /shutdown - Stop accepting requests, cancel running jobs and exit
+#[utoipa::path(post, path = "/shutdown", tag = "server", responses((status = 200, body = ApiResponse<String>)))]
+pub(crate) async fn request_shutdown(State(shutdown): State<Shutdown>) -> Json<ApiResponse<String>> {
+    info!("Shutdown requested over HTTP");
+    shutdown.trigger();
+    Json(ApiResponse {
+        success: true,
+        data: Some("Shutting down".to_string()),
+        error: None,
+        processing_time_ms: None,
+    })
+}
+
+pub fn get_shutdown_routes(shutdown: Shutdown) -> Router {
+    Router::new()
+        .route("/shutdown", post(request_shutdown))
+        .with_state(shutdown)
+}
+
+/// Endpoint list for the server startup banner
+pub fn print_shutdown_endpoints() {
+    println!("   POST /shutdown (graceful stop; API key or the launcher's X-Shutdown-Token)");
+}
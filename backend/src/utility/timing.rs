@@ -3,13 +3,17 @@
 // ============================================
 // Usage:
 //   1. As a wrapper: let result = timed("operation_name", || { /* code */ });
-//   2. As async wrapper: let result = timed_async("operation_name", async { /* code */ }).await;
+//   2. As async wrapper: let result = timed_async("operation_name", || async { /* code */ }).await;
 //   3. Manual tracking: let timer = Timer::start("name"); ... timer.stop();
-//   4. Section tracking: Timer::section("name", || { /* code */ });
+//   4. Section tracking: Timer::section("name");
+//
+// Wrappers run inside a `timed` span; the subscriber logs its duration when the
+// span closes (see utility::logging). Timers are plain stopwatches: `stop()`
+// emits an event with `elapsed_ms`, dropping a timer logs nothing.
 // ============================================
 
 use std::time::{Duration, Instant};
-use colored::Colorize;
+use tracing::{info, info_span, Instrument};
 
 /// Timer for measuring execution time
 pub struct Timer {
@@ -40,7 +44,7 @@ impl Timer {
         }
     }
 
-    /// Create a silent timer (won't auto-log on drop, use elapsed() manually)
+    /// Create a silent timer (`stop()` won't log, use elapsed() manually)
     pub fn silent(name: impl Into<String>) -> Self {
         Self {
             name: name.into(),
@@ -72,36 +76,30 @@ impl Timer {
         duration
     }
 
-    /// Log duration with emoji based on time taken
+    /// Log the duration as a tracing event
     fn log_duration(&self, duration: Duration) {
-        if self.silent {
+        if self.silent || duration.as_millis() < self.threshold_ms {
             return;
         }
 
-        let ms = duration.as_millis();
-        
-        // Only log if above threshold
-        if ms < self.threshold_ms {
-            return;
-        }
-
-        let (emoji, _color) = Self::get_emoji_and_color(ms);
-        
-        if ms < 1000 {
-            println!("{} {} - {}ms", emoji, self.name.as_str().cyan(), ms);
-        } else {
-            println!("{} {} - {:.2}s", emoji, self.name.as_str().cyan(), duration.as_secs_f64());
-        }
+        info!(
+            timer = %self.name,
+            elapsed_ms = duration.as_millis() as u64,
+            speed = Self::speed_label(duration.as_millis()),
+            "{} - {:.2}s",
+            self.name,
+            duration.as_secs_f64()
+        );
     }
 
-    /// Get emoji and color based on duration
-    fn get_emoji_and_color(ms: u128) -> (&'static str, &'static str) {
+    /// Coarse speed bucket for a duration
+    fn speed_label(ms: u128) -> &'static str {
         match ms {
-            0..=100 => ("⚡", "green"),        // Very fast
-            101..=500 => ("✅", "green"),      // Fast
-            501..=1000 => ("⏱️", "yellow"),    // Acceptable
-            1001..=5000 => ("🐌", "yellow"),   // Slow
-            _ => ("🔥", "red"),                 // Very slow
+            0..=100 => "very_fast",
+            101..=500 => "fast",
+            501..=1000 => "acceptable",
+            1001..=5000 => "slow",
+            _ => "very_slow",
         }
     }
 
@@ -114,13 +112,11 @@ impl Timer {
     where
         F: FnOnce() -> R,
     {
-        let timer = Self::start(name);
-        let result = f();
-        timer.stop();
-        result
+        let name = name.into();
+        info_span!("timed", name = %name).in_scope(f)
     }
 
-    /// Time a synchronous closure with threshold
+    /// Time a synchronous closure, logging only if it exceeds the threshold
     pub fn measure_if_slow<F, R>(name: impl Into<String>, threshold_ms: u128, f: F) -> R
     where
         F: FnOnce() -> R,
@@ -137,13 +133,11 @@ impl Timer {
         F: FnOnce() -> Fut,
         Fut: std::future::Future<Output = R>,
     {
-        let timer = Self::start(name);
-        let result = f().await;
-        timer.stop();
-        result
+        let name = name.into();
+        f().instrument(info_span!("timed", name = %name)).await
     }
 
-    /// Time an async function with threshold
+    /// Time an async function, logging only if it exceeds the threshold
     pub async fn measure_async_if_slow<F, Fut, R>(
         name: impl Into<String>,
         threshold_ms: u128,
@@ -161,20 +155,7 @@ impl Timer {
 
     /// Log a section header for grouping timings
     pub fn section(name: impl Into<String>) {
-        let name = name.into();
-        println!("\n{}", "=".repeat(60).blue());
-        println!("{} {}", "⏱️".to_string(), name.cyan().bold());
-        println!("{}", "=".repeat(60).blue());
-    }
-}
-
-// Auto-log on drop if not silent
-impl Drop for Timer {
-    fn drop(&mut self) {
-        if !self.silent {
-            let duration = self.start.elapsed();
-            self.log_duration(duration);
-        }
+        info!(section = %name.into(), "section");
     }
 }
 
@@ -271,36 +252,29 @@ impl AggregateTimer {
         }
     }
 
-    /// Print summary statistics
+    /// Log summary statistics
     pub fn summary(&self) {
         if self.count == 0 {
-            println!("📊 {} - No operations recorded", self.name.cyan());
+            info!(timer = %self.name, count = 0, "{} - no operations recorded", self.name);
             return;
         }
 
-        println!("\n{}", "=".repeat(60).blue());
-        println!("📊 {} - Summary", self.name.cyan().bold());
-        println!("{}", "=".repeat(60).blue());
-        println!("  • Count: {}", self.count);
-        println!("  • Total: {:.2}s", self.total_duration.as_secs_f64());
-        
-        if let Some(avg) = self.avg_duration() {
-            println!("  • Average: {}ms", avg.as_millis());
-        }
-        
-        if let Some(min) = self.min_duration {
-            println!("  • Min: {}ms", min.as_millis());
-        }
-        
-        if let Some(max) = self.max_duration {
-            println!("  • Max: {}ms", max.as_millis());
-        }
-        
-        if self.count > 0 {
-            let throughput = self.count as f64 / self.total_duration.as_secs_f64();
-            println!("  • Throughput: {:.2} ops/sec", throughput);
-        }
-        
-        println!("{}", "=".repeat(60).blue());
+        let avg_ms = self.avg_duration().map(|d| d.as_millis() as u64).unwrap_or(0);
+        let min_ms = self.min_duration.map(|d| d.as_millis() as u64).unwrap_or(0);
+        let max_ms = self.max_duration.map(|d| d.as_millis() as u64).unwrap_or(0);
+        let total_secs = self.total_duration.as_secs_f64();
+        let throughput = if total_secs > 0.0 { self.count as f64 / total_secs } else { 0.0 };
+
+        info!(
+            timer = %self.name,
+            count = self.count,
+            total_secs,
+            avg_ms,
+            min_ms,
+            max_ms,
+            throughput,
+            "{} - {} ops in {:.2}s (avg {}ms, min {}ms, max {}ms, {:.2} ops/sec)",
+            self.name, self.count, total_secs, avg_ms, min_ms, max_ms, throughput
+        );
     }
 }
@@ -0,0 +1,221 @@
+// ============================================
+// UPSTREAM HEALTH - session state and circuit breaker per exchange
+// ============================================
+// Usage:
+//   1. Clients: upstream::health(Exchange::Nse).call(&settings.circuit, async { ... }).await
+//   2. Session warmup: upstream::health(Exchange::Nse).mark_warmed_up()
+//   3. Readiness: upstream::health(Exchange::Nse).status(&settings.circuit)
+// ============================================
+
+use crate::contracts::Exchange;
+use crate::settings::CircuitSettings;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local};
+use reqwest::StatusCode;
+use serde::Serialize;
+use std::fmt;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    Closed,    // Requests go through
+    Open,      // Failing fast until the cooldown ends
+    HalfOpen,  // Cooldown over; one probe request decides whether it closes or reopens
+}
+
+/// Non-success HTTP status from an exchange API. Typed so the circuit breaker can tell
+/// the exchange rejecting one request from an outage without parsing messages.
+#[derive(Debug, Clone)]
+pub struct StatusError {
+    pub status: StatusCode,
+    pub body: String,  // Response preview, empty for retryable statuses
+}
+
+impl StatusError {
+    pub fn new(status: StatusCode, body: impl Into<String>) -> Self {
+        Self { status, body: body.into() }
+    }
+
+    /// Rate limits and server errors are worth retrying
+    pub fn is_retryable(&self) -> bool {
+        self.status == StatusCode::TOO_MANY_REQUESTS || self.status.is_server_error()
+    }
+
+    /// A 4xx other than 401/403/429 is the exchange answering a bad symbol or expiry, not an outage
+    pub fn is_answer(&self) -> bool {
+        self.status.is_client_error()
+            && !matches!(self.status, StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS)
+    }
+}
+
+impl fmt::Display for StatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_retryable() {
+            write!(f, "Retryable error: {}", self.status)
+        } else {
+            write!(f, "Client error {}: {}", self.status, self.body)
+        }
+    }
+}
+
+impl std::error::Error for StatusError {}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UpstreamError {
+    pub at: DateTime<Local>,
+    pub message: String,
+}
+
+/// Upstream session as seen by this process, for GET /ready
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UpstreamStatus {
+    pub warmed_up: bool,
+    pub last_success: Option<DateTime<Local>>,
+    pub last_error: Option<UpstreamError>,
+    pub consecutive_failures: u32,
+    pub circuit: CircuitState,
+    pub retry_in_secs: Option<u64>,  // While the circuit is open
+}
+
+#[derive(Default)]
+struct State {
+    warmed_up: bool,
+    last_success: Option<DateTime<Local>>,
+    last_error: Option<UpstreamError>,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    probing: bool,  // A half-open probe is in flight
+}
+
+/// Outcome of the recent upstream fetches for one exchange
+#[derive(Default)]
+pub struct UpstreamHealth {
+    state: Mutex<State>,
+}
+
+impl UpstreamHealth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn mark_warmed_up(&self) {
+        self.lock().warmed_up = true;
+    }
+
+    pub fn circuit(&self, settings: &CircuitSettings) -> CircuitState {
+        match self.lock().opened_at {
+            None => CircuitState::Closed,
+            Some(opened) if opened.elapsed() < settings.cooldown() => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Err while the circuit is open, or half-open with a probe already in flight, without
+    /// calling the exchange. Ok(true) admits the caller as the half-open probe.
+    pub fn check(&self, settings: &CircuitSettings) -> Result<bool> {
+        let mut state = self.lock();
+        let Some(opened) = state.opened_at else {
+            return Ok(false);
+        };
+        if let Some(remaining) = settings.cooldown().checked_sub(opened.elapsed()) {
+            return Err(anyhow!(
+                "Upstream circuit open after {} consecutive failures; retrying in {}s",
+                state.consecutive_failures,
+                remaining.as_secs().max(1)
+            ));
+        }
+        if state.probing {
+            return Err(anyhow!("Upstream circuit half-open; waiting for the probe request"));
+        }
+        state.probing = true;
+        Ok(true)
+    }
+
+    pub fn record_success(&self) {
+        let mut state = self.lock();
+        state.probing = false;
+        state.warmed_up = true;
+        state.last_success = Some(Local::now());
+        state.consecutive_failures = 0;
+        state.opened_at = None;
+    }
+
+    /// Count a failed fetch; opens (or reopens) the circuit at the threshold
+    pub fn record_failure(&self, settings: &CircuitSettings, error: &anyhow::Error) {
+        let mut state = self.lock();
+        state.probing = false;
+        state.last_error = Some(UpstreamError { at: Local::now(), message: format!("{:#}", error) });
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+        if settings.failure_threshold > 0 && state.consecutive_failures >= settings.failure_threshold {
+            if state.opened_at.is_none() {
+                tracing::warn!(failures = state.consecutive_failures, "Upstream circuit opened: {:#}", error);
+            }
+            state.opened_at = Some(Instant::now());
+        }
+    }
+
+    /// Run one fetch (retries included) through the circuit and record its outcome
+    pub async fn call<T>(&self, settings: &CircuitSettings, fetch: impl Future<Output = Result<T>>) -> Result<T> {
+        let _probe = ProbeGuard { health: self, armed: self.check(settings)? };
+        let result = fetch.await;
+        match &result {
+            Ok(_) => self.record_success(),
+            Err(e) if answered(e) => self.record_success(),
+            Err(e) => self.record_failure(settings, e),
+        }
+        result
+    }
+
+    pub fn status(&self, settings: &CircuitSettings) -> UpstreamStatus {
+        let circuit = self.circuit(settings);
+        let state = self.lock();
+        let retry_in_secs = (circuit == CircuitState::Open)
+            .then(|| state.opened_at.map(|opened| settings.cooldown().saturating_sub(opened.elapsed()).as_secs()))
+            .flatten();
+        UpstreamStatus {
+            warmed_up: state.warmed_up,
+            last_success: state.last_success,
+            last_error: state.last_error.clone(),
+            consecutive_failures: state.consecutive_failures,
+            circuit,
+            retry_in_secs,
+        }
+    }
+}
+
+/// Frees the half-open slot if a probe is dropped (e.g. its job was cancelled) before it recorded
+struct ProbeGuard<'a> {
+    health: &'a UpstreamHealth,
+    armed: bool,
+}
+
+impl Drop for ProbeGuard<'_> {
+    fn drop(&mut self) {
+        if self.armed {
+            self.health.lock().probing = false;
+        }
+    }
+}
+
+/// The exchange answered with a status that does not indicate an outage
+fn answered(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<StatusError>().is_some_and(StatusError::is_answer)
+}
+
+/// The tracker shared by the clients and the server of `exchange` in this process
+pub fn health(exchange: Exchange) -> &'static UpstreamHealth {
+    static NSE: OnceLock<UpstreamHealth> = OnceLock::new();
+    static MCX: OnceLock<UpstreamHealth> = OnceLock::new();
+    match exchange {
+        Exchange::Nse => NSE.get_or_init(UpstreamHealth::new),
+        Exchange::Mcx => MCX.get_or_init(UpstreamHealth::new),
+    }
+}
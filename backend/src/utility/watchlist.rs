@@ -0,0 +1,54 @@
+// ============================================
+// WATCHLIST - restrict a batch run to some symbols
+// ============================================
+// Accepted forms:
+//   --watchlist NIFTY,BANKNIFTY,RELIANCE    inline, comma or space separated
+//   --watchlist watchlist.txt               file, one or more symbols per line, '#' comments
+
+use anyhow::{anyhow, Context, Result};
+use std::path::Path;
+
+/// Parse a watchlist argument: an existing file path or an inline comma-separated list
+pub fn parse(value: &str) -> Result<Vec<String>> {
+    let path = Path::new(value.trim());
+    let text = if path.is_file() {
+        std::fs::read_to_string(path).with_context(|| format!("Failed to read watchlist {}", path.display()))?
+    } else {
+        value.to_string()
+    };
+
+    let symbols = parse_symbols(&text);
+    if symbols.is_empty() {
+        return Err(anyhow!("Watchlist '{}' contains no symbols", value.trim()));
+    }
+    Ok(symbols)
+}
+
+/// Upper-cased symbols split on commas and whitespace, comments dropped, duplicates removed
+pub fn parse_symbols(text: &str) -> Vec<String> {
+    let mut symbols: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line = line.split('#').next().unwrap_or("");
+        for symbol in line.split(|c: char| c == ',' || c.is_whitespace()) {
+            let symbol = symbol.trim().to_uppercase();
+            if !symbol.is_empty() && !symbols.contains(&symbol) {
+                symbols.push(symbol);
+            }
+        }
+    }
+    symbols
+}
+
+/// Keep the items whose symbol is on the watchlist; also returns watchlist symbols not found
+pub fn filter<T>(items: Vec<T>, watchlist: &[String], symbol: impl Fn(&T) -> &str) -> (Vec<T>, Vec<String>) {
+    let kept: Vec<T> = items
+        .into_iter()
+        .filter(|item| watchlist.iter().any(|w| w.eq_ignore_ascii_case(symbol(item))))
+        .collect();
+    let missing = watchlist
+        .iter()
+        .filter(|w| !kept.iter().any(|item| w.eq_ignore_ascii_case(symbol(item))))
+        .cloned()
+        .collect();
+    (kept, missing)
+}
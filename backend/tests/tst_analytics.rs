@@ -0,0 +1,267 @@
+use nse_analyzer::analytics::{
+    analyze_strategy,
+    build_expiry_smile,
+    classify_buildup,
+    compute_expected_move,
+    compute_gamma_exposure,
+    strike_probabilities,
+    years_to_expiry,
+    Buildup,
+    BuildupCounts,
+    ExposureQuote,
+    GammaRegime,
+    OptionKind,
+    PricingModel,
+    Side,
+    SmileQuote,
+    StrategyLegRequest,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_buildup() {
+        assert_eq!(classify_buildup(Some(1.5), Some(200.0)), Buildup::LongBuildup);
+        assert_eq!(classify_buildup(Some(1.5), Some(-200.0)), Buildup::ShortCovering);
+        assert_eq!(classify_buildup(Some(-1.5), Some(200.0)), Buildup::ShortBuildup);
+        assert_eq!(classify_buildup(Some(-1.5), Some(-200.0)), Buildup::LongCovering);
+
+        // Flat price/OI or missing data is neutral
+        assert_eq!(classify_buildup(Some(0.0), Some(200.0)), Buildup::Neutral);
+        assert_eq!(classify_buildup(None, Some(200.0)), Buildup::Neutral);
+    }
+
+    #[test]
+    fn test_buildup_serializes_to_label() {
+        let json = serde_json::to_string(&Buildup::ShortCovering).unwrap();
+        assert_eq!(json, "\"Short Covering\"");
+        assert_eq!(Buildup::ShortCovering.alert_type(), "SHORT_COVERING");
+    }
+
+    #[test]
+    fn test_buildup_counts() {
+        let counts: BuildupCounts = [
+            Buildup::LongBuildup,
+            Buildup::LongBuildup,
+            Buildup::ShortBuildup,
+            Buildup::Neutral,
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(counts.long_buildup, 2);
+        assert_eq!(counts.short_buildup, 1);
+        assert_eq!(counts.neutral, 1);
+        assert_eq!(counts.total(), 4);
+    }
+
+    #[test]
+    fn test_put_call_parity() {
+        let model = PricingModel::nse();
+        let (spot, strike, t, vol) = (24000.0, 24200.0, years_to_expiry(30), 0.15);
+
+        let call = model.price(OptionKind::Call, spot, strike, t, vol);
+        let put = model.price(OptionKind::Put, spot, strike, t, vol);
+        let parity = spot - strike * (-model.rate * t).exp();
+
+        assert!((call - put - parity).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_implied_volatility_round_trip() {
+        let model = PricingModel::mcx();
+        let t = years_to_expiry(20);
+        let price = model.price(OptionKind::Put, 6500.0, 6300.0, t, 0.32);
+
+        let iv = model.implied_volatility(OptionKind::Put, price, 6500.0, 6300.0, t).unwrap();
+        assert!((iv - 0.32).abs() < 1e-4);
+
+        // Below intrinsic has no solution
+        assert!(model.implied_volatility(OptionKind::Call, 100.0, 6500.0, 6300.0, t).is_none());
+    }
+
+    #[test]
+    fn test_flat_smile_has_no_skew() {
+        let model = PricingModel::nse();
+        let (spot, days) = (24000.0, 30);
+        let t = years_to_expiry(days);
+
+        let quotes: Vec<SmileQuote> = (0..21)
+            .map(|i| {
+                let strike = 22000.0 + 200.0 * i as f64;
+                SmileQuote {
+                    strike,
+                    call_price: Some(model.price(OptionKind::Call, spot, strike, t, 0.2)),
+                    put_price: Some(model.price(OptionKind::Put, spot, strike, t, 0.2)),
+                }
+            })
+            .collect();
+
+        let smile = build_expiry_smile(&model, "30-Oct-2026", days, spot, &quotes);
+
+        assert_eq!(smile.atm_strike, 24000.0);
+        assert!((smile.atm_iv.unwrap() - 20.0).abs() < 0.05);
+        assert!(smile.risk_reversal_25d.unwrap().abs() < 0.05);
+        assert!(smile.butterfly_25d.unwrap().abs() < 0.05);
+        assert!(smile.skew_slope.unwrap().abs() < 0.5);
+    }
+
+    fn leg(strike: f64, option_type: OptionKind, side: Side, entry_price: f64) -> StrategyLegRequest {
+        StrategyLegRequest {
+            strike,
+            option_type,
+            side,
+            lots: 1,
+            entry_price: Some(entry_price),
+        }
+    }
+
+    #[test]
+    fn test_short_straddle_profile() {
+        let legs = vec![
+            leg(24000.0, OptionKind::Call, Side::Sell, 300.0),
+            leg(24000.0, OptionKind::Put, Side::Sell, 250.0),
+        ];
+
+        let analysis = analyze_strategy(&PricingModel::nse(), &legs, &[], 24000.0, 15, 75.0).unwrap();
+
+        assert_eq!(analysis.net_premium, 550.0 * 75.0);
+        assert_eq!(analysis.max_profit, Some(550.0 * 75.0));
+        assert_eq!(analysis.max_loss, None);  // Naked call side
+        assert_eq!(analysis.breakevens, vec![23450.0, 24550.0]);
+        assert!(analysis.net_greeks.theta > 0.0);
+        assert!(analysis.net_greeks.gamma < 0.0);
+    }
+
+    #[test]
+    fn test_iron_condor_is_bounded() {
+        let legs = vec![
+            leg(23500.0, OptionKind::Put, Side::Buy, 40.0),
+            leg(23800.0, OptionKind::Put, Side::Sell, 90.0),
+            leg(24200.0, OptionKind::Call, Side::Sell, 85.0),
+            leg(24500.0, OptionKind::Call, Side::Buy, 35.0),
+        ];
+
+        let analysis = analyze_strategy(&PricingModel::nse(), &legs, &[], 24000.0, 10, 1.0).unwrap();
+
+        assert_eq!(analysis.max_profit, Some(100.0));
+        assert_eq!(analysis.max_loss, Some(-200.0));
+        assert_eq!(analysis.breakevens, vec![23700.0, 24300.0]);
+        assert!(!analysis.payoff.is_empty());
+    }
+
+    #[test]
+    fn test_entry_price_defaults_to_last_price() {
+        let quotes = vec![SmileQuote { strike: 6500.0, call_price: Some(120.0), put_price: None }];
+        let mut legs = vec![leg(6500.0, OptionKind::Call, Side::Buy, 0.0)];
+        legs[0].entry_price = None;
+
+        let analysis = analyze_strategy(&PricingModel::mcx(), &legs, &quotes, 6480.0, 20, 1.0).unwrap();
+        assert_eq!(analysis.legs[0].entry_price, 120.0);
+        assert!(analysis.legs[0].iv.is_some());
+
+        // Missing price and no explicit entry is an error
+        legs[0].option_type = OptionKind::Put;
+        assert!(analyze_strategy(&PricingModel::mcx(), &legs, &quotes, 6480.0, 20, 1.0).is_err());
+
+        // An explicit entry must be positive
+        for price in [0.0, -5.0] {
+            let legs = vec![leg(6500.0, OptionKind::Call, Side::Buy, price)];
+            let err = analyze_strategy(&PricingModel::mcx(), &legs, &quotes, 6480.0, 20, 1.0).unwrap_err();
+            assert!(err.to_string().contains("Invalid leg"), "{}", err);
+        }
+    }
+
+    fn exposure_quotes(model: &PricingModel, spot: f64, days: i32, call_oi: f64, put_oi: f64) -> Vec<ExposureQuote> {
+        let t = years_to_expiry(days);
+        (0..11)
+            .map(|i| {
+                let strike = 23000.0 + 200.0 * i as f64;
+                ExposureQuote {
+                    strike,
+                    // Calls concentrated above spot, puts below
+                    call_oi: if strike >= 24000.0 { call_oi } else { 0.0 },
+                    put_oi: if strike <= 24000.0 { put_oi } else { 0.0 },
+                    call_price: Some(model.price(OptionKind::Call, spot, strike, t, 0.15)),
+                    put_price: Some(model.price(OptionKind::Put, spot, strike, t, 0.15)),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_gamma_exposure_regime() {
+        let model = PricingModel::nse();
+
+        let call_heavy = exposure_quotes(&model, 24000.0, 7, 100_000.0, 10_000.0);
+        let gex = compute_gamma_exposure(&model, &call_heavy, 24000.0, 7, 75.0).unwrap();
+        assert_eq!(gex.regime, GammaRegime::Positive);
+        assert!(gex.net_gex > 0.0);
+        assert_eq!(gex.by_strike.len(), 11);
+
+        let put_heavy = exposure_quotes(&model, 24000.0, 7, 10_000.0, 100_000.0);
+        let gex = compute_gamma_exposure(&model, &put_heavy, 24000.0, 7, 75.0).unwrap();
+        assert_eq!(gex.regime, GammaRegime::Negative);
+    }
+
+    #[test]
+    fn test_zero_gamma_level_between_put_and_call_walls() {
+        let model = PricingModel::nse();
+        let quotes = exposure_quotes(&model, 24000.0, 7, 50_000.0, 50_000.0);
+
+        let gex = compute_gamma_exposure(&model, &quotes, 24000.0, 7, 75.0).unwrap();
+        let flip = gex.zero_gamma_level.unwrap();
+        assert!(flip > 23000.0 && flip < 25000.0);
+
+        // No priced strikes means no exposure estimate
+        let unpriced: Vec<ExposureQuote> = quotes
+            .into_iter()
+            .map(|q| ExposureQuote { call_price: None, put_price: None, ..q })
+            .collect();
+        assert!(compute_gamma_exposure(&model, &unpriced, 24000.0, 7, 75.0).is_none());
+    }
+
+    #[test]
+    fn test_expected_move_bands() {
+        let model = PricingModel::nse();
+        let (spot, days) = (24000.0, 30);
+        let t = years_to_expiry(days);
+        let call = model.price(OptionKind::Call, spot, 24000.0, t, 0.16);
+        let put = model.price(OptionKind::Put, spot, 24000.0, t, 0.16);
+
+        let em = compute_expected_move(&model, 24000.0, call, put, spot, days).unwrap();
+
+        assert!((em.atm_straddle - (call + put)).abs() < 1e-9);
+        assert!((em.atm_iv.unwrap() - 16.0).abs() < 0.01);
+
+        // 1σ ≈ spot * iv * sqrt(t)
+        let expected = spot * 0.16 * t.sqrt();
+        assert!((em.iv_move.unwrap() - expected).abs() < 1.0);
+
+        let (one, two) = (em.band_1sd.unwrap(), em.band_2sd.unwrap());
+        assert!(two.lower < one.lower && one.lower < spot);
+        assert!(two.upper > one.upper && one.upper > spot);
+
+        assert!(compute_expected_move(&model, 24000.0, 0.0, put, spot, days).is_none());
+    }
+
+    #[test]
+    fn test_strike_probabilities() {
+        let model = PricingModel::nse();
+        let t = years_to_expiry(20);
+
+        let otm_call = model.price(OptionKind::Call, 24000.0, 25000.0, t, 0.15);
+        let probs = strike_probabilities(&model, OptionKind::Call, Some(otm_call), 24000.0, 25000.0, 20).unwrap();
+        assert!(probs.prob_itm > 0.0 && probs.prob_itm < 0.5);
+        assert!((probs.prob_touch - 2.0 * probs.prob_itm).abs() < 1e-9);
+
+        let itm_put = model.price(OptionKind::Put, 24000.0, 25000.0, t, 0.15);
+        let probs = strike_probabilities(&model, OptionKind::Put, Some(itm_put), 24000.0, 25000.0, 20).unwrap();
+        assert!(probs.prob_itm > 0.5);
+        assert_eq!(probs.prob_touch, 1.0);
+
+        assert!(strike_probabilities(&model, OptionKind::Put, None, 24000.0, 25000.0, 20).is_none());
+    }
+}
@@ -0,0 +1,130 @@
+use axum::http::{header, HeaderMap, HeaderValue, Method, StatusCode};
+use axum::response::IntoResponse;
+use nse_analyzer::settings::ServerSettings;
+use nse_analyzer::utility::auth::{ApiGuard, RateLimiter, Rejection, API_KEY_HEADER, SHUTDOWN_TOKEN_HEADER};
+use std::time::{Duration, Instant};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server_settings(api_keys: &[&str], requests_per_minute: u32, batch_requests_per_hour: u32) -> ServerSettings {
+        ServerSettings {
+            bind: "127.0.0.1".to_string(),
+            api_keys: api_keys.iter().map(|k| k.to_string()).collect(),
+            requests_per_minute,
+            batch_requests_per_hour,
+        }
+    }
+
+    fn with_key(name: &'static str, value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_rate_limiter_refills_over_window() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(60));
+        let start = Instant::now();
+        assert!(limiter.check_at("a", start).is_ok());
+        assert!(limiter.check_at("a", start).is_ok());
+        let retry_after = limiter.check_at("a", start).unwrap_err();
+        assert!((retry_after.as_secs_f64() - 30.0).abs() < 0.01, "{:?}", retry_after);
+
+        // Quotas are per key, and one token comes back every window / limit
+        assert!(limiter.check_at("b", start).is_ok());
+        assert!(limiter.check_at("a", start + Duration::from_secs(30)).is_ok());
+        assert!(limiter.check_at("a", start + Duration::from_secs(30)).is_err());
+
+        let unlimited = RateLimiter::new(0, Duration::from_secs(60));
+        assert!((0..1_000).all(|_| unlimited.check_at("a", start).is_ok()));
+    }
+
+    #[test]
+    fn test_api_keys_required_when_configured() {
+        let guard = ApiGuard::new("nse", &server_settings(&["secret"], 0, 0));
+        assert!(guard.auth_enabled());
+
+        let path = "/api/nse/single-analysis";
+        assert_eq!(guard.authorize(&Method::GET, path, &HeaderMap::new()), Err(Rejection::Unauthorized));
+        assert_eq!(guard.authorize(&Method::GET, path, &with_key(API_KEY_HEADER, "wrong")), Err(Rejection::Unauthorized));
+        assert_eq!(guard.authorize(&Method::GET, path, &with_key(API_KEY_HEADER, "secret")), Ok(()));
+        assert_eq!(guard.authorize(&Method::GET, path, &with_key("authorization", "Bearer secret")), Ok(()));
+
+        // Health and readiness checks, docs and CORS preflights stay open
+        assert_eq!(guard.authorize(&Method::GET, "/nse_health", &HeaderMap::new()), Ok(()));
+        assert_eq!(guard.authorize(&Method::GET, "/ready", &HeaderMap::new()), Ok(()));
+        assert_eq!(guard.authorize(&Method::GET, "/openapi.json", &HeaderMap::new()), Ok(()));
+        assert_eq!(guard.authorize(&Method::OPTIONS, path, &HeaderMap::new()), Ok(()));
+
+        let open = ApiGuard::new("nse", &server_settings(&[], 0, 0));
+        assert!(!open.auth_enabled());
+        assert_eq!(open.authorize(&Method::GET, path, &HeaderMap::new()), Ok(()));
+    }
+
+    #[test]
+    fn test_batch_requests_have_their_own_quota() {
+        let guard = ApiGuard::new("mcx", &server_settings(&["k1", "k2"], 100, 1));
+        let (k1, k2) = (with_key(API_KEY_HEADER, "k1"), with_key(API_KEY_HEADER, "k2"));
+
+        assert_eq!(guard.authorize(&Method::POST, "/api/mcx/batch-analysis", &k1), Ok(()));
+        assert!(matches!(
+            guard.authorize(&Method::POST, "/api/mcx/batch-analysis", &k1),
+            Err(Rejection::BatchRateLimited { .. })
+        ));
+        // Reading the latest result and other keys are unaffected
+        assert_eq!(guard.authorize(&Method::GET, "/api/mcx/batch-analysis", &k1), Ok(()));
+        assert_eq!(guard.authorize(&Method::POST, "/api/mcx/batch-analysis", &k2), Ok(()));
+
+        // A screener refresh starts a batch job, so it spends the same quota
+        let guard = ApiGuard::new("nse", &server_settings(&["k1"], 100, 1));
+        assert_eq!(guard.authorize(&Method::GET, "/api/nse/screener?sort=-pcr", &k1), Ok(()));
+        assert_eq!(guard.authorize(&Method::GET, "/api/nse/screener?limit=5&refresh=true", &k1), Ok(()));
+        assert!(matches!(
+            guard.authorize(&Method::POST, "/api/nse/batch-analysis", &k1),
+            Err(Rejection::BatchRateLimited { .. })
+        ));
+        assert_eq!(guard.authorize(&Method::GET, "/api/nse/screener?refresh=false", &k1), Ok(()));
+
+        let guard = ApiGuard::new("mcx", &server_settings(&[], 1, 0));
+        assert_eq!(guard.authorize(&Method::GET, "/api/mcx/tickers", &HeaderMap::new()), Ok(()));
+        assert!(matches!(
+            guard.authorize(&Method::GET, "/api/mcx/tickers", &HeaderMap::new()),
+            Err(Rejection::RateLimited { .. })
+        ));
+    }
+
+    #[test]
+    fn test_rejection_responses() {
+        let response = Rejection::Unauthorized.into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(response.headers().get(header::RETRY_AFTER).is_none());
+
+        let response = Rejection::BatchRateLimited { retry_after: Duration::from_millis(1_500) }.into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "2");
+    }
+
+    #[test]
+    fn test_shutdown_token_only_opens_shutdown() {
+        let settings = server_settings(&["secret"], 0, 0);
+        let guard = ApiGuard::with_shutdown_token("nse", &settings, Some("launch-token".to_string()));
+        let token = with_key(SHUTDOWN_TOKEN_HEADER, "launch-token");
+
+        assert!(guard.authorize(&Method::POST, "/shutdown", &token).is_ok());
+        assert!(guard.authorize(&Method::POST, "/shutdown", &with_key(API_KEY_HEADER, "secret")).is_ok());
+        assert_eq!(
+            guard.authorize(&Method::POST, "/shutdown", &with_key(SHUTDOWN_TOKEN_HEADER, "guess")),
+            Err(Rejection::Unauthorized)
+        );
+        assert_eq!(guard.authorize(&Method::POST, "/shutdown", &HeaderMap::new()), Err(Rejection::Unauthorized));
+        assert_eq!(guard.authorize(&Method::GET, "/api/nse/securities", &token), Err(Rejection::Unauthorized));
+
+        // Without a launcher token, /shutdown needs an API key like everything else
+        let guard = ApiGuard::with_shutdown_token("nse", &settings, None);
+        assert_eq!(guard.authorize(&Method::POST, "/shutdown", &with_key(SHUTDOWN_TOKEN_HEADER, "")), Err(Rejection::Unauthorized));
+        let guard = ApiGuard::with_shutdown_token("nse", &settings, Some("  ".to_string()));
+        assert_eq!(guard.authorize(&Method::POST, "/shutdown", &with_key(SHUTDOWN_TOKEN_HEADER, "")), Err(Rejection::Unauthorized));
+    }
+}
@@ -0,0 +1,109 @@
+use nse_analyzer::analytics::Side;
+use nse_analyzer::backtest::{
+    run_backtest,
+    BacktestConfig,
+    ContractKey,
+    EntryTiming,
+    ExitReason,
+    Snapshot,
+    SnapshotAlert,
+};
+use chrono::NaiveDate;
+use std::collections::HashMap;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> ContractKey {
+        ContractKey::new("30-Dec-2025", 26000.0, "CE")
+    }
+
+    /// One snapshot per minute with the given CE price and optional alert
+    fn snapshot(minute: u32, price: f64, alert: Option<&str>) -> Snapshot {
+        let timestamp = NaiveDate::from_ymd_opt(2025, 12, 19)
+            .unwrap()
+            .and_hms_opt(10, minute, 0)
+            .unwrap();
+        Snapshot {
+            symbol: "NIFTY".to_string(),
+            timestamp,
+            underlying_value: 26000.0,
+            alerts: alert
+                .map(|t| vec![SnapshotAlert { alert_type: t.to_string(), contract: key() }])
+                .unwrap_or_default(),
+            prices: [(key(), price)].into_iter().collect::<HashMap<_, _>>(),
+        }
+    }
+
+    #[test]
+    fn test_next_snapshot_entry_and_hit_rate() {
+        let snapshots = vec![
+            snapshot(0, 100.0, Some("HIGH_VOLUME")),
+            snapshot(1, 110.0, Some("HIGH_VOLUME")),
+            snapshot(2, 99.0, None),
+            snapshot(3, 120.0, None),
+        ];
+        let report = run_backtest(snapshots, &BacktestConfig::default());
+
+        assert_eq!(report.alerts_seen, 2);
+        assert_eq!(report.trades_taken, 2);
+        // 110 -> 99 loses, 99 -> 120 wins
+        assert_eq!(report.trades[0].entry_price, 110.0);
+        assert_eq!(report.trades[0].exit_reason, ExitReason::HoldingPeriod);
+        assert_eq!(report.overall.wins, 1);
+        assert!((report.overall.hit_rate - 50.0).abs() < 1e-9);
+        assert!((report.overall.max_drawdown_pct - 10.0).abs() < 1e-9);
+        assert_eq!(report.by_alert_type.len(), 1);
+    }
+
+    #[test]
+    fn test_take_profit_and_stop_loss() {
+        let snapshots = vec![
+            snapshot(0, 100.0, Some("LOW_PRICE")),
+            snapshot(1, 105.0, None),
+            snapshot(2, 130.0, None),
+            snapshot(3, 50.0, None),
+        ];
+
+        let config = BacktestConfig {
+            entry: EntryTiming::SameSnapshot,
+            holding_snapshots: 3,
+            take_profit_pct: Some(25.0),
+            ..Default::default()
+        };
+        let report = run_backtest(snapshots.clone(), &config);
+        assert_eq!(report.trades[0].exit_reason, ExitReason::TakeProfit);
+        assert_eq!(report.trades[0].exit_price, 130.0);
+
+        // Short side: the rally to 130 is a 30% loss
+        let config = BacktestConfig {
+            side: Side::Sell,
+            entry: EntryTiming::SameSnapshot,
+            holding_snapshots: 3,
+            stop_loss_pct: Some(20.0),
+            ..Default::default()
+        };
+        let report = run_backtest(snapshots, &config);
+        assert_eq!(report.trades[0].exit_reason, ExitReason::StopLoss);
+        assert!((report.trades[0].return_pct + 30.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_alert_filter_and_missing_exit() {
+        let snapshots = vec![
+            snapshot(0, 100.0, Some("HIGH_VOLUME")),
+            snapshot(1, 100.0, Some("LOW_PRICE")),
+        ];
+        let config = BacktestConfig {
+            alert_types: Some(vec!["LOW_PRICE".to_string()]),
+            ..Default::default()
+        };
+        let report = run_backtest(snapshots, &config);
+
+        // The LOW_PRICE alert has no next snapshot to enter on
+        assert_eq!(report.alerts_seen, 1);
+        assert_eq!(report.trades_taken, 0);
+        assert_eq!(report.skipped, 1);
+    }
+}
@@ -0,0 +1,84 @@
+use nse_analyzer::contracts::Exchange;
+use nse_analyzer::report::{BatchReport, BatchReportBuilder, SymbolStatus};
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_report() -> BatchReport {
+        let mut builder = BatchReportBuilder::new(Exchange::Nse);
+        builder.record_step("fetch_fno_list", Duration::from_millis(1200));
+        builder.symbol_success("RELIANCE", Some("27-Nov-2025"));
+        builder.symbol_success("TCS", None);
+        builder.symbol_failure("INFY", None, "HTTP 401 Unauthorized");
+        builder.symbol_failure("SBIN", None, "Timeout after 30s");
+        builder.alert("RELIANCE", "LOW_PRICE", "CE");
+        builder.alert("RELIANCE", "LOW_PRICE", "PE");
+        builder.alert("TCS", "LOW_PRICE", "CE");
+        builder.finish()
+    }
+
+    #[test]
+    fn test_totals_and_timeout_classification() {
+        let report = sample_report();
+
+        assert!(report.run_id.starts_with("nse-"));
+        assert_eq!(report.totals.symbols, 4);
+        assert_eq!(report.totals.successful, 2);
+        assert_eq!(report.totals.failed, 1);
+        assert_eq!(report.totals.timed_out, 1);
+        assert_eq!(report.steps[0].name, "fetch_fno_list");
+        assert_eq!(report.steps[0].duration_ms, 1200);
+
+        let sbin = report.symbols.iter().find(|s| s.symbol == "SBIN").unwrap();
+        assert_eq!(sbin.status, SymbolStatus::TimedOut);
+        let reliance = report.symbols.iter().find(|s| s.symbol == "RELIANCE").unwrap();
+        assert_eq!(reliance.alerts, 2);
+        assert_eq!(reliance.expiry.as_deref(), Some("27-Nov-2025"));
+    }
+
+    #[test]
+    fn test_alert_grouping() {
+        let report = sample_report();
+
+        assert_eq!(report.alerts.total, 3);
+        assert_eq!(report.alerts.securities_with_alerts, 2);
+        assert_eq!(report.alerts.by_type.get("LOW_PRICE"), Some(&3));
+        assert_eq!(report.alerts.by_side.get("CE"), Some(&2));
+
+        let top = &report.alerts.groups[0];
+        assert_eq!((top.alert_type.as_str(), top.side.as_str(), top.count), ("LOW_PRICE", "CE", 2));
+        assert_eq!(top.symbols, vec!["RELIANCE", "TCS"]);
+    }
+
+    #[test]
+    fn test_markdown_and_round_trip() {
+        let report = sample_report();
+        let md = report.to_markdown();
+
+        assert!(md.contains("## NSE batch report"));
+        assert!(md.contains("| fetch_fno_list |"));
+        assert!(md.contains("| LOW_PRICE | CE | 2 | RELIANCE, TCS |"));
+        assert!(md.contains("### Failures (2)"));
+        assert!(md.contains("| SBIN | timed out |"));
+
+        let dir = std::env::temp_dir().join(format!("batch_report_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let json_path = dir.join("batch_report.json");
+        report.save(&json_path, dir.join("batch_summary.md")).unwrap();
+        let loaded = BatchReport::load(&json_path).unwrap();
+        assert_eq!(loaded.totals.successful, 2);
+        assert_eq!(loaded.alerts.groups.len(), report.alerts.groups.len());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_empty_run_markdown() {
+        let report = BatchReportBuilder::new(Exchange::Mcx).finish();
+        let md = report.to_markdown();
+        assert!(report.run_id.starts_with("mcx-"));
+        assert!(md.contains("No alerts fired."));
+        assert!(!md.contains("### Failures"));
+    }
+}
@@ -0,0 +1,165 @@
+use anyhow::anyhow;
+use chrono::{NaiveTime, TimeZone, Utc};
+use nse_analyzer::cache::{prewarm, CachePolicy, MarketHours, PrewarmSchedule, TtlCache};
+use nse_analyzer::settings::Settings;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counted_fetch(calls: &Arc<AtomicUsize>, value: u32) -> impl FnOnce() -> std::future::Ready<anyhow::Result<u32>> + Send + 'static {
+        let calls = Arc::clone(calls);
+        move || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            std::future::ready(Ok(value))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ttl_and_lru_eviction() {
+        let cache: TtlCache<String, u32> = TtlCache::new("test_lru", CachePolicy::fixed(Duration::from_secs(60), 2));
+        cache.insert("a".to_string(), 1);
+        cache.insert("b".to_string(), 2);
+        assert_eq!(cache.get(&"a".to_string()), Some(1));  // "b" is now least recently used
+
+        cache.insert("c".to_string(), 3);
+        assert_eq!(cache.get(&"b".to_string()), None);
+        assert_eq!(cache.get(&"a".to_string()), Some(1));
+        assert_eq!(cache.get(&"c".to_string()), Some(3));
+
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.evictions, stats.hits, stats.misses), (2, 1, 3, 1));
+
+        // A zero TTL never serves a cached value
+        let uncached: TtlCache<String, u32> = TtlCache::new("test_zero_ttl", CachePolicy::fixed(Duration::ZERO, 10));
+        let calls = Arc::new(AtomicUsize::new(0));
+        for _ in 0..2 {
+            assert_eq!(uncached.get_or_fetch("x".to_string(), counted_fetch(&calls, 7)).await.unwrap(), 7);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_misses_share_one_fetch() {
+        let cache: TtlCache<String, u32> = TtlCache::new("test_coalesce", CachePolicy::fixed(Duration::from_secs(60), 10));
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let requests = (0..8).map(|_| {
+            let (cache, calls) = (cache.clone(), Arc::clone(&calls));
+            tokio::spawn(async move {
+                cache.get_or_fetch("NIFTY".to_string(), move || async move {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    Ok(42)
+                })
+                .await
+                .unwrap()
+            })
+        });
+        for request in requests.collect::<Vec<_>>() {
+            assert_eq!(request.await.unwrap(), 42);
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        let stats = cache.stats();
+        assert_eq!((stats.misses, stats.coalesced, stats.in_flight, stats.entries), (8, 7, 0, 1));
+
+        // Errors are shared with waiters but not cached
+        let result = cache.get_or_fetch("BAD".to_string(), || async { Err(anyhow!("upstream down")) }).await;
+        assert!(result.unwrap_err().to_string().contains("upstream down"));
+        assert_eq!(cache.get_or_fetch("BAD".to_string(), counted_fetch(&calls, 1)).await.unwrap(), 1);
+        assert_eq!(cache.stats().errors, 1);
+    }
+
+    #[tokio::test]
+    async fn test_stale_while_revalidate() {
+        let policy = CachePolicy { stale: Duration::from_secs(60), ..CachePolicy::fixed(Duration::from_millis(20), 10) };
+        let cache: TtlCache<String, u32> = TtlCache::new("test_stale", policy);
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        cache.insert("GOLD".to_string(), 1);
+        tokio::time::sleep(Duration::from_millis(40)).await;
+
+        // Expired but within the stale window: old value now, refreshed in the background
+        assert_eq!(cache.get_or_fetch("GOLD".to_string(), counted_fetch(&calls, 2)).await.unwrap(), 1);
+        for _ in 0..50 {
+            if cache.get(&"GOLD".to_string()).is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert_eq!(cache.get_or_fetch("GOLD".to_string(), counted_fetch(&calls, 3)).await.unwrap(), 2);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let stats = cache.stats();
+        assert_eq!((stats.stale_hits, stats.refreshes), (1, 1));
+    }
+
+    #[test]
+    fn test_market_hours() {
+        let hours = MarketHours {
+            open: NaiveTime::from_hms_opt(9, 15, 0).unwrap(),
+            close: NaiveTime::from_hms_opt(15, 30, 0).unwrap(),
+        };
+        // 2025-12-18 is a Thursday; 04:00 UTC = 09:30 IST
+        assert!(hours.is_open_at(Utc.with_ymd_and_hms(2025, 12, 18, 4, 0, 0).unwrap()));
+        assert!(!hours.is_open_at(Utc.with_ymd_and_hms(2025, 12, 18, 3, 30, 0).unwrap()));  // 09:00 IST
+        assert!(!hours.is_open_at(Utc.with_ymd_and_hms(2025, 12, 18, 10, 0, 0).unwrap()));  // 15:30 IST
+        assert!(!hours.is_open_at(Utc.with_ymd_and_hms(2025, 12, 20, 4, 0, 0).unwrap()));  // Saturday
+
+        let policy = CachePolicy {
+            market_ttl: Duration::from_secs(30),
+            market_hours: Some(MarketHours { open: NaiveTime::MIN, close: NaiveTime::from_hms_opt(23, 59, 59).unwrap() }),
+            ..CachePolicy::fixed(Duration::from_secs(900), 10)
+        };
+        let weekday = !matches!(
+            chrono::Datelike::weekday(&Utc::now().with_timezone(&chrono::FixedOffset::east_opt(19_800).unwrap())),
+            chrono::Weekday::Sat | chrono::Weekday::Sun
+        );
+        assert_eq!(policy.current_ttl(), Duration::from_secs(if weekday { 30 } else { 900 }));
+    }
+
+    #[tokio::test]
+    async fn test_prewarm_refresh_before_expiry() {
+        let cache: TtlCache<String, u32> = TtlCache::new("test_prewarm", CachePolicy::fixed(Duration::from_secs(30), 10));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let key = "NIFTY".to_string();
+
+        assert!(cache.expires_within(&key, Duration::from_secs(10)));
+        assert_eq!(cache.refresh(key.clone(), counted_fetch(&calls, 1)).await.unwrap(), 1);
+        assert!(!cache.expires_within(&key, Duration::from_secs(10)));
+        assert!(cache.expires_within(&key, Duration::from_secs(30)));
+
+        // A refresh replaces a fresh entry
+        assert_eq!(cache.refresh(key.clone(), counted_fetch(&calls, 2)).await.unwrap(), 2);
+        assert_eq!(cache.get(&key), Some(2));
+        assert_eq!((calls.load(Ordering::SeqCst), cache.stats().refreshes), (2, 2));
+
+        // Nothing to keep warm when caching is disabled
+        let uncached: TtlCache<String, u32> = TtlCache::new("test_prewarm_off", CachePolicy::fixed(Duration::ZERO, 10));
+        assert!(!uncached.expires_within(&key, Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn test_prewarm_schedule_and_symbols() {
+        let schedule = PrewarmSchedule {
+            interval: Duration::from_secs(10),
+            idle_interval: Duration::from_secs(60),
+            market_hours: None,
+        };
+        assert_eq!(schedule.next_delay(true, 0), Duration::from_secs(10));
+        assert_eq!(schedule.next_delay(true, 2), Duration::from_secs(40));
+        assert_eq!(schedule.next_delay(true, 30), Duration::from_secs(60));  // Capped
+        assert_eq!(schedule.next_delay(false, 0), Duration::from_secs(60));
+
+        let mut settings = Settings::default().nse;
+        settings.prewarm.symbols = vec!["reliance".to_string(), "NIFTY".to_string()];
+        let symbols = prewarm::hot_symbols(&settings);
+        assert_eq!(&symbols[..settings.indices.len()], settings.indices.as_slice());
+        assert_eq!(symbols.iter().filter(|s| *s == "NIFTY").count(), 1);
+        assert!(symbols.contains(&"RELIANCE".to_string()));
+    }
+}
@@ -0,0 +1,122 @@
+use clap::Parser;
+use nse_analyzer::cli::{Cli, CliCommands, Command, ExportArgs, ExportFormat, RulesCommand};
+use nse_analyzer::screener::{metrics_to_csv, SymbolMetrics, METRIC_FIELDS};
+use nse_analyzer::utility::watchlist;
+use serde_json::json;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subcommand_parsing() {
+        let cli = Cli::try_parse_from(["nse-analyzer", "batch", "--exchange", "nse", "--watchlist", "NIFTY,TCS"]).unwrap();
+        match cli.command {
+            Some(Command::Batch(args)) => {
+                assert_eq!(args.exchange, "nse");
+                assert_eq!(args.watchlist.as_deref(), Some("NIFTY,TCS"));
+            }
+            other => panic!("expected batch, got {:?}", other),
+        }
+
+        let cli = Cli::try_parse_from(["nse-analyzer", "single", "NIFTY", "--expiry", "30-Dec-2025"]).unwrap();
+        match cli.command {
+            Some(Command::Single(args)) => {
+                assert_eq!(args.symbol.as_deref(), Some("NIFTY"));
+                assert_eq!(args.expiry.as_deref(), Some("30-Dec-2025"));
+            }
+            other => panic!("expected single, got {:?}", other),
+        }
+
+        let cli = Cli::try_parse_from(["nse-analyzer", "rules", "test", "RELIANCE", "--json"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Rules(RulesCommand::Test(ref args))) if args.input == "RELIANCE" && args.json));
+
+        let cli = Cli::try_parse_from(["nse-analyzer", "export", "--format", "json"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Export(ref args)) if args.format == ExportFormat::Json));
+
+        let cli = Cli::try_parse_from(["nse-analyzer", "openapi", "--exchange", "mcx", "-o", "mcx.json"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Openapi(ref args)) if args.exchange == "mcx" && args.output.is_some()));
+
+        // No subcommand falls back to the MODE / EXCHANGE env configuration
+        assert!(Cli::try_parse_from(["nse-analyzer"]).unwrap().command.is_none());
+
+        // "both" is only a batch exchange
+        assert!(Cli::try_parse_from(["nse-analyzer", "serve", "--exchange", "both"]).is_err());
+        assert!(Cli::try_parse_from(["nse-analyzer", "serve", "--port", "not-a-port"]).is_err());
+    }
+
+    #[test]
+    fn test_watchlist_parse_and_filter() {
+        let symbols = watchlist::parse_symbols("nifty, BANKNIFTY\n# indices above\nreliance tcs # equities\nNIFTY\n");
+        assert_eq!(symbols, vec!["NIFTY", "BANKNIFTY", "RELIANCE", "TCS"]);
+
+        let path = std::env::temp_dir().join(format!("watchlist_test_{}.txt", std::process::id()));
+        std::fs::write(&path, "INFY\n\nSBIN\n").unwrap();
+        assert_eq!(watchlist::parse(path.to_str().unwrap()).unwrap(), vec!["INFY", "SBIN"]);
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(watchlist::parse("NIFTY,TCS").unwrap(), vec!["NIFTY", "TCS"]);
+        assert!(watchlist::parse(" # nothing here ").is_err());
+
+        let items = vec!["NIFTY".to_string(), "TCS".to_string(), "INFY".to_string()];
+        let wanted = vec!["TCS".to_string(), "NIFTY".to_string(), "UNKNOWN".to_string()];
+        let (kept, missing) = watchlist::filter(items, &wanted, |s| s.as_str());
+        assert_eq!(kept, vec!["NIFTY", "TCS"]);
+        assert_eq!(missing, vec!["UNKNOWN"]);
+    }
+
+    #[test]
+    fn test_metrics_csv() {
+        let metrics = vec![SymbolMetrics {
+            symbol: "M&M".to_string(),
+            timestamp: "18-Dec-2025 15:30:00".to_string(),
+            expiry: Some("30-Dec-2025".to_string()),
+            underlying_value: 3000.5,
+            pcr: None,
+            alerts: 2,
+            ..Default::default()
+        }];
+        let csv = metrics_to_csv(&metrics);
+        let mut lines = csv.lines();
+
+        let header: Vec<&str> = lines.next().unwrap().split(',').collect();
+        assert_eq!(header.len(), 3 + METRIC_FIELDS.len());
+        assert_eq!(&header[..4], &["symbol", "timestamp", "expiry", "underlying_value"]);
+
+        let row: Vec<&str> = lines.next().unwrap().split(',').collect();
+        assert_eq!(row.len(), header.len());
+        assert_eq!(&row[..4], &["M&M", "18-Dec-2025 15:30:00", "30-Dec-2025", "3000.5"]);
+        let column = |name: &str| row[header.iter().position(|h| *h == name).unwrap()];
+        assert_eq!(column("pcr"), "");
+        assert_eq!(column("alerts"), "2");
+    }
+
+    #[test]
+    fn test_export_from_processed_data() {
+        let dir = std::env::temp_dir().join(format!("cli_export_test_{}", std::process::id()));
+        let data_dir = dir.join("processed_data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+
+        let side = |oi: f64| json!({ "openInterest": oi, "changeinOpenInterest": 0.0, "lastPrice": 5.0, "the_money": "ATM", "tambu": null, "time_val": 0.0, "days_to_expiry": 10 });
+        let record = json!({
+            "record": { "symbol": "NIFTY", "timestamp": "18-Dec-2025 15:30:00", "underlying_value": 25000.0, "spread": 2.5, "ce_oi": 100.0, "pe_oi": 150.0 },
+            "data": [{ "expiryDates": "30-Dec-2025", "strikePrice": 25000.0, "CE": side(100.0), "PE": side(150.0), "days_to_expiry": 10 }],
+        });
+        std::fs::write(data_dir.join("NIFTY.json"), record.to_string()).unwrap();
+
+        let output = dir.join("metrics.csv");
+        CliCommands::export(&ExportArgs {
+            format: ExportFormat::Csv,
+            output: Some(output.clone()),
+            dir: data_dir.clone(),
+            rules: dir.join("missing_rules.json"),
+        })
+        .unwrap();
+
+        let csv = std::fs::read_to_string(&output).unwrap();
+        assert_eq!(csv.lines().count(), 2);
+        assert!(csv.lines().nth(1).unwrap().starts_with("NIFTY,18-Dec-2025 15:30:00,30-Dec-2025,25000,2.5,"));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,56 @@
+use nse_analyzer::contracts::{
+    parse_nse_market_lots,
+    ContractRegistry,
+    ContractSpec,
+    Exchange,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nse_market_lots() {
+        let csv = "\
+UNDERLYING                                        ,SYMBOL    ,DEC-25    ,JAN-26    ,
+Derivatives on Individual Securities              ,Symbol    ,          ,          ,
+NIFTY 50                                          ,NIFTY     ,75        ,75        ,
+RELIANCE INDUSTRIES LTD                           ,RELIANCE  ,          ,500       ,
+";
+        let lots = parse_nse_market_lots(csv);
+
+        assert_eq!(lots.len(), 2);
+        assert_eq!(lots.get("NIFTY"), Some(&75.0));
+        // Falls through to the first populated month
+        assert_eq!(lots.get("RELIANCE"), Some(&500.0));
+    }
+
+    #[test]
+    fn test_registry_defaults_and_merge() {
+        let mut registry = ContractRegistry::with_defaults();
+
+        let crude = registry.mcx("crudeoil").unwrap();
+        assert_eq!(crude.exchange, Exchange::Mcx);
+        assert_eq!(crude.point_value(), 100.0);
+        assert_eq!(crude.unit.as_deref(), Some("100 BBL"));
+
+        assert!(registry.nse("NIFTY").is_some());
+        assert!(registry.nse("RELIANCE").is_none());
+
+        let lots = [("RELIANCE".to_string(), 500.0), ("NIFTY".to_string(), 65.0)].into_iter().collect();
+        registry.merge_nse_lot_sizes(&lots);
+
+        assert_eq!(registry.nse("RELIANCE").unwrap().lot_size, 500.0);
+        assert_eq!(registry.nse("NIFTY").unwrap().lot_size, 65.0);
+    }
+
+    #[test]
+    fn test_notional_and_tick_value() {
+        let spec = ContractSpec::nse("NIFTY", 75.0);
+        assert_eq!(spec.notional(24000.0, 10.0), 24000.0 * 10.0 * 75.0);
+        assert!((spec.tick_value() - 3.75).abs() < 1e-9);
+
+        let gold = ContractSpec::mcx("GOLD", 100.0, 1.0, "1 KG");
+        assert_eq!(gold.notional(75000.0, 2.0), 75000.0 * 2.0 * 100.0);
+    }
+}
@@ -0,0 +1,120 @@
+use nse_analyzer::analytics::{normalize_series, parse_series_date, OhlcBar, SeriesMeta};
+use nse_analyzer::mcx::processor::build_historic_series;
+use nse_analyzer::nse::processor::build_historical_series;
+use nse_analyzer::nse::NseHistoricalResponse;
+use chrono::NaiveDate;
+use serde_json::json;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 12, day).unwrap()
+    }
+
+    fn bar(day: u32, close: f64, open_interest: f64) -> OhlcBar {
+        OhlcBar {
+            open_interest: Some(open_interest),
+            volume: Some(1000.0),
+            ..OhlcBar::new(date(day), None, None, None, close)
+        }
+    }
+
+    #[test]
+    fn test_parse_series_dates() {
+        assert_eq!(parse_series_date("15-Dec-2025"), Some(date(15)));
+        assert_eq!(parse_series_date("2025-12-15T00:00:00.000+00:00"), Some(date(15)));
+        assert_eq!(parse_series_date("2025-12-15T00:00:00.000Z"), Some(date(15)));
+        assert_eq!(parse_series_date("20251215"), Some(date(15)));
+        assert!(parse_series_date("/Date(1765792800000)/").is_some());
+        assert!(parse_series_date("not a date").is_none());
+    }
+
+    #[test]
+    fn test_normalize_returns_oi_and_rolling_stats() {
+        // Out of order, with a stale duplicate for the 16th
+        let bars = vec![
+            bar(17, 110.0, 1300.0),
+            bar(15, 100.0, 1000.0),
+            bar(16, 90.0, 900.0),
+            bar(16, 105.0, 1200.0),
+            bar(18, 99.0, 1250.0),
+        ];
+        let series = normalize_series(SeriesMeta::default(), bars, 3);
+
+        let dates: Vec<NaiveDate> = series.bars.iter().map(|b| b.date).collect();
+        assert_eq!(dates, vec![date(15), date(16), date(17), date(18)]);
+        assert_eq!(series.bars[1].close, 105.0);
+
+        assert_eq!(series.bars[0].return_pct, None);
+        assert!((series.bars[1].return_pct.unwrap() - 5.0).abs() < 1e-9);
+        assert_eq!(series.bars[2].change_in_oi, Some(100.0));
+        assert_eq!(series.bars[3].change_in_oi, Some(-50.0));
+
+        // Rolling window of 3 returns is only complete on the last bar
+        assert!(series.bars[2].rolling_volatility_pct.is_none());
+        assert!(series.bars[3].rolling_volatility_pct.unwrap() > 0.0);
+
+        assert_eq!(series.stats.bars, 4);
+        assert!((series.stats.period_return_pct.unwrap() + 1.0).abs() < 1e-9);
+        assert!((series.stats.max_drawdown_pct.unwrap() - 10.0).abs() < 1e-9);
+        assert_eq!(series.stats.net_change_in_oi, Some(250.0));
+        assert_eq!(series.stats.total_volume, Some(4000.0));
+    }
+
+    #[test]
+    fn test_nse_records_parse_leniently() {
+        let response: NseHistoricalResponse = serde_json::from_value(json!({
+            "data": [
+                {
+                    "FH_TIMESTAMP": "16-Dec-2025",
+                    "FH_OPENING_PRICE": "1,200.50",
+                    "FH_TRADE_HIGH_PRICE": 1250,
+                    "FH_TRADE_LOW_PRICE": "-",
+                    "FH_CLOSING_PRICE": "0",
+                    "FH_SETTLE_PRICE": "1,240.00",
+                    "FH_OPEN_INT": "5000",
+                    "FH_TOT_TRADED_QTY": 300
+                },
+                {
+                    "FH_TIMESTAMP": "15-Dec-2025",
+                    "FH_CLOSING_PRICE": 1180.0,
+                    "FH_OPEN_INT": 4500
+                },
+                { "FH_TIMESTAMP": "bad date", "FH_CLOSING_PRICE": 1.0 }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(response.data[0].open, Some(1200.5));
+        assert_eq!(response.data[0].low, None);
+
+        let series = build_historical_series(&response.data, SeriesMeta::default(), 5);
+        assert_eq!(series.bars.len(), 2);
+        // No trade close: falls back to the settle price
+        assert_eq!(series.bars[1].close, 1240.0);
+        assert_eq!(series.bars[1].low, 1200.5);
+        assert_eq!(series.bars[1].change_in_oi, Some(500.0));
+    }
+
+    #[test]
+    fn test_mcx_series_matches_shape() {
+        let data = json!({
+            "d": {
+                "Data": [
+                    { "Date": "/Date(1765737000000)/", "Open": 6000.0, "High": 6100.0, "Low": 5950.0,
+                      "Close": 6050.0, "Volume": 120, "OpenInterest": 800 },
+                    { "Date": "/Date(1765823400000)/", "Open": 6050.0, "High": 6200.0, "Low": 6000.0,
+                      "Close": 6150.0, "Volume": 80, "OpenInterest": 900 }
+                ]
+            }
+        });
+        let series = build_historic_series(&data, SeriesMeta::default(), 5);
+        assert_eq!(series.bars.len(), 2);
+        assert_eq!(series.bars[1].change_in_oi, Some(100.0));
+        assert_eq!(series.stats.highest_high, Some(6200.0));
+
+        let value = serde_json::to_value(&series).unwrap();
+        assert!(value.get("bars").is_some() && value.get("stats").is_some() && value.get("exchange").is_some());
+    }
+}
@@ -0,0 +1,109 @@
+use nse_analyzer::analytics::indicators::{atr, bollinger, ema, rsi, sma, vwap};
+use nse_analyzer::analytics::{apply_indicators, normalize_series, IndicatorSpec, OhlcBar, SeriesMeta};
+use chrono::NaiveDate;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bars(closes: &[f64]) -> Vec<OhlcBar> {
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, close)| OhlcBar {
+                volume: Some(100.0 * (i + 1) as f64),
+                open_interest: Some(1000.0 + 10.0 * i as f64),
+                ..OhlcBar::new(
+                    NaiveDate::from_ymd_opt(2025, 12, 1).unwrap() + chrono::Days::new(i as u64),
+                    Some(*close),
+                    Some(close + 2.0),
+                    Some(close - 2.0),
+                    *close,
+                )
+            })
+            .collect()
+    }
+
+    fn values(v: &[f64]) -> Vec<Option<f64>> {
+        v.iter().copied().map(Some).collect()
+    }
+
+    #[test]
+    fn test_parse_indicator_list() {
+        let specs = IndicatorSpec::parse_list("sma:20, ema ,rsi:7,bb:10:1.5,vwap,oi_sma").unwrap();
+        assert_eq!(specs, vec![
+            IndicatorSpec::Sma { period: 20 },
+            IndicatorSpec::Ema { period: 20 },
+            IndicatorSpec::Rsi { period: 7 },
+            IndicatorSpec::Bollinger { period: 10, width: 1.5 },
+            IndicatorSpec::Vwap,
+            IndicatorSpec::OiSma { period: 5 },
+        ]);
+        assert!(IndicatorSpec::parse_list("").unwrap().is_empty());
+        assert!(IndicatorSpec::parse_list("macd").is_err());
+        assert!(IndicatorSpec::parse_list("sma:0").is_err());
+        assert!(IndicatorSpec::parse_list("bb:20:-1").is_err());
+    }
+
+    #[test]
+    fn test_moving_averages() {
+        let closes = values(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert_eq!(sma(&closes, 3), vec![None, None, Some(2.0), Some(3.0), Some(4.0)]);
+
+        // Seeded with SMA(3) = 2, then alpha = 0.5
+        assert_eq!(ema(&closes, 3), vec![None, None, Some(2.0), Some(3.0), Some(4.0)]);
+
+        // A gap breaks the average until the window is full again
+        let gappy = vec![Some(1.0), None, Some(3.0), Some(4.0)];
+        assert_eq!(sma(&gappy, 2), vec![None, None, None, Some(3.5)]);
+    }
+
+    #[test]
+    fn test_rsi_and_atr() {
+        let rising = bars(&[10.0, 11.0, 12.0, 13.0, 14.0]);
+        let out = rsi(&rising, 3);
+        assert_eq!(out[..3], [None, None, None]);
+        assert_eq!(out[3], Some(100.0));
+
+        let mixed = bars(&[10.0, 12.0, 11.0, 13.0]);
+        // Gains 2, 2; loss 1 over 3 changes
+        let value = rsi(&mixed, 3)[3].unwrap();
+        assert!((value - 80.0).abs() < 1e-9);
+
+        // High-low range is 4; the gap from 10 to 20 widens the true range to 12
+        let gapped = bars(&[10.0, 10.0, 20.0]);
+        let out = atr(&gapped, 2);
+        assert_eq!(out[0], None);
+        assert_eq!(out[1], Some(4.0));
+        assert_eq!(out[2], Some(8.0));
+    }
+
+    #[test]
+    fn test_bollinger_and_vwap() {
+        let (upper, middle, lower) = bollinger(&values(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]), 8, 2.0);
+        assert_eq!(middle[7], Some(5.0));
+        assert_eq!(upper[7], Some(9.0));
+        assert_eq!(lower[7], Some(1.0));
+        assert_eq!(upper[6], None);
+
+        // Typical price equals close here; volumes 100, 200
+        let out = vwap(&bars(&[10.0, 13.0]));
+        assert_eq!(out[0], Some(10.0));
+        assert!((out[1].unwrap() - 12.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_indicators_attach_to_series() {
+        let mut series = normalize_series(SeriesMeta::default(), bars(&[10.0, 11.0, 12.0, 13.0]), 2);
+        let specs = IndicatorSpec::parse_list("sma:2,bb:2,oi_sma:2").unwrap();
+        apply_indicators(&mut series, &specs);
+
+        for key in ["sma_2", "bb_2_2_upper", "bb_2_2_middle", "bb_2_2_lower", "oi_sma_2"] {
+            assert_eq!(series.indicators[key].len(), series.bars.len(), "{}", key);
+        }
+        assert_eq!(series.indicators["oi_sma_2"][1], Some(1005.0));
+
+        let json = serde_json::to_value(&series).unwrap();
+        assert!(json["indicators"]["sma_2"].is_array());
+    }
+}
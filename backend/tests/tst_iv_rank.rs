@@ -0,0 +1,133 @@
+use nse_analyzer::analytics::{
+    compute_iv_rank, iv_rank, realized_volatility, record_atm_ivs, IvHistoryStore, IvObservation, OhlcBar,
+};
+use nse_analyzer::contracts::Exchange;
+use nse_analyzer::nse::rules::check_iv_percentile_rule;
+use chrono::{Days, NaiveDate};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(offset: u64) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 1, 1).unwrap() + Days::new(offset)
+    }
+
+    fn history(ivs: &[f64]) -> Vec<IvObservation> {
+        ivs.iter()
+            .enumerate()
+            .map(|(i, iv)| IvObservation { date: day(i as u64), atm_iv: *iv })
+            .collect()
+    }
+
+    #[test]
+    fn test_iv_rank_and_percentile() {
+        // Current 15 in a 10..20 range; 2 of the 4 earlier days were lower
+        let obs = history(&[10.0, 20.0, 12.0, 18.0, 15.0]);
+        let (rank, percentile) = iv_rank(&obs, 30);
+        assert!((rank.unwrap() - 50.0).abs() < 1e-9);
+        assert!((percentile.unwrap() - 50.0).abs() < 1e-9);
+
+        // A 2-day window only sees 18 -> 15
+        let (rank, percentile) = iv_rank(&obs, 2);
+        assert_eq!(rank, Some(0.0));
+        assert_eq!(percentile, Some(0.0));
+
+        // Not enough history
+        assert_eq!(iv_rank(&obs[..1], 30), (None, None));
+
+        let stats = compute_iv_rank(&obs).unwrap();
+        assert_eq!(stats.atm_iv, 15.0);
+        assert_eq!(stats.observations, 5);
+        assert_eq!(stats.iv_rank_30, stats.iv_rank_252);
+    }
+
+    #[test]
+    fn test_store_records_daily_and_round_trips() {
+        let mut store = IvHistoryStore::default();
+        store.record(Exchange::Nse, "nifty", day(0), 14.0);
+        store.record(Exchange::Nse, "NIFTY", day(1), 16.0);
+        // Same day again: latest value wins
+        let stats = store.record(Exchange::Nse, "NIFTY", day(1), 12.0).unwrap();
+        assert_eq!(stats.observations, 2);
+        assert_eq!(stats.iv_percentile_30, Some(0.0));
+        assert!(store.record(Exchange::Nse, "NIFTY", day(2), f64::NAN).is_none());
+
+        assert_eq!(store.history(Exchange::Nse, "NIFTY").len(), 2);
+        assert!(store.history(Exchange::Mcx, "NIFTY").is_empty());
+
+        let path = std::env::temp_dir().join(format!("iv_history_test_{}.json", std::process::id()));
+        store.save_file(&path).unwrap();
+        let entries = vec![("NIFTY".to_string(), day(2), 20.0), ("CRUDEOIL".to_string(), day(2), 40.0)];
+        let ranks = record_atm_ivs(&path, Exchange::Nse, &entries).unwrap();
+        assert_eq!(ranks["NIFTY"].observations, 3);
+        assert_eq!(ranks["NIFTY"].iv_rank_30, Some(100.0));
+        assert_eq!(ranks["CRUDEOIL"].observations, 1);
+
+        let reloaded = IvHistoryStore::load_file(&path).unwrap();
+        assert_eq!(reloaded.history(Exchange::Nse, "NIFTY").len(), 3);
+        std::fs::remove_file(&path).unwrap();
+        let _ = std::fs::remove_file(path.with_extension("json.lock"));
+    }
+
+    #[test]
+    fn test_rank_does_not_record() {
+        let mut store = IvHistoryStore::default();
+        store.record(Exchange::Nse, "NIFTY", day(0), 14.0);
+        store.record(Exchange::Nse, "NIFTY", day(1), 16.0);
+
+        // A single-analysis lookup ranks against history but leaves it as batch recorded it
+        let stats = store.rank(Exchange::Nse, "NIFTY", day(1), 20.0).unwrap();
+        assert_eq!(stats.atm_iv, 20.0);
+        assert_eq!(stats.iv_percentile_30, Some(100.0));
+        assert_eq!(store.history(Exchange::Nse, "NIFTY")[1].atm_iv, 16.0);
+        assert!(store.rank(Exchange::Nse, "BANKNIFTY", day(1), 20.0).is_some());
+        assert!(store.history(Exchange::Nse, "BANKNIFTY").is_empty());
+    }
+
+    #[test]
+    fn test_extreme_iv_percentile_rule() {
+        // 25 days of rising IV: today is the highest
+        let rising: Vec<f64> = (0..25).map(|i| 10.0 + i as f64).collect();
+        let stats = compute_iv_rank(&history(&rising)).unwrap();
+        let alert = check_iv_percentile_rule("NIFTY", "30-Dec-2025", &stats, 50.0, 5).unwrap();
+        assert_eq!(alert.alert_type, "IV_PERCENTILE_HIGH");
+        assert_eq!(alert.option_type, "IV");
+        assert_eq!(alert.values.last_price, Some(34.0));
+
+        let falling: Vec<f64> = rising.iter().rev().copied().collect();
+        let stats = compute_iv_rank(&history(&falling)).unwrap();
+        let alert = check_iv_percentile_rule("NIFTY", "30-Dec-2025", &stats, 50.0, 5).unwrap();
+        assert_eq!(alert.alert_type, "IV_PERCENTILE_LOW");
+
+        // Too little history to trust the percentile
+        let stats = compute_iv_rank(&history(&rising[..10])).unwrap();
+        assert!(check_iv_percentile_rule("NIFTY", "30-Dec-2025", &stats, 50.0, 5).is_none());
+
+        // Mid-range
+        let mut middle = rising.clone();
+        middle.push(22.0);
+        let stats = compute_iv_rank(&history(&middle)).unwrap();
+        assert!(check_iv_percentile_rule("NIFTY", "30-Dec-2025", &stats, 50.0, 5).is_none());
+    }
+
+    #[test]
+    fn test_realized_volatility_estimators() {
+        let bars: Vec<OhlcBar> = [100.0, 110.0, 100.0]
+            .iter()
+            .enumerate()
+            .map(|(i, close)| OhlcBar::new(day(i as u64), Some(*close), Some(close * 1.02), Some(close * 0.98), *close))
+            .collect();
+        let rv = realized_volatility(&bars);
+        assert_eq!(rv.bars, 3);
+
+        let expected_cc = 2f64.sqrt() * 1.1f64.ln() * 252f64.sqrt() * 100.0;
+        assert!((rv.close_to_close_pct.unwrap() - expected_cc).abs() < 1e-9);
+
+        // Open == close, so Garman-Klass / Parkinson = sqrt(2 ln 2)
+        let ratio = rv.garman_klass_pct.unwrap() / rv.parkinson_pct.unwrap();
+        assert!((ratio - (2.0 * 2f64.ln()).sqrt()).abs() < 1e-9);
+
+        assert_eq!(realized_volatility(&bars[..1]).close_to_close_pct, None);
+    }
+}
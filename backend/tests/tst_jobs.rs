@@ -0,0 +1,194 @@
+use nse_analyzer::jobs::{FetchTasks, JobProgress, JobRegistry, JobStatus};
+use anyhow::anyhow;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn wait_finished(registry: &JobRegistry<u32>, id: &str) -> JobStatus {
+        for _ in 0..100 {
+            let job = registry.get(id).await.unwrap();
+            if job.status.is_finished() {
+                return job.status;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("job {} did not finish", id);
+    }
+
+    #[test]
+    fn test_progress_snapshot() {
+        let progress = JobProgress::new();
+        progress.set_stage("fetching");
+        progress.add_total(4);
+        progress.record_success("NIFTY");
+        progress.record_failure("INFY", "timeout");
+
+        let snapshot = progress.snapshot();
+        assert_eq!(snapshot.stage, "fetching");
+        assert_eq!((snapshot.total, snapshot.fetched, snapshot.failed, snapshot.pending), (4, 1, 1, 2));
+        assert_eq!(snapshot.percent, 50.0);
+        assert_eq!(snapshot.completed_symbols, vec!["NIFTY"]);
+        assert_eq!(snapshot.failures[0].symbol, "INFY");
+        assert_eq!(snapshot.failures[0].error, "timeout");
+
+        assert!(progress.check_cancelled().is_ok());
+        progress.cancel();
+        assert!(progress.check_cancelled().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_job_completes_and_result_is_kept() {
+        let registry: JobRegistry<u32> = JobRegistry::new();
+        let (tx, rx) = oneshot::channel::<()>();
+
+        let id = registry
+            .spawn("batch", |progress| async move {
+                progress.add_total(1);
+                progress.record_success("NIFTY");
+                rx.await.ok();
+                Ok(42)
+            })
+            .await;
+
+        // A second request while running joins the same job
+        let again = registry.spawn("batch", |_| async { Ok(0) }).await;
+        assert_eq!(again, id);
+
+        let running = registry.get(&id).await.unwrap();
+        assert_eq!(running.status, JobStatus::Running);
+        assert!(running.result.is_none());
+        assert!(registry.latest_result("batch").await.is_none());
+
+        tx.send(()).unwrap();
+        assert_eq!(wait_finished(&registry, &id).await, JobStatus::Completed);
+
+        let done = registry.get(&id).await.unwrap();
+        assert_eq!(done.result, Some(42));
+        assert_eq!(done.progress.fetched, 1);
+        assert!(done.finished_at.is_some());
+        assert_eq!(registry.latest_result("batch").await, Some(42));
+
+        // Listing omits results
+        let listed = registry.list().await;
+        assert_eq!(listed.len(), 1);
+        assert!(listed[0].result.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_job_failure_and_cancel() {
+        let registry: JobRegistry<u32> = JobRegistry::new();
+
+        let failed = registry.spawn("failing", |_| async { Err(anyhow!("NSE unavailable")) }).await;
+        assert_eq!(wait_finished(&registry, &failed).await, JobStatus::Failed);
+        assert_eq!(registry.get(&failed).await.unwrap().error.as_deref(), Some("NSE unavailable"));
+
+        let slow = registry
+            .spawn("slow", |_| async {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                Ok(1)
+            })
+            .await;
+        let cancelled = registry.cancel(&slow).await.unwrap();
+        assert_eq!(cancelled.status, JobStatus::Cancelled);
+        assert!(cancelled.finished_at.is_some());
+
+        // Cancelling again or an unknown id
+        assert_eq!(registry.cancel(&slow).await.unwrap().status, JobStatus::Cancelled);
+        assert!(registry.cancel("missing").await.is_none());
+        assert!(registry.get("missing").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_partial_results_until_completion() {
+        let registry: JobRegistry<u32> = JobRegistry::new();
+        let (tx, rx) = oneshot::channel::<()>();
+
+        let id = registry
+            .spawn("batch", |progress| async move {
+                progress.record_partial(&serde_json::json!({ "symbol": "NIFTY", "alerts": [] }));
+                rx.await.ok();
+                Ok(1)
+            })
+            .await;
+        let slow = registry
+            .spawn("slow", |progress| async move {
+                progress.record_partial(&serde_json::json!({ "symbol": "GOLD" }));
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                Ok(2)
+            })
+            .await;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let running = registry.get(&id).await.unwrap();
+        assert_eq!(running.partial_results.len(), 1);
+        assert_eq!(running.partial_results[0]["symbol"], "NIFTY");
+        assert!(registry.list().await.iter().all(|job| job.partial_results.is_empty()));
+
+        // Cancelled jobs keep what they had; completed jobs return the full result instead
+        registry.cancel(&slow).await.unwrap();
+        assert_eq!(registry.get(&slow).await.unwrap().partial_results[0]["symbol"], "GOLD");
+        tx.send(()).unwrap();
+        assert_eq!(wait_finished(&registry, &id).await, JobStatus::Completed);
+        assert!(registry.get(&id).await.unwrap().partial_results.is_empty());
+
+        // Progress outside a job (CLI batches) keeps nothing
+        let progress = JobProgress::new();
+        progress.record_partial(&1);
+        assert!(progress.partial_results().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_all_stops_running_jobs() {
+        let registry: JobRegistry<u32> = JobRegistry::new();
+        let done = registry.spawn("quick", |_| async { Ok(1) }).await;
+        assert_eq!(wait_finished(&registry, &done).await, JobStatus::Completed);
+
+        for name in ["slow-1", "slow-2"] {
+            registry
+                .spawn(name, |_| async {
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                    Ok(1)
+                })
+                .await;
+        }
+
+        assert_eq!(registry.cancel_all().await, 2);
+        let statuses: Vec<JobStatus> = registry.list().await.into_iter().map(|job| job.status).collect();
+        assert_eq!(statuses.iter().filter(|s| **s == JobStatus::Cancelled).count(), 2);
+        assert_eq!(registry.get(&done).await.unwrap().status, JobStatus::Completed);
+        assert_eq!(registry.cancel_all().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_tasks_keep_order_and_abort_on_drop() {
+        let mut tasks = FetchTasks::new();
+        for (i, delay) in [30u64, 0, 10].into_iter().enumerate() {
+            tasks.spawn(async move {
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+                if i == 1 { Err(anyhow!("symbol {} failed", i)) } else { Ok(i) }
+            });
+        }
+        let results = tasks.join_all().await;
+        assert_eq!(results[0].as_ref().unwrap(), &0);
+        assert!(results[1].is_err());
+        assert_eq!(results[2].as_ref().unwrap(), &2);
+
+        // Dropping the set aborts fetches still in flight
+        let finished = Arc::new(AtomicBool::new(false));
+        let mut tasks = FetchTasks::new();
+        let flag = finished.clone();
+        tasks.spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            flag.store(true, Ordering::SeqCst);
+            Ok(())
+        });
+        drop(tasks);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!finished.load(Ordering::SeqCst));
+    }
+}
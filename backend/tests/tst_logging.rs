@@ -0,0 +1,34 @@
+use nse_analyzer::utility::logging::LogFormat;
+use nse_analyzer::utility::{timed, timed_async, AggregateTimer, Timer};
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_format_parse() {
+        assert_eq!(LogFormat::parse("json"), LogFormat::Json);
+        assert_eq!(LogFormat::parse(" JSON "), LogFormat::Json);
+        assert_eq!(LogFormat::parse("compact"), LogFormat::Compact);
+        assert_eq!(LogFormat::parse("text"), LogFormat::Text);
+        assert_eq!(LogFormat::parse("pretty"), LogFormat::Text);
+    }
+
+    #[tokio::test]
+    async fn test_timing_wrappers_return_results() {
+        assert_eq!(timed("sync", || 2 + 2), 4);
+        assert_eq!(timed_async("async", || async { "done" }).await, "done");
+
+        let timer = Timer::silent("stopwatch");
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(timer.stop() >= Duration::from_millis(5));
+
+        let mut aggregate = AggregateTimer::new("batch");
+        assert!(aggregate.avg_duration().is_none());
+        aggregate.record(Duration::from_millis(10));
+        aggregate.record(Duration::from_millis(30));
+        assert_eq!(aggregate.avg_duration(), Some(Duration::from_millis(20)));
+        aggregate.summary();
+    }
+}
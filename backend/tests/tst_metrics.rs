@@ -0,0 +1,66 @@
+use nse_analyzer::utility::metrics::{endpoint_label, retry_tracked, MetricsRegistry};
+use anyhow::anyhow;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_label() {
+        assert_eq!(endpoint_label("https://www.nseindia.com/api/option-chain-v3?type=Indices&symbol=NIFTY"), "/api/option-chain-v3");
+        assert_eq!(endpoint_label("https://www.mcxindia.com/backpage.aspx/GetOptionChain"), "/backpage.aspx/GetOptionChain");
+        assert_eq!(endpoint_label("https://www.nseindia.com"), "/");
+    }
+
+    #[test]
+    fn test_render_counters_gauges_and_histograms() {
+        let registry = MetricsRegistry::new();
+        registry.observe_upstream("/api/quote", Duration::from_millis(300), true);
+        registry.observe_upstream("/api/quote", Duration::from_secs(3), false);
+        registry.record_retry("/api/quote");
+        registry.record_cache("nse_contract_info", true);
+        registry.record_cache("nse_contract_info", false);
+        registry.record_cache("nse_contract_info", true);
+        registry.record_batch("nse", 180, 5);
+        registry.record_batch_failure("mcx");
+        registry.record_alerts("nse", ["LOW_PRICE", "LOW_PRICE", "NEW_POSITION"]);
+
+        let text = registry.render();
+        assert!(text.contains("# TYPE nse_analyzer_upstream_request_duration_seconds histogram"));
+        assert!(text.contains("nse_analyzer_upstream_request_duration_seconds_bucket{endpoint=\"/api/quote\",le=\"0.25\"} 0"));
+        assert!(text.contains("nse_analyzer_upstream_request_duration_seconds_bucket{endpoint=\"/api/quote\",le=\"0.5\"} 1"));
+        assert!(text.contains("nse_analyzer_upstream_request_duration_seconds_bucket{endpoint=\"/api/quote\",le=\"5\"} 2"));
+        assert!(text.contains("nse_analyzer_upstream_request_duration_seconds_bucket{endpoint=\"/api/quote\",le=\"+Inf\"} 2"));
+        assert!(text.contains("nse_analyzer_upstream_request_duration_seconds_count{endpoint=\"/api/quote\"} 2"));
+        assert!(text.contains("nse_analyzer_upstream_requests_total{endpoint=\"/api/quote\",outcome=\"error\"} 1"));
+        assert!(text.contains("nse_analyzer_upstream_retries_total{endpoint=\"/api/quote\"} 1"));
+        assert!(text.contains("nse_analyzer_cache_requests_total{cache=\"nse_contract_info\",result=\"hit\"} 2"));
+        assert!(text.contains("nse_analyzer_cache_requests_total{cache=\"nse_contract_info\",result=\"miss\"} 1"));
+        assert!(text.contains("nse_analyzer_batch_runs_total{exchange=\"nse\",status=\"success\"} 1"));
+        assert!(text.contains("nse_analyzer_batch_runs_total{exchange=\"mcx\",status=\"failed\"} 1"));
+        assert!(text.contains("nse_analyzer_batch_symbols_total{exchange=\"nse\",result=\"failed\"} 5"));
+        assert!(text.contains("nse_analyzer_alerts_fired_total{alert_type=\"LOW_PRICE\",exchange=\"nse\"} 2")
+            || text.contains("nse_analyzer_alerts_fired_total{exchange=\"nse\",alert_type=\"LOW_PRICE\"} 2"));
+        assert!(text.contains("nse_analyzer_last_successful_batch_timestamp_seconds{exchange=\"nse\"}"));
+        assert!(!text.contains("last_successful_batch_timestamp_seconds{exchange=\"mcx\"}"));
+    }
+
+    #[tokio::test]
+    async fn test_retry_tracked_returns_last_outcome() {
+        let mut calls = 0;
+        let result = retry_tracked("/api/test", vec![Duration::from_millis(1); 2], || {
+            calls += 1;
+            let attempt = calls;
+            async move {
+                if attempt < 3 { Err(anyhow!("upstream timeout")) } else { Ok(attempt) }
+            }
+        })
+        .await;
+        assert_eq!(result.unwrap(), 3);
+
+        let result: anyhow::Result<u32> =
+            retry_tracked("/api/test", vec![Duration::from_millis(1)], || async { Err(anyhow!("down")) }).await;
+        assert!(result.is_err());
+    }
+}
@@ -1,4 +1,5 @@
 
+use nse_analyzer::analytics::Buildup;
 use nse_analyzer::nse::{
     calculate_days_to_expiry, 
     find_atm_strike, 
@@ -6,6 +7,8 @@ use nse_analyzer::nse::{
     classify_money_with_distance, 
     calculate_time_value,
     calculate_oi_rankings,
+    enrich_nse_futures_data,
+    NseFuturesData,
     OptionData,
     OptionDetail,
 };
@@ -292,5 +295,26 @@ mod tests {
         assert_eq!(data[1].call.as_ref().unwrap().oi_rank, Some(1));
         assert_eq!(data[0].put.as_ref().unwrap().oi_rank, Some(1));
     }
-}
 
+    #[test]
+    fn test_futures_buildup_is_typed() {
+        let json = serde_json::json!({
+            "data": [
+                {"lastPrice": "22510.5", "pchange": 0.8, "pchangeinOpenInterest": 4.2, "identifier": "FUTIDXNIFTY"},
+                {"lastPrice": 22400.0, "pchange": -0.5, "pchangeinOpenInterest": -1.0}
+            ],
+            "timestamp": "18-Oct-2026 15:30:00"
+        });
+        let mut futures: NseFuturesData = serde_json::from_value(json).unwrap();
+        assert_eq!(futures.data[0].last_price, Some(22510.5));
+        assert_eq!(futures.data[0].action, Buildup::Neutral);
+
+        enrich_nse_futures_data(&mut futures);
+        assert_eq!(futures.data[0].action, Buildup::LongBuildup);
+        assert_eq!(futures.data[1].action, Buildup::LongCovering);
+
+        let out = serde_json::to_value(&futures).unwrap();
+        assert_eq!(out["data"][0]["action"], "Long Buildup");
+        assert_eq!(out["data"][0]["identifier"], "FUTIDXNIFTY");
+    }
+}
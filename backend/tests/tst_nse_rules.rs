@@ -1,8 +1,10 @@
 use nse_analyzer::nse::{
     ProcessedOptionDetail,
     check_option_rules,
+    check_gamma_flip_rule,
     OptionDetail
 };
+use nse_analyzer::analytics::{Buildup, GammaExposure, GammaRegime};
 
 #[cfg(test)]
 mod tests {
@@ -27,6 +29,8 @@ mod tests {
             tambu: None,
             time_val: 4.0,
             days_to_expiry: 15,
+            buildup: Buildup::LongBuildup,
+            probabilities: None,
         };
         
         let alerts = check_option_rules("NIFTY", 100.0, "30-DEC-2025", "CE", &detail, 2.5, 15,105.0);
@@ -55,6 +59,8 @@ mod tests {
             tambu: None,
             time_val: 4.0,
             days_to_expiry: 10,
+            buildup: Buildup::LongCovering,
+            probabilities: None,
         };
         
         let alerts = check_option_rules("NIFTY", 100.0, "30-DEC-2025", "CE", &detail, 3.0, 10,105.0);
@@ -84,6 +90,8 @@ mod tests {
             tambu: None,
             time_val: 1.5,
             days_to_expiry: 20,
+            buildup: Buildup::LongBuildup,
+            probabilities: None,
         };
         
         let alerts = check_option_rules("NIFTY", 100.0, "30-DEC-2025", "CE", &detail, 1.8, 20, 105.0);
@@ -112,6 +120,8 @@ mod tests {
             tambu: None,
             time_val: 1.0,
             days_to_expiry: 5,  // Less than 7 days
+            buildup: Buildup::LongBuildup,
+            probabilities: None,
         };
         
         let alerts = check_option_rules("NIFTY", 100.0, "15-DEC-2025", "CE", &detail, 2.2, 5, 105.0);
@@ -124,4 +134,58 @@ mod tests {
         assert!(alert_types.contains(&&"HUGE_OI_INCREASE".to_string()));
         assert!(alert_types.contains(&&"LOW_PRICE".to_string()));
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_fresh_short_buildup_detection() {
+        let detail = ProcessedOptionDetail {
+            base: OptionDetail {
+                strike_price: Some(100.0),
+                underlying_value: Some(105.0),
+                open_interest: Some(10000.0),
+                change_in_oi: Some(6000.0),
+                per_chg_oi: Some(150.0),  // Fresh positions
+                last_price: Some(8.0),
+                price_change: Some(-4.0),
+                per_chg_price: Some(-33.0),
+                oi_rank: Some(3),
+            },
+            the_money: "1 ITM".to_string(),
+            tambu: None,
+            time_val: 3.0,
+            days_to_expiry: 12,
+            buildup: Buildup::ShortBuildup,
+            probabilities: None,
+        };
+        
+        let alerts = check_option_rules("NIFTY", 100.0, "30-DEC-2025", "CE", &detail, 2.5, 12, 105.0);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].alert_type, "SHORT_BUILDUP");
+        assert_eq!(alerts[0].values.buildup, Buildup::ShortBuildup);
+    }
+
+    #[test]
+    fn test_gamma_flip_cross() {
+        let exposure = GammaExposure {
+            underlying_value: 24100.0,
+            total_call_gex: 5.0e9,
+            total_put_gex: 3.0e9,
+            net_gex: 2.0e9,
+            regime: GammaRegime::Positive,
+            zero_gamma_level: Some(24050.0),
+            max_gex_strike: Some(24500.0),
+            by_strike: Vec::new(),
+        };
+
+        let alert = check_gamma_flip_rule("NIFTY", "30-DEC-2025", 23980.0, &exposure, 1.5, 7).unwrap();
+        assert_eq!(alert.alert_type, "GAMMA_FLIP_UP");
+        assert_eq!(alert.strike_price, 24050.0);
+        assert_eq!(alert.values.last_price, Some(24100.0));
+
+        // Spot already above the flip level - no cross
+        assert!(check_gamma_flip_rule("NIFTY", "30-DEC-2025", 24070.0, &exposure, 1.5, 7).is_none());
+
+        let below = GammaExposure { underlying_value: 24000.0, regime: GammaRegime::Negative, ..exposure };
+        let alert = check_gamma_flip_rule("NIFTY", "30-DEC-2025", 24100.0, &below, 1.5, 7).unwrap();
+        assert_eq!(alert.alert_type, "GAMMA_FLIP_DOWN");
+    }
+}
@@ -0,0 +1,92 @@
+use nse_analyzer::mcx::mcx_api_server::McxApiDoc;
+use nse_analyzer::nse::nse_api_server::NseApiDoc;
+use nse_analyzer::utility::openapi_routes;
+use serde_json::Value;
+use utoipa::OpenApi;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect_refs(value: &Value, refs: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(r)) = map.get("$ref") {
+                    refs.push(r.clone());
+                }
+                map.values().for_each(|v| collect_refs(v, refs));
+            }
+            Value::Array(items) => items.iter().for_each(|v| collect_refs(v, refs)),
+            _ => {}
+        }
+    }
+
+    fn assert_refs_resolve(spec: &Value) {
+        let schemas = spec["components"]["schemas"].as_object().expect("components.schemas");
+        let mut refs = Vec::new();
+        collect_refs(spec, &mut refs);
+        assert!(!refs.is_empty());
+        for r in refs {
+            let name = r.strip_prefix("#/components/schemas/").expect("schema ref");
+            assert!(schemas.contains_key(name), "unresolved $ref {}", r);
+        }
+    }
+
+    #[test]
+    fn test_nse_spec_covers_routes_and_types() {
+        let spec: Value = serde_json::from_str(&NseApiDoc::openapi().to_json().unwrap()).unwrap();
+        assert!(spec["openapi"].as_str().unwrap().starts_with("3."));
+
+        let paths = spec["paths"].as_object().unwrap();
+        for path in [
+            "/nse_health", "/ready", "/api/nse/single-analysis", "/api/nse/batch-analysis", "/api/nse/strategy", "/api/nse/screener",
+            "/jobs", "/jobs/{id}", "/api/portfolio/positions", "/api/portfolio/summary", "/shutdown",
+        ] {
+            assert!(paths.contains_key(path), "missing {}", path);
+        }
+        assert!(paths["/api/nse/batch-analysis"].get("get").is_some());
+        assert!(paths["/api/nse/batch-analysis"].get("post").is_some());
+
+        let params: Vec<&str> = paths["/api/nse/single-analysis"]["get"]["parameters"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(params, ["symbol", "expiry"]);
+
+        let schemas = spec["components"]["schemas"].as_object().unwrap();
+        assert!(schemas.contains_key("SingleAnalysisResponse"));
+        assert!(schemas.contains_key("ProcessedOptionData"));
+        assert!(schemas["ApiResponse_SingleAnalysisResponse"]["properties"].get("processing_time_ms").is_some());
+        for schema in ["NseFuturesQuote", "HistoricalSeries", "OhlcBar", "Position"] {
+            assert!(schemas.contains_key(schema), "missing schema {}", schema);
+        }
+        assert!(schemas["NseFuturesQuote"]["properties"].get("action").is_some());
+        assert!(schemas.keys().any(|name| name.starts_with("ApiResponse_JobView")), "missing job schema");
+        assert_refs_resolve(&spec);
+    }
+
+    #[test]
+    fn test_mcx_spec_covers_routes_and_types() {
+        let spec: Value = serde_json::from_str(&McxApiDoc::openapi().to_json().unwrap()).unwrap();
+
+        let paths = spec["paths"].as_object().unwrap();
+        for path in ["/mcx_health", "/ready", "/api/mcx/option-chain", "/api/mcx/historic-data", "/api/mcx/strategy", "/jobs", "/api/portfolio/positions", "/shutdown"] {
+            assert!(paths.contains_key(path), "missing {}", path);
+        }
+
+        let schemas = spec["components"]["schemas"].as_object().unwrap();
+        assert!(schemas.contains_key("McxSingleAnalysisResponse"));
+        assert!(schemas.contains_key("EnhancedSingleAnalysisResponse"));
+        assert!(schemas.contains_key("McxRulesOutput"));
+        assert!(schemas.contains_key("HistoricalSeries"));
+        assert_refs_resolve(&spec);
+    }
+
+    #[test]
+    fn test_openapi_routes_build() {
+        assert!(openapi_routes(&NseApiDoc::openapi()).is_ok());
+        assert!(openapi_routes(&McxApiDoc::openapi()).is_ok());
+    }
+}
@@ -0,0 +1,289 @@
+use nse_analyzer::analytics::{OptionKind, Side};
+use nse_analyzer::contracts::Exchange;
+use nse_analyzer::portfolio::market::format_mcx_strike;
+use nse_analyzer::portfolio::{
+    merge_nse_risk_alerts,
+    run_risk_rules,
+    summarize,
+    Instrument,
+    Mark,
+    OpenPositionRequest,
+    PortfolioStore,
+    PositionStatus,
+    RiskConfig,
+};
+use nse_analyzer::utility::with_file_lock;
+use std::collections::HashMap;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn option_request(side: Side, kind: OptionKind) -> OpenPositionRequest {
+        OpenPositionRequest {
+            exchange: Exchange::Nse,
+            symbol: "nifty".to_string(),
+            expiry: "30-Dec-2025".to_string(),
+            instrument: Instrument::Option,
+            strike: Some(26000.0),
+            option_type: Some(kind),
+            side,
+            lots: 2.0,
+            entry_price: None,
+            point_value: None,
+            tag: Some("HIGH_VOLUME".to_string()),
+            stop_loss: None,
+            take_profit: None,
+        }
+    }
+
+    fn future_request(side: Side) -> OpenPositionRequest {
+        OpenPositionRequest {
+            exchange: Exchange::Mcx,
+            symbol: "CRUDEOIL".to_string(),
+            expiry: "19DEC2025".to_string(),
+            instrument: Instrument::Future,
+            strike: None,
+            option_type: None,
+            side,
+            lots: 1.0,
+            entry_price: None,
+            point_value: None,
+            tag: None,
+            stop_loss: None,
+            take_profit: None,
+        }
+    }
+
+    #[test]
+    fn test_open_close_realizes_pnl() {
+        let mut store = PortfolioStore::default();
+
+        let long = store.open(option_request(Side::Buy, OptionKind::Call), 100.0, 75.0, None).unwrap();
+        let short = store.open(option_request(Side::Sell, OptionKind::Put), 80.0, 75.0, None).unwrap();
+        assert_eq!((long.id, short.id), (1, 2));
+        assert_eq!(long.symbol, "NIFTY");
+
+        // A non-positive exit leaves the position open
+        assert!(store.close(long.id, 0.0).is_err());
+        assert!(store.get(long.id).unwrap().is_open());
+
+        // 2 lots x 75 = 150 units
+        let closed = store.close(long.id, 120.0).unwrap();
+        assert_eq!(closed.status, PositionStatus::Closed);
+        assert_eq!(closed.realized_pnl, Some(3000.0));
+        assert!(store.close(long.id, 130.0).is_err());
+
+        let closed = store.close(short.id, 90.0).unwrap();
+        assert_eq!(closed.realized_pnl, Some(-1500.0));
+
+        store.remove(long.id).unwrap();
+        assert!(store.get(long.id).is_none());
+        assert!(store.remove(99).is_err());
+    }
+
+    #[test]
+    fn test_request_validation() {
+        let mut request = option_request(Side::Buy, OptionKind::Call);
+        request.strike = None;
+        assert!(request.validate().is_err());
+
+        let mut request = future_request(Side::Buy);
+        request.option_type = Some(OptionKind::Call);
+        assert!(request.validate().is_err());
+
+        let mut request = future_request(Side::Buy);
+        request.lots = 0.0;
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn test_summary_marks_pnl_greeks_and_margin() {
+        let mut store = PortfolioStore::default();
+        let call = store.open(option_request(Side::Buy, OptionKind::Call), 150.0, 75.0, None).unwrap();
+        let future = store.open(future_request(Side::Sell), 6000.0, 100.0, None).unwrap();
+        let closed = store.open(option_request(Side::Sell, OptionKind::Put), 50.0, 75.0, None).unwrap();
+        store.close(closed.id, 40.0).unwrap();
+        let unpriced = store.open(option_request(Side::Sell, OptionKind::Put), 60.0, 75.0, None).unwrap();
+
+        let marks: HashMap<u64, Mark> = [
+            (call.id, Mark { price: 200.0, underlying_value: Some(26050.0), days_to_expiry: Some(10) }),
+            (future.id, Mark { price: 5900.0, underlying_value: Some(5900.0), days_to_expiry: Some(5) }),
+        ]
+        .into_iter()
+        .collect();
+
+        let summary = summarize(&store.positions, &marks);
+        assert_eq!(summary.open_positions, 3);
+        assert_eq!(summary.closed_positions, 1);
+        assert_eq!(summary.unpriced_positions, 1);
+        assert!(summary.positions.iter().any(|v| v.position.id == unpriced.id && v.unrealized_pnl.is_none()));
+
+        // Call: +50 x 150 units; short future: +100 x 100 units; closed put: +10 x 150
+        assert!((summary.unrealized_pnl - 17500.0).abs() < 1e-6);
+        assert!((summary.realized_pnl - 1500.0).abs() < 1e-6);
+        assert!((summary.total_pnl - 19000.0).abs() < 1e-6);
+
+        // Long call delta in (0, 150) plus short future delta of -100
+        assert!(summary.net_greeks.delta > -100.0 && summary.net_greeks.delta < 50.0);
+        assert!(summary.net_greeks.gamma > 0.0);
+
+        // Long option margin is the premium; the future ties up 10% of notional
+        let margin_call = 200.0 * 150.0;
+        let margin_future = 5900.0 * 100.0 * 0.10;
+        assert!((summary.margin_exposure - (margin_call + margin_future)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_store_round_trip() {
+        let path = std::env::temp_dir().join(format!("portfolio_test_{}.json", std::process::id()));
+        let mut store = PortfolioStore::load_file(&path).unwrap();
+        assert!(store.positions.is_empty());
+
+        store.open(future_request(Side::Buy), 6000.0, 100.0, None).unwrap();
+        store.save_file(&path).unwrap();
+
+        let mut reloaded = PortfolioStore::load_file(&path).unwrap();
+        assert_eq!(reloaded.positions.len(), 1);
+        // Ids keep increasing across reloads
+        let next = reloaded.open(future_request(Side::Sell), 6100.0, 100.0, None).unwrap();
+        assert_eq!(next.id, 2);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_locked_updates_are_not_lost() {
+        let path = std::env::temp_dir().join(format!("portfolio_lock_test_{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        // Each task is a full load-modify-save, as each server process does per request
+        let updates: Vec<_> = (0..8)
+            .map(|i| {
+                tokio::spawn(with_file_lock(path.clone(), move |path| {
+                    let mut store = PortfolioStore::load_file(path)?;
+                    store.open(future_request(Side::Buy), 6000.0 + i as f64, 100.0, None)?;
+                    store.save_file(path)
+                }))
+            })
+            .collect();
+        for update in updates {
+            update.await.unwrap().unwrap();
+        }
+
+        let store = PortfolioStore::load_file(&path).unwrap();
+        assert_eq!(store.positions.len(), 8);
+        std::fs::remove_file(&path).unwrap();
+        let _ = std::fs::remove_file(path.with_extension("json.lock"));
+    }
+
+    #[test]
+    fn test_mcx_strike_format() {
+        assert_eq!(format_mcx_strike(1120.0), "1120.00");
+        assert_eq!(format_mcx_strike(6250.5), "6250.50");
+    }
+
+    fn alert_types(outputs: &[nse_analyzer::nse::RulesOutput]) -> Vec<String> {
+        outputs.iter().flat_map(|o| o.alerts.iter().map(|a| a.alert_type.clone())).collect()
+    }
+
+    #[test]
+    fn test_short_option_risk_alerts() {
+        let mut store = PortfolioStore::default();
+        let mut request = option_request(Side::Sell, OptionKind::Call);
+        request.stop_loss = Some(-5000.0);
+        let short_call = store.open(request, 100.0, 75.0, Some(0.10)).unwrap();
+        let short_put = store.open(option_request(Side::Sell, OptionKind::Put), 120.0, 75.0, None).unwrap();
+
+        // Spot has rallied to 1% below the call strike; the put has decayed 75%
+        let marks: HashMap<u64, Mark> = [
+            (short_call.id, Mark { price: 180.0, underlying_value: Some(25750.0), days_to_expiry: Some(3) }),
+            (short_put.id, Mark { price: 30.0, underlying_value: Some(25750.0), days_to_expiry: Some(3) }),
+        ]
+        .into_iter()
+        .collect();
+
+        let summary = summarize(&store.positions, &marks);
+        let outputs = run_risk_rules(&summary, &RiskConfig::default(), "19-Dec-2025 10:00:00").nse;
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].symbol, "NIFTY");
+
+        let types = alert_types(&outputs);
+        assert!(types.contains(&"SHORT_STRIKE_PROXIMITY".to_string()));
+        assert!(types.contains(&"DELTA_DRIFT".to_string()));
+        assert!(types.contains(&"PNL_STOP".to_string()));   // -80 x 150 = -12000
+        assert!(types.contains(&"THETA_TARGET".to_string()));
+        assert!(!types.contains(&"EXPIRY_ITM_SHORT".to_string()));
+    }
+
+    #[test]
+    fn test_expiry_itm_and_pnl_target() {
+        let mut store = PortfolioStore::default();
+        let short_put = store.open(option_request(Side::Sell, OptionKind::Put), 40.0, 75.0, None).unwrap();
+        let future = store.open(future_request(Side::Buy), 6000.0, 100.0, None).unwrap();
+
+        let marks: HashMap<u64, Mark> = [
+            (short_put.id, Mark { price: 60.0, underlying_value: Some(25950.0), days_to_expiry: Some(0) }),
+            (future.id, Mark { price: 6150.0, underlying_value: Some(6150.0), days_to_expiry: Some(5) }),
+        ]
+        .into_iter()
+        .collect();
+
+        let config = RiskConfig { take_profit_pct: Some(2.0), ..Default::default() };
+        let alerts = run_risk_rules(&summarize(&store.positions, &marks), &config, "19-Dec-2025 15:00:00");
+
+        let types = alert_types(&alerts.nse);
+        assert!(types.contains(&"EXPIRY_ITM_SHORT".to_string()));
+        // MCX future in the MCX output: +150 x 100 = 15000 vs 2% of 600000 = 12000
+        assert!(alerts.nse.iter().all(|o| o.symbol != "CRUDEOIL"));
+        let target = alerts.mcx.iter().find(|o| o.symbol == "CRUDEOIL").unwrap();
+        assert_eq!(target.alerts[0].alert_type, "PNL_TARGET");
+        assert_eq!(target.alerts[0].option_type, "FUT");
+    }
+
+    #[test]
+    fn test_unknown_expiry_skips_expiry_rules() {
+        let mut store = PortfolioStore::default();
+        let short_put = store.open(option_request(Side::Sell, OptionKind::Put), 40.0, 75.0, None).unwrap();
+
+        let marks: HashMap<u64, Mark> =
+            [(short_put.id, Mark { price: 60.0, underlying_value: Some(25950.0), days_to_expiry: None })].into_iter().collect();
+        let config = RiskConfig { strike_proximity_pct: -100.0, theta_target_pct: 100.0, ..Default::default() };
+        let alerts = run_risk_rules(&summarize(&store.positions, &marks), &config, "19-Dec-2025 15:00:00");
+        assert!(alerts.is_empty(), "{:?}", alerts);
+    }
+
+    #[test]
+    fn test_risk_alerts_merge_into_batch_outputs() {
+        let risk_alert = |symbol: &str| nse_analyzer::nse::RulesOutput {
+            symbol: symbol.to_string(),
+            timestamp: "19-Dec-2025 15:00:00".to_string(),
+            underlying_value: 100.0,
+            alerts: vec![nse_analyzer::nse::rules::Alert {
+                symbol: symbol.to_string(),
+                strike_price: 100.0,
+                expiry_date: "30-Dec-2025".to_string(),
+                option_type: "CE".to_string(),
+                alert_type: "PNL_STOP".to_string(),
+                description: String::new(),
+                spread: 0.0,
+                values: nse_analyzer::nse::rules::AlertValues {
+                    pchange_in_oi: None,
+                    last_price: None,
+                    open_interest: None,
+                    the_money: None,
+                    time_val: 0.0,
+                    days_to_expiry: 3,
+                    buildup: Default::default(),
+                },
+            }],
+            contract_spec: None,
+        };
+
+        let mut batch = vec![risk_alert("NIFTY")];
+        merge_nse_risk_alerts(&mut batch, vec![risk_alert("NIFTY"), risk_alert("RELIANCE")]);
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch[0].alerts.len(), 2);
+        assert_eq!(batch[1].symbol, "RELIANCE");
+    }
+}
@@ -0,0 +1,136 @@
+use anyhow::anyhow;
+use axum::http::StatusCode;
+use nse_analyzer::cache::{CachePolicy, TtlCache};
+use nse_analyzer::contracts::Exchange;
+use nse_analyzer::settings::CircuitSettings;
+use nse_analyzer::utility::readiness::ReadyStatus;
+use nse_analyzer::utility::upstream::{CircuitState, StatusError};
+use nse_analyzer::utility::{Readiness, UpstreamHealth};
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CIRCUIT: CircuitSettings = CircuitSettings { failure_threshold: 2, cooldown_secs: 60 };
+
+    async fn fail(health: &UpstreamHealth, circuit: &CircuitSettings, message: &str) -> anyhow::Error {
+        let message = message.to_string();
+        health.call(circuit, async move { Err::<(), _>(anyhow!(message)) }).await.unwrap_err()
+    }
+
+    async fn fail_status(health: &UpstreamHealth, circuit: &CircuitSettings, status: StatusCode) -> anyhow::Error {
+        let error = StatusError::new(status, "Access Denied");
+        health.call(circuit, async move { Err::<(), _>(error.into()) }).await.unwrap_err()
+    }
+
+    #[tokio::test]
+    async fn test_circuit_opens_fails_fast_and_closes_on_success() {
+        let health = UpstreamHealth::new();
+        assert_eq!(health.circuit(&CIRCUIT), CircuitState::Closed);
+
+        fail(&health, &CIRCUIT, "Non-JSON response: <html>Access Denied").await;
+        assert_eq!(health.circuit(&CIRCUIT), CircuitState::Closed);
+        fail_status(&health, &CIRCUIT, StatusCode::SERVICE_UNAVAILABLE).await;
+        assert_eq!(health.circuit(&CIRCUIT), CircuitState::Open);
+
+        // Open: the fetch is not even started
+        let err = health.call::<()>(&CIRCUIT, async { unreachable!("fetch ran while circuit open") }).await.unwrap_err();
+        assert!(err.to_string().contains("circuit open"), "{}", err);
+        let status = health.status(&CIRCUIT);
+        assert_eq!(status.consecutive_failures, 2);
+        assert!(status.retry_in_secs.is_some());
+        assert!(status.last_error.unwrap().message.contains("503"));
+
+        // After the cooldown a probe goes through; success closes the circuit
+        let probing = CircuitSettings { cooldown_secs: 0, ..CIRCUIT };
+        assert_eq!(health.circuit(&probing), CircuitState::HalfOpen);
+        assert_eq!(health.call(&probing, async { Ok(7) }).await.unwrap(), 7);
+        let status = health.status(&CIRCUIT);
+        assert_eq!(status.circuit, CircuitState::Closed);
+        assert_eq!(status.consecutive_failures, 0);
+        assert!(status.warmed_up && status.last_success.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_bad_symbol_does_not_trip_circuit() {
+        let health = UpstreamHealth::new();
+        for _ in 0..3 {
+            fail_status(&health, &CIRCUIT, StatusCode::NOT_FOUND).await;
+        }
+        assert_eq!(health.circuit(&CIRCUIT), CircuitState::Closed);
+
+        // Forbidden means the session is blocked
+        let disabled = CircuitSettings { failure_threshold: 0, ..CIRCUIT };
+        for _ in 0..3 {
+            fail_status(&health, &disabled, StatusCode::FORBIDDEN).await;
+        }
+        assert_eq!(health.circuit(&disabled), CircuitState::Closed);
+        fail_status(&health, &CIRCUIT, StatusCode::FORBIDDEN).await;
+        assert_eq!(health.circuit(&CIRCUIT), CircuitState::Open);
+
+        // The status is read from the error type, not its message
+        let health = UpstreamHealth::new();
+        for _ in 0..2 {
+            fail(&health, &CIRCUIT, "Client error 404 Not Found: no such symbol").await;
+        }
+        assert_eq!(health.circuit(&CIRCUIT), CircuitState::Open);
+    }
+
+    #[tokio::test]
+    async fn test_half_open_admits_a_single_probe() {
+        let health = UpstreamHealth::new();
+        let probing = CircuitSettings { cooldown_secs: 0, ..CIRCUIT };
+        for _ in 0..2 {
+            fail(&health, &CIRCUIT, "Timeout").await;
+        }
+
+        // First caller becomes the probe; everyone else fails fast until it reports
+        assert!(health.check(&probing).unwrap());
+        let err = health.call::<()>(&probing, async { unreachable!("second probe ran") }).await.unwrap_err();
+        assert!(err.to_string().contains("half-open"), "{}", err);
+        health.record_failure(&probing, &anyhow!("Timeout"));
+        assert!(health.check(&probing).unwrap());
+
+        // A probe dropped before finishing frees the slot
+        health.record_failure(&probing, &anyhow!("Timeout"));
+        let dropped = health.call(&probing, std::future::pending::<anyhow::Result<()>>());
+        assert!(tokio::time::timeout(Duration::from_millis(10), dropped).await.is_err());
+        assert_eq!(health.call(&probing, async { Ok(1) }).await.unwrap(), 1);
+        assert_eq!(health.circuit(&probing), CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_readiness_status_and_freshness() {
+        let health = UpstreamHealth::new();
+        let cache: TtlCache<String, u32> = TtlCache::new("test_chains", CachePolicy::fixed(Duration::from_secs(60), 10));
+
+        let starting = Readiness::evaluate(Exchange::Nse, health.status(&CIRCUIT), &[cache.stats()]);
+        assert_eq!(starting.status, ReadyStatus::Starting);
+        assert!(starting.ready);
+        assert_eq!(starting.data_age_secs, None);
+        assert!(!starting.caches[0].fresh);
+
+        health.record_success();
+        cache.insert("NIFTY".to_string(), 1);
+        let ready = Readiness::evaluate(Exchange::Nse, health.status(&CIRCUIT), &[cache.stats()]);
+        assert_eq!(ready.status, ReadyStatus::Ready);
+        assert_eq!(ready.data_age_secs, Some(0));
+        assert!(ready.caches[0].fresh);
+
+        fail(&health, &CIRCUIT, "Request send failed").await;
+        let degraded = Readiness::evaluate(Exchange::Nse, health.status(&CIRCUIT), &[cache.stats()]);
+        assert_eq!(degraded.status, ReadyStatus::Degraded);
+        assert!(degraded.ready);
+
+        fail(&health, &CIRCUIT, "Request send failed").await;
+        let blocked = Readiness::evaluate(Exchange::Nse, health.status(&CIRCUIT), &[cache.stats()]);
+        assert_eq!(blocked.status, ReadyStatus::UpstreamBlocked);
+        assert!(!blocked.ready);
+
+        let json = serde_json::to_value(&blocked).unwrap();
+        assert_eq!(json["status"], "upstream_blocked");
+        assert_eq!(json["exchange"], "NSE");
+        assert_eq!(json["upstream"]["circuit"], "open");
+    }
+}
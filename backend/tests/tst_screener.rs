@@ -0,0 +1,167 @@
+use nse_analyzer::nse::processor::ProcessedOptionData;
+use nse_analyzer::screener::{
+    max_pain, run_screener, screen, FilterExpr, ScreenerQuery, SortKey, SymbolMetrics,
+};
+use serde_json::json;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn side(oi: f64, change: f64, price: f64) -> serde_json::Value {
+        json!({
+            "openInterest": oi,
+            "changeinOpenInterest": change,
+            "lastPrice": price,
+            "the_money": "OTM",
+            "tambu": null,
+            "time_val": 0.0,
+            "days_to_expiry": 10,
+        })
+    }
+
+    fn row(strike: f64, ce_oi: f64, pe_oi: f64) -> ProcessedOptionData {
+        serde_json::from_value(json!({
+            "expiryDates": "30-Dec-2025",
+            "strikePrice": strike,
+            "CE": side(ce_oi, ce_oi / 2.0, 5.0),
+            "PE": side(pe_oi, 0.0, 5.0),
+            "days_to_expiry": 10,
+        }))
+        .unwrap()
+    }
+
+    fn metrics(symbol: &str, spread: f64, pcr: Option<f64>, alerts: usize) -> SymbolMetrics {
+        SymbolMetrics {
+            symbol: symbol.to_string(),
+            spread,
+            pcr,
+            alerts,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_filter_parsing_and_matching() {
+        let m = metrics("NIFTY", 60.0, Some(1.4), 2);
+
+        let filter = FilterExpr::parse("pcr > 1.2 and (spread >= 50 || alerts == 0)").unwrap();
+        assert!(filter.matches(&m));
+        assert_eq!(filter.fields(), vec!["pcr", "spread", "alerts"]);
+
+        assert!(!FilterExpr::parse("not pcr > 1.2").unwrap().matches(&m));
+        assert!(FilterExpr::parse("PCR<=1.4 && Alerts != 0").unwrap().matches(&m));
+        assert!(FilterExpr::parse("spread > -5 or pcr < 0").unwrap().matches(&m));
+
+        // Missing metrics never match, even when negated comparisons would
+        assert!(!FilterExpr::parse("iv_rank < 50").unwrap().matches(&m));
+        assert!(!FilterExpr::parse("basis_pct != 0").unwrap().matches(&m));
+
+        // Aliases and trailing percent signs
+        let filter = FilterExpr::parse("iv > 15% and dte <= 30").unwrap();
+        assert_eq!(filter.fields(), vec!["atm_iv", "days_to_expiry"]);
+
+        // Errors
+        assert!(FilterExpr::parse("").is_err());
+        assert!(FilterExpr::parse("unknown > 1").is_err());
+        assert!(FilterExpr::parse("pcr >").is_err());
+        assert!(FilterExpr::parse("(pcr > 1").is_err());
+        assert!(FilterExpr::parse("pcr > 1 spread").is_err());
+        assert!(FilterExpr::parse("pcr > 1 & spread > 2").is_err());
+    }
+
+    #[test]
+    fn test_filter_nesting_is_bounded() {
+        let nested = |depth: usize| format!("{}pcr > 1{}", "(".repeat(depth), ")".repeat(depth));
+        assert!(FilterExpr::parse(&nested(30)).is_ok());
+        assert!(FilterExpr::parse(&format!("{}pcr > 1", "!".repeat(30))).is_ok());
+
+        let err = FilterExpr::parse(&nested(33)).unwrap_err();
+        assert!(err.to_string().contains("deeper"), "{}", err);
+        assert!(FilterExpr::parse(&format!("{}pcr > 1", "not ".repeat(40))).is_err());
+
+        // Hostile query strings fail fast instead of overflowing the stack
+        for hostile in ["(".repeat(50_000), "!".repeat(50_000)] {
+            assert!(FilterExpr::parse(&hostile).unwrap_err().to_string().contains("longer"));
+        }
+        let err = FilterExpr::parse(&nested(500)).unwrap_err();
+        assert!(err.to_string().contains("deeper"), "{}", err);
+    }
+
+    #[test]
+    fn test_sort_and_limit() {
+        let all = vec![
+            metrics("A", 10.0, Some(0.8), 1),
+            metrics("B", 30.0, None, 1),
+            metrics("C", 20.0, Some(1.5), 3),
+            metrics("D", 20.0, Some(1.1), 0),
+        ];
+
+        // Missing PCR sorts last in either direction
+        let query = ScreenerQuery::parse(None, Some("-pcr"), None).unwrap();
+        let ranked: Vec<_> = screen(all.clone(), &query).into_iter().map(|r| r.metrics.symbol).collect();
+        assert_eq!(ranked, vec!["C", "D", "A", "B"]);
+
+        let query = ScreenerQuery::parse(None, Some("pcr:asc"), None).unwrap();
+        let ranked: Vec<_> = screen(all.clone(), &query).into_iter().map(|r| r.metrics.symbol).collect();
+        assert_eq!(ranked, vec!["A", "D", "C", "B"]);
+
+        // Tie on spread broken by the second key
+        let query = ScreenerQuery::parse(Some("spread >= 20"), Some("spread,-alerts"), Some(2)).unwrap();
+        let rows = screen(all.clone(), &query);
+        assert_eq!(rows.len(), 2);
+        assert_eq!((rows[0].rank, rows[0].metrics.symbol.as_str()), (1, "C"));
+        assert_eq!((rows[1].rank, rows[1].metrics.symbol.as_str()), (2, "D"));
+
+        // Default sort: alerts desc, then spread desc
+        let result = run_screener(all, &ScreenerQuery::parse(None, None, None).unwrap(), None, None);
+        assert_eq!(result.total_symbols, 4);
+        assert_eq!(result.matched, 4);
+        assert_eq!(result.results[0].metrics.symbol, "C");
+        assert_eq!(result.results[1].metrics.symbol, "B");
+
+        assert!(SortKey::parse_list("pcr:sideways").is_err());
+        assert!(!ScreenerQuery::parse(Some("pcr > 1"), Some("-spread"), None).unwrap().needs_futures());
+        assert!(ScreenerQuery::parse(None, Some("-basis_pct"), None).unwrap().needs_futures());
+    }
+
+    #[test]
+    fn test_max_pain() {
+        // Heavy call OI above and put OI below pins max pain at 100
+        let data = vec![
+            row(90.0, 0.0, 500.0),
+            row(100.0, 100.0, 100.0),
+            row(110.0, 500.0, 0.0),
+        ];
+        assert_eq!(max_pain(&data), Some(100.0));
+
+        // Call OI only: writers pay least at the lowest strike
+        let data = vec![row(90.0, 100.0, 0.0), row(100.0, 100.0, 0.0)];
+        assert_eq!(max_pain(&data), Some(90.0));
+
+        assert_eq!(max_pain(&[row(100.0, 0.0, 0.0)]), None);
+    }
+
+    #[test]
+    fn test_metrics_from_chain() {
+        let data = vec![row(90.0, 0.0, 500.0), row(100.0, 100.0, 100.0), row(110.0, 500.0, 0.0)];
+        let mut m = SymbolMetrics::from_chain("NIFTY", "18-Dec-2025 15:30:00", 104.0, 12.0, 600.0, 600.0, &data);
+
+        assert_eq!(m.expiry.as_deref(), Some("30-Dec-2025"));
+        assert_eq!(m.days_to_expiry, 10);
+        assert_eq!(m.pcr, Some(1.0));
+        assert_eq!(m.ce_oi_change, 300.0);
+        assert_eq!(m.pe_oi_change, 0.0);
+        // 300 added on a previous 300
+        assert!((m.ce_oi_change_pct.unwrap() - 100.0).abs() < 1e-9);
+        assert!((m.oi_change_pct.unwrap() - 100.0 / 3.0).abs() < 1e-9);
+        assert_eq!(m.max_pain, Some(100.0));
+        assert!((m.max_pain_distance_pct.unwrap() - 4.0 / 104.0 * 100.0).abs() < 1e-9);
+        assert_eq!(m.field("total_oi"), Some(1200.0));
+        assert_eq!(m.field("basis"), None);
+
+        m.set_futures_price(106.08);
+        assert!((m.basis.unwrap() - 2.08).abs() < 1e-9);
+        assert!((m.basis_pct.unwrap() - 2.0).abs() < 1e-9);
+    }
+}
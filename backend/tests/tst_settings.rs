@@ -0,0 +1,137 @@
+use nse_analyzer::settings::Settings;
+use std::collections::HashMap;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(text: &str) -> Option<toml::Table> {
+        Some(toml::from_str(text).unwrap())
+    }
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_defaults_match_constants() {
+        let settings = Settings::from_layers(None, &env(&[])).unwrap();
+        assert_eq!(settings, Settings::default());
+        assert_eq!(settings.nse.max_concurrent, nse_analyzer::nse::config::DEFAULT_MAX_CONCURRENT);
+        assert_eq!(settings.mcx.http_timeout(), nse_analyzer::mcx::config::HTTP_TIMEOUT);
+        assert_eq!(settings.nse.cache_policy("contract_info").ttl, Duration::from_secs(21_600));
+        assert_eq!(settings.mcx.cache_policy("option_chains").market_ttl, Duration::from_secs(30));
+        assert_eq!(settings.mcx.cache_policy("no_such_cache").current_ttl(), Duration::ZERO);
+        assert!(settings.nse.is_index("nifty"));
+        assert!(!settings.nse.is_index("RELIANCE"));
+        assert_eq!(settings.nse.retry.backoff().count(), settings.nse.retry.max_attempts);
+    }
+
+    #[test]
+    fn test_surface_expiries_clamped() {
+        let settings = Settings::default().nse;
+        assert_eq!(settings.surface_expiries(None).unwrap(), settings.surface_max_expiries);
+        assert_eq!(settings.surface_expiries(Some(3)).unwrap(), 3);
+        assert_eq!(settings.surface_expiries(Some(10_000)).unwrap(), settings.surface_expiries_limit);
+        assert!(settings.surface_expiries(Some(0)).unwrap_err().to_string().contains("max_expiries"));
+    }
+
+    #[test]
+    fn test_file_then_env_layering() {
+        let file = table("[nse]\nmax_concurrent = 8\nindices = [\"NIFTY\"]\n\n[mcx.retry]\nmax_attempts = 6\n");
+        let settings = Settings::from_layers(file, &env(&[
+            ("NSE_MAX_CONCURRENT", "2"),
+            ("NSE_RETRY_FACTOR", "4"),
+            ("MCX_CACHE_OPTION_CHAINS_MARKET_TTL_SECS", "10"),
+        ]))
+        .unwrap();
+
+        // env beats file, file beats defaults, untouched keys keep their defaults
+        assert_eq!(settings.nse.max_concurrent, 2);
+        assert_eq!(settings.nse.indices, vec!["NIFTY"]);
+        assert_eq!(settings.nse.retry.factor, 4);
+        assert_eq!(settings.mcx.retry.max_attempts, 6);
+        assert_eq!(settings.mcx.cache["option_chains"].market_ttl_secs, 10);
+        assert_eq!(settings.mcx.cache["option_chains"].max_entries, 200);
+        assert_eq!(settings.nse.http_timeout_secs, Settings::default().nse.http_timeout_secs);
+
+        let settings = Settings::from_layers(None, &env(&[("NSE_INDICES", "nifty, banknifty,")])).unwrap();
+        assert_eq!(settings.nse.indices, vec!["NIFTY", "BANKNIFTY"]);
+        assert_eq!(settings.nse.concurrency(true), settings.nse.ci_max_concurrent);
+
+        // API keys keep their case, unlike symbol lists
+        let settings = Settings::from_layers(None, &env(&[("NSE_SERVER_API_KEYS", "Key-One, kEy2"), ("MCX_SERVER_BIND", "0.0.0.0")])).unwrap();
+        assert_eq!(settings.nse.server.api_keys, vec!["Key-One", "kEy2"]);
+        assert!(settings.nse.server.is_loopback());
+        assert!(!settings.mcx.server.is_loopback());
+        assert_eq!(settings.mcx.server_addr(3002), "0.0.0.0:3002");
+
+        // Top-level keys keep their bare env names; risk and margin settings layer the same way
+        let file = table("snapshot_dir = \"archive\"\n\n[risk]\nstop_loss_pct = 30\n\n[mcx]\nmargin_rate = 0.15\n");
+        let settings = Settings::from_layers(file, &env(&[
+            ("IV_HISTORY_FILE", "data/iv.json"),
+            ("SNAPSHOT_DIR", "snapshots"),
+            ("RISK_DELTA_DRIFT", "0.3"),
+            ("NSE_MARGIN_RATE", "0.2"),
+        ])).unwrap();
+        assert_eq!(settings.iv_history_file, "data/iv.json");
+        assert_eq!(settings.portfolio_file, Settings::default().portfolio_file);
+        assert_eq!(settings.snapshot_dir, "snapshots");
+        assert_eq!((settings.risk.delta_drift, settings.risk.stop_loss_pct), (0.3, 30.0));
+        assert_eq!((settings.nse.margin_rate, settings.mcx.margin_rate), (0.2, 0.15));
+    }
+
+    #[test]
+    fn test_rejects_unknown_keys_and_bad_types() {
+        let err = Settings::from_layers(table("[nse]\nmax_concurent = 3\n"), &env(&[])).unwrap_err();
+        assert!(err.to_string().contains("nse.max_concurent"));
+
+        let err = Settings::from_layers(table("[bse]\nmax_concurrent = 3\n"), &env(&[])).unwrap_err();
+        assert!(err.to_string().contains("bse"));
+
+        let err = Settings::from_layers(table("[mcx]\nhttp_timeout_secs = \"30\"\n"), &env(&[])).unwrap_err();
+        assert!(err.to_string().contains("mcx.http_timeout_secs"));
+
+        let err = Settings::from_layers(table("[nse.cache.contract_infos]\nttl_secs = 60\n"), &env(&[])).unwrap_err();
+        assert!(err.to_string().contains("nse.cache.contract_infos"));
+
+        let err = Settings::from_layers(None, &env(&[("MCX_MAX_CONCURRENT", "many")])).unwrap_err();
+        assert!(err.to_string().contains("MCX_MAX_CONCURRENT"));
+
+        // Invalid RISK_* values are errors, not silently ignored
+        let err = Settings::from_layers(None, &env(&[("RISK_STOP_LOSS_PCT", "40%")])).unwrap_err();
+        assert!(err.to_string().contains("RISK_STOP_LOSS_PCT"));
+    }
+
+    #[test]
+    fn test_validation_reports_every_problem() {
+        let file = table("[nse]\nmax_concurrent = 0\nindices = []\nmarket_open = \"9am\"\n\n[mcx]\nmarket_open = \"23:45\"\n\n[mcx.retry]\nmax_attempts = 0\n\n[mcx.circuit]\ncooldown_secs = 0\n\n[mcx.cache.ticker_list]\nmax_entries = 0\n");
+        let message = Settings::from_layers(file, &env(&[("NSE_SERVER_BIND", "localhost")])).unwrap_err().to_string();
+        assert!(message.contains("nse.max_concurrent"), "{}", message);
+        assert!(message.contains("nse.server.bind must be an IP address"), "{}", message);
+        assert!(message.contains("nse.indices"), "{}", message);
+        assert!(message.contains("nse.market_open / market_close must be HH:MM"), "{}", message);
+        assert!(message.contains("mcx.market_open must be before market_close"), "{}", message);
+        assert!(message.contains("mcx.retry.max_attempts"), "{}", message);
+        assert!(message.contains("mcx.circuit.cooldown_secs"), "{}", message);
+        assert!(message.contains("mcx.cache.ticker_list.max_entries"), "{}", message);
+
+        let file = table("portfolio_file = \" \"\n\n[risk]\ndelta_drift = 0\ntheta_target_pct = 150\nstop_loss_pct = -5\n\n[nse]\nmargin_rate = 0\n");
+        let message = Settings::from_layers(file, &env(&[("MCX_MARGIN_RATE", "1.5")])).unwrap_err().to_string();
+        assert!(message.contains("portfolio_file"), "{}", message);
+        assert!(message.contains("risk.delta_drift"), "{}", message);
+        assert!(message.contains("risk.theta_target_pct"), "{}", message);
+        assert!(message.contains("risk.stop_loss_pct"), "{}", message);
+        assert!(message.contains("nse.margin_rate"), "{}", message);
+        assert!(message.contains("mcx.margin_rate"), "{}", message);
+
+        let file = table("[mcx]\nsurface_max_expiries = 10\n");
+        let message = Settings::from_layers(file, &env(&[])).unwrap_err().to_string();
+        assert!(message.contains("mcx.surface_expiries_limit"), "{}", message);
+
+        let example = std::fs::read_to_string("nse-analyzer.example.toml").unwrap();
+        assert_eq!(Settings::from_layers(table(&example), &env(&[])).unwrap(), Settings::default());
+    }
+}
@@ -0,0 +1,40 @@
+use nse_analyzer::utility::{write_atomic, Shutdown};
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_shutdown_wakes_every_waiter() {
+        let shutdown = Shutdown::new();
+        assert!(!shutdown.is_triggered());
+
+        let waiters: Vec<_> = (0..3).map(|_| tokio::spawn(shutdown.clone().triggered())).collect();
+        shutdown.trigger();
+        for waiter in waiters {
+            tokio::time::timeout(Duration::from_secs(1), waiter).await.unwrap().unwrap();
+        }
+
+        // Waiting after the fact resolves immediately
+        assert!(shutdown.is_triggered());
+        tokio::time::timeout(Duration::from_secs(1), shutdown.clone().triggered()).await.unwrap();
+    }
+
+    #[test]
+    fn test_write_atomic_replaces_without_leftovers() {
+        let dir = std::env::temp_dir().join(format!("write_atomic_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("batch.json");
+
+        write_atomic(&path, "first").unwrap();
+        write_atomic(&path, "second").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "second");
+        assert!(!dir.join("batch.json.tmp").exists());
+
+        // Missing parent directory fails and leaves nothing behind
+        assert!(write_atomic(dir.join("missing").join("batch.json"), "x").is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -240,32 +240,32 @@ export default function McxHistoricalDataModal({
         const response = await mcxApiClient.getHistoricalData(params);
         
         if (response.success && response.data) {
-          const rawData = response.data.d.Data;
+          const bars = response.data.bars;
           
-          if (!rawData || !Array.isArray(rawData)) {
+          if (!bars || !Array.isArray(bars)) {
             setError('Invalid data format received from API');
             return;
           }
 
-          const processedData: HistoricalDataPoint[] = rawData.map((item) => {
-            const priceChange = item.Close - item.PreviousClose;
+          const processedData: HistoricalDataPoint[] = bars.map((bar, i) => {
+            const previousClose = i > 0 ? bars[i - 1].close : bar.open;
             
             return {
-              DateDisplay: item.DateDisplay,
-              OpenInterest: item.OpenInterest,
-              ChangeInOI: item.ChangeInOI, // Now comes from backend
-              Close: item.Close,
-              PreviousClose: item.PreviousClose,
-              timestamp: item.Date,
-              formattedDate: item.DateDisplay,
-              priceChange: priceChange,
-              high: item.High,
-              low: item.Low,
-              open: item.Open,
-              volume: item.Volume,
-              value: item.Value
+              DateDisplay: bar.date,
+              OpenInterest: bar.open_interest ?? 0,
+              ChangeInOI: bar.change_in_oi ?? 0,
+              Close: bar.close,
+              PreviousClose: previousClose,
+              timestamp: bar.date,
+              formattedDate: bar.date,
+              priceChange: bar.close - previousClose,
+              high: bar.high,
+              low: bar.low,
+              open: bar.open,
+              volume: bar.volume ?? 0,
+              value: 0 // Turnover is not part of the normalized series
             };
-          });
+          }).reverse();
 
           // Helper function to normalize dates for comparison
           const normalizeDate = (dateStr: string): string => {
@@ -1,6 +1,8 @@
 import axios from 'axios';
 import { getDb } from '@/app/lib/db_factory';
+import type { ApiResponse, BatchJob } from '@/app/types/api_nse_type';
 import { getApiBaseUrl } from '@/app/lib/platform';
+import { waitForJob } from '@/app/lib/jobs';
 import {
   McxApiResponse,
   McxTickersResponse,
@@ -239,7 +241,10 @@ export const mcxApiClient = {
   },
 
   // MCX Batch Analysis
-  async getBatchAnalysis(forceRefresh = false): Promise<McxApiResponse<McxBatchAnalysisResponse>> {
+  async getBatchAnalysis(
+    forceRefresh = false,
+    onProgress?: (job: BatchJob<McxBatchAnalysisResponse>) => void
+  ): Promise<McxApiResponse<McxBatchAnalysisResponse>> {
     try {
       const { db, DB_KEYS } = await getDb();
       
@@ -257,8 +262,13 @@ export const mcxApiClient = {
       }
 
       const api = await createApiInstance();
+      // The server runs the batch as a background job; poll it to completion
       const response = await api.post('/api/mcx/batch-analysis');
-      const apiResponse: McxApiResponse<McxBatchAnalysisResponse> = response.data;
+      const jobResponse: ApiResponse<BatchJob<McxBatchAnalysisResponse>> = response.data;
+      if (!jobResponse.success || !jobResponse.data) {
+        return { success: false, error: jobResponse.error ?? 'Failed to start batch job' };
+      }
+      const apiResponse: McxApiResponse<McxBatchAnalysisResponse> = await waitForJob<McxBatchAnalysisResponse>(api, jobResponse.data.id, onProgress);
 
       if (apiResponse.success && apiResponse.data) {
         await db.storeData(DB_KEYS.MCX_BATCH_ANALYSIS, apiResponse.data);
@@ -284,7 +294,9 @@ export const mcxApiClient = {
         params.to_date,
         params.instrument_name,
         params.option_type || 'null',
-        params.strike_price || 'null'
+        params.strike_price || 'null',
+        params.indicators || 'null',
+        String(params.window ?? 'null')
       ];
       const key = DB_KEYS.MCX_HISTORICAL_DATA(...keyParams);
       
@@ -315,6 +327,12 @@ export const mcxApiClient = {
       if (params.strike_price) {
         queryParams.set('strike', params.strike_price);
       }
+      if (params.window) {
+        queryParams.set('window', String(params.window));
+      }
+      if (params.indicators) {
+        queryParams.set('indicators', params.indicators);
+      }
 
       const api = await createApiInstance();
       const response = await api.get(`/api/mcx/historic-data?${queryParams.toString()}`);
@@ -399,7 +417,9 @@ export const mcxApiClient = {
       params.to_date,
       params.instrument_name,
       params.option_type || 'null',
-      params.strike_price || 'null'
+      params.strike_price || 'null',
+      params.indicators || 'null',
+      String(params.window ?? 'null')
     ];
     return await db.hasData(DB_KEYS.MCX_HISTORICAL_DATA(...keyParams));
   }
@@ -6,9 +6,13 @@ import {
   SingleAnalysisResponse,
   BatchAnalysisResponse,
   FuturesDataResponse,
+  ScreenerResult,
+  BatchJob,
+  HistoricalSeries,
 } from '@/app/types/api_nse_type';
 import { getDb } from '@/app/lib/db_factory';
 import { getApiBaseUrl } from '@/app/lib/platform';
+import { waitForJob } from '@/app/lib/jobs';
 
 // Dynamic API base URL
 let apiBaseUrl: string | null = null;
@@ -178,7 +182,10 @@ export const apiClient = {
   },
 
   // Batch Analysis
-  async getBatchAnalysis(forceRefresh = false): Promise<ApiResponse<BatchAnalysisResponse>> {
+  async getBatchAnalysis(
+    forceRefresh = false,
+    onProgress?: (job: BatchJob<BatchAnalysisResponse>) => void
+  ): Promise<ApiResponse<BatchAnalysisResponse>> {
     try {
       const { db, DB_KEYS } = await getDb();
       
@@ -196,8 +203,13 @@ export const apiClient = {
       }
 
       const api = await createApiInstance();
+      // The server runs the batch as a background job; poll it to completion
       const response = await api.post('/api/nse/batch-analysis');
-      const apiResponse: ApiResponse<BatchAnalysisResponse> = response.data;
+      const jobResponse: ApiResponse<BatchJob<BatchAnalysisResponse>> = response.data;
+      if (!jobResponse.success || !jobResponse.data) {
+        return { success: false, error: jobResponse.error ?? 'Failed to start batch job' };
+      }
+      const apiResponse: ApiResponse<BatchAnalysisResponse> = await waitForJob<BatchAnalysisResponse>(api, jobResponse.data.id, onProgress);
 
       if (apiResponse.success && apiResponse.data) {
         await db.storeData(DB_KEYS.BATCH_ANALYSIS, apiResponse.data);
@@ -220,7 +232,9 @@ export const apiClient = {
     option_type?: 'CE' | 'PE';
     from_date: string;
     to_date: string;
-  }): Promise<ApiResponse<any>> {
+    window?: number;
+    indicators?: string; // e.g. "sma:20,rsi:14,bb:20:2,vwap,oi_sma:5"
+  }): Promise<ApiResponse<HistoricalSeries>> {
     try {
       const queryParams = new URLSearchParams({
         symbol: params.symbol,
@@ -233,6 +247,8 @@ export const apiClient = {
       if (params.year) queryParams.append('year', params.year);
       if (params.strike_price) queryParams.append('strike_price', params.strike_price);
       if (params.option_type) queryParams.append('option_type', params.option_type);
+      if (params.window) queryParams.append('window', String(params.window));
+      if (params.indicators) queryParams.append('indicators', params.indicators);
 
       const api = await createApiInstance();
       const response = await api.get(`/api/nse/derivatives-historical?${queryParams.toString()}`);
@@ -243,6 +259,29 @@ export const apiClient = {
     }
   },
 
+  // Screener over the last batch run's per-symbol metrics
+  async getScreener(params: {
+    filter?: string; // e.g. "pcr > 1.2 and alerts > 0"
+    sort?: string;   // e.g. "-iv_rank,spread"
+    limit?: number;
+    refresh?: boolean;
+  } = {}): Promise<ApiResponse<ScreenerResult>> {
+    try {
+      const queryParams = new URLSearchParams();
+      if (params.filter) queryParams.append('filter', params.filter);
+      if (params.sort) queryParams.append('sort', params.sort);
+      if (params.limit) queryParams.append('limit', String(params.limit));
+      if (params.refresh) queryParams.append('refresh', 'true');
+
+      const api = await createApiInstance();
+      const response = await api.get(`/api/nse/screener?${queryParams.toString()}`);
+      return response.data;
+    } catch (error) {
+      console.error('Error fetching screener results:', error);
+      throw error;
+    }
+  },
+
   // Check if data exists in cache
   async hasSecurities(): Promise<boolean> {
     const { db, DB_KEYS } = await getDb();
@@ -0,0 +1,40 @@
+import type { AxiosInstance } from 'axios';
+import type { ApiResponse, BatchJob } from '@/app/types/api_nse_type';
+
+const POLL_INTERVAL_MS = 2000;
+
+// Poll GET /jobs/{id} until the job finishes, reporting progress along the way
+export async function waitForJob<T>(
+  api: AxiosInstance,
+  jobId: string,
+  onProgress?: (job: BatchJob<T>) => void
+): Promise<ApiResponse<T>> {
+  for (;;) {
+    const response = await api.get(`/jobs/${encodeURIComponent(jobId)}`);
+    const apiResponse: ApiResponse<BatchJob<T>> = response.data;
+
+    if (!apiResponse.success || !apiResponse.data) {
+      return { success: false, error: apiResponse.error ?? `Job ${jobId} not found` };
+    }
+
+    const job = apiResponse.data;
+    onProgress?.(job);
+
+    switch (job.status) {
+      case 'completed':
+        return { success: true, data: job.result, processing_time_ms: job.elapsed_ms };
+      case 'failed':
+        return { success: false, error: job.error ?? 'Batch job failed', processing_time_ms: job.elapsed_ms };
+      case 'cancelled':
+        return { success: false, error: 'Batch job was cancelled', processing_time_ms: job.elapsed_ms };
+    }
+
+    await new Promise((resolve) => setTimeout(resolve, POLL_INTERVAL_MS));
+  }
+}
+
+// Cancel a running job
+export async function cancelJob<T>(api: AxiosInstance, jobId: string): Promise<ApiResponse<BatchJob<T>>> {
+  const response = await api.delete(`/jobs/${encodeURIComponent(jobId)}`);
+  return response.data;
+}
@@ -24,7 +24,7 @@ import {
   getToday 
 } from '@/app/lib/api_nse';
 import { db } from '@/app/lib/db';
-import { ContractInfoResponse, SingleAnalysisResponse, DataWithAge, FuturesAnalysis, HistoricalDataPoint } from '@/app/types/api_nse_type';
+import { ContractInfoResponse, SingleAnalysisResponse, DataWithAge, FuturesAnalysis, HistoricalBar, HistoricalDataPoint } from '@/app/types/api_nse_type';
 import HistoricalDataModal from '@/app/components/nse_historicalDataModal';
 
 // Separate component that uses useSearchParams - wrapped in Suspense
@@ -195,20 +195,17 @@ function SecurityPageContent() {
 
       // Handle different response structures
       if (response.success) {
-        let dataArray = null;
-        
-        // Try response.data.data first (nested structure)
-        if (response.data && response.data.data && Array.isArray(response.data.data)) {
-          dataArray = response.data.data;
-        } 
-        // Try response.data directly (flat structure)
-        else if (response.data && Array.isArray(response.data)) {
-          dataArray = response.data;
-        }
-        // Try response directly (if data is at top level)
-        else if (Array.isArray(response)) {
-          dataArray = response;
-        }
+        // Newest first, in the row shape the modal renders
+        const bars: HistoricalBar[] = response.data?.bars || [];
+        const dataArray: HistoricalDataPoint[] = bars.map((bar, i) => ({
+          FH_TIMESTAMP: bar.date,
+          FH_UNDERLYING_VALUE: bar.underlying_value ?? 0,
+          FH_OPEN_INT: bar.open_interest ?? 0,
+          FH_CHANGE_IN_OI: bar.change_in_oi ?? 0,
+          FH_SETTLE_PRICE: bar.close,
+          FH_CLOSING_PRICE: bar.close,
+          FH_PREV_CLS: i > 0 ? bars[i - 1].close : bar.open,
+        })).reverse();
 
         // Create final data array with historical data
         let finalDataArray = dataArray || [];
@@ -281,30 +278,22 @@ function SecurityPageContent() {
       
       if (response.success && response.data && response.data.data.length > 0) {
         const data = response.data.data[0];
-        const { pchange, pchangeinOpenInterest, underlyingValue, lastPrice, openInterest, changeinOpenInterest, prevClose } = data;
+        const { action, underlyingValue, lastPrice, openInterest, changeinOpenInterest, prevClose } = data;
         
-        let action = '';
         let color = '';
         
-        if (pchange > 0 && pchangeinOpenInterest > 0) {
-          action = ' Long Buildup';
+        if (action === 'Long Buildup') {
           color = 'text-green-400';
-        } else if (pchange > 0 && pchangeinOpenInterest < 0) {
-          action = ' Short Covering';
-          color = 'text-gray-300';
-        } else if (pchange < 0 && pchangeinOpenInterest > 0) {
-          action = ' Short Buildup';
+        } else if (action === 'Short Buildup') {
           color = 'text-red-400';
-        } else if (pchange < 0 && pchangeinOpenInterest < 0) {
-          action = ' Long Covering';
-          color = 'text-gray-300';
-        } else {
-          action = ' Neutral';
+        } else if (action === 'Neutral') {
           color = 'text-gray-400';
+        } else {
+          color = 'text-gray-300';
         }
         
         setFuturesAnalysis({
-          action,
+          action: ` ${action}`,
           color,
           underlyingValue,
           timestamp: response.data.timestamp,
@@ -1,3 +1,5 @@
+import type { HistoricalSeries } from './api_nse_type';
+
 // MCX API Response Types
 export interface McxApiResponse<T> {
   success: boolean;
@@ -214,38 +216,12 @@ export interface McxHistoricalDataParams {
   instrument_name: 'FUTCOM' | 'OPTFUT';
   option_type?: 'CE' | 'PE';
   strike_price?: string;
+  window?: number;
+  indicators?: string; // e.g. "sma:20,rsi:14,bb:20:2,vwap,oi_sma:5"
 }
 
-// MCX Historical Data Response Structure (Updated to match actual API response)
-export interface McxHistoricalDataResponse {
-  d: {
-    Data: Array<{
-      ChangeInOI:number;
-      Close: number;
-      Date: string;
-      DateDisplay: string;
-      ExpiryDate: string;
-      High: number;
-      InstrumentName: string;
-      Low: number;
-      Open: number;
-      OpenInterest: number;
-      OptionType: string;
-      PreviousClose: number;
-      StrikePrice: number;
-      Symbol: string;
-      Value: number;
-      Volume: number;
-      VolumeInThousands: string;
-      __type: string;
-    }>;
-    Summary: {
-      AsOn: string;
-      Count: number;
-      Status: string | null;
-    };
-  };
-}
+// MCX Historical Data Response Structure: the normalized series, oldest bar first
+export type McxHistoricalDataResponse = HistoricalSeries;
 
 // Data with age interface for MCX
 export interface McxDataWithAge<T> {
@@ -117,8 +117,11 @@ export interface CacheInfo {
   lastUpdated?: number;
 }
 
+export type Buildup = 'Long Buildup' | 'Short Covering' | 'Short Buildup' | 'Long Covering' | 'Neutral';
+
 export interface FuturesDataResponse {
   data: Array<{
+    action: Buildup;
     pchange: number;
     pchangeinOpenInterest: number;
     underlyingValue: number;
@@ -152,3 +155,98 @@ export interface HistoricalDataPoint {
   FH_CLOSING_PRICE: number;
   FH_PREV_CLS: number;
 }
+
+// Normalized OHLC/OI series returned by the NSE and MCX historical endpoints
+export interface HistoricalBar {
+  date: string;
+  open: number;
+  high: number;
+  low: number;
+  close: number;
+  volume: number | null;
+  open_interest: number | null;
+  change_in_oi: number | null;
+  underlying_value: number | null;
+  return_pct: number | null;
+  rolling_mean_return_pct: number | null;
+  rolling_volatility_pct: number | null;
+}
+
+export interface HistoricalSeries {
+  exchange: string;
+  symbol: string;
+  instrument: string;
+  expiry: string;
+  strike: number | null;
+  option_type: string | null;
+  rolling_window: number;
+  bars: HistoricalBar[];
+  stats: Record<string, number | string | null>;
+  // Requested with `indicators=`, e.g. "sma_20", "bb_20_2_upper"; aligned with `bars`
+  indicators?: Record<string, Array<number | null>>;
+}
+
+// Ranked per-symbol batch metrics from /api/nse/screener
+export interface ScreenerRow {
+  rank: number;
+  symbol: string;
+  timestamp: string;
+  expiry: string | null;
+  underlying_value: number;
+  spread: number;
+  days_to_expiry: number;
+  ce_oi: number;
+  pe_oi: number;
+  pcr: number | null;
+  ce_oi_change: number;
+  pe_oi_change: number;
+  ce_oi_change_pct: number | null;
+  pe_oi_change_pct: number | null;
+  oi_change_pct: number | null;
+  alerts: number;
+  max_pain: number | null;
+  max_pain_distance_pct: number | null;
+  atm_iv: number | null;
+  expected_move_pct: number | null;
+  iv_rank: number | null;
+  iv_percentile: number | null;
+  futures_price: number | null;
+  basis: number | null;
+  basis_pct: number | null;
+}
+
+export interface ScreenerResult {
+  total_symbols: number;
+  matched: number;
+  filter: string | null;
+  sort: string;
+  timestamp: string;
+  results: ScreenerRow[];
+}
+
+// Background batch jobs (POST batch-analysis returns one; poll GET /jobs/{id})
+export type JobStatus = 'running' | 'completed' | 'failed' | 'cancelled';
+
+export interface JobProgress {
+  stage: string;
+  total: number;
+  fetched: number;
+  failed: number;
+  pending: number;
+  percent: number;
+  completed_symbols: string[];
+  failures: { symbol: string; error: string }[];
+}
+
+export interface BatchJob<T, P = unknown> {
+  id: string;
+  kind: string;
+  status: JobStatus;
+  created_at: string;
+  finished_at: string | null;
+  elapsed_ms: number;
+  progress: JobProgress;
+  result?: T;
+  partial_results?: P[]; // Per-symbol alerts so far, until the job completes
+  error: string | null;
+}
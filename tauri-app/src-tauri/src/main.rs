@@ -1,24 +1,43 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use std::collections::hash_map::RandomState;
 use std::fs;
+use std::hash::BuildHasher;
 use std::io::Write;
-use std::net::TcpListener;
+use std::net::{TcpListener, TcpStream};
 use std::process::{Child, Command, Stdio};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime};
 use tauri::{AppHandle, Emitter, Manager, State, WindowEvent};
 
+// Backends get this long to drain requests and cancel batches after POST /shutdown
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(12);
+
 #[derive(Clone, serde::Serialize)]
 struct BackendConfig {
     exchange: String,
     port: String,
     health_path: String,
+    ready_path: String,
+}
+
+// Backend readiness as shown to the user: "healthy", "starting", "degraded",
+// "upstream_blocked" (backend up but the exchange is refusing requests) or "down"
+#[derive(Clone, serde::Serialize)]
+struct BackendReadiness {
+    exchange: String,
+    port: String,
+    state: String,
+    detail: Option<String>,
 }
 
 struct BackendManager {
     processes: Arc<Mutex<Vec<Child>>>,
     configs: Arc<Mutex<Vec<BackendConfig>>>,
+    // Secret the backends accept on POST /shutdown in place of an API key
+    shutdown_token: String,
 }
 
 impl BackendManager {
@@ -26,18 +45,14 @@ impl BackendManager {
         Self {
             processes: Arc::new(Mutex::new(Vec::new())),
             configs: Arc::new(Mutex::new(Vec::new())),
+            shutdown_token: generate_shutdown_token(),
         }
     }
 
+    /// Callers stop any running backends first (see `stop_all`)
     fn start_all(&self, backend_path: &str, app_handle: &AppHandle) -> Result<Vec<BackendConfig>, String> {
         let mut guard = self.processes.lock().unwrap();
         let mut configs_guard = self.configs.lock().unwrap();
-
-        // Kill existing
-        for mut child in guard.drain(..) {
-            let _ = child.kill();
-            let _ = child.wait();
-        }
         configs_guard.clear();
 
         // Define backend configurations
@@ -66,6 +81,7 @@ impl BackendManager {
                     .env("MODE", "server")
                     .env("EXCHANGE", exchange)
                     .env("PORT", &port)
+                    .env("SHUTDOWN_TOKEN", &self.shutdown_token)
                     .creation_flags(CREATE_NO_WINDOW)  // Hide console window on Windows
                     .stdout(Stdio::null())  // Discard stdout
                     .stderr(Stdio::null())  // Discard stderr
@@ -83,6 +99,7 @@ impl BackendManager {
                 .env("MODE", "server")
                 .env("EXCHANGE", exchange)
                 .env("PORT", &port)
+                .env("SHUTDOWN_TOKEN", &self.shutdown_token)
                 .stdout(Stdio::piped())
                 .stderr(Stdio::piped())
                 .spawn()
@@ -99,6 +116,7 @@ impl BackendManager {
                 exchange: exchange.to_string(),
                 port: port.clone(),
                 health_path: health_path.to_string(),
+                ready_path: "/ready".to_string(),
             };
 
             started_configs.push(config.clone());
@@ -114,12 +132,24 @@ impl BackendManager {
         Ok(started_configs)
     }
 
-    fn stop_all(&self) {
-        let mut guard = self.processes.lock().unwrap();
-        for mut child in guard.drain(..) {
-            let _ = child.kill();
-            let _ = child.wait();
-        }
+    /// Stop the backends on a background thread, which can take up to SHUTDOWN_GRACE;
+    /// join the handle to wait for them
+    fn stop_all(&self) -> JoinHandle<()> {
+        // Take the children out so the locks are not held through the grace period
+        let children: Vec<Child> = self.processes.lock().unwrap().drain(..).collect();
+        let configs = self.configs.lock().unwrap().clone();
+        let token = self.shutdown_token.clone();
+        std::thread::spawn(move || stop_children(children, &configs, &token))
+    }
+
+    /// Stop the backends without blocking the async runtime
+    async fn stop_all_async(&self) {
+        let stopping = self.stop_all();
+        let _ = tauri::async_runtime::spawn_blocking(move || stopping.join()).await;
+    }
+
+    fn has_children(&self) -> bool {
+        !self.processes.lock().unwrap().is_empty()
     }
 
     fn is_any_running(&self) -> bool {
@@ -137,6 +167,46 @@ impl BackendManager {
     }
 }
 
+/// Ask each backend to shut down gracefully, then kill any still running after the grace period
+fn stop_children(children: Vec<Child>, configs: &[BackendConfig], token: &str) {
+    for config in configs {
+        request_shutdown(&config.port, token);
+    }
+
+    let deadline = Instant::now() + SHUTDOWN_GRACE;
+    for mut child in children {
+        while Instant::now() < deadline && matches!(child.try_wait(), Ok(None)) {
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+/// POST /shutdown to a backend with its launch token; best effort, the caller kills it if this fails
+fn request_shutdown(port: &str, token: &str) {
+    let Ok(addr) = format!("127.0.0.1:{}", port).parse() else {
+        return;
+    };
+    let Ok(mut stream) = TcpStream::connect_timeout(&addr, Duration::from_secs(1)) else {
+        return;
+    };
+    let request = format!(
+        "POST /shutdown HTTP/1.1\r\nHost: localhost:{}\r\nX-Shutdown-Token: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        port, token
+    );
+    let _ = stream.write_all(request.as_bytes());
+}
+
+// Per-launch secret for POST /shutdown. RandomState keys come from the OS random source,
+// so the hashes are unpredictable without pulling in an RNG crate.
+fn generate_shutdown_token() -> String {
+    let seed = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_nanos();
+    (0..2)
+        .map(|i| format!("{:016x}", RandomState::new().hash_one((seed, i, std::process::id()))))
+        .collect()
+}
+
 // Find an available port excluding already used ports
 fn find_available_port_excluding(used_ports: &[String]) -> Option<String> {
     // Try ports in range 3001-3100
@@ -262,6 +332,7 @@ async fn start_backends(
         std::fs::set_permissions(&backend_path, perms).ok();
     }
 
+    backend_manager.stop_all_async().await;
     let configs = backend_manager.start_all(&backend_path.to_string_lossy(), &app_handle)?;
 
     tokio::time::sleep(Duration::from_secs(5)).await;
@@ -283,7 +354,7 @@ async fn start_backends(
 
 #[tauri::command]
 async fn stop_backends(manager: State<'_, BackendManager>) -> Result<String, String> {
-    manager.stop_all();
+    manager.stop_all_async().await;
     Ok("All backends stopped".to_string())
 }
 
@@ -292,6 +363,15 @@ async fn backend_status(manager: State<'_, BackendManager>) -> Result<bool, Stri
     Ok(manager.is_any_running())
 }
 
+#[tauri::command]
+async fn backend_readiness(manager: State<'_, BackendManager>) -> Result<Vec<BackendReadiness>, String> {
+    let mut readiness = Vec::new();
+    for config in manager.get_configs() {
+        readiness.push(check_ready(&config).await);
+    }
+    Ok(readiness)
+}
+
 #[tauri::command]
 async fn get_backend_urls(manager: State<'_, BackendManager>) -> Result<Vec<String>, String> {
     let configs = manager.get_configs();
@@ -330,6 +410,36 @@ async fn check_health(port: &str, path: &str) -> Result<(), reqwest::Error> {
     Ok(())
 }
 
+// GET /ready: 200 when ready, starting or degraded, 503 when the exchange blocks the backend
+async fn check_ready(config: &BackendConfig) -> BackendReadiness {
+    let readiness = |state: &str, detail: Option<String>| BackendReadiness {
+        exchange: config.exchange.clone(),
+        port: config.port.clone(),
+        state: state.to_string(),
+        detail,
+    };
+
+    let response = reqwest::Client::new()
+        .get(format!("http://localhost:{}{}", config.port, config.ready_path))
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await;
+    let body: serde_json::Value = match response {
+        Ok(response) => match response.json().await {
+            Ok(body) => body,
+            Err(e) => return readiness("down", Some(format!("Invalid readiness response: {}", e))),
+        },
+        Err(e) => return readiness("down", Some(e.to_string())),
+    };
+
+    let detail = body["upstream"]["last_error"]["message"].as_str().map(str::to_string);
+    match body["status"].as_str() {
+        Some("ready") => readiness("healthy", None),
+        Some(status) => readiness(status, detail),
+        None => readiness("down", Some("Readiness response without status".to_string())),
+    }
+}
+
 fn main() {
     env_logger::init();
 
@@ -340,6 +450,7 @@ fn main() {
             start_backends,
             stop_backends,
             backend_status,
+            backend_readiness,
             get_backend_urls,
             get_backend_configs,
             get_platform_info
@@ -362,9 +473,18 @@ fn main() {
             Ok(())
         })
         .on_window_event(|window, event| {
-            if let WindowEvent::CloseRequested { .. } = event {
+            // Keep the window open while the backends drain, without blocking the UI thread
+            if let WindowEvent::CloseRequested { api, .. } = event {
                 let manager: State<BackendManager> = window.state();
-                manager.stop_all();
+                if manager.has_children() {
+                    api.prevent_close();
+                    let stopping = manager.stop_all();
+                    let window = window.clone();
+                    std::thread::spawn(move || {
+                        let _ = stopping.join();
+                        let _ = window.destroy();
+                    });
+                }
             }
         })
         .run(tauri::generate_context!())